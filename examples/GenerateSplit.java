@@ -104,7 +104,7 @@ public class GenerateSplit {
                     }
                     
                     System.out.println();
-                    System.out.println("Split files written to: " + outputPath.toAbsolutePath());
+                    System.out.println("Split written to: " + outputPath.toAbsolutePath());
                     
                 } catch (Exception e) {
                     System.err.println("Failed to generate split: " + e.getMessage());
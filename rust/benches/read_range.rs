@@ -0,0 +1,65 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+//! Reads a large range with various buffer sizes, with and without direct IO
+//!
+//! Run with `cargo bench --bench read_range`. The file is written once and
+//! read several times, so buffered reads are served from the page cache
+//! while direct IO reads hit the device: compare rows of the same mode
+//! across buffer sizes, and modes against the cold-cache numbers of the
+//! target deployment.
+
+use std::time::{Duration, Instant};
+use tantivy4java_splits::hotcache::ByteRange;
+use tantivy4java_splits::range_read::{read_file_range, ReadOptions};
+use tempfile::TempDir;
+
+/// Size of the file read, and of the range read from it
+const FILE_SIZE: usize = 256 * 1024 * 1024;
+
+/// Reads per configuration, the first one being discarded as a warmup
+const ITERATIONS: usize = 6;
+
+fn main() {
+    let temp_dir = TempDir::new().expect("create temp dir");
+    let path = temp_dir.path().join("large.fast");
+    let content: Vec<u8> = (0..FILE_SIZE).map(|i| (i * 31 % 251) as u8).collect();
+    std::fs::write(&path, &content).expect("write file");
+    
+    let range = ByteRange { start: 0, end: FILE_SIZE as u64 };
+    println!("{:>10} {:>12} {:>12}", "direct_io", "buffer", "MiB/s");
+    for direct_io in [false, true] {
+        for buffer_size in [64 * 1024, 256 * 1024, 1024 * 1024, 4 * 1024 * 1024, 16 * 1024 * 1024] {
+            let options = ReadOptions { buffer_size, direct_io };
+            let mut total = Duration::ZERO;
+            for iteration in 0..ITERATIONS {
+                let start = Instant::now();
+                let data = read_file_range(&path, &range, &options).expect("read range");
+                let elapsed = start.elapsed();
+                assert_eq!(data.len(), FILE_SIZE);
+                if iteration > 0 {
+                    total += elapsed;
+                }
+            }
+            
+            let mib_per_sec = (FILE_SIZE * (ITERATIONS - 1)) as f64 / (1024.0 * 1024.0) / total.as_secs_f64();
+            println!("{:>10} {:>12} {:>12.0}", direct_io, buffer_size, mib_per_sec);
+        }
+    }
+}
@@ -2,6 +2,13 @@ use std::env;
 use std::path::PathBuf;
 
 fn main() {
+    println!("cargo:rerun-if-env-changed=JAVA_HOME");
+    
+    // Without the JNI bridge there is nothing to link against the JVM
+    if env::var_os("CARGO_FEATURE_JNI").is_none() {
+        return;
+    }
+    
     let java_home = env::var("JAVA_HOME").expect("JAVA_HOME must be set");
     let java_include = PathBuf::from(&java_home).join("include");
     
@@ -17,6 +24,4 @@ fn main() {
     } else if cfg!(target_os = "windows") {
         println!("cargo:rustc-link-search=native={}/win32", java_include.display());
     }
-    
-    println!("cargo:rerun-if-env-changed=JAVA_HOME");
 }
\ No newline at end of file
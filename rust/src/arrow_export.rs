@@ -0,0 +1,237 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+//! Arrow IPC export of split columns
+//!
+//! Columns, as read by `QuickwitSplitReader::column`, are serialized as an
+//! Arrow IPC stream holding a single record batch with one row per live
+//! document. Tantivy types map to Arrow types as follows:
+//!
+//! | Tantivy | Arrow                          |
+//! |---------|--------------------------------|
+//! | text    | `Utf8`                         |
+//! | u64     | `UInt64`                       |
+//! | i64     | `Int64`                        |
+//! | f64     | `Float64`                      |
+//! | bool    | `Boolean`                      |
+//! | date    | `Timestamp(Millisecond, UTC)`  |
+//!
+//! Tantivy schemas don't record whether a field is multi-valued, so a column
+//! becomes a `List` of its type as soon as one document holds several
+//! values; documents with a single value then get a one-element list. A
+//! document without a value is null either way.
+
+use crate::error::{Result, SplitsError};
+use crate::split_reader::FieldKind;
+use arrow::array::{
+    ArrayBuilder, ArrayRef, BooleanBuilder, Float64Builder, Int64Builder, ListBuilder, StringBuilder,
+    TimestampMillisecondBuilder, UInt64Builder,
+};
+use arrow::datatypes::{DataType, Field, Schema, TimeUnit};
+use arrow::ipc::writer::StreamWriter;
+use arrow::record_batch::RecordBatch;
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Time zone of exported date columns
+const DATE_TIME_ZONE: &str = "UTC";
+
+/// Gets the Arrow type of single values of a field, failing with a
+/// `FieldError` if its Tantivy type has no Arrow mapping
+pub fn data_type(field: &str, kind: FieldKind) -> Result<DataType> {
+    match kind {
+        FieldKind::Text => Ok(DataType::Utf8),
+        FieldKind::U64 => Ok(DataType::UInt64),
+        FieldKind::I64 => Ok(DataType::Int64),
+        FieldKind::F64 => Ok(DataType::Float64),
+        FieldKind::Bool => Ok(DataType::Boolean),
+        FieldKind::Date => Ok(DataType::Timestamp(TimeUnit::Millisecond, Some(DATE_TIME_ZONE.into()))),
+        other => Err(SplitsError::FieldError(format!(
+            "Field '{}' of type {} has no Arrow mapping", field, other.name()
+        ))),
+    }
+}
+
+/// Serializes named columns of equal length as an Arrow IPC stream
+pub fn to_ipc_stream(columns: Vec<(String, FieldKind, Vec<Value>)>) -> Result<Vec<u8>> {
+    let mut fields = Vec::with_capacity(columns.len());
+    let mut arrays = Vec::with_capacity(columns.len());
+    for (name, kind, values) in columns {
+        let array = build_array(&name, kind, &values)?;
+        fields.push(Field::new(name, array.data_type().clone(), true));
+        arrays.push(array);
+    }
+    
+    let schema = Arc::new(Schema::new(fields));
+    let batch = RecordBatch::try_new(schema.clone(), arrays)?;
+    
+    let mut bytes = Vec::new();
+    let mut writer = StreamWriter::try_new(&mut bytes, &schema)?;
+    writer.write(&batch)?;
+    writer.finish()?;
+    drop(writer);
+    
+    Ok(bytes)
+}
+
+/// Builds the Arrow array of a column, as a list array if any document is multi-valued
+fn build_array(field: &str, kind: FieldKind, values: &[Value]) -> Result<ArrayRef> {
+    data_type(field, kind)?;
+    
+    match kind {
+        FieldKind::Text => build_column(field, values, StringBuilder::new(), |builder, value| {
+            builder.append_option(value.map(|value| value.as_str().ok_or(())).transpose()?);
+            Ok(())
+        }),
+        FieldKind::U64 => build_column(field, values, UInt64Builder::new(), |builder, value| {
+            builder.append_option(value.map(|value| value.as_u64().ok_or(())).transpose()?);
+            Ok(())
+        }),
+        FieldKind::I64 => build_column(field, values, Int64Builder::new(), |builder, value| {
+            builder.append_option(value.map(|value| value.as_i64().ok_or(())).transpose()?);
+            Ok(())
+        }),
+        FieldKind::F64 => build_column(field, values, Float64Builder::new(), |builder, value| {
+            builder.append_option(value.map(|value| value.as_f64().ok_or(())).transpose()?);
+            Ok(())
+        }),
+        FieldKind::Bool => build_column(field, values, BooleanBuilder::new(), |builder, value| {
+            builder.append_option(value.map(|value| value.as_bool().ok_or(())).transpose()?);
+            Ok(())
+        }),
+        FieldKind::Date => {
+            let builder = TimestampMillisecondBuilder::new().with_timezone(DATE_TIME_ZONE);
+            build_column(field, values, builder, |builder, value| {
+                builder.append_option(value.map(date_millis).transpose()?);
+                Ok(())
+            })
+        }
+        _ => unreachable!("checked by data_type"),
+    }
+}
+
+/// Appends every document's values with `append`, which is given `None` for a null
+fn build_column<B: ArrayBuilder>(
+    field: &str,
+    values: &[Value],
+    mut builder: B,
+    append: impl Fn(&mut B, Option<&Value>) -> std::result::Result<(), ()>,
+) -> Result<ArrayRef> {
+    let unexpected = |value: &Value| SplitsError::Serialization(format!(
+        "Unexpected value {} in field '{}'", value, field
+    ));
+    
+    if !values.iter().any(Value::is_array) {
+        for value in values {
+            let value = Some(value).filter(|value| !value.is_null());
+            append(&mut builder, value).map_err(|_| unexpected(value.unwrap_or(&Value::Null)))?;
+        }
+        return Ok(builder.finish());
+    }
+    
+    let mut list_builder = ListBuilder::new(builder);
+    for value in values {
+        let items = match value {
+            Value::Null => {
+                list_builder.append_null();
+                continue;
+            }
+            Value::Array(items) => items.as_slice(),
+            single => std::slice::from_ref(single),
+        };
+        for item in items {
+            append(list_builder.values(), Some(item)).map_err(|_| unexpected(item))?;
+        }
+        list_builder.append(true);
+    }
+    Ok(Arc::new(list_builder.finish()))
+}
+
+/// Parses a date serialized by Tantivy, as RFC 3339, into Unix milliseconds
+fn date_millis(value: &Value) -> std::result::Result<i64, ()> {
+    let date = value.as_str().ok_or(())?;
+    chrono::DateTime::parse_from_rfc3339(date)
+        .map(|date| date.timestamp_millis())
+        .map_err(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Array, AsArray};
+    use arrow::datatypes::{Int64Type, TimestampMillisecondType};
+    use arrow::ipc::reader::StreamReader;
+    use serde_json::json;
+    
+    fn read_batch(bytes: &[u8]) -> RecordBatch {
+        let mut reader = StreamReader::try_new(bytes, None).unwrap();
+        let batch = reader.next().unwrap().unwrap();
+        assert!(reader.next().is_none());
+        batch
+    }
+    
+    #[test]
+    fn test_ipc_stream_round_trip() {
+        let bytes = to_ipc_stream(vec![
+            ("title".to_string(), FieldKind::Text, vec![json!("a"), Value::Null, json!("c")]),
+            ("count".to_string(), FieldKind::I64, vec![json!(-1), json!(2), Value::Null]),
+            ("timestamp".to_string(), FieldKind::Date, vec![json!("2023-11-14T22:13:20.123Z"), Value::Null, Value::Null]),
+        ]).unwrap();
+        
+        let batch = read_batch(&bytes);
+        assert_eq!(batch.num_rows(), 3);
+        
+        let titles = batch.column_by_name("title").unwrap().as_string::<i32>();
+        assert_eq!(titles.value(0), "a");
+        assert!(titles.is_null(1));
+        
+        let counts = batch.column_by_name("count").unwrap().as_primitive::<Int64Type>();
+        assert_eq!(counts.value(0), -1);
+        assert!(counts.is_null(2));
+        
+        let timestamps = batch.column_by_name("timestamp").unwrap();
+        assert_eq!(timestamps.data_type(), &DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())));
+        assert_eq!(timestamps.as_primitive::<TimestampMillisecondType>().value(0), 1_700_000_000_123);
+    }
+    
+    #[test]
+    fn test_multi_valued_field_becomes_list() {
+        let bytes = to_ipc_stream(vec![
+            ("tags".to_string(), FieldKind::Text, vec![json!(["x", "y"]), json!("z"), Value::Null]),
+        ]).unwrap();
+        
+        let batch = read_batch(&bytes);
+        let tags = batch.column(0).as_list::<i32>();
+        assert_eq!(tags.value(0).as_string::<i32>().iter().flatten().collect::<Vec<_>>(), vec!["x", "y"]);
+        assert_eq!(tags.value(1).as_string::<i32>().iter().flatten().collect::<Vec<_>>(), vec!["z"]);
+        assert!(tags.is_null(2));
+    }
+    
+    #[test]
+    fn test_unmapped_type_is_rejected() {
+        match to_ipc_stream(vec![("payload".to_string(), FieldKind::Bytes, vec![])]) {
+            Err(SplitsError::FieldError(msg)) => assert!(msg.contains("type bytes")),
+            other => panic!("Expected FieldError, got {:?}", other.map(|bytes| bytes.len())),
+        }
+        assert!(matches!(
+            to_ipc_stream(vec![("count".to_string(), FieldKind::U64, vec![json!("x")])]),
+            Err(SplitsError::Serialization(_))
+        ));
+    }
+}
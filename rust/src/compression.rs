@@ -0,0 +1,201 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+//! Split files compressed as a whole, as archival tiers store them
+//!
+//! `QuickwitSplitReader::open` recognizes `<split_id>.split.gz` and
+//! `<split_id>.split.zst` by their extension and decompresses them before
+//! reading, since compressed bytes can't be read by range. Splits up to
+//! `IN_MEMORY_MAX_BYTES` decompressed are held in memory; larger ones are
+//! written to a temporary file in `decompression_temp_dir`, removed when the
+//! reader is dropped. Opening thus reads and decompresses the whole split,
+//! and holds its decompressed size in memory or on disk for the reader's
+//! lifetime. `set_max_decompressed_bytes` refuses splits decompressing
+//! beyond a size, checked while decompressing, so a small archive can't
+//! expand unbounded. Decompression requires the `compression` feature.
+
+use crate::error::{Result, SplitsError};
+use once_cell::sync::Lazy;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::RwLock;
+
+#[cfg(feature = "compression")]
+use std::io::{self, Read, Write};
+#[cfg(feature = "compression")]
+use uuid::Uuid;
+
+/// Largest decompressed split held in memory rather than in a temporary file
+pub const IN_MEMORY_MAX_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Largest decompressed split accepted, 0 meaning unlimited
+static MAX_DECOMPRESSED_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Directory of temporary decompressed splits, the system one when unset
+static TEMP_DIR: Lazy<RwLock<Option<PathBuf>>> = Lazy::new(|| RwLock::new(None));
+
+/// Compression of a whole split file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// Detects the compression of a split file from its extension, `.gz` or `.zst`
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "gz" => Some(Compression::Gzip),
+            "zst" => Some(Compression::Zstd),
+            _ => None,
+        }
+    }
+}
+
+/// Sets the largest decompressed size of compressed splits readers accept
+///
+/// A limit of 0 disables it, which is the default.
+pub fn set_max_decompressed_bytes(bytes: u64) {
+    MAX_DECOMPRESSED_BYTES.store(bytes, Ordering::SeqCst);
+}
+
+/// Gets the largest decompressed size of compressed splits, 0 meaning unlimited
+pub fn max_decompressed_bytes() -> u64 {
+    MAX_DECOMPRESSED_BYTES.load(Ordering::SeqCst)
+}
+
+/// Sets the directory large compressed splits are decompressed into
+///
+/// `None` restores the default, the system temporary directory. Only splits
+/// opened afterwards are affected.
+pub fn set_decompression_temp_dir(dir: Option<PathBuf>) -> Result<()> {
+    let mut current = TEMP_DIR.write()
+        .map_err(|e| SplitsError::InvalidOperation(format!("Failed to set decompression directory: {}", e)))?;
+    *current = dir;
+    Ok(())
+}
+
+/// Gets the directory large compressed splits are decompressed into
+pub fn decompression_temp_dir() -> PathBuf {
+    TEMP_DIR.read().ok()
+        .and_then(|dir| dir.clone())
+        .unwrap_or_else(std::env::temp_dir)
+}
+
+/// A decompressed split
+pub enum DecompressedSplit {
+    /// The serialized split, for splits up to `IN_MEMORY_MAX_BYTES`
+    InMemory(Vec<u8>),
+    /// A temporary split file, for larger splits
+    TempFile(TempFile),
+}
+
+/// A temporary file, removed when dropped
+#[derive(Debug)]
+pub struct TempFile {
+    path: PathBuf,
+}
+
+impl TempFile {
+    /// Gets the path of the file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_file(&self.path) {
+            log::warn!("Failed to remove decompressed split {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// Decompresses the split file at `path`
+///
+/// Fails with `InvalidOperation` if the split decompresses beyond
+/// `max_decompressed_bytes`, and `InvalidSplit` if it isn't validly
+/// compressed.
+#[cfg(feature = "compression")]
+pub fn decompress_split(path: &Path, compression: Compression) -> Result<DecompressedSplit> {
+    let file = io::BufReader::new(fs::File::open(path)?);
+    let decoder: Box<dyn Read> = match compression {
+        Compression::Gzip => Box::new(flate2::read::MultiGzDecoder::new(file)),
+        Compression::Zstd => Box::new(zstd::stream::read::Decoder::with_buffer(file)?),
+    };
+    
+    let max_bytes = max_decompressed_bytes();
+    let read_error = |e: io::Error| match e.kind() {
+        io::ErrorKind::InvalidData | io::ErrorKind::InvalidInput | io::ErrorKind::UnexpectedEof => {
+            SplitsError::InvalidSplit(format!("Failed to decompress split {}: {}", path.display(), e))
+        }
+        _ => SplitsError::Io(e),
+    };
+    let too_large = || SplitsError::InvalidOperation(format!(
+        "Split {} decompresses beyond the limit of {} bytes", path.display(), max_bytes
+    ));
+    // One byte past the limit tells a split exactly at it from a larger one
+    let mut decoder = decoder.take(if max_bytes == 0 { u64::MAX } else { max_bytes.saturating_add(1) });
+    
+    let mut bytes = Vec::new();
+    (&mut decoder).take(IN_MEMORY_MAX_BYTES + 1).read_to_end(&mut bytes).map_err(read_error)?;
+    if bytes.len() as u64 <= IN_MEMORY_MAX_BYTES {
+        if max_bytes > 0 && bytes.len() as u64 > max_bytes {
+            return Err(too_large());
+        }
+        return Ok(DecompressedSplit::InMemory(bytes));
+    }
+    
+    let temp_dir = decompression_temp_dir();
+    fs::create_dir_all(&temp_dir)?;
+    // Removed on any failure below, when dropped
+    let temp_file = TempFile {
+        path: temp_dir.join(format!(".quickwit-split.{}.split", Uuid::new_v4().simple())),
+    };
+    let mut output = io::BufWriter::new(fs::File::create(temp_file.path())?);
+    output.write_all(&bytes)?;
+    drop(bytes);
+    let len = IN_MEMORY_MAX_BYTES + 1 + io::copy(&mut decoder, &mut output).map_err(read_error)?;
+    if max_bytes > 0 && len > max_bytes {
+        return Err(too_large());
+    }
+    output.into_inner().map_err(|e| SplitsError::Io(e.into_error()))?.sync_all()?;
+    
+    log::debug!("Decompressed split {} to {} ({} bytes)", path.display(), temp_file.path().display(), len);
+    Ok(DecompressedSplit::TempFile(temp_file))
+}
+
+#[cfg(not(feature = "compression"))]
+pub fn decompress_split(_path: &Path, _compression: Compression) -> Result<DecompressedSplit> {
+    Err(SplitsError::InvalidOperation("Reading compressed splits requires the compression feature".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_compression_from_path() {
+        assert_eq!(Compression::from_path(Path::new("a.split.gz")), Some(Compression::Gzip));
+        assert_eq!(Compression::from_path(Path::new("/splits/a.split.zst")), Some(Compression::Zstd));
+        assert_eq!(Compression::from_path(Path::new("a.split")), None);
+        assert_eq!(Compression::from_path(Path::new("gz")), None);
+    }
+}
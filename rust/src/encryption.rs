@@ -0,0 +1,261 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+//! Encryption at rest of split files
+//!
+//! With `SplitConfig::encryption_key` set, every segment file of a split is
+//! encrypted with AES-256-GCM once written, under a fresh random nonce and
+//! with the file name as associated data, so files can't be swapped between
+//! names unnoticed. The store file is encrypted before its hotcache footer is
+//! appended. The nonce and ciphertext length of each file are recorded in the
+//! hotcache, which stays in cleartext: it keeps the split id, document count,
+//! provenance, tokenizers and custom metadata readable for pruning, but no
+//! field ranges or term data. The `meta.json`, holding only the schema and
+//! segment metas, and the sidecar metadata aren't encrypted either.
+//!
+//! Readers given the same key decrypt the files whole when opening the index,
+//! as GCM authenticates a file only once fully read. A wrong key or altered
+//! file fails with `SplitsError::Decryption`. Encryption requires the
+//! `encryption` feature; the key and metadata types are always available so
+//! configurations and hotcaches parse the same in every build.
+
+use crate::error::{Result, SplitsError};
+use serde::{Deserialize, Deserializer, Serialize};
+use std::fmt;
+
+use crate::split_storage::SplitStorage;
+#[cfg(feature = "encryption")]
+use crate::hotcache::ByteRange;
+#[cfg(feature = "encryption")]
+use crate::range_read::ReadOptions;
+#[cfg(feature = "encryption")]
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+#[cfg(feature = "encryption")]
+use aes_gcm::{Aes256Gcm, Nonce};
+use std::collections::BTreeMap;
+#[cfg(feature = "encryption")]
+use std::fs;
+use std::path::Path;
+#[cfg(feature = "encryption")]
+use tantivy::directory::Directory;
+use tantivy::directory::RamDirectory;
+
+/// Length of encryption keys in bytes
+pub const ENCRYPTION_KEY_LEN: usize = 32;
+
+/// Length of AES-GCM nonces in bytes
+#[cfg(feature = "encryption")]
+const NONCE_LEN: usize = 12;
+
+/// Name of the index meta file, left in cleartext
+#[cfg(feature = "encryption")]
+const META_FILE_NAME: &str = "meta.json";
+
+/// An AES-256 key, deserialized from its hex encoding
+///
+/// Never printed: its `Debug` output is redacted so configurations can be
+/// logged.
+#[derive(Clone, PartialEq, Eq)]
+pub struct EncryptionKey([u8; ENCRYPTION_KEY_LEN]);
+
+impl EncryptionKey {
+    /// Checks and converts a caller-supplied key
+    pub fn new(key: &[u8]) -> Result<Self> {
+        key.try_into().map(EncryptionKey).map_err(|_| SplitsError::InvalidOperation(format!(
+            "Encryption key must be {} bytes, got {}", ENCRYPTION_KEY_LEN, key.len()
+        )))
+    }
+    
+    /// Parses a key from its hex encoding
+    pub fn from_hex(hex: &str) -> Result<Self> {
+        let key = decode_hex(hex)
+            .ok_or_else(|| SplitsError::InvalidOperation("Encryption key is not valid hex".to_string()))?;
+        Self::new(&key)
+    }
+}
+
+impl fmt::Debug for EncryptionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EncryptionKey(<redacted>)")
+    }
+}
+
+impl<'de> Deserialize<'de> for EncryptionKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let hex = String::deserialize(deserializer)?;
+        EncryptionKey::from_hex(&hex).map_err(serde::de::Error::custom)
+    }
+}
+
+/// How a split file was encrypted, as recorded in the hotcache
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncryptedFile {
+    /// Hex encoded AES-GCM nonce
+    pub nonce: String,
+    /// Length of the ciphertext, authentication tag included, at the start of the file
+    pub len: u64,
+}
+
+/// Encrypts every file of a split directory but `meta.json`, in place
+///
+/// Returns how each file was encrypted, by file name.
+#[cfg(feature = "encryption")]
+pub fn encrypt_split_files(split_dir: &Path, key: &EncryptionKey) -> Result<BTreeMap<String, EncryptedFile>> {
+    let cipher = Aes256Gcm::new(&key.0.into());
+    let mut encrypted_files = BTreeMap::new();
+    
+    for entry in fs::read_dir(split_dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if !entry.metadata()?.is_file() || file_name == META_FILE_NAME {
+            continue;
+        }
+        
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let plaintext = fs::read(entry.path())?;
+        let ciphertext = cipher.encrypt(&nonce, Payload { msg: &plaintext, aad: file_name.as_bytes() })
+            .map_err(|_| SplitsError::InvalidOperation(format!("Failed to encrypt {}", file_name)))?;
+        fs::write(entry.path(), &ciphertext)?;
+        
+        encrypted_files.insert(file_name, EncryptedFile {
+            nonce: encode_hex(&nonce),
+            len: ciphertext.len() as u64,
+        });
+    }
+    
+    Ok(encrypted_files)
+}
+
+#[cfg(not(feature = "encryption"))]
+pub fn encrypt_split_files(_split_dir: &Path, _key: &EncryptionKey) -> Result<BTreeMap<String, EncryptedFile>> {
+    Err(feature_disabled())
+}
+
+/// Decrypts a file encrypted by `encrypt_split_files`
+///
+/// Fails with `Decryption` if the key is wrong or the file was altered.
+#[cfg(feature = "encryption")]
+pub fn decrypt_file(key: &EncryptionKey, file_name: &str, encrypted: &EncryptedFile, ciphertext: &[u8]) -> Result<Vec<u8>> {
+    let nonce = decode_hex(&encrypted.nonce)
+        .filter(|nonce| nonce.len() == NONCE_LEN)
+        .ok_or_else(|| SplitsError::InvalidSplit(format!("Malformed nonce for encrypted file {}", file_name)))?;
+    
+    Aes256Gcm::new(&key.0.into())
+        .decrypt(Nonce::from_slice(&nonce), Payload { msg: ciphertext, aad: file_name.as_bytes() })
+        .map_err(|_| SplitsError::Decryption(format!(
+            "Failed to decrypt {}: wrong key, or the file was altered", file_name
+        )))
+}
+
+/// Decrypts the files of a split into a RAM directory, to open its index from
+///
+/// Files that weren't encrypted, such as `meta.json`, are copied as is.
+#[cfg(feature = "encryption")]
+pub fn decrypt_split(
+    storage: &dyn SplitStorage,
+    encrypted_files: &BTreeMap<String, EncryptedFile>,
+    key: &EncryptionKey,
+) -> Result<RamDirectory> {
+    let directory = RamDirectory::create();
+    for file_name in storage.list_files()? {
+        let data = match encrypted_files.get(&file_name) {
+            Some(encrypted) => {
+                let range = ByteRange { start: 0, end: encrypted.len };
+                let ciphertext = storage.read_range(&file_name, &range, &ReadOptions::default())?;
+                decrypt_file(key, &file_name, encrypted, &ciphertext)?
+            }
+            None => storage.read_file(&file_name)?,
+        };
+        directory.atomic_write(Path::new(&file_name), &data)?;
+    }
+    Ok(directory)
+}
+
+#[cfg(not(feature = "encryption"))]
+pub fn decrypt_split(
+    _storage: &dyn SplitStorage,
+    _encrypted_files: &BTreeMap<String, EncryptedFile>,
+    _key: &EncryptionKey,
+) -> Result<RamDirectory> {
+    Err(feature_disabled())
+}
+
+#[cfg(not(feature = "encryption"))]
+fn feature_disabled() -> SplitsError {
+    SplitsError::InvalidOperation("Split encryption requires the encryption feature".to_string())
+}
+
+#[cfg(feature = "encryption")]
+fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn decode_hex(hex: &str) -> Option<Vec<u8>> {
+    if !hex.is_ascii() || !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_encryption_key_parsing() {
+        let key = EncryptionKey::from_hex(&"ab".repeat(ENCRYPTION_KEY_LEN)).unwrap();
+        assert_eq!(key, EncryptionKey::new(&[0xab; ENCRYPTION_KEY_LEN]).unwrap());
+        assert_eq!(format!("{:?}", key), "EncryptionKey(<redacted>)");
+        
+        assert!(matches!(EncryptionKey::new(b"short"), Err(SplitsError::InvalidOperation(_))));
+        assert!(matches!(EncryptionKey::from_hex("not hex"), Err(SplitsError::InvalidOperation(_))));
+        assert!(serde_json::from_str::<EncryptionKey>("\"abcd\"").is_err());
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_encrypt_decrypt_round_trip() {
+        use tempfile::TempDir;
+        
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.store"), b"stored documents").unwrap();
+        fs::write(temp_dir.path().join(META_FILE_NAME), b"{}").unwrap();
+        let key = EncryptionKey::new(&[7; ENCRYPTION_KEY_LEN]).unwrap();
+        
+        let encrypted_files = encrypt_split_files(temp_dir.path(), &key).unwrap();
+        assert_eq!(encrypted_files.keys().collect::<Vec<_>>(), vec!["a.store"]);
+        let ciphertext = fs::read(temp_dir.path().join("a.store")).unwrap();
+        assert_ne!(ciphertext, b"stored documents");
+        assert_eq!(fs::read(temp_dir.path().join(META_FILE_NAME)).unwrap(), b"{}");
+        
+        let encrypted = &encrypted_files["a.store"];
+        assert_eq!(decrypt_file(&key, "a.store", encrypted, &ciphertext).unwrap(), b"stored documents");
+        
+        // A wrong key, a renamed file or altered bytes all fail authentication
+        let wrong_key = EncryptionKey::new(&[8; ENCRYPTION_KEY_LEN]).unwrap();
+        assert!(matches!(decrypt_file(&wrong_key, "a.store", encrypted, &ciphertext), Err(SplitsError::Decryption(_))));
+        assert!(matches!(decrypt_file(&key, "b.store", encrypted, &ciphertext), Err(SplitsError::Decryption(_))));
+        let mut altered = ciphertext.clone();
+        altered[0] ^= 1;
+        assert!(matches!(decrypt_file(&key, "a.store", encrypted, &altered), Err(SplitsError::Decryption(_))));
+    }
+}
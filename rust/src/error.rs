@@ -47,6 +47,12 @@ pub enum SplitsError {
     
     /// JNI operation failed
     Jni(String),
+    
+    /// Query could not be parsed or evaluated
+    Query(String),
+    
+    /// Encrypted split data failed authentication, e.g. under a wrong key
+    Decryption(String),
 }
 
 impl fmt::Display for SplitsError {
@@ -59,6 +65,8 @@ impl fmt::Display for SplitsError {
             SplitsError::FieldError(msg) => write!(f, "Field error: {}", msg),
             SplitsError::InvalidOperation(msg) => write!(f, "Invalid operation: {}", msg),
             SplitsError::Jni(msg) => write!(f, "JNI error: {}", msg),
+            SplitsError::Query(msg) => write!(f, "Query error: {}", msg),
+            SplitsError::Decryption(msg) => write!(f, "Decryption error: {}", msg),
         }
     }
 }
@@ -97,6 +105,13 @@ impl From<serde_json::Error> for SplitsError {
     }
 }
 
+#[cfg(feature = "arrow")]
+impl From<arrow::error::ArrowError> for SplitsError {
+    fn from(err: arrow::error::ArrowError) -> Self {
+        SplitsError::Serialization(format!("Arrow export failed: {}", err))
+    }
+}
+
 /// Convert SplitsError to a JNI exception class name
 pub fn error_to_exception_class(err: &SplitsError) -> &'static str {
     match err {
@@ -107,5 +122,7 @@ pub fn error_to_exception_class(err: &SplitsError) -> &'static str {
         SplitsError::FieldError(_) => "java/lang/IllegalArgumentException",
         SplitsError::InvalidOperation(_) => "java/lang/IllegalStateException",
         SplitsError::Jni(_) => "java/lang/RuntimeException",
+        SplitsError::Query(_) => "java/lang/IllegalArgumentException",
+        SplitsError::Decryption(_) => "java/io/IOException",
     }
 }
\ No newline at end of file
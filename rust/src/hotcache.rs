@@ -19,9 +19,148 @@
 
 //! Hotcache implementation using Quickwit's existing libraries
 
-use crate::error::Result;
-use std::collections::HashMap;
+use crate::encryption::EncryptedFile;
+use crate::error::{Result, SplitsError};
+use crate::tokenizers::TokenizerConfig;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, PoisonError};
 use serde::{Serialize, Deserialize};
+use tantivy::directory::error::{DeleteError, LockError, OpenReadError, OpenWriteError};
+use tantivy::directory::{Directory, DirectoryLock, FileHandle, Lock, MmapDirectory, OwnedBytes, WatchCallback, WatchHandle, WritePtr};
+use tantivy::index::{SegmentComponent, SegmentId};
+use tantivy::schema::{FieldType, IndexRecordOption};
+use tantivy::{HasLen, Index};
+#[cfg(feature = "quickwit-compat")]
+pub use quickwit_directories::StaticDirectoryCache;
+
+/// Metadata key under which custom tokenizer configurations are stored, as JSON
+pub const TOKENIZERS_METADATA_KEY: &str = "tokenizers";
+
+/// Metadata keys under which the split provenance is stored
+const CREATED_AT_MILLIS_METADATA_KEY: &str = "created_at_millis";
+const CRATE_VERSION_METADATA_KEY: &str = "crate_version";
+const TANTIVY_VERSION_METADATA_KEY: &str = "tantivy_version";
+
+/// Metadata keys under which the footer alignment is stored
+const SECTION_ALIGNMENT_METADATA_KEY: &str = "section_alignment";
+const STORE_DATA_LEN_METADATA_KEY: &str = "store_data_len";
+
+/// Metadata key under which the opstamp of the source index's last commit is stored
+const OPSTAMP_METADATA_KEY: &str = "opstamp";
+
+/// Metadata key under which per-field section ranges are stored, as JSON
+const FIELD_METADATA_METADATA_KEY: &str = "field_metadata";
+
+/// Metadata key under which user-supplied key/value metadata is stored, as JSON
+const CUSTOM_METADATA_METADATA_KEY: &str = "custom_metadata";
+
+/// Metadata key under which the nonces and lengths of encrypted files are stored, as JSON
+const ENCRYPTED_FILES_METADATA_KEY: &str = "encrypted_files";
+
+/// Byte range within a split file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ByteRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+impl ByteRange {
+    /// Number of bytes in the range
+    pub fn size(&self) -> u64 {
+        self.end - self.start
+    }
+    
+    /// Smallest range covering both ranges
+    fn union(self, other: ByteRange) -> ByteRange {
+        ByteRange {
+            start: self.start.min(other.start),
+            end: self.end.max(other.end),
+        }
+    }
+}
+
+/// Where a field's sections sit in the segment files, for warming them up
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FieldMetadata {
+    /// Range of the field's posting lists in the segment's `.idx` file
+    pub posting_range: Option<ByteRange>,
+    /// Range of the field's columns in the segment's `.fast` file
+    pub fast_field_range: Option<ByteRange>,
+    /// Range of the field's term dictionary in the segment's `.term` file
+    #[serde(default)]
+    pub term_dict_range: Option<ByteRange>,
+}
+
+/// Layout of the hotcache embedded in a split's store file footer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HotcacheFormat {
+    /// Our `HotcacheInfo` metadata
+    #[default]
+    Simple,
+    /// Quickwit's `StaticDirectoryCache`: the byte ranges of each split file
+    /// read when opening the index, so Quickwit can use its fast open path.
+    /// Requires the `quickwit-compat` feature.
+    Quickwit,
+}
+
+impl HotcacheFormat {
+    /// Whether this build can write and read the format
+    pub fn is_supported(&self) -> bool {
+        match self {
+            HotcacheFormat::Simple => true,
+            HotcacheFormat::Quickwit => cfg!(feature = "quickwit-compat"),
+        }
+    }
+}
+
+/// Builds a Quickwit hotcache by opening the Tantivy index in `split_dir`
+/// and recording the byte ranges read
+#[cfg(feature = "quickwit-compat")]
+pub fn build_quickwit_hotcache(split_dir: &Path) -> Result<Vec<u8>> {
+    let directory = MmapDirectory::open(split_dir)
+        .map_err(|e| SplitsError::InvalidOperation(format!("Failed to open split directory: {}", e)))?;
+    
+    let mut hotcache_bytes = Vec::new();
+    // Quickwit reports errors as anyhow errors
+    quickwit_directories::write_hotcache(directory, &mut hotcache_bytes)
+        .map_err(|e| SplitsError::InvalidOperation(format!("Failed to build Quickwit hotcache: {}", e)))?;
+    Ok(hotcache_bytes)
+}
+
+/// Parses a hotcache written by `build_quickwit_hotcache` or by Quickwit itself
+#[cfg(feature = "quickwit-compat")]
+pub fn parse_quickwit_hotcache(data: Vec<u8>) -> Result<StaticDirectoryCache> {
+    StaticDirectoryCache::open(OwnedBytes::new(data))
+        .map_err(|e| SplitsError::InvalidSplit(format!("Invalid Quickwit hotcache: {}", e)))
+}
+
+/// When and by what a split was produced
+///
+/// Fields are `None` for splits produced before provenance was recorded.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// Creation time in Unix milliseconds
+    pub created_at_millis: Option<i64>,
+    /// Version of this crate that produced the split
+    pub crate_version: Option<String>,
+    /// Tantivy version and index format the split was written with
+    pub tantivy_version: Option<String>,
+}
+
+impl Provenance {
+    /// Provenance of a split produced now, by this build
+    pub fn current() -> Self {
+        Self {
+            created_at_millis: Some(chrono::Utc::now().timestamp_millis()),
+            crate_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            tantivy_version: Some(tantivy::version_string().to_string()),
+        }
+    }
+}
 
 /// Simplified hotcache wrapper that can interface with Quickwit's implementations
 /// This is a thin adapter layer over Quickwit's native hotcache format
@@ -32,7 +171,8 @@ pub struct HotcacheInfo {
     pub size_bytes: u64,
     pub byte_range_start: u64,
     pub byte_range_end: u64,
-    pub metadata: HashMap<String, String>,
+    /// Extension entries, sorted so the serialized hotcache is reproducible
+    pub metadata: BTreeMap<String, String>,
 }
 
 impl HotcacheInfo {
@@ -44,7 +184,7 @@ impl HotcacheInfo {
             size_bytes,
             byte_range_start: 0,
             byte_range_end: 0,
-            metadata: HashMap::new(),
+            metadata: BTreeMap::new(),
         }
     }
 
@@ -59,20 +199,410 @@ impl HotcacheInfo {
         self.byte_range_end = end;
     }
 
+    /// Records the custom tokenizers the split was indexed with
+    pub fn set_tokenizers(&mut self, tokenizers: &BTreeMap<String, TokenizerConfig>) -> Result<()> {
+        if tokenizers.is_empty() {
+            self.metadata.remove(TOKENIZERS_METADATA_KEY);
+        } else {
+            self.metadata.insert(TOKENIZERS_METADATA_KEY.to_string(), serde_json::to_string(tokenizers)?);
+        }
+        Ok(())
+    }
+
+    /// Gets the custom tokenizers the split was indexed with
+    pub fn tokenizers(&self) -> Result<BTreeMap<String, TokenizerConfig>> {
+        match self.metadata.get(TOKENIZERS_METADATA_KEY) {
+            Some(json) => Ok(serde_json::from_str(json)?),
+            None => Ok(BTreeMap::new()),
+        }
+    }
+
+    /// Records when and by what the split was produced
+    pub fn set_provenance(&mut self, provenance: &Provenance) {
+        let entries = [
+            (CREATED_AT_MILLIS_METADATA_KEY, provenance.created_at_millis.map(|millis| millis.to_string())),
+            (CRATE_VERSION_METADATA_KEY, provenance.crate_version.clone()),
+            (TANTIVY_VERSION_METADATA_KEY, provenance.tantivy_version.clone()),
+        ];
+        for (key, value) in entries {
+            match value {
+                Some(value) => self.metadata.insert(key.to_string(), value),
+                None => self.metadata.remove(key),
+            };
+        }
+    }
+
+    /// Gets when and by what the split was produced
+    pub fn provenance(&self) -> Provenance {
+        Provenance {
+            created_at_millis: self.metadata.get(CREATED_AT_MILLIS_METADATA_KEY)
+                .and_then(|millis| millis.parse().ok()),
+            crate_version: self.metadata.get(CRATE_VERSION_METADATA_KEY).cloned(),
+            tantivy_version: self.metadata.get(TANTIVY_VERSION_METADATA_KEY).cloned(),
+        }
+    }
+
+    /// Records the opstamp of the source index's last commit
+    pub fn set_opstamp(&mut self, opstamp: u64) {
+        self.metadata.insert(OPSTAMP_METADATA_KEY.to_string(), opstamp.to_string());
+    }
+
+    /// Gets the opstamp of the source index's last commit, if recorded
+    pub fn opstamp(&self) -> Option<u64> {
+        self.metadata.get(OPSTAMP_METADATA_KEY)
+            .and_then(|opstamp| opstamp.parse().ok())
+    }
+
+    /// Records that the footer payload was aligned to `alignment` bytes
+    ///
+    /// `store_data_len` is where the store data ends, before the padding that
+    /// precedes the payload.
+    pub fn set_section_alignment(&mut self, alignment: u64, store_data_len: u64) {
+        self.metadata.insert(SECTION_ALIGNMENT_METADATA_KEY.to_string(), alignment.to_string());
+        self.set_store_data_len(store_data_len);
+    }
+
+    /// Records where the store data ends, ahead of any padding or footer
+    pub fn set_store_data_len(&mut self, store_data_len: u64) {
+        self.metadata.insert(STORE_DATA_LEN_METADATA_KEY.to_string(), store_data_len.to_string());
+    }
+
+    /// Gets the alignment of the footer payload, if it was aligned
+    pub fn section_alignment(&self) -> Option<u64> {
+        self.metadata.get(SECTION_ALIGNMENT_METADATA_KEY)
+            .and_then(|alignment| alignment.parse().ok())
+    }
+
+    /// Gets where the store data ends, if padding or a dual footer separates it from the legacy footer
+    pub fn store_data_len(&self) -> Option<u64> {
+        self.metadata.get(STORE_DATA_LEN_METADATA_KEY)
+            .and_then(|len| len.parse().ok())
+    }
+
+    /// Records the section ranges of the hotcached fields
+    pub fn set_field_metadata(&mut self, field_metadata: &BTreeMap<String, FieldMetadata>) -> Result<()> {
+        if field_metadata.is_empty() {
+            self.metadata.remove(FIELD_METADATA_METADATA_KEY);
+        } else {
+            self.metadata.insert(FIELD_METADATA_METADATA_KEY.to_string(), serde_json::to_string(field_metadata)?);
+        }
+        Ok(())
+    }
+
+    /// Gets the section ranges of the hotcached fields
+    ///
+    /// Fields without an entry weren't hotcached and are read from the full files.
+    pub fn field_metadata(&self) -> Result<BTreeMap<String, FieldMetadata>> {
+        match self.metadata.get(FIELD_METADATA_METADATA_KEY) {
+            Some(json) => Ok(serde_json::from_str(json)?),
+            None => Ok(BTreeMap::new()),
+        }
+    }
+
+    /// Records user-supplied key/value metadata
+    pub fn set_custom_metadata(&mut self, custom_metadata: &BTreeMap<String, String>) -> Result<()> {
+        if custom_metadata.is_empty() {
+            self.metadata.remove(CUSTOM_METADATA_METADATA_KEY);
+        } else {
+            self.metadata.insert(CUSTOM_METADATA_METADATA_KEY.to_string(), serde_json::to_string(custom_metadata)?);
+        }
+        Ok(())
+    }
+
+    /// Gets the user-supplied key/value metadata
+    pub fn custom_metadata(&self) -> Result<BTreeMap<String, String>> {
+        match self.metadata.get(CUSTOM_METADATA_METADATA_KEY) {
+            Some(json) => Ok(serde_json::from_str(json)?),
+            None => Ok(BTreeMap::new()),
+        }
+    }
+
+    /// Records how the split files were encrypted, by file name
+    pub fn set_encrypted_files(&mut self, encrypted_files: &BTreeMap<String, EncryptedFile>) -> Result<()> {
+        if encrypted_files.is_empty() {
+            self.metadata.remove(ENCRYPTED_FILES_METADATA_KEY);
+        } else {
+            self.metadata.insert(ENCRYPTED_FILES_METADATA_KEY.to_string(), serde_json::to_string(encrypted_files)?);
+        }
+        Ok(())
+    }
+
+    /// Gets how the split files were encrypted, empty for a split in cleartext
+    pub fn encrypted_files(&self) -> Result<BTreeMap<String, EncryptedFile>> {
+        match self.metadata.get(ENCRYPTED_FILES_METADATA_KEY) {
+            Some(json) => Ok(serde_json::from_str(json)?),
+            None => Ok(BTreeMap::new()),
+        }
+    }
+
     /// Serialize to bytes for storage
     pub fn to_bytes(&self) -> Result<Vec<u8>> {
         bincode::serialize(self)
-            .map_err(|e| crate::error::SplitsError::SerializationError(e.to_string()).into())
+            .map_err(|e| crate::error::SplitsError::Serialization(e.to_string()))
     }
 
     /// Deserialize from bytes
     pub fn from_bytes(data: &[u8]) -> Result<Self> {
         bincode::deserialize(data)
-            .map_err(|e| crate::error::SplitsError::SerializationError(e.to_string()).into())
+            .map_err(|e| crate::error::SplitsError::Serialization(e.to_string()))
     }
 }
 
 /// Create a hotcache from basic split information
 pub fn create_hotcache(split_id: String, num_docs: u32, size_bytes: u64) -> Result<HotcacheInfo> {
     Ok(HotcacheInfo::new(split_id, num_docs, size_bytes))
-}
\ No newline at end of file
+}
+/// Records the posting, term dictionary and fast field ranges of `fields` in a split's segment
+///
+/// Tantivy doesn't expose where a field's section starts in the segment
+/// files, so, like Quickwit's hotcache builder, the segment is opened through
+/// a directory logging the byte ranges read while each field's first and last
+/// posting lists and its columns are loaded. Term dictionaries are located
+/// from the `.term` file's own footer. The fast field range of a JSON field
+/// covers the columns of all its paths. Fields with neither postings nor fast
+/// columns get no entry.
+pub fn record_field_ranges(
+    split_dir: &Path,
+    segment_id: &SegmentId,
+    fields: &[String],
+) -> Result<BTreeMap<String, FieldMetadata>> {
+    let directory = RecordingDirectory {
+        inner: Box::new(MmapDirectory::open(split_dir)
+            .map_err(|e| SplitsError::InvalidOperation(format!("Failed to open split directory: {}", e)))?),
+        reads: Arc::default(),
+    };
+    let index = Index::open(directory.clone())?;
+    let segment_meta = index.searchable_segment_metas()?
+        .into_iter()
+        .find(|segment_meta| segment_meta.id() == *segment_id)
+        .ok_or_else(|| SplitsError::InvalidOperation("Segment not found in split".to_string()))?;
+    let postings_path = segment_meta.relative_path(SegmentComponent::Postings);
+    let terms_path = split_dir.join(segment_meta.relative_path(SegmentComponent::Terms));
+    let terms_file = std::fs::File::open(&terms_path)?;
+    let terms_len = terms_file.metadata()?.len();
+    let fast_fields_path = segment_meta.relative_path(SegmentComponent::FastFields);
+    
+    let searcher = index.reader()?.searcher();
+    let segment_reader = searcher.segment_readers().iter()
+        .find(|segment_reader| segment_reader.segment_id() == *segment_id)
+        .ok_or_else(|| SplitsError::InvalidOperation("Segment not found in searcher".to_string()))?;
+    let schema = index.schema();
+    
+    let mut field_metadata = BTreeMap::new();
+    for field_name in fields {
+        let field = schema.get_field(field_name)
+            .map_err(|_| SplitsError::FieldError(format!("Field '{}' not found", field_name)))?;
+        let field_entry = schema.get_field_entry(field);
+        let mut metadata = FieldMetadata::default();
+        
+        if field_entry.is_indexed() {
+            // Posting lists are laid out in term order, so the first and last span the section
+            directory.clear_reads();
+            let inverted_index = segment_reader.inverted_index(field)?;
+            let mut terms = inverted_index.terms().stream()?;
+            let mut first_and_last = None;
+            while terms.advance() {
+                let term_info = terms.value().clone();
+                first_and_last = match first_and_last {
+                    None => Some((term_info.clone(), term_info)),
+                    Some((first, _)) => Some((first, term_info)),
+                };
+            }
+            if let Some((first, last)) = first_and_last {
+                inverted_index.read_postings_from_terminfo(&first, IndexRecordOption::Basic)?;
+                inverted_index.read_postings_from_terminfo(&last, IndexRecordOption::Basic)?;
+                metadata.posting_range = directory.read_range(&postings_path);
+            }
+            
+            metadata.term_dict_range = term_dict_range(terms_len, field.field_id(), |range| {
+                let mut data = vec![0u8; range.size() as usize];
+                let mut terms_file = &terms_file;
+                terms_file.seek(SeekFrom::Start(range.start))?;
+                terms_file.read_exact(&mut data)?;
+                Ok(data)
+            })?;
+        }
+        
+        if field_entry.is_fast() {
+            directory.clear_reads();
+            let mut column_handles = segment_reader.fast_fields().dynamic_column_handles(field_name)?;
+            if matches!(field_entry.field_type(), FieldType::JsonObject(_)) {
+                // Each path of a JSON field is a column of its own, named after it
+                column_handles.extend(segment_reader.fast_fields().dynamic_subpath_column_handles(field_name)?);
+            }
+            for column_handle in column_handles {
+                column_handle.file_slice().read_bytes()?;
+            }
+            metadata.fast_field_range = directory.read_range(&fast_fields_path);
+        }
+        
+        if metadata != FieldMetadata::default() {
+            field_metadata.insert(field_name.clone(), metadata);
+        }
+    }
+    
+    Ok(field_metadata)
+}
+
+/// Magic number ending Tantivy's footer, which wraps every segment file
+const TANTIVY_FOOTER_MAGIC: u32 = 1337;
+
+/// Locates a field's term dictionary in a segment's `.term` file
+///
+/// The file is a Tantivy composite file: one section per indexed field, then
+/// a footer listing each section's start offset, field id and index, then the
+/// footer length as a little-endian u32, all followed by Tantivy's file
+/// footer. `read` reads a range of the `file_len`-byte file. Returns `None`
+/// if the field has no section.
+pub fn term_dict_range(
+    file_len: u64,
+    field_id: u32,
+    read: impl Fn(ByteRange) -> Result<Vec<u8>>,
+) -> Result<Option<ByteRange>> {
+    let invalid = || SplitsError::InvalidSplit("Malformed term dictionary file".to_string());
+    
+    // Tantivy's footer ends with the length of its JSON payload and the magic number
+    let trailer_start = file_len.checked_sub(8).ok_or_else(invalid)?;
+    let trailer = read(ByteRange { start: trailer_start, end: file_len })?;
+    let json_len = match (read_u32(&trailer, 0), read_u32(&trailer, 4)) {
+        (Some(json_len), Some(TANTIVY_FOOTER_MAGIC)) => json_len,
+        _ => return Err(invalid()),
+    };
+    let composite_end = trailer_start.checked_sub(u64::from(json_len))
+        .filter(|composite_end| *composite_end >= 4)
+        .ok_or_else(invalid)?;
+    
+    let footer_len = read_u32(&read(ByteRange { start: composite_end - 4, end: composite_end })?, 0)
+        .ok_or_else(invalid)?;
+    let footer_start = (composite_end - 4).checked_sub(u64::from(footer_len)).ok_or_else(invalid)?;
+    let footer = read(ByteRange { start: footer_start, end: composite_end - 4 })?;
+    
+    // Sections are listed by start offset, delta-encoded, each followed by its field id and index
+    let mut cursor = footer.as_slice();
+    let num_sections = read_vint(&mut cursor).ok_or_else(invalid)?;
+    let mut sections = Vec::new();
+    let mut offset = 0u64;
+    for _ in 0..num_sections {
+        offset = offset.checked_add(read_vint(&mut cursor).ok_or_else(invalid)?).ok_or_else(invalid)?;
+        let section_field_id = read_u32(cursor, 0).ok_or_else(invalid)?;
+        cursor = &cursor[4..];
+        let idx = read_vint(&mut cursor).ok_or_else(invalid)?;
+        sections.push((offset, section_field_id, idx));
+    }
+    
+    let position = match sections.iter().position(|(_, id, idx)| *id == field_id && *idx == 0) {
+        Some(position) => position,
+        None => return Ok(None),
+    };
+    let range = ByteRange {
+        start: sections[position].0,
+        end: sections.get(position + 1).map_or(footer_start, |(next_offset, _, _)| *next_offset),
+    };
+    if range.start > range.end || range.end > footer_start {
+        return Err(invalid());
+    }
+    Ok(Some(range))
+}
+
+/// Reads a little-endian u32 at `offset`, if in bounds
+fn read_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let bytes = bytes.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes(bytes.try_into().ok()?))
+}
+
+/// Reads a Tantivy VInt: 7 bits per byte, least significant first, the last byte flagged by its high bit
+fn read_vint(bytes: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for (i, byte) in bytes.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 != 0 {
+            *bytes = &bytes[i + 1..];
+            return Some(value);
+        }
+    }
+    None
+}
+
+/// Directory logging, per file, the range covering every read
+#[derive(Debug, Clone)]
+struct RecordingDirectory {
+    inner: Box<dyn Directory>,
+    reads: Arc<Mutex<HashMap<PathBuf, ByteRange>>>,
+}
+
+impl RecordingDirectory {
+    fn clear_reads(&self) {
+        self.reads.lock().unwrap_or_else(PoisonError::into_inner).clear();
+    }
+    
+    fn read_range(&self, path: &Path) -> Option<ByteRange> {
+        self.reads.lock().unwrap_or_else(PoisonError::into_inner).get(path).copied()
+    }
+}
+
+impl Directory for RecordingDirectory {
+    fn get_file_handle(&self, path: &Path) -> std::result::Result<Arc<dyn FileHandle>, OpenReadError> {
+        Ok(Arc::new(RecordingFileHandle {
+            inner: self.inner.get_file_handle(path)?,
+            path: path.to_path_buf(),
+            reads: self.reads.clone(),
+        }))
+    }
+    
+    fn delete(&self, path: &Path) -> std::result::Result<(), DeleteError> {
+        self.inner.delete(path)
+    }
+    
+    fn exists(&self, path: &Path) -> std::result::Result<bool, OpenReadError> {
+        self.inner.exists(path)
+    }
+    
+    fn open_write(&self, path: &Path) -> std::result::Result<WritePtr, OpenWriteError> {
+        self.inner.open_write(path)
+    }
+    
+    fn atomic_read(&self, path: &Path) -> std::result::Result<Vec<u8>, OpenReadError> {
+        self.inner.atomic_read(path)
+    }
+    
+    fn atomic_write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        self.inner.atomic_write(path, data)
+    }
+    
+    fn sync_directory(&self) -> io::Result<()> {
+        self.inner.sync_directory()
+    }
+    
+    fn watch(&self, watch_callback: WatchCallback) -> tantivy::Result<WatchHandle> {
+        self.inner.watch(watch_callback)
+    }
+    
+    // Recording only reads, so it needs no lock file, which would be left in the split
+    fn acquire_lock(&self, _lock: &Lock) -> std::result::Result<DirectoryLock, LockError> {
+        Ok(DirectoryLock::from(Box::new(())))
+    }
+}
+
+#[derive(Debug)]
+struct RecordingFileHandle {
+    inner: Arc<dyn FileHandle>,
+    path: PathBuf,
+    reads: Arc<Mutex<HashMap<PathBuf, ByteRange>>>,
+}
+
+impl HasLen for RecordingFileHandle {
+    fn len(&self) -> usize {
+        self.inner.len()
+    }
+}
+
+impl FileHandle for RecordingFileHandle {
+    fn read_bytes(&self, range: Range<usize>) -> io::Result<OwnedBytes> {
+        let read = ByteRange { start: range.start as u64, end: range.end as u64 };
+        self.reads.lock().unwrap_or_else(PoisonError::into_inner)
+            .entry(self.path.clone())
+            .and_modify(|covered| *covered = covered.union(read))
+            .or_insert(read);
+        self.inner.read_bytes(range)
+    }
+}
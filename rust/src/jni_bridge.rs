@@ -20,13 +20,29 @@
 //! JNI bridge implementation for Quickwit Splits4Java
 
 use crate::error::{SplitsError, error_to_exception_class};
-use crate::split_generator::{QuickwitSplitGenerator, SplitMetadata};
-use crate::split_reader::QuickwitSplitReader;
+use crate::hotcache::Provenance;
+use crate::range_read::ReadOptions;
+use crate::split_bytes::SchemaCompatibility;
+use crate::split_generator::{QuickwitSplitGenerator, SplitConfig, SplitMetadata, SplitPlan};
+use crate::split_reader::{CorpusStats, FastFieldFormat, FieldDescriptor, QuickwitSplitReader};
+use crate::streaming::COPY_BUFFER_SIZE;
+use crate::streaming_builder::StreamingSplitBuilder;
+use crate::tokenizers::TokenizerConfig;
 use crate::{register_generator, unregister_generator, register_reader, unregister_reader};
-use jni::JNIEnv;
-use jni::objects::{JClass, JString, JValue};
-use jni::sys::{jlong, jint, jobject, jintArray, jbyteArray, jstring};
-use std::path::Path;
+use crate::{register_posting_cursor, unregister_posting_cursor};
+use crate::{register_streaming_builder, unregister_streaming_builder};
+use crate::{check_handle, HandleKind, Registered};
+use crate::{get_generator, get_posting_cursor, get_reader, get_streaming_builder};
+use crate::{generator_progress, handle_created_at, reap_stale_handles, registry_sizes};
+use crate::jvm::{set_java_vm, with_attached_env};
+use jni::{JNIEnv, JavaVM};
+use jni::objects::{JClass, JObject, JString, JValue};
+use jni::sys::{jboolean, jlong, jint, jobject, jobjectArray, jintArray, jlongArray, jbyteArray, jstring, JNI_FALSE, JNI_TRUE, JNI_VERSION_1_8};
+use std::ffi::c_void;
+use std::fs;
+use std::io::BufWriter;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, MutexGuard};
 use tantivy::{Index, schema::SchemaBuilder, doc};
 
 // ===================================================================================
@@ -34,10 +50,29 @@ use tantivy::{Index, schema::SchemaBuilder, doc};
 // ===================================================================================
 
 /// Safely convert JString to Rust String
-fn jstring_to_string(env: &JNIEnv, jstr: JString) -> Result<String, SplitsError> {
-    env.get_string(jstr)
-        .map(|s| s.into())
-        .map_err(|e| SplitsError::Jni(format!("Failed to convert JString: {}", e)))
+///
+/// `arg_name` identifies the argument in error messages. Strings that can't be
+/// represented as UTF-8 (e.g. containing unpaired surrogates) are rejected
+/// rather than lossily converted into a corrupt path or field name.
+fn jstring_to_string(env: &JNIEnv, jstr: JString, arg_name: &str) -> Result<String, SplitsError> {
+    if jstr.is_null() {
+        return Err(SplitsError::Jni(format!("String argument '{}' is null", arg_name)));
+    }
+    
+    let java_str = env.get_string(jstr)
+        .map_err(|e| SplitsError::Jni(format!("Failed to read string argument '{}': {}", arg_name, e)))?;
+    
+    decode_java_string(java_str.to_bytes(), arg_name)
+}
+
+/// Decode JVM modified UTF-8 into a Rust String, failing on invalid content
+fn decode_java_string(bytes: &[u8], arg_name: &str) -> Result<String, SplitsError> {
+    cesu8::from_java_cesu8(bytes)
+        .map(|s| s.into_owned())
+        .map_err(|_| SplitsError::Jni(format!(
+            "String argument '{}' is not valid UTF-8 (it may contain unpaired surrogates)",
+            arg_name
+        )))
 }
 
 /// Throw Java exception with the given error
@@ -46,8 +81,190 @@ fn throw_exception(env: &JNIEnv, error: &SplitsError) {
     let message = error.to_string();
     
     if let Err(e) = env.throw_new(exception_class, &message) {
-        eprintln!("Failed to throw Java exception: {}", e);
+        log::error!("Failed to throw Java exception '{}': {}", message, e);
+    }
+}
+
+/// Lock a registered object, mapping a poisoned lock to an error
+fn lock_registered<'a, T>(object: &'a Registered<T>, kind: &str) -> Result<MutexGuard<'a, T>, SplitsError> {
+    object.lock()
+        .map_err(|e| SplitsError::InvalidOperation(format!("Failed to access {}: {}", kind, e)))
+}
+
+/// Run `f` against the split reader registered under `handle`
+fn with_reader<T>(
+    handle: jlong,
+    f: impl FnOnce(&QuickwitSplitReader) -> Result<T, SplitsError>,
+) -> Result<T, SplitsError> {
+    check_handle(handle, HandleKind::Reader)?;
+    
+    let reader = get_reader(handle)?;
+    let reader = lock_registered(&reader, "reader")?;
+    f(&reader)
+}
+
+/// Run `f` against the two split readers registered under `handle` and `other_handle`
+///
+/// Readers are locked in handle order, so that calls on the same pair
+/// passed the other way round can't deadlock, and once when both handles
+/// are the same.
+fn with_reader_pair<T>(
+    handle: jlong,
+    other_handle: jlong,
+    f: impl FnOnce(&QuickwitSplitReader, &QuickwitSplitReader) -> Result<T, SplitsError>,
+) -> Result<T, SplitsError> {
+    check_handle(handle, HandleKind::Reader)?;
+    check_handle(other_handle, HandleKind::Reader)?;
+    
+    let reader = get_reader(handle)?;
+    let other = get_reader(other_handle)?;
+    if Arc::ptr_eq(&reader, &other) {
+        let reader = lock_registered(&reader, "reader")?;
+        return f(&reader, &reader);
+    }
+    
+    if handle < other_handle {
+        let reader = lock_registered(&reader, "reader")?;
+        let other = lock_registered(&other, "reader")?;
+        f(&reader, &other)
+    } else {
+        let other = lock_registered(&other, "reader")?;
+        let reader = lock_registered(&reader, "reader")?;
+        f(&reader, &other)
+    }
+}
+
+/// Run `f` against the split generator registered under `handle`, with mutable access
+fn with_generator_mut<T>(
+    handle: jlong,
+    f: impl FnOnce(&mut QuickwitSplitGenerator) -> Result<T, SplitsError>,
+) -> Result<T, SplitsError> {
+    check_handle(handle, HandleKind::Generator)?;
+    
+    let generator = get_generator(handle)?;
+    let mut generator = lock_registered(&generator, "generator")?;
+    f(&mut generator)
+}
+
+/// Run `f` against the split reader registered under `handle`, with mutable access
+fn with_reader_mut<T>(
+    handle: jlong,
+    f: impl FnOnce(&mut QuickwitSplitReader) -> Result<T, SplitsError>,
+) -> Result<T, SplitsError> {
+    check_handle(handle, HandleKind::Reader)?;
+    
+    let reader = get_reader(handle)?;
+    let mut reader = lock_registered(&reader, "reader")?;
+    f(&mut reader)
+}
+
+/// Run `f` against the streaming split builder registered under `handle`
+fn with_streaming_builder_mut<T>(
+    handle: jlong,
+    f: impl FnOnce(&mut StreamingSplitBuilder) -> Result<T, SplitsError>,
+) -> Result<T, SplitsError> {
+    check_handle(handle, HandleKind::StreamingBuilder)?;
+    
+    let builder = get_streaming_builder(handle)?;
+    let mut builder = lock_registered(&builder, "streaming builder")?;
+    f(&mut builder)
+}
+
+/// Create a Java list of split metadata objects
+fn new_split_metadata_list(env: &JNIEnv, splits: &[SplitMetadata]) -> Result<jobject, SplitsError> {
+    let list = new_java_array_list(env)?;
+    for metadata in splits {
+        let element = create_split_metadata_object(env, metadata)?;
+        java_list_add(env, list, JObject::from(element))?;
+    }
+    Ok(list.into_inner())
+}
+
+/// Create a Java string, mapping failures to a SplitsError
+fn new_java_string(env: &JNIEnv, value: &str) -> Result<jstring, SplitsError> {
+    env.new_string(value)
+        .map(|s| s.into_inner())
+        .map_err(|e| SplitsError::Jni(format!("Failed to create string: {}", e)))
+}
+
+/// Convert a Java String[] into a Vec of Rust Strings
+fn jstring_array_to_vec(env: &JNIEnv, array: jobjectArray, arg_name: &str) -> Result<Vec<String>, SplitsError> {
+    let length = env.get_array_length(array)
+        .map_err(|e| SplitsError::Jni(format!("Failed to get array length: {}", e)))?;
+    
+    let mut values = Vec::with_capacity(length as usize);
+    for i in 0..length {
+        let element = env.get_object_array_element(array, i)
+            .map_err(|e| SplitsError::Jni(format!("Failed to get array element {}: {}", i, e)))?;
+        values.push(jstring_to_string(env, JString::from(element), &format!("{}[{}]", arg_name, i))?);
     }
+    
+    Ok(values)
+}
+
+/// Copy a Java byte[][] into a Vec of byte vectors
+fn jbyte_array_array_to_vec(env: &JNIEnv, array: jobjectArray) -> Result<Vec<Vec<u8>>, SplitsError> {
+    let length = env.get_array_length(array)
+        .map_err(|e| SplitsError::Jni(format!("Failed to get array length: {}", e)))?;
+    
+    let mut values = Vec::with_capacity(length as usize);
+    for i in 0..length {
+        let element = env.get_object_array_element(array, i)
+            .map_err(|e| SplitsError::Jni(format!("Failed to get array element {}: {}", i, e)))?;
+        let bytes = env.convert_byte_array(element.into_inner())
+            .map_err(|e| SplitsError::Jni(format!("Failed to read byte array {}: {}", i, e)))?;
+        values.push(bytes);
+    }
+    
+    Ok(values)
+}
+
+/// Copy a Java int[] of document IDs into a Vec, rejecting negative IDs
+fn jint_array_to_doc_ids(env: &JNIEnv, array: jintArray) -> Result<Vec<u32>, SplitsError> {
+    let length = env.get_array_length(array)
+        .map_err(|e| SplitsError::Jni(format!("Failed to get array length: {}", e)))?;
+    
+    let mut values = vec![0; length as usize];
+    env.get_int_array_region(array, 0, &mut values)
+        .map_err(|e| SplitsError::Jni(format!("Failed to read int array: {}", e)))?;
+    
+    values.into_iter()
+        .map(|doc_id| u32::try_from(doc_id).map_err(|_| SplitsError::InvalidOperation(
+            format!("Document ID cannot be negative: {}", doc_id)
+        )))
+        .collect()
+}
+
+/// Create an empty java.util.HashMap
+fn new_java_hash_map<'a>(env: &JNIEnv<'a>) -> Result<JObject<'a>, SplitsError> {
+    env.new_object("java/util/HashMap", "()V", &[])
+        .map_err(|e| SplitsError::Jni(format!("Failed to create HashMap: {}", e)))
+}
+
+/// Put a key/value pair into a java.util.Map
+fn java_map_put(env: &JNIEnv, map: JObject, key: JObject, value: JObject) -> Result<(), SplitsError> {
+    env.call_method(
+        map,
+        "put",
+        "(Ljava/lang/Object;Ljava/lang/Object;)Ljava/lang/Object;",
+        &[JValue::Object(key), JValue::Object(value)],
+    ).map_err(|e| SplitsError::Jni(format!("Failed to put map entry: {}", e)))?;
+    
+    Ok(())
+}
+
+/// Create an empty java.util.ArrayList
+fn new_java_array_list<'a>(env: &JNIEnv<'a>) -> Result<JObject<'a>, SplitsError> {
+    env.new_object("java/util/ArrayList", "()V", &[])
+        .map_err(|e| SplitsError::Jni(format!("Failed to create ArrayList: {}", e)))
+}
+
+/// Append an element to a java.util.List
+fn java_list_add(env: &JNIEnv, list: JObject, element: JObject) -> Result<(), SplitsError> {
+    env.call_method(list, "add", "(Ljava/lang/Object;)Z", &[JValue::Object(element)])
+        .map_err(|e| SplitsError::Jni(format!("Failed to add list element: {}", e)))?;
+    
+    Ok(())
 }
 
 /// Create a Java SplitMetadata object from Rust SplitMetadata
@@ -77,11 +294,16 @@ fn create_split_metadata_object(env: &JNIEnv, metadata: &SplitMetadata) -> Resul
     let split_id = env.new_string(&metadata.split_id)
         .map_err(|e| SplitsError::Jni(format!("Failed to create split ID string: {}", e)))?;
     
+    let warnings = new_java_array_list(env)?;
+    for warning in &metadata.warnings {
+        java_list_add(env, warnings, JObject::from(new_java_string(env, warning)?))?;
+    }
+    
     // Find SplitMetadata constructor
     let constructor = env.get_method_id(
         class, 
         "<init>", 
-        "(Ljava/lang/String;IJLcom/tantivy4java/splits/ByteRange;)V"
+        "(Ljava/lang/String;IJLcom/tantivy4java/splits/ByteRange;Ljava/util/List;)V"
     ).map_err(|e| SplitsError::Jni(format!("Failed to find SplitMetadata constructor: {}", e)))?;
     
     // Create SplitMetadata object
@@ -93,14 +315,51 @@ fn create_split_metadata_object(env: &JNIEnv, metadata: &SplitMetadata) -> Resul
             JValue::Int(metadata.num_docs as i32),
             JValue::Long(metadata.size_bytes as i64),
             JValue::Object(hotcache_range),
+            JValue::Object(warnings),
         ]
     ).map_err(|e| SplitsError::Jni(format!("Failed to create SplitMetadata object: {}", e)))?;
     
     Ok(split_metadata.into_inner())
 }
 
-/// Create a Java HotcacheInfo object from Rust Hotcache
-fn create_hotcache_info_object(env: &JNIEnv, hotcache: &crate::hotcache::Hotcache) -> Result<jobject, SplitsError> {
+/// Create a Java FieldDescriptor object from a Rust FieldDescriptor
+fn create_field_descriptor_object(env: &JNIEnv, field: &FieldDescriptor) -> Result<jobject, SplitsError> {
+    let type_name = env.new_string(field.field_type.name())
+        .map_err(|e| SplitsError::Jni(format!("Failed to create type name string: {}", e)))?;
+    let field_type = env.call_static_method(
+        "com/tantivy4java/splits/FieldDescriptor$Type",
+        "fromName",
+        "(Ljava/lang/String;)Lcom/tantivy4java/splits/FieldDescriptor$Type;",
+        &[JValue::Object(type_name.into())],
+    ).and_then(|value| value.l())
+        .map_err(|e| SplitsError::Jni(format!("Failed to resolve field type: {}", e)))?;
+    
+    let name = env.new_string(&field.name)
+        .map_err(|e| SplitsError::Jni(format!("Failed to create field name string: {}", e)))?;
+    
+    let json_paths = new_java_array_list(env)?;
+    for path in &field.json_paths {
+        java_list_add(env, json_paths, JObject::from(new_java_string(env, path)?))?;
+    }
+    
+    let descriptor = env.new_object(
+        "com/tantivy4java/splits/FieldDescriptor",
+        "(Ljava/lang/String;Lcom/tantivy4java/splits/FieldDescriptor$Type;ZZZLjava/util/List;)V",
+        &[
+            JValue::Object(name.into()),
+            JValue::Object(field_type),
+            JValue::Bool(field.stored as u8),
+            JValue::Bool(field.indexed as u8),
+            JValue::Bool(field.fast as u8),
+            JValue::Object(json_paths),
+        ]
+    ).map_err(|e| SplitsError::Jni(format!("Failed to create FieldDescriptor object: {}", e)))?;
+    
+    Ok(descriptor.into_inner())
+}
+
+/// Create a Java HotcacheInfo object from Rust HotcacheInfo
+fn create_hotcache_info_object(env: &JNIEnv, hotcache: &crate::hotcache::HotcacheInfo) -> Result<jobject, SplitsError> {
     // This is a simplified implementation
     // In a complete implementation, this would create the full HotcacheInfo object
     // with all field metadata
@@ -113,7 +372,8 @@ fn create_hotcache_info_object(env: &JNIEnv, hotcache: &crate::hotcache::Hotcach
     let split_id = env.new_string(&hotcache.split_id)
         .map_err(|e| SplitsError::Jni(format!("Failed to create split ID string: {}", e)))?;
     
-    let schema_hash = env.new_string(&hotcache.schema_hash)
+    // The hotcache doesn't record a schema hash
+    let schema_hash = env.new_string("")
         .map_err(|e| SplitsError::Jni(format!("Failed to create schema hash string: {}", e)))?;
     
     // Create empty HashMap for fields (simplified)
@@ -148,6 +408,95 @@ fn create_hotcache_info_object(env: &JNIEnv, hotcache: &crate::hotcache::Hotcach
     Ok(hotcache_info.into_inner())
 }
 
+/// Create a Java CorpusStats object from Rust CorpusStats
+fn create_corpus_stats_object(env: &JNIEnv, stats: &CorpusStats) -> Result<jobject, SplitsError> {
+    let corpus_stats = env.new_object(
+        "com/tantivy4java/splits/CorpusStats",
+        "(JJJJ)V",
+        &[
+            JValue::Long(stats.total_docs as i64),
+            JValue::Long(stats.docs_with_field as i64),
+            JValue::Long(stats.total_tokens as i64),
+            JValue::Long(stats.unique_terms as i64),
+        ]
+    ).map_err(|e| SplitsError::Jni(format!("Failed to create CorpusStats object: {}", e)))?;
+    
+    Ok(corpus_stats.into_inner())
+}
+
+/// Create a Java SplitPlan object from Rust SplitPlan
+fn create_split_plan_object(env: &JNIEnv, plan: &SplitPlan) -> Result<jobject, SplitsError> {
+    let split_plan = env.new_object(
+        "com/tantivy4java/splits/SplitPlan",
+        "(JIJJ)V",
+        &[
+            JValue::Long(plan.num_docs as i64),
+            JValue::Int(plan.source_segments as jint),
+            JValue::Long(plan.min_size_bytes as i64),
+            JValue::Long(plan.max_size_bytes as i64),
+        ]
+    ).map_err(|e| SplitsError::Jni(format!("Failed to create SplitPlan object: {}", e)))?;
+    
+    Ok(split_plan.into_inner())
+}
+
+/// Create a Java BatchResult object from the result of one input of a batch generation
+fn create_batch_result_object(
+    env: &JNIEnv,
+    index_path: &Path,
+    output_path: &Path,
+    result: &Result<SplitMetadata, SplitsError>,
+) -> Result<jobject, SplitsError> {
+    let (metadata, error) = match result {
+        Ok(metadata) => (JObject::from(create_split_metadata_object(env, metadata)?), JObject::null()),
+        Err(e) => (JObject::null(), JObject::from(new_java_string(env, &e.to_string())?)),
+    };
+    let index_path = new_java_string(env, &index_path.to_string_lossy())?;
+    let output_path = new_java_string(env, &output_path.to_string_lossy())?;
+    
+    let batch_result = env.new_object(
+        "com/tantivy4java/splits/BatchResult",
+        "(Ljava/lang/String;Ljava/lang/String;Lcom/tantivy4java/splits/SplitMetadata;Ljava/lang/String;)V",
+        &[
+            JValue::Object(JObject::from(index_path)),
+            JValue::Object(JObject::from(output_path)),
+            JValue::Object(metadata),
+            JValue::Object(error),
+        ]
+    ).map_err(|e| SplitsError::Jni(format!("Failed to create BatchResult object: {}", e)))?;
+    
+    Ok(batch_result.into_inner())
+}
+
+/// Create a Java Provenance object from Rust Provenance
+fn create_provenance_object(env: &JNIEnv, provenance: &Provenance) -> Result<jobject, SplitsError> {
+    let created_at_millis = match provenance.created_at_millis {
+        Some(millis) => env.new_object("java/lang/Long", "(J)V", &[JValue::Long(millis)])
+            .map_err(|e| SplitsError::Jni(format!("Failed to create Long: {}", e)))?,
+        None => JObject::null(),
+    };
+    let crate_version = match &provenance.crate_version {
+        Some(version) => JObject::from(new_java_string(env, version)?),
+        None => JObject::null(),
+    };
+    let tantivy_version = match &provenance.tantivy_version {
+        Some(version) => JObject::from(new_java_string(env, version)?),
+        None => JObject::null(),
+    };
+    
+    let provenance_object = env.new_object(
+        "com/tantivy4java/splits/Provenance",
+        "(Ljava/lang/Long;Ljava/lang/String;Ljava/lang/String;)V",
+        &[
+            JValue::Object(created_at_millis),
+            JValue::Object(crate_version),
+            JValue::Object(tantivy_version),
+        ]
+    ).map_err(|e| SplitsError::Jni(format!("Failed to create Provenance object: {}", e)))?;
+    
+    Ok(provenance_object.into_inner())
+}
+
 /// Creates a minimal mock index for testing (when real tantivy4java integration isn't available)
 fn create_mock_index() -> Result<Index, tantivy::TantivyError> {
     let mut schema_builder = SchemaBuilder::default();
@@ -215,6 +564,50 @@ pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitGenerator_creat
     }
 }
 
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitGenerator_createFromPathNative(
+    env: JNIEnv,
+    _class: JClass,
+    index_path: JString,
+    target_docs_per_split: jint,
+) -> jlong {
+    if target_docs_per_split <= 0 {
+        throw_exception(&env, &SplitsError::InvalidOperation(
+            "Target docs per split must be positive".to_string()
+        ));
+        return 0;
+    }
+    
+    match jstring_to_string(&env, index_path, "indexPath")
+        .and_then(|index_path| QuickwitSplitGenerator::open_in_dir(Path::new(&index_path), target_docs_per_split as usize))
+    {
+        Ok(generator) => register_generator(generator),
+        Err(e) => {
+            throw_exception(&env, &e);
+            0
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitGenerator_configureNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    config_json: JString,
+) {
+    let result = jstring_to_string(&env, config_json, "configJson")
+        .and_then(|json| SplitConfig::from_json(&json))
+        .and_then(|config| with_generator_mut(handle, |generator| {
+            generator.set_config(config);
+            Ok(())
+        }));
+    
+    if let Err(e) = result {
+        throw_exception(&env, &e);
+    }
+}
+
 #[no_mangle]
 pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitGenerator_generateSplitNative(
     env: JNIEnv,
@@ -222,7 +615,7 @@ pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitGenerator_gener
     handle: jlong,
     output_path: JString,
 ) -> jobject {
-    let path_str = match jstring_to_string(&env, output_path) {
+    let path_str = match jstring_to_string(&env, output_path, "outputPath") {
         Ok(s) => s,
         Err(e) => {
             throw_exception(&env, &e);
@@ -232,38 +625,252 @@ pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitGenerator_gener
     
     let path = Path::new(&path_str);
     
-    // Get the generator from the registry
-    let mut registry = match crate::GENERATOR_REGISTRY.lock() {
-        Ok(registry) => registry,
+    // Generate the split
+    let metadata = match with_generator_mut(handle, |generator| generator.generate_split(path)) {
+        Ok(metadata) => metadata,
         Err(e) => {
-            throw_exception(&env, &SplitsError::InvalidOperation(
-                format!("Failed to access generator registry: {}", e)
-            ));
+            throw_exception(&env, &e);
             return std::ptr::null_mut();
         }
     };
     
-    let generator = match registry.get_mut(&handle) {
-        Some(gen) => gen,
-        None => {
-            throw_exception(&env, &SplitsError::InvalidOperation(
-                "Invalid generator handle".to_string()
-            ));
-            return std::ptr::null_mut();
+    match create_split_metadata_object(&env, &metadata) {
+        Ok(obj) => obj,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
         }
-    };
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitGenerator_generatePartitionedSplitsNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    partition_field: JString,
+    output_dir: JString,
+) -> jobject {
+    let result = jstring_to_string(&env, partition_field, "partitionField")
+        .and_then(|field| Ok((field, jstring_to_string(&env, output_dir, "outputDir")?)))
+        .and_then(|(field, output_dir)| with_generator_mut(handle, |generator| {
+            generator.generate_partitioned_splits(&field, Path::new(&output_dir))
+        }))
+        .and_then(|splits| {
+            let list = new_java_array_list(&env)?;
+            for metadata in &splits {
+                let element = create_split_metadata_object(&env, metadata)?;
+                java_list_add(&env, list, JObject::from(element))?;
+            }
+            Ok(list.into_inner())
+        });
     
-    // Generate the split
-    let metadata = match generator.generate_split(path) {
-        Ok(metadata) => metadata,
+    match result {
+        Ok(list) => list,
         Err(e) => {
             throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitGenerator_generateSplitStreamingNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    sink: JObject,
+) -> jobject {
+    // Held globally, as chunks may be uploaded from any thread
+    let sink = match env.new_global_ref(sink) {
+        Ok(sink) => sink,
+        Err(e) => {
+            throw_exception(&env, &SplitsError::Jni(format!("Failed to retain upload sink: {}", e)));
             return std::ptr::null_mut();
         }
     };
+    let upload = |chunk: &[u8], offset: u64| -> Result<(), SplitsError> {
+        with_attached_env(|env| {
+            let array = env.byte_array_from_slice(chunk)
+                .map_err(|e| SplitsError::Jni(format!("Failed to create byte array: {}", e)))?;
+            let result = env.call_method(
+                sink.as_obj(),
+                "upload",
+                "([BJ)V",
+                &[JValue::Object(JObject::from(array)), JValue::Long(offset as jlong)],
+            );
+            let _ = env.delete_local_ref(JObject::from(array));
+            result.map(|_| ())
+                .map_err(|e| SplitsError::Jni(format!("Upload sink failed at offset {}: {}", offset, e)))
+        })
+    };
     
-    match create_split_metadata_object(&env, &metadata) {
+    // Generated under the generator's lock, then streamed once it's released,
+    // so a slow sink doesn't hold up other calls on the generator
+    let result = with_generator_mut(handle, |generator| generator.stage_split())
+        .and_then(|staged| staged.stream(&upload))
+        .and_then(|metadata| create_split_metadata_object(&env, &metadata));
+    
+    match result {
         Ok(obj) => obj,
+        Err(e) => {
+            // An exception thrown by the sink is left pending for the caller, as is
+            if !env.exception_check().unwrap_or(false) {
+                throw_exception(&env, &e);
+            }
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitGenerator_signSplitNative(
+    env: JNIEnv,
+    _class: JClass,
+    split_path: JString,
+    key: jbyteArray,
+) {
+    let result = jstring_to_string(&env, split_path, "splitPath")
+        .and_then(|split_path| {
+            let key = env.convert_byte_array(key)
+                .map_err(|e| SplitsError::Jni(format!("Failed to read signing key: {}", e)))?;
+            crate::signing::sign_split(Path::new(&split_path), &key)
+        });
+    
+    if let Err(e) = result {
+        throw_exception(&env, &e);
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitGenerator_generateBatchNative(
+    env: JNIEnv,
+    _class: JClass,
+    index_paths: jobjectArray,
+    output_paths: jobjectArray,
+    config_json: JString,
+) -> jobject {
+    let result = jstring_array_to_vec(&env, index_paths, "indexPaths")
+        .and_then(|index_paths| Ok((index_paths, jstring_array_to_vec(&env, output_paths, "outputPaths")?)))
+        .and_then(|(index_paths, output_paths)| {
+            if index_paths.len() != output_paths.len() {
+                return Err(SplitsError::InvalidOperation(format!(
+                    "Got {} index paths but {} output paths", index_paths.len(), output_paths.len()
+                )));
+            }
+            let config = jstring_to_string(&env, config_json, "configJson")
+                .and_then(|json| SplitConfig::from_json(&json))?;
+            let inputs: Vec<(PathBuf, PathBuf)> = index_paths.into_iter()
+                .map(PathBuf::from)
+                .zip(output_paths.into_iter().map(PathBuf::from))
+                .collect();
+            let results = QuickwitSplitGenerator::generate_batch(&inputs, &config);
+            
+            let list = new_java_array_list(&env)?;
+            for ((index_path, output_path), result) in inputs.iter().zip(&results) {
+                let element = create_batch_result_object(&env, index_path, output_path, result)?;
+                java_list_add(&env, list, JObject::from(element))?;
+            }
+            Ok(list.into_inner())
+        });
+    
+    match result {
+        Ok(list) => list,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitGenerator_updateMetadataNative(
+    env: JNIEnv,
+    _class: JClass,
+    split_path: JString,
+    keys: jobjectArray,
+    values: jobjectArray,
+) -> jobject {
+    let result = jstring_to_string(&env, split_path, "splitPath")
+        .and_then(|split_path| {
+            let keys = jstring_array_to_vec(&env, keys, "keys")?;
+            let values = jstring_array_to_vec(&env, values, "values")?;
+            if keys.len() != values.len() {
+                return Err(SplitsError::InvalidOperation(format!(
+                    "Got {} metadata keys but {} values", keys.len(), values.len()
+                )));
+            }
+            let updates = keys.into_iter().zip(values).collect();
+            QuickwitSplitGenerator::update_metadata(Path::new(&split_path), updates)
+        })
+        .and_then(|metadata| create_split_metadata_object(&env, &metadata));
+    
+    match result {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitGenerator_refreshNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    if let Err(e) = with_generator_mut(handle, |generator| generator.refresh()) {
+        throw_exception(&env, &e);
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitGenerator_getProgressNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jint {
+    // Read without the generator registry, which a running generation holds
+    let result = check_handle(handle, HandleKind::Generator).and_then(|_| {
+        generator_progress(handle)
+            .ok_or_else(|| SplitsError::InvalidOperation("Invalid generator handle".to_string()))
+    });
+    
+    match result {
+        Ok(percent) => percent as jint,
+        Err(e) => {
+            throw_exception(&env, &e);
+            0
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitGenerator_sourceSegmentCountNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jint {
+    match with_generator_mut(handle, |generator| generator.source_segment_count()) {
+        Ok(count) => count as jint,
+        Err(e) => {
+            throw_exception(&env, &e);
+            0
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitGenerator_planSplitNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jobject {
+    match with_generator_mut(handle, |generator| generator.plan_split())
+        .and_then(|plan| create_split_plan_object(&env, &plan))
+    {
+        Ok(plan) => plan,
         Err(e) => {
             throw_exception(&env, &e);
             std::ptr::null_mut()
@@ -277,7 +884,11 @@ pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitGenerator_destr
     _class: JClass,
     handle: jlong,
 ) {
-    // In a real implementation, this would remove the generator from the registry
+    if let Err(e) = check_handle(handle, HandleKind::Generator) {
+        throw_exception(&env, &e);
+        return;
+    }
+    
     unregister_generator(handle);
 }
 
@@ -291,7 +902,7 @@ pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_openNati
     _class: JClass,
     split_path: JString,
 ) -> jlong {
-    let path_str = match jstring_to_string(&env, split_path) {
+    let path_str = match jstring_to_string(&env, split_path, "splitPath") {
         Ok(s) => s,
         Err(e) => {
             throw_exception(&env, &e);
@@ -320,33 +931,16 @@ pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_getHotca
     _class: JClass,
     handle: jlong,
 ) -> jobject {
-    // Get the reader from the registry
-    let registry = match crate::READER_REGISTRY.lock() {
-        Ok(registry) => registry,
-        Err(e) => {
-            throw_exception(&env, &SplitsError::InvalidOperation(
-                format!("Failed to access reader registry: {}", e)
-            ));
-            return std::ptr::null_mut();
-        }
-    };
-    
-    let reader = match registry.get(&handle) {
-        Some(reader) => reader,
-        None => {
-            throw_exception(&env, &SplitsError::InvalidOperation(
-                "Invalid reader handle".to_string()
-            ));
-            return std::ptr::null_mut();
-        }
-    };
-    
     // Get hotcache info
-    let hotcache = match reader.get_hotcache_info() {
-        Some(hotcache) => hotcache,
-        None => {
+    let hotcache = match with_reader(handle, |reader| Ok(reader.get_hotcache_info().cloned())) {
+        Ok(Some(hotcache)) => hotcache,
+        Ok(None) => {
             return std::ptr::null_mut(); // Return null if no hotcache available
         }
+        Err(e) => {
+            throw_exception(&env, &e);
+            return std::ptr::null_mut();
+        }
     };
     
     match create_hotcache_info_object(&env, &hotcache) {
@@ -438,7 +1032,7 @@ pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_readPost
     field: JString,
     term: JString,
 ) -> jintArray {
-    let field_str = match jstring_to_string(&env, field) {
+    let field_str = match jstring_to_string(&env, field, "field") {
         Ok(s) => s,
         Err(e) => {
             throw_exception(&env, &e);
@@ -446,7 +1040,7 @@ pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_readPost
         }
     };
     
-    let term_str = match jstring_to_string(&env, term) {
+    let term_str = match jstring_to_string(&env, term, "term") {
         Ok(s) => s,
         Err(e) => {
             throw_exception(&env, &e);
@@ -477,39 +1071,1284 @@ pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_readPost
     }
 }
 
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_openPostingCursorNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    field: JString,
+    term: JString,
+) -> jlong {
+    let result = jstring_to_string(&env, field, "field")
+        .and_then(|field| Ok((field, jstring_to_string(&env, term, "term")?)))
+        .and_then(|(field, term)| with_reader(handle, |reader| reader.posting_cursor(&field, &term)));
+    
+    match result {
+        Ok(cursor) => register_posting_cursor(cursor),
+        Err(e) => {
+            throw_exception(&env, &e);
+            0
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_PostingCursor_nextBatchNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    max_docs: jint,
+) -> jintArray {
+    if max_docs <= 0 {
+        throw_exception(&env, &SplitsError::InvalidOperation(
+            "Batch size must be positive".to_string()
+        ));
+        return std::ptr::null_mut();
+    }
+    
+    let batch = check_handle(handle, HandleKind::PostingCursor)
+        .and_then(|_| get_posting_cursor(handle))
+        .and_then(|cursor| lock_registered(&cursor, "posting cursor")?.next_batch(max_docs as usize));
+    
+    let doc_ids: Vec<i32> = match batch {
+        Ok(batch) => batch.into_iter().map(|doc| doc as i32).collect(),
+        Err(e) => {
+            throw_exception(&env, &e);
+            return std::ptr::null_mut();
+        }
+    };
+    
+    match env.new_int_array(doc_ids.len() as i32) {
+        Ok(array) => {
+            if let Err(e) = env.set_int_array_region(array, 0, &doc_ids) {
+                throw_exception(&env, &SplitsError::Jni(format!("Failed to set array region: {}", e)));
+                return std::ptr::null_mut();
+            }
+            array
+        }
+        Err(e) => {
+            throw_exception(&env, &SplitsError::Jni(format!("Failed to create int array: {}", e)));
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_PostingCursor_closeNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    if let Err(e) = check_handle(handle, HandleKind::PostingCursor) {
+        throw_exception(&env, &e);
+        return;
+    }
+    
+    unregister_posting_cursor(handle);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_termFreqNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    field: JString,
+    term: JString,
+    doc_id: jint,
+) -> jint {
+    if doc_id < 0 {
+        throw_exception(&env, &SplitsError::InvalidOperation(
+            format!("Document ID cannot be negative: {}", doc_id)
+        ));
+        return 0;
+    }
+    
+    let result = jstring_to_string(&env, field, "field")
+        .and_then(|field| Ok((field, jstring_to_string(&env, term, "term")?)))
+        .and_then(|(field, term)| with_reader(handle, |reader| reader.term_freq(&field, &term, doc_id as u32)));
+    
+    match result {
+        Ok(term_freq) => term_freq as jint,
+        Err(e) => {
+            throw_exception(&env, &e);
+            0
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_fieldnormNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    field: JString,
+    doc_id: jint,
+) -> jint {
+    if doc_id < 0 {
+        throw_exception(&env, &SplitsError::InvalidOperation(
+            format!("Document ID cannot be negative: {}", doc_id)
+        ));
+        return 0;
+    }
+    
+    let result = jstring_to_string(&env, field, "field")
+        .and_then(|field| with_reader(handle, |reader| reader.fieldnorm(&field, doc_id as u32)));
+    
+    match result {
+        Ok(fieldnorm) => fieldnorm as jint,
+        Err(e) => {
+            throw_exception(&env, &e);
+            0
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_fieldTermsNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    field: JString,
+) -> jobject {
+    let result = jstring_to_string(&env, field, "field")
+        .and_then(|field| with_reader(handle, |reader| reader.field_terms(&field)))
+        .and_then(|terms| {
+            let list = new_java_array_list(&env)?;
+            for term in &terms {
+                let element = new_java_string(&env, term)?;
+                java_list_add(&env, list, JObject::from(element))?;
+            }
+            Ok(list.into_inner())
+        });
+    
+    match result {
+        Ok(list) => list,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_termsWithPrefixNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    field: JString,
+    prefix: JString,
+    limit: jint,
+) -> jobject {
+    let result = jstring_to_string(&env, field, "field")
+        .and_then(|field| Ok((field, jstring_to_string(&env, prefix, "prefix")?)))
+        .and_then(|(field, prefix)| {
+            with_reader(handle, |reader| reader.terms_with_prefix(&field, &prefix, limit as usize))
+        })
+        .and_then(|terms| {
+            let list = new_java_array_list(&env)?;
+            for term in &terms {
+                let element = new_java_string(&env, term)?;
+                java_list_add(&env, list, JObject::from(element))?;
+            }
+            Ok(list.into_inner())
+        });
+    
+    match result {
+        Ok(list) => list,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_termDictBytesNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    field: JString,
+) -> jbyteArray {
+    let result = jstring_to_string(&env, field, "field")
+        .and_then(|field| with_reader(handle, |reader| reader.term_dict_bytes(&field)))
+        .and_then(|bytes| env.byte_array_from_slice(&bytes)
+            .map_err(|e| SplitsError::Jni(format!("Failed to create byte array: {}", e))));
+    
+    match result {
+        Ok(array) => array,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_totalTermFreqNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    field: JString,
+    term: JString,
+) -> jlong {
+    let result = jstring_to_string(&env, field, "field")
+        .and_then(|field| Ok((field, jstring_to_string(&env, term, "term")?)))
+        .and_then(|(field, term)| with_reader(handle, |reader| reader.total_term_freq(&field, &term)));
+    
+    match result {
+        Ok(total) => total as jlong,
+        Err(e) => {
+            throw_exception(&env, &e);
+            0
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_fieldCardinalityNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    field: JString,
+    exact: jboolean,
+) -> jlong {
+    let result = jstring_to_string(&env, field, "field")
+        .and_then(|field| with_reader(handle, |reader| reader.field_cardinality(&field, exact == JNI_TRUE)));
+    
+    match result {
+        Ok(cardinality) => cardinality as jlong,
+        Err(e) => {
+            throw_exception(&env, &e);
+            0
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_numericBoundsNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    field: JString,
+) -> jlongArray {
+    // Returned as [min, max]
+    let result = jstring_to_string(&env, field, "field")
+        .and_then(|field| with_reader(handle, |reader| reader.numeric_bounds(&field)))
+        .and_then(|(min, max)| {
+            let array = env.new_long_array(2)
+                .map_err(|e| SplitsError::Jni(format!("Failed to create long array: {}", e)))?;
+            env.set_long_array_region(array, 0, &[min, max])
+                .map_err(|e| SplitsError::Jni(format!("Failed to set array region: {}", e)))?;
+            Ok(array)
+        });
+    
+    match result {
+        Ok(array) => array,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_contentEqualsNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    other_handle: jlong,
+) -> jboolean {
+    match with_reader_pair(handle, other_handle, |reader, other| reader.content_equals(other)) {
+        Ok(true) => JNI_TRUE,
+        Ok(false) => JNI_FALSE,
+        Err(e) => {
+            throw_exception(&env, &e);
+            JNI_FALSE
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_contentDigestNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    match with_reader(handle, |reader| reader.content_digest())
+        .and_then(|digest| new_java_string(&env, &digest))
+    {
+        Ok(s) => s,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_splitChecksumNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    match with_reader(handle, |reader| reader.split_checksum())
+        .and_then(|checksum| new_java_string(&env, &checksum))
+    {
+        Ok(s) => s,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_overlapsTimeRangeNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    start: jlong,
+    end: jlong,
+) -> jboolean {
+    match with_reader(handle, |reader| reader.overlaps_time_range(start, end)) {
+        Ok(true) => JNI_TRUE,
+        Ok(false) => JNI_FALSE,
+        Err(e) => {
+            throw_exception(&env, &e);
+            JNI_FALSE
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_termPositionsNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    field: JString,
+    term: JString,
+    doc_id: jint,
+) -> jintArray {
+    if doc_id < 0 {
+        throw_exception(&env, &SplitsError::InvalidOperation(
+            format!("Document ID cannot be negative: {}", doc_id)
+        ));
+        return std::ptr::null_mut();
+    }
+    
+    let result = jstring_to_string(&env, field, "field")
+        .and_then(|field| Ok((field, jstring_to_string(&env, term, "term")?)))
+        .and_then(|(field, term)| with_reader(handle, |reader| reader.term_positions(&field, &term, doc_id as u32)));
+    
+    let positions: Vec<i32> = match result {
+        Ok(positions) => positions.into_iter().map(|position| position as i32).collect(),
+        Err(e) => {
+            throw_exception(&env, &e);
+            return std::ptr::null_mut();
+        }
+    };
+    
+    match env.new_int_array(positions.len() as i32) {
+        Ok(array) => {
+            if let Err(e) = env.set_int_array_region(array, 0, &positions) {
+                throw_exception(&env, &SplitsError::Jni(format!("Failed to set array region: {}", e)));
+                return std::ptr::null_mut();
+            }
+            array
+        }
+        Err(e) => {
+            throw_exception(&env, &SplitsError::Jni(format!("Failed to create int array: {}", e)));
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_readPostingListWithFreqsNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    field: JString,
+    term: JString,
+) -> jintArray {
+    let result = jstring_to_string(&env, field, "field")
+        .and_then(|field| Ok((field, jstring_to_string(&env, term, "term")?)))
+        .and_then(|(field, term)| with_reader(handle, |reader| reader.read_posting_list_with_freqs(&field, &term)));
+    
+    // Pairs are flattened as [doc_id, term_freq, doc_id, term_freq, ...]
+    let doc_freqs: Vec<i32> = match result {
+        Ok(doc_freqs) => doc_freqs.into_iter()
+            .flat_map(|(doc_id, term_freq)| [doc_id as i32, term_freq as i32])
+            .collect(),
+        Err(e) => {
+            throw_exception(&env, &e);
+            return std::ptr::null_mut();
+        }
+    };
+    
+    match env.new_int_array(doc_freqs.len() as i32) {
+        Ok(array) => {
+            if let Err(e) = env.set_int_array_region(array, 0, &doc_freqs) {
+                throw_exception(&env, &SplitsError::Jni(format!("Failed to set array region: {}", e)));
+                return std::ptr::null_mut();
+            }
+            array
+        }
+        Err(e) => {
+            throw_exception(&env, &SplitsError::Jni(format!("Failed to create int array: {}", e)));
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_estimateHitsNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    query: JString,
+) -> jlong {
+    let result = jstring_to_string(&env, query, "query")
+        .and_then(|query| with_reader(handle, |reader| reader.estimate_hits(&query)));
+    
+    match result {
+        Ok(hits) => hits as jlong,
+        Err(e) => {
+            throw_exception(&env, &e);
+            0
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_facetCountsNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    field: JString,
+    prefix: JString,
+    top_k: jint,
+) -> jobject {
+    if top_k < 0 {
+        throw_exception(&env, &SplitsError::InvalidOperation(
+            format!("Top k cannot be negative: {}", top_k)
+        ));
+        return std::ptr::null_mut();
+    }
+    
+    let result = jstring_to_string(&env, field, "field")
+        .and_then(|field| Ok((field, jstring_to_string(&env, prefix, "prefix")?)))
+        .and_then(|(field, prefix)| with_reader(handle, |reader| reader.facet_counts(&field, &prefix, top_k as usize)))
+        .and_then(|facet_counts| {
+            // Ordered like the counts, highest first
+            let map = env.new_object("java/util/LinkedHashMap", "()V", &[])
+                .map_err(|e| SplitsError::Jni(format!("Failed to create LinkedHashMap: {}", e)))?;
+            for (facet, count) in facet_counts {
+                let key = env.new_string(&facet)
+                    .map_err(|e| SplitsError::Jni(format!("Failed to create string: {}", e)))?;
+                let value = env.new_object("java/lang/Long", "(J)V", &[JValue::Long(count as i64)])
+                    .map_err(|e| SplitsError::Jni(format!("Failed to create Long: {}", e)))?;
+                java_map_put(&env, map, key.into(), value)?;
+            }
+            Ok(map.into_inner())
+        });
+    
+    match result {
+        Ok(map) => map,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_snippetsNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    query: JString,
+    field: JString,
+    doc_ids: jintArray,
+    max_chars: jint,
+) -> jobject {
+    if max_chars <= 0 {
+        throw_exception(&env, &SplitsError::InvalidOperation(
+            format!("Max chars must be positive: {}", max_chars)
+        ));
+        return std::ptr::null_mut();
+    }
+    
+    let result = jstring_to_string(&env, query, "query")
+        .and_then(|query| Ok((query, jstring_to_string(&env, field, "field")?)))
+        .and_then(|(query, field)| Ok((query, field, jint_array_to_doc_ids(&env, doc_ids)?)))
+        .and_then(|(query, field, doc_ids)| {
+            with_reader(handle, |reader| reader.snippets(&query, &field, &doc_ids, max_chars as usize))
+        })
+        .and_then(|snippets| {
+            let list = new_java_array_list(&env)?;
+            for snippet in snippets {
+                let snippet = env.new_string(&snippet)
+                    .map_err(|e| SplitsError::Jni(format!("Failed to create string: {}", e)))?;
+                java_list_add(&env, list, snippet.into())?;
+            }
+            Ok(list.into_inner())
+        });
+    
+    match result {
+        Ok(list) => list,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_columnNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    field: JString,
+) -> jstring {
+    let result = jstring_to_string(&env, field, "field")
+        .and_then(|field| with_reader(handle, |reader| reader.column(&field)))
+        .and_then(|values| {
+            serde_json::to_string(&values)
+                .map_err(|e| SplitsError::Serialization(format!("Failed to serialize column: {}", e)))
+        })
+        .and_then(|json| new_java_string(&env, &json));
+    
+    match result {
+        Ok(s) => s,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_getDocumentNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    doc_id: jint,
+) -> jstring {
+    let result = with_reader(handle, |reader| reader.get_document(doc_id as u32))
+        .and_then(|document| {
+            serde_json::to_string(&document)
+                .map_err(|e| SplitsError::Serialization(format!("Failed to serialize document: {}", e)))
+        })
+        .and_then(|json| new_java_string(&env, &json));
+    
+    match result {
+        Ok(s) => s,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_sampleNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    n: jint,
+    seed: jlong,
+) -> jobject {
+    if n < 0 {
+        throw_exception(&env, &SplitsError::InvalidOperation(
+            format!("Sample size cannot be negative: {}", n)
+        ));
+        return std::ptr::null_mut();
+    }
+    
+    let result = with_reader(handle, |reader| reader.sample(n as usize, seed as u64))
+        .and_then(|documents| {
+            let list = new_java_array_list(&env)?;
+            for document in &documents {
+                let json = serde_json::to_string(document)
+                    .map_err(|e| SplitsError::Serialization(format!("Failed to serialize document: {}", e)))?;
+                java_list_add(&env, list, JObject::from(new_java_string(&env, &json)?))?;
+            }
+            Ok(list.into_inner())
+        });
+    
+    match result {
+        Ok(list) => list,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_toArrowNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    fields: jobjectArray,
+) -> jbyteArray {
+    let result = jstring_array_to_vec(&env, fields, "fields")
+        .and_then(|fields| {
+            let field_refs: Vec<&str> = fields.iter().map(String::as_str).collect();
+            with_reader(handle, |reader| reader_to_arrow(reader, &field_refs))
+        })
+        .and_then(|bytes| env.byte_array_from_slice(&bytes)
+            .map_err(|e| SplitsError::Jni(format!("Failed to create byte array: {}", e))));
+    
+    match result {
+        Ok(array) => array,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[cfg(feature = "arrow")]
+fn reader_to_arrow(reader: &QuickwitSplitReader, fields: &[&str]) -> Result<Vec<u8>, SplitsError> {
+    reader.to_arrow(fields)
+}
+
+/// Arrow export is compiled out; fail the call instead of leaving the native method unresolved
+#[cfg(not(feature = "arrow"))]
+fn reader_to_arrow(_reader: &QuickwitSplitReader, _fields: &[&str]) -> Result<Vec<u8>, SplitsError> {
+    Err(SplitsError::InvalidOperation("Arrow export requires the arrow feature".to_string()))
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_storeBlockRangesNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jlongArray {
+    // Flattened as [start0, end0, start1, end1, ...]
+    let bounds: Vec<i64> = match with_reader(handle, |reader| reader.store_block_ranges()) {
+        Ok(ranges) => ranges.iter()
+            .flat_map(|range| [range.start as i64, range.end as i64])
+            .collect(),
+        Err(e) => {
+            throw_exception(&env, &e);
+            return std::ptr::null_mut();
+        }
+    };
+    
+    match env.new_long_array(bounds.len() as i32) {
+        Ok(array) => {
+            if let Err(e) = env.set_long_array_region(array, 0, &bounds) {
+                throw_exception(&env, &SplitsError::Jni(format!("Failed to set array region: {}", e)));
+                return std::ptr::null_mut();
+            }
+            array
+        }
+        Err(e) => {
+            throw_exception(&env, &SplitsError::Jni(format!("Failed to create long array: {}", e)));
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_docFreqsNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    field: JString,
+    terms: jobjectArray,
+) -> jlongArray {
+    let result = jstring_to_string(&env, field, "field")
+        .and_then(|field| Ok((field, jstring_array_to_vec(&env, terms, "terms")?)))
+        .and_then(|(field, terms)| {
+            let term_refs: Vec<&str> = terms.iter().map(String::as_str).collect();
+            with_reader(handle, |reader| reader.doc_freqs(&field, &term_refs))
+        });
+    
+    let doc_freqs: Vec<i64> = match result {
+        Ok(doc_freqs) => doc_freqs.into_iter().map(|doc_freq| doc_freq as i64).collect(),
+        Err(e) => {
+            throw_exception(&env, &e);
+            return std::ptr::null_mut();
+        }
+    };
+    
+    match env.new_long_array(doc_freqs.len() as i32) {
+        Ok(array) => {
+            if let Err(e) = env.set_long_array_region(array, 0, &doc_freqs) {
+                throw_exception(&env, &SplitsError::Jni(format!("Failed to set array region: {}", e)));
+                return std::ptr::null_mut();
+            }
+            array
+        }
+        Err(e) => {
+            throw_exception(&env, &SplitsError::Jni(format!("Failed to create long array: {}", e)));
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_aliveBitsetNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jbyteArray {
+    let result = with_reader(handle, |reader| reader.alive_bitset())
+        .and_then(|bitset| {
+            env.byte_array_from_slice(&bitset)
+                .map_err(|e| SplitsError::Jni(format!("Failed to create byte array: {}", e)))
+        });
+    
+    match result {
+        Ok(array) => array,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_storeBlockOfNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    doc_id: jint,
+) -> jint {
+    if doc_id < 0 {
+        throw_exception(&env, &SplitsError::InvalidOperation(
+            format!("Document ID cannot be negative: {}", doc_id)
+        ));
+        return 0;
+    }
+    
+    match with_reader(handle, |reader| reader.store_block_of(doc_id as u32)) {
+        Ok(block) => block as jint,
+        Err(e) => {
+            throw_exception(&env, &e);
+            0
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_readStoreBlockNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    index: jint,
+) -> jbyteArray {
+    let result = with_reader(handle, |reader| reader.read_store_block(index as usize))
+        .and_then(|block| {
+            env.byte_array_from_slice(&block)
+                .map_err(|e| SplitsError::Jni(format!("Failed to create byte array: {}", e)))
+        });
+    
+    match result {
+        Ok(array) => array,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_storeFileRangeNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jlongArray {
+    // Returned as [start, end]
+    let result = with_reader(handle, |reader| reader.store_file_range())
+        .and_then(|range| {
+            let array = env.new_long_array(2)
+                .map_err(|e| SplitsError::Jni(format!("Failed to create long array: {}", e)))?;
+            env.set_long_array_region(array, 0, &[range.start as i64, range.end as i64])
+                .map_err(|e| SplitsError::Jni(format!("Failed to set array region: {}", e)))?;
+            Ok(array)
+        });
+    
+    match result {
+        Ok(array) => array,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_readStoreDataNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jbyteArray {
+    let result = with_reader(handle, |reader| reader.read_store_data())
+        .and_then(|data| {
+            env.byte_array_from_slice(&data)
+                .map_err(|e| SplitsError::Jni(format!("Failed to create byte array: {}", e)))
+        });
+    
+    match result {
+        Ok(array) => array,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_readByteRangeNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    file_name: JString,
+    start: jlong,
+    length: jlong,
+) -> jbyteArray {
+    let result = jstring_to_string(&env, file_name, "fileName")
+        .and_then(|file_name| with_reader(handle, |reader| {
+            reader.read_file_range(&file_name, start as u64, length as u64)
+        }))
+        .and_then(|bytes| {
+            env.byte_array_from_slice(&bytes)
+                .map_err(|e| SplitsError::Jni(format!("Failed to create byte array: {}", e)))
+        });
+    
+    match result {
+        Ok(array) => array,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_exportIndexNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    output_dir: JString,
+) {
+    let result = jstring_to_string(&env, output_dir, "outputDir")
+        .and_then(|output_dir| with_reader(handle, |reader| reader.export_index(Path::new(&output_dir))));
+    
+    if let Err(e) = result {
+        throw_exception(&env, &e);
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_toTarNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    output_path: JString,
+) {
+    let result = jstring_to_string(&env, output_path, "outputPath")
+        .and_then(|output_path| with_reader(handle, |reader| {
+            let output_path = Path::new(&output_path);
+            let file = fs::File::create(output_path)?;
+            let result = reader.to_tar(BufWriter::with_capacity(COPY_BUFFER_SIZE, file));
+            if result.is_err() {
+                // Don't leave a truncated archive behind
+                let _ = fs::remove_file(output_path);
+            }
+            result
+        }));
+    
+    if let Err(e) = result {
+        throw_exception(&env, &e);
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_fieldsNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jobject {
+    let result = with_reader(handle, |reader| reader.fields())
+        .and_then(|fields| {
+            let list = new_java_array_list(&env)?;
+            for field in &fields {
+                let element = create_field_descriptor_object(&env, field)?;
+                java_list_add(&env, list, JObject::from(element))?;
+            }
+            Ok(list.into_inner())
+        });
+    
+    match result {
+        Ok(list) => list,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_corpusStatsNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    field: JString,
+) -> jobject {
+    let result = jstring_to_string(&env, field, "field")
+        .and_then(|field| with_reader(handle, |reader| reader.corpus_stats(&field)))
+        .and_then(|stats| create_corpus_stats_object(&env, &stats));
+    
+    match result {
+        Ok(stats) => stats,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_registerTokenizerNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    name: JString,
+    config_json: JString,
+) {
+    let result = jstring_to_string(&env, name, "name").and_then(|name| {
+        let config = TokenizerConfig::from_json(&jstring_to_string(&env, config_json, "configJson")?)?;
+        let tokenizer = config.build()?;
+        with_reader(handle, |reader| reader.register_tokenizer(&name, tokenizer))
+    });
+    
+    if let Err(e) = result {
+        throw_exception(&env, &e);
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_segmentFilesWithSizesNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jobject {
+    let result = with_reader(handle, |reader| reader.segment_files_with_sizes())
+        .and_then(|files| {
+            // Sorted like the files themselves
+            let map = env.new_object("java/util/TreeMap", "()V", &[])
+                .map_err(|e| SplitsError::Jni(format!("Failed to create TreeMap: {}", e)))?;
+            for (file_name, size) in files {
+                let key = env.new_string(&file_name)
+                    .map_err(|e| SplitsError::Jni(format!("Failed to create string: {}", e)))?;
+                let value = env.new_object("java/lang/Long", "(J)V", &[JValue::Long(size as i64)])
+                    .map_err(|e| SplitsError::Jni(format!("Failed to create Long: {}", e)))?;
+                java_map_put(&env, map, key.into(), value)?;
+            }
+            Ok(map.into_inner())
+        });
+    
+    match result {
+        Ok(map) => map,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_tantivyVersionNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    let result = with_reader(handle, |reader| Ok(reader.tantivy_version()))
+        .and_then(|version| match version {
+            Some(version) => new_java_string(&env, &version),
+            None => Ok(std::ptr::null_mut()),
+        });
+    
+    match result {
+        Ok(version) => version,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_provenanceNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jobject {
+    match with_reader(handle, |reader| Ok(reader.provenance()))
+        .and_then(|provenance| create_provenance_object(&env, &provenance))
+    {
+        Ok(provenance) => provenance,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_opstampNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jlong {
+    match with_reader(handle, |reader| reader.opstamp()) {
+        Ok(opstamp) => opstamp as jlong,
+        Err(e) => {
+            throw_exception(&env, &e);
+            0
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_customMetadataNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jobject {
+    let result = with_reader(handle, |reader| reader.custom_metadata())
+        .and_then(|custom_metadata| {
+            let map = new_java_hash_map(&env)?;
+            for (key, value) in custom_metadata {
+                let key = env.new_string(&key)
+                    .map_err(|e| SplitsError::Jni(format!("Failed to create string: {}", e)))?;
+                let value = env.new_string(&value)
+                    .map_err(|e| SplitsError::Jni(format!("Failed to create string: {}", e)))?;
+                java_map_put(&env, map, key.into(), value.into())?;
+            }
+            Ok(map.into_inner())
+        });
+    
+    match result {
+        Ok(map) => map,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
 #[no_mangle]
 pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_getFastFieldDataNative(
     env: JNIEnv,
     _class: JClass,
     handle: jlong,
-    field: JString,
-    start_doc: jint,
-    end_doc: jint,
-) -> jbyteArray {
-    let field_str = match jstring_to_string(&env, field) {
+    field: JString,
+    start_doc: jint,
+    end_doc: jint,
+    encoding: jint,
+) -> jbyteArray {
+    if start_doc < 0 || end_doc < start_doc {
+        throw_exception(&env, &SplitsError::InvalidOperation(
+            "Invalid document range".to_string()
+        ));
+        return std::ptr::null_mut();
+    }
+    
+    let result = jstring_to_string(&env, field, "field")
+        .and_then(|field| Ok((field, FastFieldFormat::from_code(encoding)?)))
+        .and_then(|(field, format)| with_reader(handle, |reader| {
+            reader.get_fast_field_data(&field, start_doc as u32..end_doc as u32, format)
+        }))
+        .and_then(|data| env.byte_array_from_slice(&data)
+            .map_err(|e| SplitsError::Jni(format!("Failed to create byte array: {}", e))));
+    
+    match result {
+        Ok(array) => array,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_getFastFieldsNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    fields: jobjectArray,
+    start_doc: jint,
+    end_doc: jint,
+) -> jobject {
+    if start_doc < 0 || end_doc < start_doc {
+        throw_exception(&env, &SplitsError::InvalidOperation(
+            "Invalid document range".to_string()
+        ));
+        return std::ptr::null_mut();
+    }
+    
+    let result = jstring_array_to_vec(&env, fields, "fields")
+        .and_then(|fields| {
+            let field_refs: Vec<&str> = fields.iter().map(String::as_str).collect();
+            with_reader(handle, |reader| {
+                reader.get_fast_fields(&field_refs, start_doc as u32..end_doc as u32)
+            })
+        })
+        .and_then(|data_by_field| {
+            let map = new_java_hash_map(&env)?;
+            for (field, data) in data_by_field {
+                let key = env.new_string(&field)
+                    .map_err(|e| SplitsError::Jni(format!("Failed to create string: {}", e)))?;
+                let value = env.byte_array_from_slice(&data)
+                    .map_err(|e| SplitsError::Jni(format!("Failed to create byte array: {}", e)))?;
+                java_map_put(&env, map, key.into(), JObject::from(value))?;
+            }
+            Ok(map.into_inner())
+        });
+    
+    match result {
+        Ok(map) => map,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_fastFieldColumnNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    field: JString,
+) -> jlongArray {
+    // Returned as [address, length]
+    let result = jstring_to_string(&env, field, "field")
+        .and_then(|field| with_reader(handle, |reader| reader.fast_field_column_ptr(&field)))
+        .and_then(|(address, len)| {
+            let array = env.new_long_array(2)
+                .map_err(|e| SplitsError::Jni(format!("Failed to create long array: {}", e)))?;
+            env.set_long_array_region(array, 0, &[address as i64, len as i64])
+                .map_err(|e| SplitsError::Jni(format!("Failed to set array region: {}", e)))?;
+            Ok(array)
+        });
+    
+    match result {
+        Ok(array) => array,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_segmentMetaJsonNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    match with_reader(handle, |reader| reader.segment_meta_json())
+        .and_then(|json| new_java_string(&env, &json))
+    {
+        Ok(s) => s,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_indexStatsNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jstring {
+    match with_reader(handle, |reader| reader.index_stats())
+        .and_then(|stats| Ok(serde_json::to_string(&stats)?))
+        .and_then(|json| new_java_string(&env, &json))
+    {
         Ok(s) => s,
         Err(e) => {
             throw_exception(&env, &e);
-            return std::ptr::null_mut();
+            std::ptr::null_mut()
         }
-    };
-    
-    if start_doc < 0 || end_doc < start_doc {
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_reloadNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    // The registry lock is held for the whole reload, so concurrent reads on
+    // this handle observe either the previous or the reloaded split
+    if let Err(e) = with_reader_mut(handle, |reader| reader.reload()) {
+        throw_exception(&env, &e);
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_setReadOptionsNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    buffer_size: jint,
+    direct_io: jboolean,
+) {
+    if buffer_size <= 0 {
         throw_exception(&env, &SplitsError::InvalidOperation(
-            "Invalid document range".to_string()
+            format!("Read buffer size must be positive: {}", buffer_size)
         ));
-        return std::ptr::null_mut();
+        return;
     }
     
-    // Return placeholder data
-    let doc_count = (end_doc - start_doc) as usize;
-    let data = vec![0u8; doc_count * 8]; // 8 bytes per document
+    let options = ReadOptions {
+        buffer_size: buffer_size as usize,
+        direct_io: direct_io == JNI_TRUE,
+    };
+    if let Err(e) = with_reader_mut(handle, |reader| reader.set_read_options(options)) {
+        throw_exception(&env, &e);
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_setEncryptionKeyNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    key: jbyteArray,
+) {
+    let result = env.convert_byte_array(key)
+        .map_err(|e| SplitsError::Jni(format!("Failed to read encryption key: {}", e)))
+        .and_then(|key| with_reader_mut(handle, |reader| reader.set_encryption_key(&key)));
     
-    match env.byte_array_from_slice(&data) {
-        Ok(array) => array,
+    if let Err(e) = result {
+        throw_exception(&env, &e);
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_isEncryptedNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jboolean {
+    match with_reader(handle, |reader| reader.is_encrypted()) {
+        Ok(true) => JNI_TRUE,
+        Ok(false) => JNI_FALSE,
         Err(e) => {
-            throw_exception(&env, &SplitsError::Jni(format!("Failed to create byte array: {}", e)));
-            std::ptr::null_mut()
+            throw_exception(&env, &e);
+            JNI_FALSE
         }
     }
 }
@@ -520,14 +2359,340 @@ pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplitReader_closeNat
     _class: JClass,
     handle: jlong,
 ) {
+    if let Err(e) = check_handle(handle, HandleKind::Reader) {
+        throw_exception(&env, &e);
+        return;
+    }
+    
     // Remove reader from registry
     unregister_reader(handle);
 }
 
+// ===================================================================================
+// Streaming Split Builder JNI Functions
+// ===================================================================================
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_StreamingSplitBuilder_beginStreamingNative(
+    env: JNIEnv,
+    _class: JClass,
+    schema_json: JString,
+    output_dir: JString,
+    target_docs_per_split: jint,
+    config_json: JString,
+) -> jlong {
+    if target_docs_per_split <= 0 {
+        throw_exception(&env, &SplitsError::InvalidOperation(
+            "Target docs per split must be positive".to_string()
+        ));
+        return 0;
+    }
+    
+    let result = jstring_to_string(&env, schema_json, "schemaJson")
+        .and_then(|schema_json| {
+            serde_json::from_str(&schema_json)
+                .map_err(|e| SplitsError::Serialization(format!("Invalid schema: {}", e)))
+        })
+        .and_then(|schema| {
+            let output_dir = jstring_to_string(&env, output_dir, "outputDir")?;
+            let config = SplitConfig::from_json(&jstring_to_string(&env, config_json, "configJson")?)?;
+            StreamingSplitBuilder::new(schema, Path::new(&output_dir), target_docs_per_split as usize, config)
+        });
+    
+    match result {
+        Ok(builder) => register_streaming_builder(builder),
+        Err(e) => {
+            throw_exception(&env, &e);
+            0
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_StreamingSplitBuilder_addDocumentNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    document_json: JString,
+) -> jobject {
+    let result = jstring_to_string(&env, document_json, "documentJson")
+        .and_then(|json| with_streaming_builder_mut(handle, |builder| builder.add_json_document(&json)))
+        .and_then(|emitted| match emitted {
+            Some(metadata) => create_split_metadata_object(&env, &metadata),
+            None => Ok(std::ptr::null_mut()),
+        });
+    
+    match result {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_StreamingSplitBuilder_addSegmentNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+    index_path: JString,
+    segment_id: JString,
+) -> jobject {
+    let result = jstring_to_string(&env, index_path, "indexPath")
+        .and_then(|index_path| Ok((index_path, jstring_to_string(&env, segment_id, "segmentId")?)))
+        .and_then(|(index_path, segment_id)| {
+            let index = Index::open_in_dir(&index_path)?;
+            with_streaming_builder_mut(handle, |builder| builder.add_segment(&index, &segment_id))
+        })
+        .and_then(|splits| new_split_metadata_list(&env, &splits));
+    
+    match result {
+        Ok(list) => list,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_StreamingSplitBuilder_finishNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jobject {
+    let result = with_streaming_builder_mut(handle, |builder| builder.finish())
+        .and_then(|splits| new_split_metadata_list(&env, &splits));
+    
+    match result {
+        Ok(list) => list,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_StreamingSplitBuilder_closeNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) {
+    if let Err(e) = check_handle(handle, HandleKind::StreamingBuilder) {
+        throw_exception(&env, &e);
+        return;
+    }
+    
+    unregister_streaming_builder(handle);
+}
+
 // ===================================================================================
 // Library Functions
 // ===================================================================================
 
+/// Captures the Java VM for callbacks into Java from native threads
+#[no_mangle]
+pub extern "system" fn JNI_OnLoad(vm: JavaVM, _reserved: *mut c_void) -> jint {
+    set_java_vm(Some(vm));
+    JNI_VERSION_1_8
+}
+
+#[no_mangle]
+pub extern "system" fn JNI_OnUnload(_vm: JavaVM, _reserved: *mut c_void) {
+    set_java_vm(None);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplits_setMaxThreadsNative(
+    env: JNIEnv,
+    _class: JClass,
+    max_threads: jint,
+) {
+    if max_threads <= 0 {
+        throw_exception(&env, &SplitsError::InvalidOperation(
+            format!("Max threads must be positive, got: {}", max_threads)
+        ));
+        return;
+    }
+    
+    if let Err(e) = crate::thread_pool::set_max_threads(max_threads as usize) {
+        throw_exception(&env, &e);
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplits_setReaderMemoryBudgetNative(
+    env: JNIEnv,
+    _class: JClass,
+    bytes: jlong,
+) {
+    if bytes < 0 {
+        throw_exception(&env, &SplitsError::InvalidOperation(
+            format!("Reader memory budget cannot be negative, got: {}", bytes)
+        ));
+        return;
+    }
+    
+    if let Err(e) = crate::memory_budget::set_reader_memory_budget(bytes as u64) {
+        throw_exception(&env, &e);
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplits_setMaxDecompressedSplitBytesNative(
+    env: JNIEnv,
+    _class: JClass,
+    bytes: jlong,
+) {
+    if bytes < 0 {
+        throw_exception(&env, &SplitsError::InvalidOperation(
+            format!("Max decompressed split bytes cannot be negative, got: {}", bytes)
+        ));
+        return;
+    }
+    
+    crate::compression::set_max_decompressed_bytes(bytes as u64);
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplits_setDecompressionTempDirNative(
+    env: JNIEnv,
+    _class: JClass,
+    dir: JString,
+) {
+    let result = if dir.is_null() {
+        crate::compression::set_decompression_temp_dir(None)
+    } else {
+        jstring_to_string(&env, dir, "dir")
+            .and_then(|dir| crate::compression::set_decompression_temp_dir(Some(PathBuf::from(dir))))
+    };
+    
+    if let Err(e) = result {
+        throw_exception(&env, &e);
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplits_setLogCallbackNative(
+    env: JNIEnv,
+    _class: JClass,
+    listener: JObject,
+) {
+    let listener = if listener.is_null() {
+        Ok(None)
+    } else {
+        env.new_global_ref(listener)
+            .map(Some)
+            .map_err(|e| SplitsError::Jni(format!("Failed to retain log listener: {}", e)))
+    };
+    
+    if let Err(e) = listener.and_then(crate::logging::set_log_listener) {
+        throw_exception(&env, &e);
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplits_reapStaleHandlesNative(
+    env: JNIEnv,
+    _class: JClass,
+    max_age_millis: jlong,
+) -> jint {
+    if max_age_millis < 0 {
+        throw_exception(&env, &SplitsError::InvalidOperation(
+            format!("Max age cannot be negative, got: {}", max_age_millis)
+        ));
+        return 0;
+    }
+    
+    match reap_stale_handles(std::time::Duration::from_millis(max_age_millis as u64)) {
+        Ok(reaped) => reaped as jint,
+        Err(e) => {
+            throw_exception(&env, &e);
+            0
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplits_registrySizesNative(
+    env: JNIEnv,
+    _class: JClass,
+) -> jobject {
+    let result = registry_sizes().and_then(|sizes| {
+        let map = env.new_object("java/util/TreeMap", "()V", &[])
+            .map_err(|e| SplitsError::Jni(format!("Failed to create TreeMap: {}", e)))?;
+        for (name, size) in [
+            ("generators", sizes.generators),
+            ("readers", sizes.readers),
+            ("postingCursors", sizes.posting_cursors),
+            ("streamingBuilders", sizes.streaming_builders),
+        ] {
+            let key = env.new_string(name)
+                .map_err(|e| SplitsError::Jni(format!("Failed to create string: {}", e)))?;
+            let value = env.new_object("java/lang/Integer", "(I)V", &[JValue::Int(size as jint)])
+                .map_err(|e| SplitsError::Jni(format!("Failed to create Integer: {}", e)))?;
+            java_map_put(&env, map, key.into(), value)?;
+        }
+        Ok(map.into_inner())
+    });
+    
+    match result {
+        Ok(map) => map,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplits_handleCreatedAtNative(
+    env: JNIEnv,
+    _class: JClass,
+    handle: jlong,
+) -> jlong {
+    let created_at = handle_created_at(handle)
+        .ok_or_else(|| SplitsError::InvalidOperation(format!("Invalid handle {}", handle)))
+        .map(|created_at| created_at.duration_since(std::time::UNIX_EPOCH).map_or(0, |d| d.as_millis() as jlong));
+    
+    match created_at {
+        Ok(millis) => millis,
+        Err(e) => {
+            throw_exception(&env, &e);
+            0
+        }
+    }
+}
+
+#[no_mangle]
+pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplits_mergeSplitBytesNative(
+    env: JNIEnv,
+    _class: JClass,
+    splits: jobjectArray,
+    compatibility: jint,
+) -> jbyteArray {
+    let result = SchemaCompatibility::from_code(compatibility)
+        .and_then(|compatibility| Ok((compatibility, jbyte_array_array_to_vec(&env, splits)?)))
+        .and_then(|(compatibility, splits)| {
+            let splits: Vec<&[u8]> = splits.iter().map(Vec::as_slice).collect();
+            crate::split_bytes::merge_split_bytes_with(&splits, compatibility)
+        })
+        .and_then(|merged| {
+            env.byte_array_from_slice(&merged)
+                .map_err(|e| SplitsError::Jni(format!("Failed to create byte array: {}", e)))
+        });
+    
+    match result {
+        Ok(array) => array,
+        Err(e) => {
+            throw_exception(&env, &e);
+            std::ptr::null_mut()
+        }
+    }
+}
+
 #[no_mangle]
 pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplits_getVersion(
     env: JNIEnv,
@@ -541,4 +2706,41 @@ pub extern "system" fn Java_com_tantivy4java_splits_QuickwitSplits_getVersion(
             std::ptr::null_mut()
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    #[test]
+    fn test_decode_java_string_accepts_valid_modified_utf8() {
+        // Modified UTF-8 encodes NUL as 0xC0 0x80
+        assert_eq!(decode_java_string(b"title", "field").unwrap(), "title");
+        assert_eq!(decode_java_string(&[b'a', 0xC0, 0x80, b'b'], "field").unwrap(), "a\0b");
+        assert_eq!(decode_java_string("caf\u{e9}".as_bytes(), "field").unwrap(), "caf\u{e9}");
+    }
+    
+    #[test]
+    fn test_decode_java_string_rejects_unpaired_surrogate() {
+        // A lone high surrogate U+D800 as encoded by the JVM
+        match decode_java_string(&[b'a', 0xED, 0xA0, 0x80], "outputPath") {
+            Err(SplitsError::Jni(msg)) => assert!(msg.contains("outputPath")),
+            other => panic!("Expected Jni error, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_with_reader_rejects_generator_handle() {
+        let generator = QuickwitSplitGenerator::new(create_mock_index().unwrap(), 1000).unwrap();
+        let handle = register_generator(generator);
+        
+        match with_reader(handle, |_| Ok(())) {
+            Err(SplitsError::InvalidOperation(msg)) => {
+                assert_eq!(msg, "expected reader handle, got generator handle");
+            }
+            other => panic!("Expected InvalidOperation, got {:?}", other),
+        }
+        
+        unregister_generator(handle);
+    }
+}
@@ -0,0 +1,67 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+//! The Java VM hosting the library, for calls back into Java
+//!
+//! The VM is captured in `JNI_OnLoad` and released in `JNI_OnUnload`.
+//! Callbacks into Java, such as log listeners, progress reporters or upload
+//! sinks, may run on arbitrary threads, including pool threads the JVM
+//! doesn't know about. They must all go through `with_attached_env`, which
+//! attaches such threads for the duration of the callback and detaches them
+//! afterwards; threads already attached, e.g. the one running a native
+//! method, are left attached. Calling into the JVM from a thread that isn't
+//! attached crashes the process.
+
+use crate::error::{Result, SplitsError};
+use jni::{JNIEnv, JavaVM};
+use once_cell::sync::Lazy;
+use std::sync::{Arc, RwLock};
+
+static JAVA_VM: Lazy<RwLock<Option<Arc<JavaVM>>>> = Lazy::new(|| RwLock::new(None));
+
+/// Records the VM the library was loaded into, or forgets it with `None`
+pub(crate) fn set_java_vm(vm: Option<JavaVM>) {
+    if let Ok(mut current) = JAVA_VM.write() {
+        *current = vm.map(Arc::new);
+    }
+}
+
+/// Gets the VM the library was loaded into
+///
+/// Fails with `InvalidOperation` if the library wasn't loaded by a JVM, or
+/// was unloaded.
+pub(crate) fn java_vm() -> Result<Arc<JavaVM>> {
+    JAVA_VM.read()
+        .map_err(|e| SplitsError::InvalidOperation(format!("Failed to access the Java VM: {}", e)))?
+        .clone()
+        .ok_or_else(|| SplitsError::InvalidOperation("The library was not loaded by a Java VM".to_string()))
+}
+
+/// Runs `callback` with a `JNIEnv` for the current thread
+///
+/// The thread is attached to the VM if needed, and detached again once the
+/// callback returns, so this is safe to call from any thread. Attaching is
+/// expensive: threads calling back repeatedly should batch their calls into
+/// one callback where possible.
+pub(crate) fn with_attached_env<T>(callback: impl FnOnce(&JNIEnv) -> Result<T>) -> Result<T> {
+    let vm = java_vm()?;
+    let env = vm.attach_current_thread()
+        .map_err(|e| SplitsError::Jni(format!("Failed to attach thread to the Java VM: {}", e)))?;
+    callback(&env)
+}
@@ -22,100 +22,475 @@
 //! This library provides Java Native Interface (JNI) bindings for creating and reading
 //! Quickwit splits from Tantivy indices. It enables Java applications to generate
 //! optimized split files with embedded hotcache metadata for efficient distributed search.
+//!
+//! The JNI bridge and the handle registries are behind the default `jni` feature.
+//! Building with `--no-default-features` yields a pure Rust crate exposing the
+//! split generation and reading core, with no JDK required.
 
+#[cfg(feature = "jni")]
 use once_cell::sync::Lazy;
-use std::sync::Mutex;
+#[cfg(feature = "jni")]
+use std::sync::{Arc, Mutex};
+#[cfg(feature = "jni")]
+use std::sync::atomic::{AtomicU8, Ordering};
+#[cfg(feature = "jni")]
 use std::collections::HashMap;
+#[cfg(feature = "jni")]
+use std::time::{Duration, Instant, SystemTime};
 
 pub mod split_generator;
 pub mod split_reader;
+pub mod split_bytes;
+pub mod split_storage;
 pub mod hotcache;
+pub mod tokenizers;
+pub mod thread_pool;
+pub mod streaming;
+pub mod streaming_builder;
+pub mod range_read;
+pub mod memory_budget;
+pub mod signing;
+pub mod encryption;
+pub mod compression;
+#[cfg(feature = "arrow")]
+pub mod arrow_export;
+#[cfg(feature = "jni")]
 pub mod jni_bridge;
+#[cfg(feature = "jni")]
+pub mod logging;
+#[cfg(feature = "jni")]
+pub(crate) mod jvm;
 pub mod error;
 
+#[cfg(feature = "jni")]
 use split_generator::QuickwitSplitGenerator;
-use split_reader::QuickwitSplitReader;
+#[cfg(feature = "jni")]
+use split_reader::{PostingCursor, QuickwitSplitReader};
+#[cfg(feature = "jni")]
+use streaming_builder::StreamingSplitBuilder;
+
+/// A native object registered under a handle
+///
+/// Lookups clone the `Arc` and release the registry lock before locking the
+/// object, so a long running call, or one calling into Java, only holds up
+/// calls on the same object. Unregistering drops the object once the
+/// registry lock is released, or once the last call using it returns.
+#[cfg(feature = "jni")]
+pub(crate) type Registered<T> = Arc<Mutex<T>>;
 
 /// Global registry for managing native object handles
 /// This ensures proper cleanup and prevents memory leaks
-static GENERATOR_REGISTRY: Lazy<Mutex<HashMap<i64, Box<QuickwitSplitGenerator>>>> = 
+#[cfg(feature = "jni")]
+static GENERATOR_REGISTRY: Lazy<Mutex<HashMap<i64, Registered<QuickwitSplitGenerator>>>> = 
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[cfg(feature = "jni")]
+static READER_REGISTRY: Lazy<Mutex<HashMap<i64, Registered<QuickwitSplitReader>>>> = 
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+#[cfg(feature = "jni")]
+static POSTING_CURSOR_REGISTRY: Lazy<Mutex<HashMap<i64, Registered<PostingCursor>>>> = 
     Lazy::new(|| Mutex::new(HashMap::new()));
 
-static READER_REGISTRY: Lazy<Mutex<HashMap<i64, Box<QuickwitSplitReader>>>> = 
+#[cfg(feature = "jni")]
+static STREAMING_BUILDER_REGISTRY: Lazy<Mutex<HashMap<i64, Registered<StreamingSplitBuilder>>>> = 
     Lazy::new(|| Mutex::new(HashMap::new()));
 
-/// Generate a unique handle for native objects
-fn generate_handle() -> i64 {
+/// Progress of every registered generator, see `generator_progress`
+///
+/// Kept apart from the generators, which a generation keeps locked until it
+/// returns, so that progress can be read while it runs.
+#[cfg(feature = "jni")]
+static GENERATOR_PROGRESS: Lazy<Mutex<HashMap<i64, Arc<AtomicU8>>>> = 
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Creation and last use times of every registered handle
+///
+/// Kept apart from the registries so that touching a handle never waits on
+/// an object in use.
+#[cfg(feature = "jni")]
+static HANDLE_ACTIVITY: Lazy<Mutex<HashMap<i64, HandleActivity>>> = 
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// When a registered handle was created and last used
+#[cfg(feature = "jni")]
+#[derive(Debug, Clone, Copy)]
+struct HandleActivity {
+    created_at: SystemTime,
+    last_used: Instant,
+}
+
+/// Number of objects in each handle registry, as returned by `registry_sizes`
+#[cfg(feature = "jni")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct RegistrySizes {
+    pub generators: usize,
+    pub readers: usize,
+    pub posting_cursors: usize,
+    pub streaming_builders: usize,
+}
+
+/// Bit position of the kind tag in a handle
+///
+/// The tag sits below the sign bit so handles stay positive, leaving 56 bits
+/// for the counter.
+#[cfg(feature = "jni")]
+const HANDLE_KIND_SHIFT: u32 = 56;
+
+/// Kind of native object a handle refers to, encoded in the handle's high bits
+///
+/// Tagging keeps a handle of one kind from being looked up in another
+/// kind's registry, where it could match an unrelated object.
+#[cfg(feature = "jni")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum HandleKind {
+    Generator = 1,
+    Reader = 2,
+    PostingCursor = 3,
+    StreamingBuilder = 4,
+}
+
+#[cfg(feature = "jni")]
+impl HandleKind {
+    /// Decodes the kind tag of a handle, if it carries a known one
+    fn of(handle: i64) -> Option<Self> {
+        match handle >> HANDLE_KIND_SHIFT {
+            1 => Some(HandleKind::Generator),
+            2 => Some(HandleKind::Reader),
+            3 => Some(HandleKind::PostingCursor),
+            4 => Some(HandleKind::StreamingBuilder),
+            _ => None,
+        }
+    }
+    
+    fn name(self) -> &'static str {
+        match self {
+            HandleKind::Generator => "generator",
+            HandleKind::Reader => "reader",
+            HandleKind::PostingCursor => "posting cursor",
+            HandleKind::StreamingBuilder => "streaming builder",
+        }
+    }
+}
+
+/// Generate a unique handle for a native object of the given kind
+#[cfg(feature = "jni")]
+fn generate_handle(kind: HandleKind) -> i64 {
     use std::sync::atomic::{AtomicI64, Ordering};
     static COUNTER: AtomicI64 = AtomicI64::new(1);
-    COUNTER.fetch_add(1, Ordering::SeqCst)
+    let id = COUNTER.fetch_add(1, Ordering::SeqCst) & ((1 << HANDLE_KIND_SHIFT) - 1);
+    ((kind as i64) << HANDLE_KIND_SHIFT) | id
+}
+
+/// Check that `handle` refers to an object of the `expected` kind
+#[cfg(feature = "jni")]
+pub(crate) fn check_handle(handle: i64, expected: HandleKind) -> error::Result<()> {
+    match HandleKind::of(handle) {
+        Some(kind) if kind == expected => {
+            touch_handle(handle);
+            Ok(())
+        }
+        Some(kind) => Err(error::SplitsError::InvalidOperation(format!(
+            "expected {} handle, got {} handle", expected.name(), kind.name()
+        ))),
+        None => Err(error::SplitsError::InvalidOperation(format!(
+            "expected {} handle, got unknown handle {}", expected.name(), handle
+        ))),
+    }
 }
 
 /// Register a split generator and return its handle
+#[cfg(feature = "jni")]
 pub(crate) fn register_generator(generator: QuickwitSplitGenerator) -> i64 {
-    let handle = generate_handle();
+    let handle = generate_handle(HandleKind::Generator);
+    record_handle(handle);
+    GENERATOR_PROGRESS.lock().unwrap().insert(handle, generator.progress_handle());
     let mut registry = GENERATOR_REGISTRY.lock().unwrap();
-    registry.insert(handle, Box::new(generator));
+    registry.insert(handle, Arc::new(Mutex::new(generator)));
     handle
 }
 
 /// Get a split generator by handle
-pub(crate) fn get_generator(handle: i64) -> Option<std::sync::MutexGuard<'static, HashMap<i64, Box<QuickwitSplitGenerator>>>> {
-    let registry = GENERATOR_REGISTRY.lock().ok()?;
-    if registry.contains_key(&handle) {
-        Some(registry)
-    } else {
-        None
-    }
+#[cfg(feature = "jni")]
+pub(crate) fn get_generator(handle: i64) -> error::Result<Registered<QuickwitSplitGenerator>> {
+    lookup(&GENERATOR_REGISTRY, handle, HandleKind::Generator)
+}
+
+/// Get the progress of a split generator by handle, in percent
+///
+/// Never waits on a running generation.
+#[cfg(feature = "jni")]
+pub(crate) fn generator_progress(handle: i64) -> Option<u8> {
+    let progress = GENERATOR_PROGRESS.lock().ok()?;
+    progress.get(&handle).map(|progress| progress.load(Ordering::Relaxed))
 }
 
 /// Unregister and destroy a split generator
+#[cfg(feature = "jni")]
 pub(crate) fn unregister_generator(handle: i64) -> bool {
-    let mut registry = GENERATOR_REGISTRY.lock().unwrap();
-    registry.remove(&handle).is_some()
+    forget_handle(handle);
+    GENERATOR_PROGRESS.lock().unwrap().remove(&handle);
+    let removed = GENERATOR_REGISTRY.lock().unwrap().remove(&handle);
+    removed.is_some()
 }
 
 /// Register a split reader and return its handle
+#[cfg(feature = "jni")]
 pub(crate) fn register_reader(reader: QuickwitSplitReader) -> i64 {
-    let handle = generate_handle();
+    let handle = generate_handle(HandleKind::Reader);
+    record_handle(handle);
     let mut registry = READER_REGISTRY.lock().unwrap();
-    registry.insert(handle, Box::new(reader));
+    registry.insert(handle, Arc::new(Mutex::new(reader)));
     handle
 }
 
 /// Get a split reader by handle
-pub(crate) fn get_reader(handle: i64) -> Option<std::sync::MutexGuard<'static, HashMap<i64, Box<QuickwitSplitReader>>>> {
-    let registry = READER_REGISTRY.lock().ok()?;
-    if registry.contains_key(&handle) {
-        Some(registry)
-    } else {
-        None
-    }
+#[cfg(feature = "jni")]
+pub(crate) fn get_reader(handle: i64) -> error::Result<Registered<QuickwitSplitReader>> {
+    lookup(&READER_REGISTRY, handle, HandleKind::Reader)
 }
 
 /// Unregister and destroy a split reader
+#[cfg(feature = "jni")]
 pub(crate) fn unregister_reader(handle: i64) -> bool {
-    let mut registry = READER_REGISTRY.lock().unwrap();
-    registry.remove(&handle).is_some()
+    forget_handle(handle);
+    let removed = READER_REGISTRY.lock().unwrap().remove(&handle);
+    removed.is_some()
+}
+
+/// Register a posting cursor and return its handle
+#[cfg(feature = "jni")]
+pub(crate) fn register_posting_cursor(cursor: PostingCursor) -> i64 {
+    let handle = generate_handle(HandleKind::PostingCursor);
+    record_handle(handle);
+    let mut registry = POSTING_CURSOR_REGISTRY.lock().unwrap();
+    registry.insert(handle, Arc::new(Mutex::new(cursor)));
+    handle
+}
+
+/// Get a posting cursor by handle
+#[cfg(feature = "jni")]
+pub(crate) fn get_posting_cursor(handle: i64) -> error::Result<Registered<PostingCursor>> {
+    lookup(&POSTING_CURSOR_REGISTRY, handle, HandleKind::PostingCursor)
+}
+
+/// Unregister and destroy a posting cursor
+#[cfg(feature = "jni")]
+pub(crate) fn unregister_posting_cursor(handle: i64) -> bool {
+    forget_handle(handle);
+    let removed = POSTING_CURSOR_REGISTRY.lock().unwrap().remove(&handle);
+    removed.is_some()
+}
+
+/// Register a streaming split builder and return its handle
+#[cfg(feature = "jni")]
+pub(crate) fn register_streaming_builder(builder: StreamingSplitBuilder) -> i64 {
+    let handle = generate_handle(HandleKind::StreamingBuilder);
+    record_handle(handle);
+    let mut registry = STREAMING_BUILDER_REGISTRY.lock().unwrap();
+    registry.insert(handle, Arc::new(Mutex::new(builder)));
+    handle
+}
+
+/// Get a streaming split builder by handle
+#[cfg(feature = "jni")]
+pub(crate) fn get_streaming_builder(handle: i64) -> error::Result<Registered<StreamingSplitBuilder>> {
+    lookup(&STREAMING_BUILDER_REGISTRY, handle, HandleKind::StreamingBuilder)
+}
+
+/// Unregister and destroy a streaming split builder, dropping the documents it buffered
+#[cfg(feature = "jni")]
+pub(crate) fn unregister_streaming_builder(handle: i64) -> bool {
+    forget_handle(handle);
+    let removed = STREAMING_BUILDER_REGISTRY.lock().unwrap().remove(&handle);
+    removed.is_some()
+}
+
+/// Look up a registered object, holding the registry lock only for the lookup
+#[cfg(feature = "jni")]
+fn lookup<T>(
+    registry: &Mutex<HashMap<i64, Registered<T>>>,
+    handle: i64,
+    kind: HandleKind,
+) -> error::Result<Registered<T>> {
+    let registry = registry.lock().map_err(|e| error::SplitsError::InvalidOperation(
+        format!("Failed to access {} registry: {}", kind.name(), e)
+    ))?;
+    registry.get(&handle).cloned().ok_or_else(|| error::SplitsError::InvalidOperation(
+        format!("Invalid {} handle", kind.name())
+    ))
+}
+
+/// Start tracking the activity of a newly registered handle
+#[cfg(feature = "jni")]
+fn record_handle(handle: i64) {
+    let now = HandleActivity { created_at: SystemTime::now(), last_used: Instant::now() };
+    HANDLE_ACTIVITY.lock().unwrap().insert(handle, now);
+}
+
+/// Stop tracking the activity of an unregistered handle
+#[cfg(feature = "jni")]
+fn forget_handle(handle: i64) {
+    HANDLE_ACTIVITY.lock().unwrap().remove(&handle);
+}
+
+/// Mark a handle as used now, if it is registered
+#[cfg(feature = "jni")]
+fn touch_handle(handle: i64) {
+    if let Ok(mut activity) = HANDLE_ACTIVITY.lock() {
+        if let Some(entry) = activity.get_mut(&handle) {
+            entry.last_used = Instant::now();
+        }
+    }
+}
+
+/// Get the creation time of a registered handle
+#[cfg(feature = "jni")]
+pub(crate) fn handle_created_at(handle: i64) -> Option<SystemTime> {
+    HANDLE_ACTIVITY.lock().ok()?.get(&handle).map(|entry| entry.created_at)
+}
+
+/// Unregister and destroy every object whose handle went unused for longer than `max_age`
+///
+/// A safety net for Java code that never closes its objects, not a
+/// substitute for closing them: a reaped object's Java wrapper fails on next
+/// use with an invalid handle error. A handle counts as used whenever a
+/// native call is made with it. Returns the number of objects destroyed.
+#[cfg(feature = "jni")]
+pub(crate) fn reap_stale_handles(max_age: Duration) -> error::Result<usize> {
+    // Stale handles are claimed under the activity lock, then unregistered
+    // once it's released, as unregistering takes the registry locks
+    let stale = {
+        let mut activity = HANDLE_ACTIVITY.lock()
+            .map_err(|e| error::SplitsError::InvalidOperation(format!("Failed to access handle activity: {}", e)))?;
+        claim_stale_handles(&mut activity, Instant::now(), max_age)
+    };
+    Ok(unregister_stale_handles(stale))
+}
+
+/// Remove the handles unused for longer than `max_age` at `now` from `activity`, returning them
+#[cfg(feature = "jni")]
+fn claim_stale_handles(activity: &mut HashMap<i64, HandleActivity>, now: Instant, max_age: Duration) -> Vec<i64> {
+    let stale: Vec<i64> = activity.iter()
+        .filter(|(_, entry)| now.saturating_duration_since(entry.last_used) > max_age)
+        .map(|(handle, _)| *handle)
+        .collect();
+    for handle in &stale {
+        activity.remove(handle);
+    }
+    stale
+}
+
+/// Unregister and destroy the objects of stale handles, returning how many were still registered
+#[cfg(feature = "jni")]
+fn unregister_stale_handles(stale: Vec<i64>) -> usize {
+    let mut reaped = 0;
+    for handle in stale {
+        let kind = match HandleKind::of(handle) {
+            Some(kind) => kind,
+            None => continue,
+        };
+        let removed = match kind {
+            HandleKind::Generator => unregister_generator(handle),
+            HandleKind::Reader => unregister_reader(handle),
+            HandleKind::PostingCursor => unregister_posting_cursor(handle),
+            HandleKind::StreamingBuilder => unregister_streaming_builder(handle),
+        };
+        if removed {
+            log::warn!("Reaped {} handle {} that was never closed", kind.name(), handle);
+            reaped += 1;
+        }
+    }
+    reaped
 }
 
-#[cfg(test)]
+/// Count the objects in each handle registry, for leak monitoring
+#[cfg(feature = "jni")]
+pub(crate) fn registry_sizes() -> error::Result<RegistrySizes> {
+    let poisoned = |e: String| error::SplitsError::InvalidOperation(format!("Failed to access registry: {}", e));
+    
+    Ok(RegistrySizes {
+        generators: GENERATOR_REGISTRY.lock().map_err(|e| poisoned(e.to_string()))?.len(),
+        readers: READER_REGISTRY.lock().map_err(|e| poisoned(e.to_string()))?.len(),
+        posting_cursors: POSTING_CURSOR_REGISTRY.lock().map_err(|e| poisoned(e.to_string()))?.len(),
+        streaming_builders: STREAMING_BUILDER_REGISTRY.lock().map_err(|e| poisoned(e.to_string()))?.len(),
+    })
+}
+
+#[cfg(all(test, feature = "jni"))]
 mod tests {
     use super::*;
 
     #[test]
     fn test_handle_generation() {
-        let handle1 = generate_handle();
-        let handle2 = generate_handle();
+        let handle1 = generate_handle(HandleKind::Reader);
+        let handle2 = generate_handle(HandleKind::Reader);
         assert_ne!(handle1, handle2);
         assert!(handle1 > 0);
         assert!(handle2 > 0);
+        assert_eq!(HandleKind::of(handle1), Some(HandleKind::Reader));
     }
 
     #[test]
     fn test_generator_registry() {
         // This is a placeholder test - would need actual generator instance
-        let handle = generate_handle();
+        let handle = generate_handle(HandleKind::Generator);
         assert!(handle > 0);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_check_handle_rejects_mismatched_kind() {
+        let handle = generate_handle(HandleKind::Generator);
+        assert!(check_handle(handle, HandleKind::Generator).is_ok());
+
+        match check_handle(handle, HandleKind::Reader) {
+            Err(error::SplitsError::InvalidOperation(msg)) => {
+                assert_eq!(msg, "expected reader handle, got generator handle");
+            }
+            other => panic!("Expected InvalidOperation, got {:?}", other),
+        }
+
+        // Untagged handles, e.g. a stale or forged jlong, are rejected too
+        assert!(matches!(check_handle(42, HandleKind::Reader), Err(error::SplitsError::InvalidOperation(_))));
+    }
+
+    #[test]
+    fn test_claim_stale_handles() {
+        let start = Instant::now();
+        let at = |millis: u64| HandleActivity { created_at: SystemTime::now(), last_used: start + Duration::from_millis(millis) };
+        let mut activity = HashMap::from([(1, at(0)), (2, at(150)), (3, at(250))]);
+        
+        let now = start + Duration::from_millis(200);
+        assert_eq!(claim_stale_handles(&mut activity, now, Duration::from_millis(100)), vec![1]);
+        // Handles used after `now`, as by a concurrent call, aren't stale
+        assert_eq!(activity.keys().copied().collect::<std::collections::BTreeSet<_>>(), [2, 3].into());
+        assert!(claim_stale_handles(&mut activity, now, Duration::from_millis(100)).is_empty());
+    }
+
+    #[test]
+    fn test_reap_stale_handles() {
+        let new_generator = || {
+            let mut schema_builder = tantivy::schema::Schema::builder();
+            schema_builder.add_text_field("title", tantivy::schema::TEXT);
+            let index = tantivy::Index::create_in_ram(schema_builder.build());
+            QuickwitSplitGenerator::new(index, 1000).unwrap()
+        };
+        
+        let stale = register_generator(new_generator());
+        let used = register_generator(new_generator());
+        assert!(handle_created_at(stale).is_some());
+        
+        // Using a handle marks it as used now
+        let before_use = Instant::now();
+        check_handle(used, HandleKind::Generator).unwrap();
+        assert!(HANDLE_ACTIVITY.lock().unwrap()[&used].last_used >= before_use);
+        
+        // Reaping handles this test claimed leaves other tests' handles alone
+        assert_eq!(unregister_stale_handles(vec![stale]), 1);
+        assert_eq!(unregister_stale_handles(vec![stale]), 0);
+        assert!(handle_created_at(stale).is_none());
+        assert!(!GENERATOR_REGISTRY.lock().unwrap().contains_key(&stale));
+        assert!(GENERATOR_REGISTRY.lock().unwrap().contains_key(&used));
+        assert!(unregister_generator(used));
+    }
+}
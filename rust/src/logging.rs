@@ -0,0 +1,154 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+//! Routing of native log records to a Java `LogListener`
+//!
+//! The crate logs through the `log` facade. Once a listener is set from
+//! Java, a process-wide logger forwards each record at debug level and above
+//! to it; with no listener, logging is disabled.
+//!
+//! Records can be emitted from any thread, including pool threads the JVM
+//! doesn't know about, which `jvm::with_attached_env` attaches for the
+//! duration of the call. The
+//! listener is cloned out of its lock before calling into the JVM, panics are
+//! contained, and exceptions thrown by the listener are cleared so they never
+//! surface in an unrelated native call.
+
+use crate::error::{Result, SplitsError};
+use crate::jvm::with_attached_env;
+use jni::objects::{GlobalRef, JObject, JValue};
+use jni::JNIEnv;
+use log::{LevelFilter, Log, Metadata, Record};
+use once_cell::sync::Lazy;
+use std::cell::Cell;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::{Arc, RwLock};
+
+/// Most verbose level forwarded to the listener
+const FORWARDED_LEVEL: LevelFilter = LevelFilter::Debug;
+
+/// A Java `LogListener`
+struct JavaListener {
+    listener: GlobalRef,
+}
+
+static LISTENER: Lazy<RwLock<Option<Arc<JavaListener>>>> = Lazy::new(|| RwLock::new(None));
+
+/// Whether `JavaLogger` is the process logger; `log` allows installing one only once
+static LOGGER_INSTALLED: Lazy<bool> = Lazy::new(|| log::set_logger(&JavaLogger).is_ok());
+
+thread_local! {
+    /// Set while a record is delivered, so records logged on the way, e.g. by
+    /// a listener calling back into the library, are dropped instead of recursing
+    static DELIVERING: Cell<bool> = Cell::new(false);
+}
+
+/// Sets the Java listener receiving log records, or disables logging with `None`
+///
+/// Fails with `InvalidOperation` if the host process installed another logger.
+pub fn set_log_listener(listener: Option<GlobalRef>) -> Result<()> {
+    if !*LOGGER_INSTALLED {
+        return Err(SplitsError::InvalidOperation(
+            "Another logger is already installed in this process".to_string()
+        ));
+    }
+    
+    let enabled = listener.is_some();
+    let listener = listener.map(|listener| Arc::new(JavaListener { listener }));
+    let previous = {
+        let mut current = LISTENER.write()
+            .map_err(|e| SplitsError::InvalidOperation(format!("Failed to access log listener: {}", e)))?;
+        std::mem::replace(&mut *current, listener)
+    };
+    log::set_max_level(if enabled { FORWARDED_LEVEL } else { LevelFilter::Off });
+    
+    // Releasing the global reference calls into the JVM, so it happens after the lock is released
+    drop(previous);
+    Ok(())
+}
+
+struct JavaLogger;
+
+impl Log for JavaLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level()
+    }
+    
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) || DELIVERING.with(|delivering| delivering.replace(true)) {
+            return;
+        }
+        
+        let listener = LISTENER.read().ok().and_then(|listener| listener.clone());
+        if let Some(listener) = listener {
+            // Losing a record beats unwinding into the caller, or across the JNI boundary
+            let _ = panic::catch_unwind(AssertUnwindSafe(|| with_attached_env(|env| {
+                listener.deliver(env, record)
+                    .map_err(|e| SplitsError::Jni(format!("Log listener failed: {}", e)))
+            })));
+        }
+        
+        DELIVERING.with(|delivering| delivering.set(false));
+    }
+    
+    fn flush(&self) {}
+}
+
+impl JavaListener {
+    /// Calls the listener's `onLog` with the record, on the current thread
+    fn deliver(&self, env: &JNIEnv, record: &Record) -> jni::errors::Result<()> {
+        // No JNI call is allowed while an exception is pending, e.g. when
+        // logging that throwing one failed
+        if env.exception_check()? {
+            return Ok(());
+        }
+        
+        let level_name = env.new_string(record.level().as_str())?;
+        let level = env.call_static_method(
+            "com/tantivy4java/splits/LogListener$Level",
+            "valueOf",
+            "(Ljava/lang/String;)Lcom/tantivy4java/splits/LogListener$Level;",
+            &[JValue::Object(level_name.into())],
+        )?.l()?;
+        let target = env.new_string(record.target())?;
+        let message = env.new_string(record.args().to_string())?;
+        
+        let result = env.call_method(
+            self.listener.as_obj(),
+            "onLog",
+            "(Lcom/tantivy4java/splits/LogListener$Level;Ljava/lang/String;Ljava/lang/String;)V",
+            &[
+                JValue::Object(level),
+                JValue::Object(target.into()),
+                JValue::Object(message.into()),
+            ],
+        );
+        
+        if env.exception_check()? {
+            env.exception_clear()?;
+        }
+        
+        // Records logged during a long native call would otherwise pile up local references
+        for local in [level, JObject::from(level_name), JObject::from(target), JObject::from(message)] {
+            env.delete_local_ref(local)?;
+        }
+        
+        result.map(|_| ())
+    }
+}
@@ -0,0 +1,198 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+//! Memory budget shared by the caches of every open split reader
+//!
+//! Readers keep expensive state, such as the opened Tantivy index, in cache
+//! slots registered here. When the total size of the cached values exceeds
+//! the budget, the least recently used slots are evicted across all readers;
+//! an evicted value is rebuilt transparently on next use.
+//!
+//! Sizes are estimates. An opened index is charged the size of the split
+//! files it maps, but mmap pages are managed by the OS, which may keep them
+//! resident or page them out regardless of this accounting: the budget bounds
+//! what readers pin, not the process RSS.
+
+use crate::error::{Result, SplitsError};
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+
+/// Budget in bytes, 0 meaning unlimited
+static BUDGET_BYTES: AtomicU64 = AtomicU64::new(0);
+
+/// Source of slot ids
+static NEXT_SLOT_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Logical clock ordering slot accesses
+static CLOCK: AtomicU64 = AtomicU64::new(0);
+
+/// Cached slots, by slot id
+static ENTRIES: Lazy<Mutex<HashMap<u64, Entry>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Accounting of a populated cache slot
+struct Entry {
+    /// Estimated size of the cached value
+    size_bytes: u64,
+    /// Clock value at last access
+    last_used: u64,
+    /// The slot, gone once its reader is dropped
+    slot: Weak<dyn Evict + Send + Sync>,
+}
+
+/// A cache slot whose value can be dropped to reclaim memory
+trait Evict {
+    fn evict(&self);
+}
+
+/// Sets the memory budget of the reader caches, evicting down to it
+///
+/// A budget of 0 disables the limit, which is the default.
+pub fn set_reader_memory_budget(bytes: u64) -> Result<()> {
+    BUDGET_BYTES.store(bytes, Ordering::SeqCst);
+    
+    let mut entries = lock_entries()?;
+    evict_over_budget(&mut entries, None);
+    Ok(())
+}
+
+/// Gets the memory budget of the reader caches, 0 meaning unlimited
+pub fn reader_memory_budget() -> u64 {
+    BUDGET_BYTES.load(Ordering::SeqCst)
+}
+
+/// Gets the estimated memory currently held by reader caches
+pub fn cached_bytes() -> Result<u64> {
+    let mut entries = lock_entries()?;
+    entries.retain(|_, entry| entry.slot.strong_count() > 0);
+    Ok(entries.values().map(|entry| entry.size_bytes).sum())
+}
+
+fn lock_entries() -> Result<std::sync::MutexGuard<'static, HashMap<u64, Entry>>> {
+    ENTRIES.lock()
+        .map_err(|e| SplitsError::InvalidOperation(format!("Failed to access reader cache registry: {}", e)))
+}
+
+/// Evicts least recently used slots until the budget is met
+///
+/// `keep` is never evicted, so a single value larger than the budget can
+/// still be used.
+fn evict_over_budget(entries: &mut HashMap<u64, Entry>, keep: Option<u64>) {
+    entries.retain(|_, entry| entry.slot.strong_count() > 0);
+    
+    let budget = reader_memory_budget();
+    if budget == 0 {
+        return;
+    }
+    
+    let mut total: u64 = entries.values().map(|entry| entry.size_bytes).sum();
+    while total > budget {
+        let victim = entries.iter()
+            .filter(|(id, _)| Some(**id) != keep)
+            .min_by_key(|(_, entry)| entry.last_used)
+            .map(|(id, _)| *id);
+        let victim = match victim {
+            Some(victim) => victim,
+            None => break,
+        };
+        
+        if let Some(entry) = entries.remove(&victim) {
+            total -= entry.size_bytes;
+            if let Some(slot) = entry.slot.upgrade() {
+                slot.evict();
+            }
+        }
+    }
+}
+
+/// A lazily built value accounted against the reader memory budget
+///
+/// Values are handed out as clones, so they must be cheap to clone, e.g.
+/// reference counted. Evicting a slot doesn't invalidate clones already
+/// handed out; it only drops the cache's own reference.
+pub(crate) struct CacheSlot<T> {
+    id: u64,
+    value: Mutex<Option<T>>,
+}
+
+impl<T: Clone + Send + 'static> CacheSlot<T> {
+    /// Creates an empty slot
+    pub(crate) fn new() -> Arc<Self> {
+        Arc::new(CacheSlot {
+            id: NEXT_SLOT_ID.fetch_add(1, Ordering::Relaxed),
+            value: Mutex::new(None),
+        })
+    }
+    
+    /// Gets the cached value, if present, without marking it as used
+    pub(crate) fn peek(&self) -> Option<T> {
+        self.value.lock().ok().and_then(|value| value.clone())
+    }
+    
+    /// Gets the cached value, building it with `init` if absent
+    ///
+    /// `init` returns the value and its estimated size in bytes. Admitting a
+    /// new value may evict other slots to stay within the budget.
+    pub(crate) fn get_or_try_init(self: &Arc<Self>, init: impl FnOnce() -> Result<(T, u64)>) -> Result<T> {
+        let (value, size_bytes) = {
+            let mut cached = self.value.lock()
+                .map_err(|e| SplitsError::InvalidOperation(format!("Failed to access reader cache: {}", e)))?;
+            if let Some(value) = cached.as_ref() {
+                let value = value.clone();
+                drop(cached);
+                self.touch()?;
+                return Ok(value);
+            }
+            
+            let (value, size_bytes) = init()?;
+            *cached = Some(value.clone());
+            (value, size_bytes)
+        };
+        
+        // The slot lock is released first: eviction locks other slots while
+        // holding the registry, so the reverse order could deadlock
+        let slot: Weak<dyn Evict + Send + Sync> = Arc::downgrade(self) as Weak<dyn Evict + Send + Sync>;
+        let mut entries = lock_entries()?;
+        entries.insert(self.id, Entry {
+            size_bytes,
+            last_used: CLOCK.fetch_add(1, Ordering::Relaxed),
+            slot,
+        });
+        evict_over_budget(&mut entries, Some(self.id));
+        
+        Ok(value)
+    }
+    
+    /// Marks the slot as most recently used
+    fn touch(&self) -> Result<()> {
+        if let Some(entry) = lock_entries()?.get_mut(&self.id) {
+            entry.last_used = CLOCK.fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+}
+
+impl<T: Send> Evict for CacheSlot<T> {
+    fn evict(&self) {
+        if let Ok(mut value) = self.value.lock() {
+            *value = None;
+        }
+    }
+}
@@ -0,0 +1,164 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+//! Detached signatures of split files
+//!
+//! A signature is a keyed BLAKE3 MAC over a manifest of every file of the
+//! split and its BLAKE3 hash, stored as JSON in a `split.sig` file inside the
+//! split directory. Anyone holding the key can check that no file was added,
+//! removed or altered since signing. Being a MAC, the same secret key signs
+//! and verifies.
+
+use crate::error::{Result, SplitsError};
+use crate::streaming::hash_file;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the signature file within a signed split
+pub const SIGNATURE_FILE_NAME: &str = "split.sig";
+
+/// Algorithm recorded in signatures produced by this version
+const SIGNATURE_ALGORITHM: &str = "blake3-keyed";
+
+/// Length of signing keys in bytes
+pub const SIGNING_KEY_LEN: usize = 32;
+
+/// Contents of a signature file
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct SplitSignature {
+    /// MAC algorithm
+    algorithm: String,
+    /// BLAKE3 hash of each signed file, by file name
+    files: BTreeMap<String, String>,
+    /// Keyed hash of the manifest of `files`, hex encoded
+    mac: String,
+}
+
+/// Signs the files of a split, writing the signature into the split directory
+///
+/// Any previous signature is replaced. Returns the path of the signature file.
+pub fn sign_split(split_path: &Path, key: &[u8]) -> Result<PathBuf> {
+    let key = signing_key(key)?;
+    let files = hash_split_files(split_path)?;
+    let signature = SplitSignature {
+        algorithm: SIGNATURE_ALGORITHM.to_string(),
+        mac: manifest_mac(&key, &files).to_hex().to_string(),
+        files,
+    };
+    
+    let signature_path = split_path.join(SIGNATURE_FILE_NAME);
+    fs::write(&signature_path, serde_json::to_vec_pretty(&signature)?)?;
+    Ok(signature_path)
+}
+
+/// Checks a split against its signature
+///
+/// Fails with `InvalidSplit` if the split isn't signed, or if its files don't
+/// match the signature under `key`.
+pub fn verify_split(split_path: &Path, key: &[u8]) -> Result<()> {
+    let key = signing_key(key)?;
+    let signature_path = split_path.join(SIGNATURE_FILE_NAME);
+    let json = fs::read(&signature_path).map_err(|e| SplitsError::InvalidSplit(
+        format!("Failed to read split signature {}: {}", signature_path.display(), e)
+    ))?;
+    let signature: SplitSignature = serde_json::from_slice(&json)?;
+    
+    if signature.algorithm != SIGNATURE_ALGORITHM {
+        return Err(SplitsError::InvalidSplit(format!(
+            "Unsupported split signature algorithm: {}", signature.algorithm
+        )));
+    }
+    
+    // Constant-time comparison of the MAC over the signed manifest
+    let expected = blake3::Hash::from_hex(&signature.mac)
+        .map_err(|e| SplitsError::InvalidSplit(format!("Malformed split signature: {}", e)))?;
+    if manifest_mac(&key, &signature.files) != expected {
+        return Err(SplitsError::InvalidSplit("Split signature does not match the key".to_string()));
+    }
+    
+    if hash_split_files(split_path)? != signature.files {
+        return Err(SplitsError::InvalidSplit("Split files do not match their signature".to_string()));
+    }
+    
+    Ok(())
+}
+
+/// Checks and converts a caller-supplied key
+fn signing_key(key: &[u8]) -> Result<[u8; SIGNING_KEY_LEN]> {
+    key.try_into().map_err(|_| SplitsError::InvalidOperation(format!(
+        "Signing key must be {} bytes, got {}", SIGNING_KEY_LEN, key.len()
+    )))
+}
+
+/// Hashes every file of the split but the signature itself
+fn hash_split_files(split_path: &Path) -> Result<BTreeMap<String, String>> {
+    let mut files = BTreeMap::new();
+    for entry in fs::read_dir(split_path)? {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if !entry.metadata()?.is_file() || file_name == SIGNATURE_FILE_NAME {
+            continue;
+        }
+        files.insert(file_name, hash_file(&entry.path())?.hash.to_hex().to_string());
+    }
+    Ok(files)
+}
+
+/// Computes the MAC of a file manifest, one `name hash` line per file
+fn manifest_mac(key: &[u8; SIGNING_KEY_LEN], files: &BTreeMap<String, String>) -> blake3::Hash {
+    let mut hasher = blake3::Hasher::new_keyed(key);
+    for (file_name, hash) in files {
+        hasher.update(file_name.as_bytes());
+        hasher.update(b" ");
+        hasher.update(hash.as_bytes());
+        hasher.update(b"\n");
+    }
+    hasher.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    
+    #[test]
+    fn test_signature_detects_tampering() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.store"), b"store").unwrap();
+        fs::write(temp_dir.path().join("meta.json"), b"{}").unwrap();
+        let key = [7u8; SIGNING_KEY_LEN];
+        
+        let signature_path = sign_split(temp_dir.path(), &key).unwrap();
+        assert_eq!(signature_path, temp_dir.path().join(SIGNATURE_FILE_NAME));
+        verify_split(temp_dir.path(), &key).unwrap();
+        
+        assert!(matches!(verify_split(temp_dir.path(), &[8u8; SIGNING_KEY_LEN]), Err(SplitsError::InvalidSplit(_))));
+        
+        fs::write(temp_dir.path().join("a.store"), b"tampered").unwrap();
+        assert!(matches!(verify_split(temp_dir.path(), &key), Err(SplitsError::InvalidSplit(_))));
+    }
+    
+    #[test]
+    fn test_signing_key_length_is_checked() {
+        let temp_dir = TempDir::new().unwrap();
+        assert!(matches!(sign_split(temp_dir.path(), b"short"), Err(SplitsError::InvalidOperation(_))));
+    }
+}
@@ -0,0 +1,1147 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+//! In-memory split serialization and merging
+//!
+//! A split serialized to bytes is a flat list of its files:
+//!
+//! ```text
+//! magic "QWSPLIT\x01" (8 bytes)
+//! file count             u32 LE
+//! per file, sorted by name (streamed splits put the store file last):
+//!   name length          u32 LE
+//!   name                 UTF-8
+//!   data length          u64 LE
+//!   data
+//! ```
+//!
+//! This lets splits held in memory, e.g. freshly downloaded from object
+//! storage, be merged without touching local disk. The same bytes written
+//! to a `<split_id>.split` file make a single-file split, as Quickwit names
+//! them, which readers open in place without unpacking.
+
+use crate::error::{Result, SplitsError};
+use crate::hotcache::{create_hotcache, ByteRange, HotcacheInfo, Provenance};
+use crate::split_generator::{encode_footer, locate_footer, StoreFooter};
+use crate::split_reader::FieldKind;
+use crate::streaming::COPY_BUFFER_SIZE;
+use crate::thread_pool;
+use crate::tokenizers::TokenizerConfig;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::io::{self, BufReader, ErrorKind, Read, Seek, Write};
+use std::path::Path;
+use tantivy::directory::{Directory, RamDirectory};
+use tantivy::schema::{Field, Schema, SchemaBuilder};
+use tantivy::{Index, IndexMeta, IndexSettings, ReloadPolicy, TantivyDocument, TantivyError};
+
+/// Magic number opening a serialized split, including the format version
+const SPLIT_BYTES_MAGIC: &[u8; 8] = b"QWSPLIT\x01";
+
+/// Name of the Tantivy index meta file embedded in a split
+const META_FILE_NAME: &str = "meta.json";
+
+/// Memory budget of the index writer merging in-memory splits
+const MERGE_MEMORY_BUDGET_BYTES: usize = 50_000_000;
+
+/// Largest split that fits in a Java byte array
+pub const MAX_SPLIT_BYTES: usize = i32::MAX as usize;
+
+/// Size of every chunk passed to an upload callback but the last
+///
+/// Above the 5 MiB minimum part size of S3 multipart uploads.
+pub const UPLOAD_CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Extension of single-file splits
+pub const SPLIT_FILE_EXTENSION: &str = "split";
+
+/// Receives a serialized split chunk by chunk, with the offset of each chunk
+pub type UploadFn<'a> = dyn Fn(&[u8], u64) -> Result<()> + 'a;
+
+/// Streams the split directory at `split_dir` to `upload`, serialized
+///
+/// Chunks are passed in order, back to back, and are `UPLOAD_CHUNK_SIZE`
+/// bytes except the last, so chunk `offset / UPLOAD_CHUNK_SIZE` maps to a
+/// multipart upload part. Files are sorted by name, except the store file
+/// carrying the hotcache footer, which comes last: the footer is in the last
+/// chunk. Files are read incrementally, never holding the serialized split in
+/// memory. Returns the serialized size; fails on the first upload error.
+pub fn stream_split(split_dir: &Path, upload: &UploadFn) -> Result<u64> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(split_dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if entry.file_type()?.is_file() && !file_name.starts_with('.') {
+            files.push((file_name, entry.metadata()?.len()));
+        }
+    }
+    files.sort_by_key(|(name, _)| (name.ends_with(".store"), name.clone()));
+    
+    let mut chunks = ChunkedUpload { upload, buffer: Vec::with_capacity(UPLOAD_CHUNK_SIZE), offset: 0 };
+    chunks.write(SPLIT_BYTES_MAGIC)?;
+    chunks.write(&(files.len() as u32).to_le_bytes())?;
+    
+    let mut read_buffer = vec![0u8; COPY_BUFFER_SIZE];
+    for (name, len) in &files {
+        chunks.write(&(name.len() as u32).to_le_bytes())?;
+        chunks.write(name.as_bytes())?;
+        chunks.write(&len.to_le_bytes())?;
+        
+        let mut file = fs::File::open(split_dir.join(name))?.take(*len);
+        let mut copied = 0u64;
+        loop {
+            let read = file.read(&mut read_buffer)?;
+            if read == 0 {
+                break;
+            }
+            chunks.write(&read_buffer[..read])?;
+            copied += read as u64;
+        }
+        if copied != *len {
+            return Err(SplitsError::InvalidOperation(format!(
+                "Split file {} changed while being streamed", name
+            )));
+        }
+    }
+    
+    chunks.finish()
+}
+
+/// Regroups written bytes into `UPLOAD_CHUNK_SIZE` chunks passed to an upload callback
+struct ChunkedUpload<'a, 'b> {
+    upload: &'a UploadFn<'b>,
+    buffer: Vec<u8>,
+    offset: u64,
+}
+
+impl ChunkedUpload<'_, '_> {
+    fn write(&mut self, mut data: &[u8]) -> Result<()> {
+        while !data.is_empty() {
+            let taken = data.len().min(UPLOAD_CHUNK_SIZE - self.buffer.len());
+            self.buffer.extend_from_slice(&data[..taken]);
+            data = &data[taken..];
+            if self.buffer.len() == UPLOAD_CHUNK_SIZE {
+                self.flush()?;
+            }
+        }
+        Ok(())
+    }
+    
+    fn flush(&mut self) -> Result<()> {
+        (self.upload)(&self.buffer, self.offset)?;
+        self.offset += self.buffer.len() as u64;
+        self.buffer.clear();
+        Ok(())
+    }
+    
+    /// Uploads the last chunk, possibly short, and returns the total size
+    fn finish(mut self) -> Result<u64> {
+        if !self.buffer.is_empty() {
+            self.flush()?;
+        }
+        Ok(self.offset)
+    }
+}
+
+/// Writes the split directory at `split_dir` to a single split file at `path`
+///
+/// The file holds the serialized split, laid out as by `stream_split`, and is
+/// fsynced before returning its size and `split_file_checksum`, computed
+/// while writing. `path` is created or truncated.
+pub fn write_split_file(split_dir: &Path, path: &Path) -> Result<(u64, String)> {
+    let file = fs::File::create(path)?;
+    let hasher = RefCell::new(blake3::Hasher::new());
+    let len = stream_split(split_dir, &|chunk, _offset| {
+        hasher.borrow_mut().update(chunk);
+        Ok((&file).write_all(chunk)?)
+    })?;
+    file.sync_all()?;
+    Ok((len, hasher.into_inner().finalize().to_hex().to_string()))
+}
+
+/// Copies the split file at `path` to `output`, replacing the data of its file `name`
+///
+/// Every other file is copied byte for byte, in the same order; `name` gets
+/// the `data_len` bytes read from `data` instead. `output` is created or
+/// truncated, and fsynced before returning its size.
+pub fn write_split_file_replacing(path: &Path, output: &Path, name: &str, data: &mut dyn Read, data_len: u64) -> Result<u64> {
+    let files = split_file_ranges(path)?;
+    if !files.iter().any(|(file_name, _)| file_name == name) {
+        return Err(SplitsError::InvalidSplit(format!("File {} missing from split {}", name, path.display())));
+    }
+    
+    let mut input = fs::File::open(path)?;
+    let mut writer = io::BufWriter::new(fs::File::create(output)?);
+    writer.write_all(SPLIT_BYTES_MAGIC)?;
+    writer.write_all(&(files.len() as u32).to_le_bytes())?;
+    let mut len = SPLIT_BYTES_MAGIC.len() as u64 + 4;
+    for (file_name, range) in &files {
+        writer.write_all(&(file_name.len() as u32).to_le_bytes())?;
+        writer.write_all(file_name.as_bytes())?;
+        let (source, file_len): (&mut dyn Read, u64) = if file_name == name {
+            (&mut *data, data_len)
+        } else {
+            input.seek(io::SeekFrom::Start(range.start))?;
+            (&mut input, range.size())
+        };
+        writer.write_all(&file_len.to_le_bytes())?;
+        let copied = io::copy(&mut source.take(file_len), &mut writer)?;
+        if copied != file_len {
+            return Err(SplitsError::InvalidSplit(format!(
+                "File {} of split {} ended after {} of {} bytes", file_name, path.display(), copied, file_len
+            )));
+        }
+        len += 4 + file_name.len() as u64 + 8 + file_len;
+    }
+    
+    writer.into_inner().map_err(|e| SplitsError::Io(e.into_error()))?.sync_all()?;
+    Ok(len)
+}
+
+/// Computes the checksum of the split file at `path`
+///
+/// The checksum is the hex BLAKE3 hash of the file's raw bytes, hotcache
+/// footer included, so it changes with any byte of the file, but also with
+/// anything that doesn't change the documents, such as the split id.
+/// Compare it against a hash of the same bytes after a transfer; use
+/// `QuickwitSplitReader::content_digest` to compare logical content.
+pub fn split_file_checksum(path: &Path) -> Result<String> {
+    let mut hasher = blake3::Hasher::new();
+    io::copy(&mut fs::File::open(path)?, &mut hasher)?;
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+/// Computes the checksum of the split directory at `split_dir`
+///
+/// This is the `split_file_checksum` of the directory serialized by
+/// `stream_split`, so it matches the checksum of its split file, and of the
+/// bytes streamed to an upload.
+pub fn split_dir_checksum(split_dir: &Path) -> Result<String> {
+    let hasher = RefCell::new(blake3::Hasher::new());
+    stream_split(split_dir, &|chunk, _offset| {
+        hasher.borrow_mut().update(chunk);
+        Ok(())
+    })?;
+    Ok(hasher.into_inner().finalize().to_hex().to_string())
+}
+
+/// Lists the files of the split file at `path`, with the byte range of each within it
+///
+/// Only the file headers are read; file data is skipped over, so this is
+/// cheap whatever the split size.
+pub fn split_file_ranges(path: &Path) -> Result<Vec<(String, ByteRange)>> {
+    let file = fs::File::open(path)?;
+    let total_len = file.metadata()?.len();
+    let mut reader = BufReader::new(file);
+    let truncated = || SplitsError::InvalidSplit(format!("Split file {} is truncated", path.display()));
+    let read_array = |reader: &mut BufReader<fs::File>, buffer: &mut [u8]| {
+        reader.read_exact(buffer).map_err(|e| match e.kind() {
+            ErrorKind::UnexpectedEof => truncated(),
+            _ => SplitsError::Io(e),
+        })
+    };
+    
+    let mut magic = [0u8; 8];
+    read_array(&mut reader, &mut magic)?;
+    if &magic != SPLIT_BYTES_MAGIC {
+        return Err(SplitsError::InvalidSplit(format!(
+            "Not a split file: bad magic number in {}", path.display()
+        )));
+    }
+    
+    let mut u32_bytes = [0u8; 4];
+    let mut u64_bytes = [0u8; 8];
+    read_array(&mut reader, &mut u32_bytes)?;
+    let num_files = u32::from_le_bytes(u32_bytes);
+    let mut files = Vec::new();
+    for _ in 0..num_files {
+        read_array(&mut reader, &mut u32_bytes)?;
+        let name_len = u32::from_le_bytes(u32_bytes) as u64;
+        if name_len > total_len {
+            return Err(truncated());
+        }
+        let mut name = vec![0u8; name_len as usize];
+        read_array(&mut reader, &mut name)?;
+        let name = String::from_utf8(name)
+            .map_err(|_| SplitsError::InvalidSplit("Split file name is not valid UTF-8".to_string()))?;
+        read_array(&mut reader, &mut u64_bytes)?;
+        let data_len = u64::from_le_bytes(u64_bytes);
+        
+        let start = reader.stream_position()?;
+        let end = start.checked_add(data_len)
+            .filter(|end| *end <= total_len)
+            .ok_or_else(truncated)?;
+        reader.seek_relative(data_len as i64)?;
+        files.push((name, ByteRange { start, end }));
+    }
+    
+    let end = reader.stream_position()?;
+    if end != total_len {
+        return Err(SplitsError::InvalidSplit(format!(
+            "Split file {} has {} trailing bytes", path.display(), total_len - end
+        )));
+    }
+    
+    Ok(files)
+}
+
+/// Serializes the split directory at `split_dir` into bytes
+///
+/// Hidden files, such as Tantivy lock files, are skipped.
+pub fn split_to_bytes(split_dir: &Path) -> Result<Vec<u8>> {
+    let mut files = Vec::new();
+    for entry in fs::read_dir(split_dir)? {
+        let entry = entry?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        if entry.file_type()?.is_file() && !file_name.starts_with('.') {
+            files.push((file_name, fs::read(entry.path())?));
+        }
+    }
+    
+    encode_split_files(files)
+}
+
+/// Parses serialized split bytes into `(file name, data)` pairs
+pub fn split_files_from_bytes(bytes: &[u8]) -> Result<Vec<(String, &[u8])>> {
+    let mut cursor = ByteCursor { bytes, pos: 0 };
+    
+    if cursor.take(SPLIT_BYTES_MAGIC.len())? != SPLIT_BYTES_MAGIC {
+        return Err(SplitsError::InvalidSplit(
+            "Not a serialized split: bad magic number".to_string()
+        ));
+    }
+    
+    let num_files = u32::from_le_bytes(cursor.take_array()?);
+    let mut files = Vec::with_capacity(num_files as usize);
+    for _ in 0..num_files {
+        let name_len = u32::from_le_bytes(cursor.take_array()?) as usize;
+        let name = std::str::from_utf8(cursor.take(name_len)?)
+            .map_err(|_| SplitsError::InvalidSplit("Split file name is not valid UTF-8".to_string()))?;
+        let data_len = u64::from_le_bytes(cursor.take_array()?) as usize;
+        files.push((name.to_string(), cursor.take(data_len)?));
+    }
+    
+    if cursor.pos != bytes.len() {
+        return Err(SplitsError::InvalidSplit(format!(
+            "Serialized split has {} trailing bytes", bytes.len() - cursor.pos
+        )));
+    }
+    
+    Ok(files)
+}
+
+/// How the schemas of merged splits must relate, see `merge_split_bytes_with`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SchemaCompatibility {
+    /// All splits must have the same schema, fields declared in the same order
+    #[default]
+    Strict,
+    /// Splits may declare their fields in any order, and each may lack fields
+    /// of the others, as long as fields of the same name are identical
+    Union,
+}
+
+impl SchemaCompatibility {
+    /// Maps the code passed across JNI: 0 `Strict`, 1 `Union`
+    pub fn from_code(code: i32) -> Result<Self> {
+        match code {
+            0 => Ok(SchemaCompatibility::Strict),
+            1 => Ok(SchemaCompatibility::Union),
+            other => Err(SplitsError::InvalidOperation(format!("Unknown schema compatibility code {}", other))),
+        }
+    }
+}
+
+/// Merges serialized splits into a single serialized split, entirely in memory
+///
+/// All splits must share the same schema; see `merge_split_bytes_with` to
+/// merge splits with compatible schemas. The merged split holds a single
+/// segment and a hotcache footer, like splits written by the generator. Fails
+/// if the result is larger than `MAX_SPLIT_BYTES`, or with `InvalidSplit` if
+/// two splits hold the same segment (e.g. one is a copy of the other), since
+/// their files would collide.
+///
+/// The merged hotcache carries the tokenizers, custom metadata and opstamp
+/// recorded in the splits' hotcaches. Merging fails with `InvalidOperation`
+/// if two splits record different tokenizers of the same name, different
+/// values of the same custom metadata key, or different opstamps.
+pub fn merge_split_bytes(splits: &[&[u8]]) -> Result<Vec<u8>> {
+    merge_split_bytes_with(splits, SchemaCompatibility::Strict)
+}
+
+/// Merges serialized splits whose schemas relate as `compatibility` allows
+///
+/// With `SchemaCompatibility::Union`, the merged split's schema holds every
+/// field of every split: those of the first split in their order, then those
+/// only later splits have, in order of appearance. Documents of a split
+/// lacking a field have no value for it. Fields of the same name must have
+/// the same type and options in every split, or the merge fails with
+/// `InvalidOperation`.
+///
+/// Splits with identical schemas are merged as by `merge_split_bytes`.
+/// Otherwise Tantivy can't merge their segments, which number fields by
+/// declaration order, so every document is re-indexed from the doc store into
+/// the merged schema: expect roughly the cost of indexing them again, and a
+/// `FieldError` if a split has fields that aren't stored. Custom tokenizers
+/// recorded in the splits' hotcaches are used for re-indexing.
+pub fn merge_split_bytes_with(splits: &[&[u8]], compatibility: SchemaCompatibility) -> Result<Vec<u8>> {
+    if splits.is_empty() {
+        return Err(SplitsError::InvalidOperation("No splits to merge".to_string()));
+    }
+    let hotcache_metadata = merged_hotcache_metadata(splits)?;
+    
+    if compatibility == SchemaCompatibility::Union {
+        let schemas = splits.iter()
+            .enumerate()
+            .map(|(split_idx, split)| split_schema(split, split_idx))
+            .collect::<Result<Vec<_>>>()?;
+        if schemas.windows(2).any(|pair| pair[0] != pair[1]) {
+            return reindex_split_bytes(splits, union_schema(&schemas)?, &hotcache_metadata);
+        }
+    }
+    
+    // Gather every split's segment files into one directory
+    let directory = RamDirectory::create();
+    let mut merged_meta: Option<IndexMeta> = None;
+    let mut segment_owners = HashMap::new();
+    
+    for (split_idx, split) in splits.iter().enumerate() {
+        let (split_index, split_directory) = open_split_in_ram(split, split_idx)?;
+        let split_meta = split_index.load_metas()?;
+        
+        // Segment files are named after the segment id, so a shared id means colliding files
+        for segment_meta in &split_meta.segments {
+            if let Some(owner_idx) = segment_owners.insert(segment_meta.id(), split_idx) {
+                return Err(SplitsError::InvalidSplit(format!(
+                    "Splits {} and {} both contain segment {}", owner_idx, split_idx, segment_meta.id().uuid_string()
+                )));
+            }
+        }
+        
+        for segment_meta in &split_meta.segments {
+            for path in segment_meta.list_files() {
+                if !split_directory.exists(&path).map_err(TantivyError::from)? {
+                    continue;
+                }
+                let data = split_directory.open_read(&path)
+                    .map_err(TantivyError::from)?
+                    .read_bytes()?;
+                directory.atomic_write(&path, data.as_slice())?;
+            }
+        }
+        
+        match merged_meta.as_mut() {
+            None => merged_meta = Some(split_meta),
+            Some(merged_meta) => {
+                if merged_meta.schema != split_meta.schema {
+                    return Err(SplitsError::InvalidOperation(format!(
+                        "Split {} has a different schema than split 0", split_idx
+                    )));
+                }
+                merged_meta.opstamp = merged_meta.opstamp.max(split_meta.opstamp);
+                merged_meta.segments.extend(split_meta.segments);
+            }
+        }
+    }
+    
+    let merged_meta = merged_meta.expect("at least one split");
+    directory.atomic_write(Path::new(META_FILE_NAME), serde_json::to_string(&merged_meta)?.as_bytes())?;
+    
+    let index = Index::open(directory.clone())?;
+    let segment_ids = index.searchable_segment_ids()?;
+    if segment_ids.len() > 1 {
+        thread_pool::install(|| -> Result<()> {
+            let mut index_writer: tantivy::IndexWriter = index.writer(MERGE_MEMORY_BUDGET_BYTES)?;
+            index_writer.merge(&segment_ids).wait()?;
+            index_writer.wait_merging_threads()?;
+            Ok(())
+        })?;
+    }
+    
+    serialize_merged_split(&index, &directory, &hotcache_metadata)
+}
+
+/// Merges splits by re-indexing their stored documents into `schema`
+///
+/// Fields are matched by name, so `schema` must hold every field of every
+/// split with the same type and options, as built by `union_schema`.
+fn reindex_split_bytes(splits: &[&[u8]], schema: Schema, hotcache_metadata: &MergedHotcacheMetadata) -> Result<Vec<u8>> {
+    let directory = RamDirectory::create();
+    let index = Index::create(directory.clone(), schema.clone(), IndexSettings::default())?;
+    for (name, config) in &hotcache_metadata.tokenizers {
+        index.tokenizers().register(name, config.build()?);
+    }
+    
+    thread_pool::install(|| -> Result<()> {
+        let mut index_writer: tantivy::IndexWriter = index.writer(MERGE_MEMORY_BUDGET_BYTES)?;
+        for (split_idx, split) in splits.iter().enumerate() {
+            let (split_index, _) = open_split_in_ram(split, split_idx)?;
+            let split_schema = split_index.schema();
+            
+            let unstored: Vec<&str> = split_schema.fields()
+                .filter(|(_, entry)| !entry.is_stored())
+                .map(|(_, entry)| entry.name())
+                .collect();
+            if !unstored.is_empty() {
+                return Err(SplitsError::FieldError(format!(
+                    "Split {} must be re-indexed to merge it, but these fields aren't stored: {}",
+                    split_idx, unstored.join(", ")
+                )));
+            }
+            
+            // Indexed by the split's field ids
+            let fields: Vec<Field> = split_schema.fields()
+                .map(|(_, entry)| schema.get_field(entry.name()))
+                .collect::<std::result::Result<_, _>>()?;
+            
+            let searcher = split_index.reader_builder()
+                .reload_policy(ReloadPolicy::Manual)
+                .try_into()?
+                .searcher();
+            for segment_reader in searcher.segment_readers() {
+                let store_reader = segment_reader.get_store_reader(1)?;
+                for doc in store_reader.iter::<TantivyDocument>(segment_reader.alive_bitset()) {
+                    let doc = doc?;
+                    let mut merged_doc = TantivyDocument::new();
+                    for (field, value) in doc.field_values() {
+                        merged_doc.add_field_value(fields[field.field_id() as usize], value);
+                    }
+                    index_writer.add_document(merged_doc)?;
+                }
+            }
+        }
+        index_writer.commit()?;
+        
+        let segment_ids = index.searchable_segment_ids()?;
+        if segment_ids.len() > 1 {
+            index_writer.merge(&segment_ids).wait()?;
+        }
+        index_writer.wait_merging_threads()?;
+        Ok(())
+    })?;
+    
+    serialize_merged_split(&index, &directory, hotcache_metadata)
+}
+
+/// Builds a schema holding every field of `schemas`, in order of first appearance
+///
+/// Fails with `InvalidOperation` if two schemas declare the same field with
+/// different types or options.
+fn union_schema(schemas: &[Schema]) -> Result<Schema> {
+    let mut schema_builder = SchemaBuilder::default();
+    let mut fields = HashMap::new();
+    
+    for (split_idx, schema) in schemas.iter().enumerate() {
+        for (_, entry) in schema.fields() {
+            let (first_idx, first_entry) = match fields.get(entry.name()) {
+                Some(&(first_idx, ref first_entry)) => (first_idx, first_entry),
+                None => {
+                    fields.insert(entry.name().to_string(), (split_idx, entry.clone()));
+                    schema_builder.add_field(entry.clone());
+                    continue;
+                }
+            };
+            
+            let (first_kind, kind) = (FieldKind::of(first_entry.field_type()), FieldKind::of(entry.field_type()));
+            if first_kind != kind {
+                return Err(SplitsError::InvalidOperation(format!(
+                    "Field '{}' is of type {} in split {} but of type {} in split {}",
+                    entry.name(), first_kind.name(), first_idx, kind.name(), split_idx
+                )));
+            }
+            if first_entry != entry {
+                return Err(SplitsError::InvalidOperation(format!(
+                    "Field '{}' has different options in splits {} and {}",
+                    entry.name(), first_idx, split_idx
+                )));
+            }
+        }
+    }
+    
+    Ok(schema_builder.build())
+}
+
+/// Reads the schema of a serialized split from its `meta.json`, without loading the split
+fn split_schema(split: &[u8], split_idx: usize) -> Result<Schema> {
+    let files = split_files_from_bytes(split).map_err(|e| in_split(split_idx, e))?;
+    let (_, meta) = files.iter()
+        .find(|(file_name, _)| file_name == META_FILE_NAME)
+        .ok_or_else(|| SplitsError::InvalidSplit(format!(
+            "Split {} has no {}", split_idx, META_FILE_NAME
+        )))?;
+    
+    let mut meta: serde_json::Value = serde_json::from_slice(meta)?;
+    Ok(serde_json::from_value(meta["schema"].take())?)
+}
+
+/// Hotcache metadata of the merged splits carried over to the merged split
+#[derive(Debug, Default)]
+struct MergedHotcacheMetadata {
+    tokenizers: BTreeMap<String, TokenizerConfig>,
+    custom_metadata: BTreeMap<String, String>,
+    opstamp: Option<u64>,
+}
+
+/// Gathers the tokenizers, custom metadata and opstamps recorded in the splits' hotcaches
+///
+/// Fails with `InvalidOperation` on the first split recording a value
+/// differing from an earlier split's, see `merge_split_bytes`.
+fn merged_hotcache_metadata(splits: &[&[u8]]) -> Result<MergedHotcacheMetadata> {
+    let mut merged = MergedHotcacheMetadata::default();
+    for (split_idx, split) in splits.iter().enumerate() {
+        let hotcache = match split_hotcache(split, split_idx)? {
+            Some(hotcache) => hotcache,
+            None => continue,
+        };
+        
+        for (name, config) in hotcache.tokenizers()? {
+            if merged.tokenizers.get(&name).is_some_and(|merged_config| *merged_config != config) {
+                return Err(SplitsError::InvalidOperation(format!(
+                    "Split {} records a different tokenizer '{}' than an earlier split", split_idx, name
+                )));
+            }
+            merged.tokenizers.insert(name, config);
+        }
+        for (key, value) in hotcache.custom_metadata()? {
+            if merged.custom_metadata.get(&key).is_some_and(|merged_value| *merged_value != value) {
+                return Err(SplitsError::InvalidOperation(format!(
+                    "Split {} records a different value of custom metadata '{}' than an earlier split", split_idx, key
+                )));
+            }
+            merged.custom_metadata.insert(key, value);
+        }
+        if let Some(opstamp) = hotcache.opstamp() {
+            if let Some(merged_opstamp) = merged.opstamp.filter(|merged_opstamp| *merged_opstamp != opstamp) {
+                return Err(SplitsError::InvalidOperation(format!(
+                    "Split {} records opstamp {}, an earlier split {}", split_idx, opstamp, merged_opstamp
+                )));
+            }
+            merged.opstamp = Some(opstamp);
+        }
+    }
+    Ok(merged)
+}
+
+/// Reads the hotcache of a serialized split from its store file footer
+///
+/// Splits without hotcache, or with a hotcache in the Quickwit format, yield
+/// `None`.
+fn split_hotcache(split: &[u8], split_idx: usize) -> Result<Option<HotcacheInfo>> {
+    let files = split_files_from_bytes(split).map_err(|e| in_split(split_idx, e))?;
+    let store = match footer_store_file(&files) {
+        Some((_, store)) => *store,
+        None => return Ok(None),
+    };
+    
+    let footer = store_footer(store).map_err(|e| in_split(split_idx, e))?;
+    Ok(HotcacheInfo::from_bytes(&store[footer.payload.start as usize..footer.payload.end as usize]).ok())
+}
+
+/// Finds the store file carrying the hotcache footer, the first by name as the generator picks it
+///
+/// Splits of several segments have a store file per segment, the others
+/// as Tantivy wrote them.
+fn footer_store_file<D>(files: &[(String, D)]) -> Option<&(String, D)> {
+    files.iter()
+        .filter(|(file_name, _)| file_name.ends_with(".store"))
+        .min_by(|a, b| a.0.cmp(&b.0))
+}
+
+/// Serializes the single-segment index held in `directory`, adding the hotcache footer
+fn serialize_merged_split(index: &Index, directory: &RamDirectory, hotcache_metadata: &MergedHotcacheMetadata) -> Result<Vec<u8>> {
+    let index_meta = index.load_metas()?;
+    
+    let mut files = vec![(META_FILE_NAME.to_string(), serde_json::to_vec_pretty(&index_meta)?)];
+    let mut num_docs = 0;
+    let mut split_id = None;
+    
+    for segment_meta in &index_meta.segments {
+        num_docs += segment_meta.num_docs();
+        split_id.get_or_insert_with(|| segment_meta.id().uuid_string());
+        
+        for path in segment_meta.list_files() {
+            if directory.exists(&path).map_err(TantivyError::from)? {
+                let data = directory.open_read(&path)
+                    .map_err(TantivyError::from)?
+                    .read_bytes()?;
+                files.push((path.to_string_lossy().to_string(), data.as_slice().to_vec()));
+            }
+        }
+    }
+    
+    let size_bytes = files.iter().map(|(_, data)| data.len() as u64).sum();
+    let mut hotcache = create_hotcache(split_id.unwrap_or_default(), num_docs, size_bytes)?;
+    hotcache.set_provenance(&Provenance::current());
+    hotcache.set_tokenizers(&hotcache_metadata.tokenizers)?;
+    hotcache.set_custom_metadata(&hotcache_metadata.custom_metadata)?;
+    if let Some(opstamp) = hotcache_metadata.opstamp {
+        hotcache.set_opstamp(opstamp);
+    }
+    let footer = encode_footer(&hotcache.to_bytes()?);
+    
+    let footer_store = footer_store_file(&files).map(|(file_name, _)| file_name.clone());
+    for (file_name, data) in files.iter_mut() {
+        if Some(&*file_name) == footer_store.as_ref() {
+            data.extend_from_slice(&footer);
+        }
+    }
+    
+    encode_split_files(files)
+}
+
+/// Loads a serialized split into a RAM directory and opens it as an index
+///
+/// The hotcache footer is stripped from the store file carrying it so
+/// Tantivy sees the file as it wrote it.
+fn open_split_in_ram(split: &[u8], split_idx: usize) -> Result<(Index, RamDirectory)> {
+    let files = split_files_from_bytes(split).map_err(|e| in_split(split_idx, e))?;
+    
+    if !files.iter().any(|(file_name, _)| file_name == META_FILE_NAME) {
+        return Err(SplitsError::InvalidSplit(format!(
+            "Split {} has no {}", split_idx, META_FILE_NAME
+        )));
+    }
+    
+    let footer_store = footer_store_file(&files).map(|(file_name, _)| file_name.clone());
+    let directory = RamDirectory::create();
+    for (file_name, data) in files {
+        let data = if Some(&file_name) == footer_store.as_ref() {
+            strip_footer(data).map_err(|e| in_split(split_idx, e))?
+        } else {
+            data
+        };
+        directory.atomic_write(Path::new(&file_name), data)?;
+    }
+    
+    let index = Index::open(directory.clone())?;
+    Ok((index, directory))
+}
+
+/// Prefixes an invalid split error with the index of the offending split
+fn in_split(split_idx: usize, err: SplitsError) -> SplitsError {
+    match err {
+        SplitsError::InvalidSplit(msg) => SplitsError::InvalidSplit(format!("Split {}: {}", split_idx, msg)),
+        other => other,
+    }
+}
+
+/// Locates the footers of a store file's content
+fn store_footer(store: &[u8]) -> Result<StoreFooter> {
+    locate_footer(store.len() as u64, |range| Ok(store[range.start as usize..range.end as usize].to_vec()))
+}
+
+/// Returns the store file content without its hotcache footers
+fn strip_footer(store: &[u8]) -> Result<&[u8]> {
+    Ok(&store[..store_footer(store)?.start as usize])
+}
+
+/// Encodes `(file name, data)` pairs, sorted by name, as split bytes
+fn encode_split_files(mut files: Vec<(String, Vec<u8>)>) -> Result<Vec<u8>> {
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    
+    let total_len = SPLIT_BYTES_MAGIC.len() + 4
+        + files.iter().map(|(name, data)| 12 + name.len() + data.len()).sum::<usize>();
+    if total_len > MAX_SPLIT_BYTES {
+        return Err(SplitsError::InvalidOperation(format!(
+            "Split is {} bytes, exceeding the {} byte limit of a Java byte array", total_len, MAX_SPLIT_BYTES
+        )));
+    }
+    
+    let mut bytes = Vec::with_capacity(total_len);
+    bytes.extend_from_slice(SPLIT_BYTES_MAGIC);
+    bytes.extend_from_slice(&(files.len() as u32).to_le_bytes());
+    for (name, data) in &files {
+        bytes.extend_from_slice(&(name.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(name.as_bytes());
+        bytes.extend_from_slice(&(data.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(data);
+    }
+    
+    Ok(bytes)
+}
+
+/// Bounds-checked reader over serialized split bytes
+struct ByteCursor<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> ByteCursor<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(len)
+            .filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| SplitsError::InvalidSplit("Serialized split is truncated".to_string()))?;
+        let slice = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(slice)
+    }
+    
+    fn take_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::schema::{Value, FAST, STORED, STRING, TEXT};
+    use tempfile::TempDir;
+    
+    /// Writes a split with `docs` titles, as the generator lays it out
+    fn write_split(dir: &Path, schema: Schema, docs: &[&str]) -> Vec<u8> {
+        let docs: Vec<String> = docs.iter().map(|title| serde_json::json!({ "title": title }).to_string()).collect();
+        write_json_split(dir, schema, &docs)
+    }
+    
+    /// Writes a split with documents given as JSON, as the generator lays it out
+    fn write_json_split(dir: &Path, schema: Schema, docs: &[String]) -> Vec<u8> {
+        let index = Index::create_in_dir(dir, schema.clone()).unwrap();
+        let mut index_writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for doc in docs {
+            index_writer.add_document(TantivyDocument::parse_json(&schema, doc).unwrap()).unwrap();
+        }
+        index_writer.commit().unwrap();
+        index_writer.wait_merging_threads().unwrap();
+        
+        for entry in fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_some_and(|ext| ext == "store") {
+                let mut store = fs::read(&path).unwrap();
+                store.extend_from_slice(&encode_footer(&[]));
+                fs::write(&path, store).unwrap();
+            }
+        }
+        
+        split_to_bytes(dir).unwrap()
+    }
+    
+    fn title_schema() -> Schema {
+        let mut schema_builder = SchemaBuilder::default();
+        schema_builder.add_text_field("title", TEXT | STORED);
+        schema_builder.build()
+    }
+    
+    #[test]
+    fn test_stream_split_chunks_with_store_last() {
+        let temp_dir = TempDir::new().unwrap();
+        let large: Vec<u8> = (0..UPLOAD_CHUNK_SIZE + 100).map(|i| (i % 251) as u8).collect();
+        fs::write(temp_dir.path().join("a.store"), b"store data and footer").unwrap();
+        fs::write(temp_dir.path().join("b.idx"), &large).unwrap();
+        fs::write(temp_dir.path().join(".lock"), b"skipped").unwrap();
+        
+        let chunks = RefCell::new(Vec::new());
+        let size = stream_split(temp_dir.path(), &|chunk: &[u8], offset: u64| {
+            chunks.borrow_mut().push((offset, chunk.to_vec()));
+            Ok(())
+        }).unwrap();
+        
+        let chunks = chunks.into_inner();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].0, 0);
+        assert_eq!(chunks[0].1.len(), UPLOAD_CHUNK_SIZE);
+        assert_eq!(chunks[1].0, UPLOAD_CHUNK_SIZE as u64);
+        
+        let bytes: Vec<u8> = chunks.into_iter().flat_map(|(_, chunk)| chunk).collect();
+        assert_eq!(bytes.len() as u64, size);
+        let files = split_files_from_bytes(&bytes).unwrap();
+        assert_eq!(files.len(), 2);
+        assert_eq!((files[0].0.as_str(), files[0].1), ("b.idx", large.as_slice()));
+        assert_eq!((files[1].0.as_str(), files[1].1), ("a.store", b"store data and footer".as_slice()));
+    }
+    
+    #[test]
+    fn test_stream_split_stops_on_upload_error() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.store"), b"data").unwrap();
+        
+        let result = stream_split(temp_dir.path(), &|_: &[u8], _: u64| {
+            Err(SplitsError::InvalidOperation("upload failed".to_string()))
+        });
+        assert!(matches!(result, Err(SplitsError::InvalidOperation(msg)) if msg == "upload failed"));
+    }
+    
+    #[test]
+    fn test_split_checksums_agree() {
+        let temp_dir = TempDir::new().unwrap();
+        let split_dir = temp_dir.path().join("split");
+        fs::create_dir(&split_dir).unwrap();
+        fs::write(split_dir.join("a.store"), b"store data and footer").unwrap();
+        fs::write(split_dir.join("b.idx"), b"postings").unwrap();
+        
+        let split_file = temp_dir.path().join("a.split");
+        let (len, checksum) = write_split_file(&split_dir, &split_file).unwrap();
+        assert_eq!(len, fs::metadata(&split_file).unwrap().len());
+        assert_eq!(checksum, blake3::hash(&fs::read(&split_file).unwrap()).to_hex().to_string());
+        assert_eq!(split_file_checksum(&split_file).unwrap(), checksum);
+        assert_eq!(split_dir_checksum(&split_dir).unwrap(), checksum);
+        
+        fs::write(split_dir.join("b.idx"), b"postingz").unwrap();
+        assert_ne!(split_dir_checksum(&split_dir).unwrap(), checksum);
+    }
+    
+    #[test]
+    fn test_split_bytes_round_trip() {
+        let files = vec![
+            ("b.store".to_string(), vec![1, 2, 3]),
+            ("a.term".to_string(), vec![]),
+        ];
+        let bytes = encode_split_files(files).unwrap();
+        
+        let decoded = split_files_from_bytes(&bytes).unwrap();
+        assert_eq!(decoded, vec![("a.term".to_string(), &[][..]), ("b.store".to_string(), &[1u8, 2, 3][..])]);
+        
+        assert!(matches!(split_files_from_bytes(&bytes[..bytes.len() - 1]), Err(SplitsError::InvalidSplit(_))));
+        assert!(matches!(split_files_from_bytes(b"not a split"), Err(SplitsError::InvalidSplit(_))));
+    }
+    
+    #[test]
+    fn test_merge_split_bytes() {
+        let first_dir = TempDir::new().unwrap();
+        let second_dir = TempDir::new().unwrap();
+        let first = write_split(first_dir.path(), title_schema(), &["a", "b"]);
+        let second = write_split(second_dir.path(), title_schema(), &["c"]);
+        
+        let merged = merge_split_bytes(&[&first, &second]).unwrap();
+        
+        let (index, _) = open_split_in_ram(&merged, 0).unwrap();
+        let segment_metas = index.searchable_segment_metas().unwrap();
+        assert_eq!(segment_metas.len(), 1);
+        assert_eq!(segment_metas[0].num_docs(), 3);
+    }
+    
+    #[test]
+    fn test_merge_split_bytes_with_dual_footers() {
+        use crate::split_generator::encode_footers;
+        
+        let first_dir = TempDir::new().unwrap();
+        let second_dir = TempDir::new().unwrap();
+        let first = write_split(first_dir.path(), title_schema(), &["a", "b"]);
+        let second = write_split(second_dir.path(), title_schema(), &["c"]);
+        
+        // Both footers are stripped before Tantivy reads the store file
+        let hotcache = create_hotcache("second".to_string(), 1, 0).unwrap().to_bytes().unwrap();
+        let files = split_files_from_bytes(&second).unwrap().into_iter()
+            .map(|(name, data)| match name.ends_with(".store") {
+                true => (name, [strip_footer(data).unwrap(), &encode_footers(&hotcache, true)].concat()),
+                false => (name, data.to_vec()),
+            })
+            .collect();
+        let second = encode_split_files(files).unwrap();
+        
+        let merged = merge_split_bytes(&[&first, &second]).unwrap();
+        let (index, _) = open_split_in_ram(&merged, 0).unwrap();
+        assert_eq!(index.searchable_segment_metas().unwrap()[0].num_docs(), 3);
+    }
+    
+    #[test]
+    fn test_merge_split_bytes_rejects_mismatched_schemas() {
+        let mut schema_builder = SchemaBuilder::default();
+        schema_builder.add_text_field("title", TEXT);
+        schema_builder.add_text_field("body", TEXT);
+        
+        let first_dir = TempDir::new().unwrap();
+        let second_dir = TempDir::new().unwrap();
+        let first = write_split(first_dir.path(), title_schema(), &["a"]);
+        let second = write_split(second_dir.path(), schema_builder.build(), &["b"]);
+        
+        match merge_split_bytes(&[&first, &second]) {
+            Err(SplitsError::InvalidOperation(msg)) => assert!(msg.contains("different schema")),
+            other => panic!("Expected InvalidOperation, got {:?}", other.map(|bytes| bytes.len())),
+        }
+    }
+    
+    #[test]
+    fn test_union_merge_of_reordered_schemas() {
+        let mut schema_builder = SchemaBuilder::default();
+        schema_builder.add_text_field("title", TEXT | STORED);
+        schema_builder.add_u64_field("id", FAST | STORED);
+        let first_schema = schema_builder.build();
+        let mut schema_builder = SchemaBuilder::default();
+        schema_builder.add_u64_field("id", FAST | STORED);
+        schema_builder.add_text_field("title", TEXT | STORED);
+        let second_schema = schema_builder.build();
+        
+        let first_dir = TempDir::new().unwrap();
+        let second_dir = TempDir::new().unwrap();
+        let first = write_json_split(first_dir.path(), first_schema.clone(), &[r#"{"title": "a", "id": 1}"#.to_string()]);
+        let second = write_json_split(second_dir.path(), second_schema, &[r#"{"title": "b", "id": 2}"#.to_string()]);
+        
+        assert!(matches!(merge_split_bytes(&[&first, &second]), Err(SplitsError::InvalidOperation(_))));
+        let merged = merge_split_bytes_with(&[&first, &second], SchemaCompatibility::Union).unwrap();
+        
+        let (index, _) = open_split_in_ram(&merged, 0).unwrap();
+        assert_eq!(index.schema(), first_schema);
+        let segment_metas = index.searchable_segment_metas().unwrap();
+        assert_eq!(segment_metas.len(), 1);
+        assert_eq!(segment_metas[0].num_docs(), 2);
+        
+        // Values land in the field of the same name, whatever its position
+        let searcher = index.reader().unwrap().searcher();
+        let id = first_schema.get_field("id").unwrap();
+        let title = first_schema.get_field("title").unwrap();
+        let docs: Vec<(String, u64)> = (0..2)
+            .map(|doc_id| {
+                let doc: TantivyDocument = searcher.doc(tantivy::DocAddress::new(0, doc_id)).unwrap();
+                let title = doc.get_first(title).and_then(|value| value.as_str().map(str::to_string)).unwrap();
+                (title, doc.get_first(id).and_then(|value| value.as_u64()).unwrap())
+            })
+            .collect();
+        assert!(docs.contains(&("a".to_string(), 1)));
+        assert!(docs.contains(&("b".to_string(), 2)));
+    }
+    
+    #[test]
+    fn test_union_merge_of_superset_schema() {
+        let mut schema_builder = SchemaBuilder::default();
+        schema_builder.add_text_field("title", TEXT | STORED);
+        schema_builder.add_text_field("tenant", STRING | STORED);
+        let superset_schema = schema_builder.build();
+        
+        let first_dir = TempDir::new().unwrap();
+        let second_dir = TempDir::new().unwrap();
+        let first = write_split(first_dir.path(), title_schema(), &["a", "b"]);
+        let second = write_json_split(second_dir.path(), superset_schema.clone(), &[r#"{"title": "c", "tenant": "acme"}"#.to_string()]);
+        
+        let merged = merge_split_bytes_with(&[&first, &second], SchemaCompatibility::Union).unwrap();
+        
+        let (index, _) = open_split_in_ram(&merged, 0).unwrap();
+        assert_eq!(index.schema(), superset_schema);
+        let searcher = index.reader().unwrap().searcher();
+        assert_eq!(searcher.num_docs(), 3);
+        
+        // Documents of the first split have no tenant
+        let tenant = superset_schema.get_field("tenant").unwrap();
+        let query = tantivy::query::TermQuery::new(
+            tantivy::Term::from_field_text(tenant, "acme"),
+            tantivy::schema::IndexRecordOption::Basic,
+        );
+        assert_eq!(searcher.search(&query, &tantivy::collector::Count).unwrap(), 1);
+    }
+    
+    #[test]
+    fn test_union_merge_rejects_conflicting_fields() {
+        let mut schema_builder = SchemaBuilder::default();
+        schema_builder.add_u64_field("title", STORED);
+        let conflicting_type = schema_builder.build();
+        let mut schema_builder = SchemaBuilder::default();
+        schema_builder.add_text_field("title", STRING | STORED);
+        let conflicting_options = schema_builder.build();
+        
+        let schemas = [title_schema(), conflicting_type];
+        match union_schema(&schemas) {
+            Err(SplitsError::InvalidOperation(msg)) => {
+                assert_eq!(msg, "Field 'title' is of type text in split 0 but of type u64 in split 1");
+            }
+            other => panic!("Expected InvalidOperation, got {:?}", other),
+        }
+        
+        let schemas = [title_schema(), conflicting_options];
+        match union_schema(&schemas) {
+            Err(SplitsError::InvalidOperation(msg)) => assert!(msg.contains("different options")),
+            other => panic!("Expected InvalidOperation, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_merge_split_bytes_rejects_duplicate_segments() {
+        let dir = TempDir::new().unwrap();
+        let split = write_split(dir.path(), title_schema(), &["a", "b"]);
+        let copy = split.clone();
+        
+        let (index, _) = open_split_in_ram(&split, 0).unwrap();
+        let segment_id = index.searchable_segment_ids().unwrap()[0].uuid_string();
+        
+        match merge_split_bytes(&[&split, &copy]) {
+            Err(SplitsError::InvalidSplit(msg)) => {
+                assert!(msg.contains("Splits 0 and 1"));
+                assert!(msg.contains(&segment_id));
+            }
+            other => panic!("Expected InvalidSplit, got {:?}", other.map(|bytes| bytes.len())),
+        }
+    }
+    
+    /// Generates a split of one segment per doc, with the given custom metadata
+    fn generate_split_bytes(dir: &Path, docs: &[&str], custom_metadata: &[(&str, &str)]) -> Vec<u8> {
+        use crate::split_generator::{MergePolicy, QuickwitSplitGenerator, SplitConfig};
+        
+        let index_dir = dir.join("index");
+        fs::create_dir(&index_dir).unwrap();
+        let schema = title_schema();
+        let title = schema.get_field("title").unwrap();
+        let index = Index::create_in_dir(&index_dir, schema).unwrap();
+        let mut index_writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        index_writer.set_merge_policy(Box::new(tantivy::indexer::NoMergePolicy));
+        for doc in docs {
+            index_writer.add_document(tantivy::doc!(title => *doc)).unwrap();
+            index_writer.commit().unwrap();
+        }
+        drop(index_writer);
+        
+        let config = SplitConfig {
+            merge_policy: MergePolicy::NoMerge,
+            legacy_directory_layout: true,
+            tokenizers: BTreeMap::from([("shingles".to_string(), TokenizerConfig::Whitespace { lowercase: true })]),
+            custom_metadata: custom_metadata.iter().map(|(key, value)| (key.to_string(), value.to_string())).collect(),
+            ..SplitConfig::default()
+        };
+        let split_path = dir.join("split");
+        QuickwitSplitGenerator::new(index, 1000).unwrap()
+            .with_config(config)
+            .generate_split(&split_path)
+            .unwrap();
+        split_to_bytes(&split_path).unwrap()
+    }
+    
+    #[test]
+    fn test_merge_split_bytes_of_segments_carries_hotcache_metadata() {
+        let dir = TempDir::new().unwrap();
+        let split = generate_split_bytes(dir.path(), &["a", "b", "c"], &[("source", "test")]);
+        let source = split_hotcache(&split, 0).unwrap().unwrap();
+        let files = split_files_from_bytes(&split).unwrap();
+        assert_eq!(files.iter().filter(|(file_name, _)| file_name.ends_with(".store")).count(), 3);
+        
+        let merged = merge_split_bytes(&[&split]).unwrap();
+        
+        let (index, _) = open_split_in_ram(&merged, 0).unwrap();
+        let segment_metas = index.searchable_segment_metas().unwrap();
+        assert_eq!(segment_metas.len(), 1);
+        assert_eq!(segment_metas[0].num_docs(), 3);
+        
+        let hotcache = split_hotcache(&merged, 0).unwrap().unwrap();
+        assert_eq!(hotcache.tokenizers().unwrap(), source.tokenizers().unwrap());
+        assert_eq!(hotcache.tokenizers().unwrap().len(), 1);
+        assert_eq!(hotcache.custom_metadata().unwrap(), BTreeMap::from([("source".to_string(), "test".to_string())]));
+        assert!(source.opstamp().is_some());
+        assert_eq!(hotcache.opstamp(), source.opstamp());
+    }
+    
+    #[test]
+    fn test_merge_split_bytes_rejects_conflicting_hotcache_metadata() {
+        let first_dir = TempDir::new().unwrap();
+        let second_dir = TempDir::new().unwrap();
+        let first = generate_split_bytes(first_dir.path(), &["a"], &[("source", "first")]);
+        let second = generate_split_bytes(second_dir.path(), &["b"], &[("source", "second")]);
+        
+        match merge_split_bytes(&[&first, &second]) {
+            Err(SplitsError::InvalidOperation(msg)) => assert!(msg.contains("'source'")),
+            other => panic!("Expected InvalidOperation, got {:?}", other.map(|bytes| bytes.len())),
+        }
+    }
+}
@@ -19,38 +19,788 @@
 
 //! Quickwit split generation functionality
 
+use crate::encryption::{encrypt_split_files, EncryptionKey};
 use crate::error::{Result, SplitsError};
-use crate::hotcache::{HotcacheInfo, create_hotcache};
-use tantivy::Index;
-use tantivy::index::SegmentId;
-use std::path::Path;
+use crate::hotcache::{record_field_ranges, ByteRange, HotcacheFormat, HotcacheInfo, Provenance, create_hotcache};
+use crate::split_bytes::{split_dir_checksum, split_file_checksum, split_file_ranges, stream_split, write_split_file, write_split_file_replacing, UploadFn, SPLIT_FILE_EXTENSION};
+use crate::thread_pool;
+use crate::tokenizers::TokenizerConfig;
+use fs2::FileExt;
+use rayon::prelude::*;
+use tantivy::{DocAddress, HasLen, Index, IndexReader, ReloadPolicy, SegmentReader, TantivyDocument, Term};
+use tantivy::columnar::Column;
+use tantivy::collector::DocSetCollector;
+use tantivy::directory::{Directory, DirectoryClone, MmapDirectory};
+use tantivy::index::{SegmentComponent, SegmentId, SegmentMeta};
+use tantivy::indexer::NoMergePolicy;
+use tantivy::query::TermQuery;
+use tantivy::schema::{FieldType, IndexRecordOption};
+use serde::{Serialize, Deserialize};
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{self, Read, Seek, Write};
+use std::path::{Component, Path, PathBuf};
 use std::fs;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+/// Largest number of distinct values of a tag field recorded as split tags, as in Quickwit
+pub const MAX_VALUES_PER_TAG_FIELD: usize = 1000;
+
+/// Memory budget of the index writer merging segments
+const MERGE_WRITER_MEMORY_BYTES: usize = 100_000_000;
+
+/// Smallest memory arena Tantivy accepts per indexing thread
+const WRITER_THREAD_MIN_MEMORY_BYTES: usize = 15_000_000;
+
+/// Hook run on a fully written split before it is published
+pub type PostProcessHook = Arc<dyn Fn(&Path) -> Result<()> + Send + Sync>;
+
 /// Generator for creating Quickwit splits from Tantivy indices
 pub struct QuickwitSplitGenerator {
     /// The Tantivy index to generate splits from
     index: Index,
     /// Target number of documents per split
     target_docs_per_split: usize,
+    /// Options controlling the generated split
+    config: SplitConfig,
+    /// Optional hook to sign, encrypt or otherwise post-process the split
+    post_process: Option<PostProcessHook>,
+    /// Reader pinned to the index state generation works on, see `refresh`
+    reader: IndexReader,
+    /// Opstamp of the last commit `reader` is known to include
+    reader_opstamp: u64,
+    /// Opstamp recorded instead of the index's own, for splits re-indexed from another index
+    source_opstamp: Option<u64>,
+    /// Percent complete of the running or last generation, see `progress`
+    progress: Arc<AtomicU8>,
+}
+
+/// Options controlling how a split is generated
+///
+/// Deserializable from JSON with snake_case keys; omitted keys keep their
+/// default value.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SplitConfig {
+    /// Field holding document timestamps, used to record the split time range
+    pub timestamp_field: Option<String>,
+    /// Fields whose values are recorded as split tags
+    ///
+    /// Fields with more than `MAX_VALUES_PER_TAG_FIELD` distinct values
+    /// record no tags and get a warning in `SplitMetadata::warnings`.
+    pub tag_fields: Vec<String>,
+    /// Fields for which bloom filters are recorded
+    pub bloom_fields: Vec<String>,
+    /// Whether to write the split metadata as a `<split>.json` file next to the split
+    pub write_sidecar_metadata: bool,
+    /// Whether to replace a split already present at the output path
+    ///
+    /// When unset, generation fails if the output file exists, or the output
+    /// directory exists and isn't empty, rather than mixing new files with
+    /// stale ones. When set, a split directory of the legacy layout at the
+    /// output path is replaced by the split file rather than receiving it.
+    pub overwrite: bool,
+    /// Whether to write splits as a directory of files rather than a single `.split` file
+    ///
+    /// Single-file splits match Quickwit's `<split_id>.split` naming. The
+    /// directory layout is the one of earlier versions, kept for tooling that
+    /// expects the segment files side by side on disk.
+    pub legacy_directory_layout: bool,
+    /// Split id to record in the split metadata
+    ///
+    /// Lets callers derive reproducible ids, e.g. from a partition key. Must
+    /// be usable as a file name. Defaults to a fresh, time-ordered UUID v7.
+    pub split_id: Option<String>,
+    /// Creation time to record in the split provenance, in Unix milliseconds
+    ///
+    /// Defaults to the time of generation. Pinned along with `split_id`, it
+    /// makes generation reproducible.
+    pub created_at_millis: Option<i64>,
+    /// Custom tokenizers the index was built with, by name
+    ///
+    /// Recorded in the hotcache so readers parse queries with the same
+    /// tokenizers. Tantivy's built-in tokenizers need no entry.
+    pub tokenizers: BTreeMap<String, TokenizerConfig>,
+    /// Whether to embed the hotcache in the store file footer
+    ///
+    /// When disabled the footer is still written, with an empty payload, so
+    /// readers can tell a hotcache-less split from a corrupt one.
+    pub embed_hotcache: bool,
+    /// Layout of the embedded hotcache
+    ///
+    /// `Quickwit` requires the `quickwit-compat` feature and can't be combined
+    /// with `align_sections`. Splits of empty indexes have no Tantivy index to
+    /// cache and always carry a `Simple` hotcache.
+    pub hotcache_format: HotcacheFormat,
+    /// Fields whose posting and fast field ranges are recorded in the hotcache; all when `None`
+    ///
+    /// Each hotcached field grows the footer and the reader warm-up. Fields
+    /// left out stay fully queryable, but their first access reads the
+    /// segment files without knowing where the field's sections lie, which is
+    /// slower on remote storage. Only applies to the `Simple` format.
+    pub hotcache_fields: Option<Vec<String>>,
+    /// Fields left out of the hotcache, even if listed in `hotcache_fields`
+    pub hotcache_exclude: Vec<String>,
+    /// Largest number of splits `generate_partitioned_splits` may produce
+    pub max_partitions: usize,
+    /// Boundary, in bytes, the hotcache payload is aligned to within the store file
+    ///
+    /// Padding the store data up to e.g. 4096 lets the warm-up path read the
+    /// hotcache from whole pages. The unpadded store data length is recorded
+    /// in the hotcache. Must be a power of two. Other sections are separate
+    /// files of the split and start on their own. Unset by default, to avoid
+    /// the padding overhead; ignored when the hotcache isn't embedded.
+    pub align_sections: Option<u64>,
+    /// Whether to write a versioned footer ahead of the legacy one
+    ///
+    /// The versioned footer carries a magic and version and is read in
+    /// preference by this build, while readers of the legacy size-only footer
+    /// still find theirs in the last 8 bytes. It repeats the hotcache payload,
+    /// so the store file grows by the payload size plus 16 bytes: meant only
+    /// for migration windows where both reader versions open the same splits.
+    /// Only applies to an embedded `Simple` hotcache. Off by default.
+    pub dual_footer: bool,
+    /// Whether generation holds an exclusive lock on the output's parent directory
+    ///
+    /// Producers generating into the same base directory, from any process,
+    /// then run one at a time instead of racing. The lock is an advisory
+    /// `flock` on a `.quickwit-splits.lock` file, released when generation
+    /// ends, even on failure or process exit.
+    pub exclusive: bool,
+    /// How long to wait for the exclusive lock before failing with `InvalidOperation`
+    pub lock_timeout_millis: u64,
+    /// Directory output paths must resolve inside, symlinks followed
+    pub output_base_dir: Option<PathBuf>,
+    /// Key/value metadata recorded in the hotcache, e.g. the source or pipeline of the split
+    ///
+    /// Read back with `QuickwitSplitReader::custom_metadata`. Only recorded
+    /// with the `Simple` hotcache format, and not when the hotcache isn't
+    /// embedded.
+    pub custom_metadata: BTreeMap<String, String>,
+    /// Largest size, in bytes, of a generated split
+    ///
+    /// Checked once the split is fully written, post-process hook included:
+    /// larger splits fail with `InvalidOperation` and nothing is published or
+    /// uploaded. Unlimited when `None`.
+    pub max_split_size_bytes: Option<u64>,
+    /// Whether to check that merging the index segments left a single one
+    ///
+    /// Only applies when the merge policy merges several segments: once
+    /// merged, the index is re-read and generation fails with
+    /// `InvalidOperation`, naming the number of segments left, if they didn't
+    /// collapse into one. Off by default.
+    pub verify_merge: bool,
+    /// How many segments the split keeps out of the index's segments
+    ///
+    /// Only `ForceSingle`, the default, is fully Quickwit-compatible, see
+    /// `MergePolicy`.
+    pub merge_policy: MergePolicy,
+    /// Number of threads of the index writer merging segments; Tantivy's default when `None`
+    ///
+    /// Tantivy sizes its writer to the machine's cores, which oversubscribes
+    /// containers limited to fewer. Must be at least 1; a count above the
+    /// available parallelism is allowed, with a warning.
+    pub merge_threads: Option<usize>,
+    /// Key to encrypt the split files with, hex encoded in JSON
+    ///
+    /// Segment files are encrypted with AES-256-GCM and the hotcache keeps
+    /// no field ranges, see the `encryption` module. Readers need the same
+    /// key to open the split. Requires the `encryption` feature and an
+    /// embedded hotcache in the `Simple` format.
+    pub encryption_key: Option<EncryptionKey>,
+}
+
+impl Default for SplitConfig {
+    fn default() -> Self {
+        Self {
+            timestamp_field: None,
+            tag_fields: Vec::new(),
+            bloom_fields: Vec::new(),
+            write_sidecar_metadata: false,
+            overwrite: false,
+            legacy_directory_layout: false,
+            split_id: None,
+            created_at_millis: None,
+            tokenizers: BTreeMap::new(),
+            embed_hotcache: true,
+            hotcache_format: HotcacheFormat::Simple,
+            hotcache_fields: None,
+            hotcache_exclude: Vec::new(),
+            max_partitions: 100,
+            align_sections: None,
+            dual_footer: false,
+            exclusive: false,
+            lock_timeout_millis: 30_000,
+            output_base_dir: None,
+            custom_metadata: BTreeMap::new(),
+            max_split_size_bytes: None,
+            verify_merge: false,
+            merge_policy: MergePolicy::ForceSingle,
+            merge_threads: None,
+            encryption_key: None,
+        }
+    }
+}
+
+impl SplitConfig {
+    /// Parses a configuration from JSON
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| SplitsError::Serialization(format!("Invalid split config: {}", e)))
+    }
+    
+    /// Returns every field name referenced by this configuration
+    pub fn referenced_fields(&self) -> impl Iterator<Item = &str> {
+        self.timestamp_field.iter()
+            .chain(self.tag_fields.iter())
+            .chain(self.bloom_fields.iter())
+            .chain(self.hotcache_fields.iter().flatten())
+            .chain(self.hotcache_exclude.iter())
+            .map(String::as_str)
+    }
+}
+
+/// How many segments a split keeps out of the index's segments
+///
+/// Deserialized from `"force_single"`, `{"max_segments": n}` or `"no_merge"`.
+/// Several segments let searches run on them in parallel, but only
+/// `ForceSingle` is fully Quickwit-compatible: Quickwit expects a split to
+/// hold a single segment. Readers number documents across the segments, see
+/// `QuickwitSplitReader::posting_cursor`. Field ranges are only hotcached for
+/// single-segment splits.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MergePolicy {
+    /// Merges every segment into one
+    #[default]
+    ForceSingle,
+    /// Merges the segments with the fewest documents together until at most this many remain
+    MaxSegments(usize),
+    /// Keeps the index's segments as they are
+    NoMerge,
 }
 
 /// Metadata describing a generated split
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SplitMetadata {
     /// Unique identifier for the split
     pub split_id: String,
     /// Number of documents in the split
     pub num_docs: u32,
-    /// Total size of split files in bytes
+    /// Size of the split in bytes: of its split file, or of all its files with the legacy layout
     pub size_bytes: u64,
     /// Byte range where hotcache metadata is stored
     pub hotcache_start: u64,
     pub hotcache_end: u64,
+    /// Smallest value of the configured timestamp field, if any
+    #[serde(default)]
+    pub time_start: Option<i64>,
+    /// Largest value of the configured timestamp field, if any
+    #[serde(default)]
+    pub time_end: Option<i64>,
+    /// `field:value` tags collected from the configured tag fields
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+    /// Names of the files making up the split
+    #[serde(default)]
+    pub files: Vec<String>,
+    /// When and by what the split was produced
+    #[serde(default)]
+    pub provenance: Provenance,
+    /// Number of segments in the source index before they were merged
+    #[serde(default)]
+    pub source_segments: usize,
+    /// Opstamp of the last commit of the source index the split reflects
+    #[serde(default)]
+    pub opstamp: u64,
+    /// Hex BLAKE3 hash of the split's raw bytes, as `split_file_checksum`
+    /// computes it, to verify transfers against
+    #[serde(default)]
+    pub checksum: Option<String>,
+    /// Whether the split holds more documents than the generator's target per split
+    #[serde(default)]
+    pub exceeds_target_docs: bool,
+    /// Non-fatal issues met while generating the split, e.g. truncated tags
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+/// Size estimate of the split an index would generate, as returned by `plan_split`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SplitPlan {
+    /// Number of live documents the split would hold
+    pub num_docs: u64,
+    /// Number of source segments merged into the split
+    pub source_segments: usize,
+    /// Lower bound of the split size, assuming deleted documents' data is fully compacted away
+    pub min_size_bytes: u64,
+    /// Upper bound of the split size: the source segment files as they are
+    pub max_size_bytes: u64,
+}
+
+/// Resolves an output path to the absolute, symlink-free path the split is written to
+///
+/// Relative paths are resolved against the current directory, and `foo/`,
+/// `foo/.` and `foo` all resolve alike. Every existing component is
+/// canonicalized, so a symlink anywhere on the path, the output path itself
+/// included, is replaced by its target; components that don't exist yet are
+/// appended as-is, and dangling symlinks are rejected. With a `base_dir`, the
+/// resolved path must lie strictly inside it.
+fn resolve_output_path(output_path: &Path, base_dir: Option<&Path>) -> Result<PathBuf> {
+    let absolute = if output_path.is_absolute() {
+        output_path.to_path_buf()
+    } else {
+        std::env::current_dir()?.join(output_path)
+    };
+    
+    let mut resolved = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::Prefix(_) | Component::RootDir => resolved.push(component),
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            Component::Normal(name) => {
+                resolved.push(name);
+                if fs::symlink_metadata(&resolved).is_ok() {
+                    resolved = fs::canonicalize(&resolved).map_err(|e| SplitsError::InvalidOperation(format!(
+                        "Failed to resolve output path {}: {}", resolved.display(), e
+                    )))?;
+                }
+            }
+        }
+    }
+    
+    if let Some(base_dir) = base_dir {
+        let base_dir = fs::canonicalize(base_dir).map_err(|e| SplitsError::InvalidOperation(format!(
+            "Failed to resolve output base directory {}: {}", base_dir.display(), e
+        )))?;
+        if resolved == base_dir || !resolved.starts_with(&base_dir) {
+            return Err(SplitsError::InvalidOperation(format!(
+                "Split output path {} resolves to {}, outside the allowed base {}",
+                output_path.display(), resolved.display(), base_dir.display()
+            )));
+        }
+    }
+    
+    Ok(resolved)
+}
+
+/// Fails if two split files have names differing only in case
+///
+/// They would overwrite each other once written on a case-insensitive
+/// filesystem, as on macOS or Windows, leaving a split silently missing a
+/// file. Tantivy names files after lowercase segment ids, so only custom
+/// naming can get here.
+fn check_case_collisions(file_names: &[String]) -> Result<()> {
+    let mut by_lowercase_name = BTreeMap::new();
+    for file_name in file_names {
+        if let Some(other) = by_lowercase_name.insert(file_name.to_lowercase(), file_name) {
+            if other != file_name {
+                return Err(SplitsError::InvalidOperation(format!(
+                    "Split files {} and {} differ only in case and would collide on case-insensitive filesystems",
+                    other, file_name
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Returns the name of the single-file split with id `split_id`
+pub fn split_file_name(split_id: &str) -> String {
+    format!("{}.{}", split_id, SPLIT_FILE_EXTENSION)
+}
+
+/// Returns a unique staging path next to `output_path`
+fn staging_path_for(output_path: &Path) -> PathBuf {
+    let file_name = output_path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    output_path.with_file_name(format!(".{}.{}.tmp", file_name, Uuid::new_v4().simple()))
+}
+
+/// Returns true if `path` doesn't exist or is an empty directory
+fn is_missing_or_empty_dir(path: &Path) -> Result<bool> {
+    if !path.exists() {
+        return Ok(true);
+    }
+    if !path.is_dir() {
+        return Ok(false);
+    }
+    Ok(fs::read_dir(path)?.next().is_none())
+}
+
+/// Finds the store file of a split, which carries the hotcache footer
+///
+/// Should several be present, the first by name is picked, whatever the
+/// order the filesystem lists them in.
+fn find_store_file(split_dir: &Path) -> Result<PathBuf> {
+    let mut store_files = Vec::new();
+    for entry in fs::read_dir(split_dir)? {
+        let path = entry?.path();
+        if path.extension().is_some_and(|ext| ext == "store") {
+            store_files.push(path);
+        }
+    }
+    
+    store_files.into_iter().min().ok_or_else(|| SplitsError::InvalidSplit(
+        "No store file found to embed hotcache".to_string()
+    ))
+}
+
+/// Magic number of the versioned store file footer, "QWSF" as a little-endian u32
+const FOOTER_MAGIC: u32 = u32::from_le_bytes(*b"QWSF");
+
+/// Version of the versioned store file footer this build writes and reads
+const FOOTER_VERSION: u32 = 1;
+
+/// Length of the versioned footer's trailer: version, magic and footer length
+const VERSIONED_FOOTER_TRAILER_LEN: u64 = 16;
+
+/// Encodes the legacy store file footer carrying `payload`
+///
+/// The footer is the payload followed by its length plus 8 as a little-endian
+/// u64, so readers can locate it from the end of the file. An empty payload
+/// marks a split without hotcache.
+pub(crate) fn encode_footer(payload: &[u8]) -> Vec<u8> {
+    let mut footer = Vec::with_capacity(payload.len() + 8);
+    footer.extend_from_slice(payload);
+    footer.extend_from_slice(&(payload.len() as u64 + 8).to_le_bytes());
+    footer
+}
+
+/// Encodes the versioned store file footer carrying `payload`
+///
+/// The payload is followed by `FOOTER_VERSION` and `FOOTER_MAGIC` as
+/// little-endian u32s, then the footer's length as a little-endian u64.
+fn encode_versioned_footer(payload: &[u8]) -> Vec<u8> {
+    let mut footer = Vec::with_capacity(payload.len() + VERSIONED_FOOTER_TRAILER_LEN as usize);
+    footer.extend_from_slice(payload);
+    footer.extend_from_slice(&FOOTER_VERSION.to_le_bytes());
+    footer.extend_from_slice(&FOOTER_MAGIC.to_le_bytes());
+    footer.extend_from_slice(&(payload.len() as u64 + VERSIONED_FOOTER_TRAILER_LEN).to_le_bytes());
+    footer
+}
+
+/// Encodes the footers ending a store file, see `SplitConfig::dual_footer`
+///
+/// A dual footer is the versioned footer followed by the legacy one, so the
+/// legacy footer stays in the last 8 bytes where its readers look for it.
+pub(crate) fn encode_footers(payload: &[u8], dual_footer: bool) -> Vec<u8> {
+    if !dual_footer {
+        return encode_footer(payload);
+    }
+    let mut footers = encode_versioned_footer(payload);
+    footers.extend_from_slice(&encode_footer(payload));
+    footers
+}
+
+/// Footers found at the end of a store file
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct StoreFooter {
+    /// Offset the footers start at, after the store data and any alignment padding
+    pub start: u64,
+    /// Byte range of the hotcache payload, the versioned footer's when there is one
+    pub payload: ByteRange,
+    /// Whether a versioned footer precedes the legacy one
+    pub dual: bool,
+}
+
+/// Locates the footers ending a store file of `store_len` bytes
+///
+/// `read` returns the bytes of a range of the store file. The legacy footer
+/// is read from the last 8 bytes, then a versioned footer of this build's
+/// version just before it is preferred; one of another version is skipped,
+/// leaving the legacy footer to read.
+pub(crate) fn locate_footer(store_len: u64, mut read: impl FnMut(ByteRange) -> Result<Vec<u8>>) -> Result<StoreFooter> {
+    let legacy_len = match store_len.checked_sub(8) {
+        Some(trailer_start) => u64::from_le_bytes(read_array(&mut read, trailer_start)?),
+        None => return Err(SplitsError::InvalidSplit("Store file too small to contain footer".to_string())),
+    };
+    if legacy_len < 8 || legacy_len > store_len {
+        return Err(SplitsError::InvalidSplit("Invalid hotcache size in footer".to_string()));
+    }
+    let legacy_start = store_len - legacy_len;
+    let legacy_footer = StoreFooter {
+        start: legacy_start,
+        payload: ByteRange { start: legacy_start, end: store_len - 8 },
+        dual: false,
+    };
+    
+    let trailer: [u8; 16] = match legacy_start.checked_sub(VERSIONED_FOOTER_TRAILER_LEN) {
+        Some(trailer_start) => read_array(&mut read, trailer_start)?,
+        None => return Ok(legacy_footer),
+    };
+    let version = u32::from_le_bytes(trailer[0..4].try_into().unwrap());
+    let magic = u32::from_le_bytes(trailer[4..8].try_into().unwrap());
+    let versioned_len = u64::from_le_bytes(trailer[8..16].try_into().unwrap());
+    if magic != FOOTER_MAGIC || version != FOOTER_VERSION
+        || versioned_len < VERSIONED_FOOTER_TRAILER_LEN || versioned_len > legacy_start {
+        return Ok(legacy_footer);
+    }
+    let start = legacy_start - versioned_len;
+    Ok(StoreFooter {
+        start,
+        payload: ByteRange { start, end: legacy_start - VERSIONED_FOOTER_TRAILER_LEN },
+        dual: true,
+    })
+}
+
+/// Reads `N` bytes of a store file from `start`, see `locate_footer`
+fn read_array<const N: usize>(read: &mut impl FnMut(ByteRange) -> Result<Vec<u8>>, start: u64) -> Result<[u8; N]> {
+    read(ByteRange { start, end: start + N as u64 })?
+        .try_into()
+        .map_err(|_| SplitsError::InvalidSplit("Failed to read store file footer".to_string()))
+}
+
+/// Reads the hotcache from the footer of the store file at `store_range` of `file`
+///
+/// Returns it with the length of the store data, before any alignment
+/// padding, and whether the store file has a dual footer. Fails with
+/// `InvalidOperation` if the footer holds no hotcache, or one in the Quickwit
+/// format.
+fn read_footer_hotcache(file: &mut fs::File, store_range: &ByteRange, split_path: &Path) -> Result<(u64, HotcacheInfo, bool)> {
+    let footer = locate_footer(store_range.size(), |range| {
+        let mut bytes = vec![0u8; range.size() as usize];
+        file.seek(io::SeekFrom::Start(store_range.start + range.start))?;
+        file.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }).map_err(|e| match e {
+        SplitsError::InvalidSplit(msg) => SplitsError::InvalidSplit(format!("{} of split {}", msg, split_path.display())),
+        other => other,
+    })?;
+    
+    let mut payload = vec![0u8; footer.payload.size() as usize];
+    file.seek(io::SeekFrom::Start(store_range.start + footer.payload.start))?;
+    file.read_exact(&mut payload)?;
+    let hotcache = (!payload.is_empty())
+        .then(|| HotcacheInfo::from_bytes(&payload).ok())
+        .flatten()
+        .ok_or_else(|| SplitsError::InvalidOperation(format!(
+            "Split {} has no embedded hotcache in the Simple format to record metadata in", split_path.display()
+        )))?;
+    let data_len = hotcache.store_data_len().unwrap_or(footer.start);
+    Ok((data_len, hotcache, footer.dual))
+}
+
+/// Appends the footers to the store file and returns the byte range of the first payload
+///
+/// With an `alignment`, the store data is zero-padded so the payload starts
+/// on a multiple of it.
+fn write_footer(store_file_path: &Path, payload: &[u8], alignment: Option<u64>, dual_footer: bool) -> Result<(u64, u64)> {
+    let data_len = fs::metadata(store_file_path)?.len();
+    let payload_start = match alignment {
+        Some(alignment) => data_len.div_ceil(alignment) * alignment,
+        None => data_len,
+    };
+    
+    let mut file = fs::OpenOptions::new()
+        .append(true)
+        .open(store_file_path)?;
+    
+    file.write_all(&vec![0u8; (payload_start - data_len) as usize])?;
+    file.write_all(&encode_footers(payload, dual_footer))?;
+    file.sync_all()?;
+    
+    Ok((payload_start, payload_start + payload.len() as u64))
+}
+
+/// Name of the lock file serializing exclusive generations in a directory
+const LOCK_FILE_NAME: &str = ".quickwit-splits.lock";
+
+/// Interval between attempts to take a contended generation lock
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(10);
+
+/// Takes the exclusive generation lock of `dir`, waiting up to `timeout`
+///
+/// The lock is held until the returned file is dropped.
+fn lock_directory(dir: &Path, timeout: Duration) -> Result<fs::File> {
+    fs::create_dir_all(dir)?;
+    let lock_file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(dir.join(LOCK_FILE_NAME))?;
+    
+    let deadline = Instant::now() + timeout;
+    loop {
+        match lock_file.try_lock_exclusive() {
+            Ok(()) => return Ok(lock_file),
+            Err(e) if e.kind() == fs2::lock_contended_error().kind() => {
+                if Instant::now() >= deadline {
+                    return Err(SplitsError::InvalidOperation(format!(
+                        "Timed out after {:?} waiting for the generation lock of {}", timeout, dir.display()
+                    )));
+                }
+                std::thread::sleep(LOCK_RETRY_INTERVAL);
+            }
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+/// Gets the bounds of a column's values over the alive documents of a segment
+fn alive_column_bounds<T>(segment_reader: &SegmentReader, column: &Column<T>) -> Option<(T, T)>
+where
+    T: PartialOrd + Copy + std::fmt::Debug + Send + Sync + 'static,
+{
+    if !segment_reader.has_deletes() {
+        return Some((column.min_value(), column.max_value()));
+    }
+    segment_reader.doc_ids_alive()
+        .flat_map(|doc| column.values_for_doc(doc))
+        .fold(None, |bounds, value| Some(match bounds {
+            Some((min, max)) => (if value < min { value } else { min }, if value > max { value } else { max }),
+            None => (value, value),
+        }))
+}
+
+/// Whether `path` is a split directory of the legacy layout, recognized by its doc store file
+///
+/// Splits of empty indices have no `meta.json`, but every split has a `.store` file.
+fn is_legacy_split_dir(path: &Path) -> bool {
+    fs::read_dir(path).is_ok_and(|entries| entries.flatten()
+        .any(|entry| entry.path().extension().is_some_and(|ext| ext == "store")))
+}
+
+/// Scratch directory merges are written to, removed when dropped
+struct ScratchDir {
+    path: PathBuf,
+}
+
+impl ScratchDir {
+    /// Creates a scratch directory next to `path`
+    fn next_to(path: &Path) -> Result<Self> {
+        let scratch_dir = ScratchDir { path: staging_path_for(path) };
+        fs::create_dir(&scratch_dir.path)?;
+        Ok(scratch_dir)
+    }
+}
+
+impl Drop for ScratchDir {
+    fn drop(&mut self) {
+        if let Err(e) = fs::remove_dir_all(&self.path) {
+            log::warn!("Failed to remove merge scratch directory {}: {}", self.path.display(), e);
+        }
+    }
+}
+
+/// A split generated into a temporary directory, see `QuickwitSplitGenerator::stage_split`
+///
+/// The directory is removed when dropped, streamed or not.
+pub struct StagedSplit {
+    staging_path: PathBuf,
+    metadata: SplitMetadata,
+    /// Progress of the generator that staged the split
+    progress: Arc<AtomicU8>,
+}
+
+impl StagedSplit {
+    /// Streams the split to `upload`, see `QuickwitSplitGenerator::generate_split_streaming`
+    pub fn stream(self, upload: &UploadFn) -> Result<SplitMetadata> {
+        let hasher = RefCell::new(blake3::Hasher::new());
+        stream_split(&self.staging_path, &|chunk, offset| {
+            hasher.borrow_mut().update(chunk);
+            upload(chunk, offset)
+        })?;
+        
+        let mut metadata = self.metadata.clone();
+        metadata.checksum = Some(hasher.into_inner().finalize().to_hex().to_string());
+        self.progress.store(100, Ordering::Relaxed);
+        Ok(metadata)
+    }
+}
+
+impl Drop for StagedSplit {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.staging_path);
+    }
+}
+
+/// The segments a split is made of, with the directory holding the files of each
+struct SplitSegments {
+    /// Segments kept from the index as they are, in index order, then the merged one if any
+    segments: Vec<(SegmentMeta, Box<dyn Directory>)>,
+    /// Scratch directory holding the merged segment, removed once the split is written
+    _scratch_dir: Option<ScratchDir>,
+}
+
+impl SplitSegments {
+    /// Gets the metas of the segments
+    fn metas(&self) -> Vec<SegmentMeta> {
+        self.segments.iter().map(|(segment_meta, _)| segment_meta.clone()).collect()
+    }
+}
+
+/// Fails if merging `source_segments` didn't leave `merged_segment_id` as the only one of them
+///
+/// Reads the segments committed to `index`, the scratch index the merge
+/// ran in, rather than the metas the merge returned.
+fn verify_merge(index: &Index, source_segments: &[SegmentId], merged_segment_id: &SegmentId) -> Result<()> {
+    let remaining = index.load_metas()?.segments.iter()
+        .map(|segment_meta| segment_meta.id())
+        .filter(|segment_id| segment_id == merged_segment_id || source_segments.contains(segment_id))
+        .count();
+    if remaining > 1 {
+        return Err(SplitsError::InvalidOperation(format!(
+            "Merging {} segments left {} segments in the index instead of one",
+            source_segments.len(), remaining
+        )));
+    }
+    Ok(())
+}
+
+/// Checks that a section alignment is a usable power of two
+fn validate_alignment(alignment: u64) -> Result<()> {
+    if !alignment.is_power_of_two() {
+        return Err(SplitsError::InvalidOperation(format!(
+            "Section alignment must be a power of two, got {}", alignment
+        )));
+    }
+    Ok(())
+}
+
+/// Checks that a caller-supplied split id can be used as a file name component
+fn validate_split_id(split_id: &str) -> Result<()> {
+    let is_valid = !split_id.is_empty()
+        && split_id.len() <= 255
+        && split_id != "."
+        && split_id != ".."
+        && !split_id.contains(|c: char| c == '/' || c == '\\' || c.is_control());
+    
+    if !is_valid {
+        return Err(SplitsError::InvalidOperation(format!(
+            "Split id '{}' is not a valid file name component", split_id.escape_debug()
+        )));
+    }
+    
+    Ok(())
+}
+
+/// Returns the path of the sidecar metadata file for a split
+///
+/// The sidecar sits next to the split, named after it with a `.json` suffix.
+pub fn sidecar_metadata_path(split_path: &Path) -> PathBuf {
+    let mut file_name = split_path.file_name()
+        .map(|name| name.to_os_string())
+        .unwrap_or_default();
+    file_name.push(".json");
+    split_path.with_file_name(file_name)
 }
 
 impl QuickwitSplitGenerator {
     /// Creates a new split generator for the given index
+    ///
+    /// The generator works on a snapshot of the index as of its creation;
+    /// see `refresh` to pick up later commits. `target_docs_per_split` isn't
+    /// enforced: `generate_split` always makes one split of the whole index,
+    /// flagged with `SplitMetadata::exceeds_target_docs` and a warning when it
+    /// holds more documents than the target.
     pub fn new(index: Index, target_docs_per_split: usize) -> Result<Self> {
         if target_docs_per_split == 0 {
             return Err(SplitsError::InvalidOperation(
@@ -58,282 +808,2474 @@ impl QuickwitSplitGenerator {
             ));
         }
         
+        // Loaded before the reader, so the opstamp never claims operations the reader misses
+        let reader_opstamp = index.load_metas()?.opstamp;
+        let reader = index.reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()?;
+        
         Ok(QuickwitSplitGenerator {
             index,
             target_docs_per_split,
+            config: SplitConfig::default(),
+            post_process: None,
+            reader,
+            reader_opstamp,
+            source_opstamp: None,
+            progress: Arc::new(AtomicU8::new(0)),
         })
     }
     
-    /// Generates a Quickwit split from the current state of the index
-    pub fn generate_split(&self, output_path: &Path) -> Result<SplitMetadata> {
-        // Ensure output directory exists
-        if let Some(parent) = output_path.parent() {
-            fs::create_dir_all(parent)?;
-        }
-        fs::create_dir_all(output_path)?;
-        
-        // Step 1: Get all segments from the index
-        let segment_ids = self.get_all_segments()?;
+    /// Creates a new split generator for the Tantivy index stored in `index_dir`
+    ///
+    /// Fails with an IO error of kind `InvalidInput` naming the directory if
+    /// it doesn't hold a readable Tantivy index.
+    pub fn open_in_dir(index_dir: &Path, target_docs_per_split: usize) -> Result<Self> {
+        let index = Index::open_in_dir(index_dir).map_err(|e| io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{} is not a valid Tantivy index: {}", index_dir.display(), e),
+        ))?;
+        Self::new(index, target_docs_per_split)
+    }
+    
+    /// Generates one split per `(index directory, output path)` pair, with `config`
+    ///
+    /// Each index is opened and generated as by `open_in_dir` and
+    /// `generate_split`. Inputs run concurrently on the shared thread pool,
+    /// so at most `thread_pool::max_threads` at once, and a failing input
+    /// doesn't stop the others: results come back in input order, so callers
+    /// can retry only the failures.
+    pub fn generate_batch(inputs: &[(PathBuf, PathBuf)], config: &SplitConfig) -> Vec<Result<SplitMetadata>> {
+        // Every index makes a single split, whatever its size
+        let generate = |(index_dir, output_path): &(PathBuf, PathBuf)| -> Result<SplitMetadata> {
+            Self::open_in_dir(index_dir, usize::MAX)?
+                .with_config(config.clone())
+                .generate_split(output_path)
+        };
         
-        if segment_ids.is_empty() {
-            // Handle empty index case
-            return self.create_empty_split(output_path);
+        match thread_pool::global_pool() {
+            Ok(pool) => pool.install(|| inputs.par_iter().map(&generate).collect()),
+            Err(e) => {
+                log::warn!("Generating the batch sequentially: {}", e);
+                inputs.iter().map(generate).collect()
+            }
         }
+    }
+    
+    /// Merges `updates` into the custom metadata of an existing split, rewriting only its footer
+    ///
+    /// `split_path` is a split file, or a split directory of the legacy
+    /// layout. The store data and every other file are copied byte for byte
+    /// into a temporary file next to the split file, or store file, which is
+    /// then renamed over it, so readers never see a partial rewrite. Keys of
+    /// `updates` replace existing ones, other keys are kept. The sidecar
+    /// metadata, if any, is updated the same way and provides the fields the
+    /// split itself doesn't record, such as tags and time range; without it
+    /// they are left empty. Fails with `InvalidOperation` for splits without
+    /// an embedded hotcache in the `Simple` format.
+    pub fn update_metadata(split_path: &Path, updates: HashMap<String, String>) -> Result<SplitMetadata> {
+        let (target, store_file, store_range) = if split_path.is_dir() {
+            let store_path = find_store_file(split_path)?;
+            let store_len = fs::metadata(&store_path)?.len();
+            (store_path, None, ByteRange { start: 0, end: store_len })
+        } else {
+            // The first store file by name, as `find_store_file` picks
+            let (store_file, store_range) = split_file_ranges(split_path)?.into_iter()
+                .filter(|(name, _)| name.ends_with(".store"))
+                .min_by(|a, b| a.0.cmp(&b.0))
+                .ok_or_else(|| SplitsError::InvalidSplit(format!(
+                    "No store file found in split {}", split_path.display()
+                )))?;
+            (split_path.to_path_buf(), Some(store_file), store_range)
+        };
         
-        // Step 2: Merge all segments into a single segment (Quickwit requirement)
-        let merged_segment_id = self.merge_segments(&segment_ids)?;
-        
-        // Step 3: Generate hotcache metadata
-        let hotcache = self.generate_hotcache(&merged_segment_id)?;
+        let mut input = fs::File::open(&target)?;
+        let (data_len, mut hotcache, dual_footer) = read_footer_hotcache(&mut input, &store_range, split_path)?;
+        let mut custom_metadata = hotcache.custom_metadata()?;
+        custom_metadata.extend(updates);
+        hotcache.set_custom_metadata(&custom_metadata)?;
         
-        // Step 4: Copy segment files to output location
-        self.copy_segment_files(&merged_segment_id, output_path)?;
+        let payload = hotcache.to_bytes()?;
+        let payload_start = match hotcache.section_alignment() {
+            Some(alignment) => data_len.div_ceil(alignment) * alignment,
+            None => data_len,
+        };
+        let mut tail = vec![0u8; (payload_start - data_len) as usize];
+        tail.extend_from_slice(&encode_footers(&payload, dual_footer));
+        input.seek(io::SeekFrom::Start(store_range.start))?;
+        let mut store = (&mut input).take(data_len).chain(tail.as_slice());
+        let store_len = data_len + tail.len() as u64;
         
-        // Step 5: Embed hotcache as footer in appropriate file
-        let (hotcache_start, hotcache_end) = self.embed_hotcache(output_path, &hotcache)?;
+        let staging_path = staging_path_for(&target);
+        let result = match &store_file {
+            Some(store_file) => write_split_file_replacing(split_path, &staging_path, store_file, &mut store, store_len)
+                .map(|_| ()),
+            None => fs::File::create(&staging_path)
+                .and_then(|mut output| {
+                    io::copy(&mut store, &mut output)?;
+                    output.sync_all()
+                })
+                .map_err(SplitsError::from),
+        };
+        let result = result.and_then(|()| Ok(fs::rename(&staging_path, &target)?));
+        if result.is_err() {
+            let _ = fs::remove_file(&staging_path);
+        }
+        result?;
         
-        // Step 6: Calculate final split size
-        let size_bytes = self.calculate_split_size(output_path)?;
-        let num_docs = self.count_documents(&merged_segment_id)?;
+        let sidecar_path = sidecar_metadata_path(split_path);
+        let has_sidecar = sidecar_path.is_file();
+        let mut metadata = if has_sidecar {
+            serde_json::from_str(&fs::read_to_string(&sidecar_path)?)?
+        } else {
+            SplitMetadata {
+                split_id: hotcache.split_id.clone(),
+                num_docs: hotcache.num_docs,
+                size_bytes: 0,
+                hotcache_start: 0,
+                hotcache_end: 0,
+                time_start: None,
+                time_end: None,
+                tags: BTreeSet::new(),
+                files: Vec::new(),
+                provenance: hotcache.provenance(),
+                source_segments: 0,
+                opstamp: hotcache.opstamp().unwrap_or(0),
+                checksum: None,
+                exceeds_target_docs: false,
+                warnings: Vec::new(),
+            }
+        };
+        metadata.hotcache_start = payload_start;
+        metadata.hotcache_end = payload_start + payload.len() as u64;
+        if split_path.is_dir() {
+            metadata.files.clear();
+            for entry in fs::read_dir(split_path)? {
+                let name = entry?.file_name().to_string_lossy().to_string();
+                if !name.starts_with('.') {
+                    metadata.files.push(name);
+                }
+            }
+            metadata.files.sort();
+            metadata.size_bytes = metadata.files.iter()
+                .map(|name| Ok(fs::metadata(split_path.join(name))?.len()))
+                .sum::<Result<u64>>()?;
+            metadata.checksum = Some(split_dir_checksum(split_path)?);
+        } else {
+            // Listed in name order, as generation does, rather than in layout order
+            metadata.files = split_file_ranges(split_path)?.into_iter().map(|(name, _)| name).collect();
+            metadata.files.sort();
+            metadata.size_bytes = fs::metadata(split_path)?.len();
+            metadata.checksum = Some(split_file_checksum(split_path)?);
+        }
         
-        Ok(SplitMetadata {
-            split_id: merged_segment_id.uuid_string(),
-            num_docs,
-            size_bytes,
-            hotcache_start,
-            hotcache_end,
-        })
+        if has_sidecar {
+            let staging_path = staging_path_for(&sidecar_path);
+            fs::write(&staging_path, serde_json::to_string_pretty(&metadata)?)?;
+            fs::rename(&staging_path, &sidecar_path)?;
+        }
+        Ok(metadata)
     }
     
-    /// Gets all segment IDs from the index
-    fn get_all_segments(&self) -> Result<Vec<SegmentId>> {
-        let reader = self.index.reader()?;
-        let searcher = reader.searcher();
-        let segment_readers = searcher.segment_readers();
-        
-        Ok(segment_readers.iter()
-           .map(|sr| sr.segment_id())
-           .collect())
+    /// Picks up the commits made to the index since the generator was created or last refreshed
+    ///
+    /// Generation works on a snapshot of the index: a split reflects the
+    /// index state as of the most recent refresh, or of the generator's
+    /// creation, whatever was committed since. This keeps every step of a
+    /// generation consistent while the index is being written to, and lets a
+    /// long-lived generator decide when to follow the index.
+    pub fn refresh(&mut self) -> Result<()> {
+        let reader_opstamp = self.index.load_metas()?.opstamp;
+        self.reader.reload()?;
+        self.reader_opstamp = reader_opstamp;
+        Ok(())
+    }
+    
+    /// Sets the options used for subsequent split generation
+    pub fn with_config(mut self, config: SplitConfig) -> Self {
+        self.config = config;
+        self
+    }
+    
+    /// Replaces the options used for subsequent split generation
+    pub fn set_config(&mut self, config: SplitConfig) {
+        self.config = config;
+    }
+    
+    /// Gets the options used for split generation
+    pub fn config(&self) -> &SplitConfig {
+        &self.config
+    }
+    
+    /// Gets how far the running or last generation got, in percent
+    ///
+    /// Progress is reset to 0 when a generation starts and advances at the
+    /// boundaries of its phases: merging segments, copying their files,
+    /// building the hotcache, post-processing and publishing. Partitioned
+    /// generation advances once per partition instead. It reaches 100 only
+    /// once every split is published, and stays where it was if generation
+    /// fails.
+    pub fn progress(&self) -> u8 {
+        self.progress.load(Ordering::Relaxed)
+    }
+    
+    /// Gets a shared handle on the generator's progress
+    ///
+    /// Reading it never waits on the generator, so another thread can poll
+    /// the progress of a generation while it runs; see `progress`.
+    pub fn progress_handle(&self) -> Arc<AtomicU8> {
+        self.progress.clone()
+    }
+    
+    /// Records that generation is `percent` complete
+    fn report_progress(&self, percent: u8) {
+        self.progress.store(percent, Ordering::Relaxed);
+    }
+    
+    /// Sets a hook run on each split once its files are written
+    ///
+    /// The hook receives the staging directory holding the complete split,
+    /// after the store file and its footer are fsynced, and before the split
+    /// is bundled into its split file or, with the legacy directory layout,
+    /// the directory is renamed to the output path. It may modify or add files,
+    /// e.g. to sign or encrypt the split; they are reflected in the returned
+    /// metadata. If the hook fails, nothing is published and `generate_split`
+    /// returns its error. Files the hook writes aren't fsynced by the
+    /// generator, nor is the final rename.
+    pub fn with_post_process(mut self, hook: impl Fn(&Path) -> Result<()> + Send + Sync + 'static) -> Self {
+        self.post_process = Some(Arc::new(hook));
+        self
     }
     
-    /// Merges multiple segments into a single segment
-    fn merge_segments(&self, segment_ids: &[SegmentId]) -> Result<SegmentId> {
-        if segment_ids.len() == 1 {
-            // Already a single segment
-            return Ok(segment_ids[0]);
+    /// Generates a Quickwit split from the current state of the index
+    ///
+    /// The split is written as a single split file at `output_path`, or at
+    /// `output_path/<split_id>.split` if `output_path` is an existing
+    /// directory. With `SplitConfig::legacy_directory_layout`, it is written
+    /// as a directory of files at `output_path` instead.
+    ///
+    /// The split is staged next to its final path and moved into place once
+    /// complete, so concurrent generations targeting the same path never
+    /// interleave their files: exactly one of them publishes its split, the
+    /// others fail with `InvalidOperation`. An existing split at the final
+    /// path is only replaced when `SplitConfig::overwrite` is set.
+    ///
+    /// Generation is deterministic: split directories are always walked in
+    /// file name order and the hotcache is serialized with sorted entries, so
+    /// the same index generates byte-identical splits, up to the split id,
+    /// the creation time and the ids of merged segments. Pinning
+    /// `SplitConfig::split_id` and `SplitConfig::created_at_millis` removes
+    /// the first two.
+    pub fn generate_split(&self, output_path: &Path) -> Result<SplitMetadata> {
+        self.report_progress(0);
+        self.validate_config()?;
+        let split_id = self.new_split_id();
+        
+        let mut resolved_path = resolve_output_path(output_path, self.config.output_base_dir.as_deref())?;
+        // A legacy split directory is a split to replace, not a directory to write into
+        let replaces_split_dir = self.config.overwrite && is_legacy_split_dir(&resolved_path);
+        if !self.config.legacy_directory_layout && resolved_path.is_dir() && !replaces_split_dir {
+            resolved_path.push(split_file_name(&split_id));
         }
+        let parent = resolved_path.parent()
+            .ok_or_else(|| SplitsError::InvalidOperation(format!(
+                "Split output path can't be a filesystem root: {}", output_path.display()
+            )))?;
+        let output_path = resolved_path.as_path();
+        
+        // Held until generation returns, sidecar included
+        let _lock = if self.config.exclusive {
+            Some(lock_directory(parent, Duration::from_millis(self.config.lock_timeout_millis))?)
+        } else {
+            None
+        };
         
-        // Create a new index writer for merging
-        let mut index_writer = self.index.writer_in_ram(100_000_000)?;
+        self.ensure_output_available(output_path)?;
         
-        // Force merge all segments into one
-        // Note: Tantivy 0.24+ has async merge, we'll use a different approach
-        // For now, if there's only one segment, return it
-        if segment_ids.len() == 1 {
-            return Ok(segment_ids[0]);
+        // Stage the split in the same parent directory so publishing it is atomic
+        let staging_path = staging_path_for(output_path);
+        let staging_file = (!self.config.legacy_directory_layout).then(|| staging_path_for(output_path));
+        fs::create_dir_all(parent)?;
+        fs::create_dir(&staging_path)?;
+        
+        let result = self.write_split(&staging_path, split_id)
+            .and_then(|metadata| self.run_post_process(&staging_path, metadata))
+            .and_then(|mut metadata| {
+                self.report_progress(90);
+                let checksum = match &staging_file {
+                    Some(staging_file) => {
+                        let (size_bytes, checksum) = write_split_file(&staging_path, staging_file)?;
+                        metadata.size_bytes = size_bytes;
+                        checksum
+                    }
+                    None => split_dir_checksum(&staging_path)?,
+                };
+                metadata.checksum = Some(checksum);
+                self.check_split_size(metadata)
+            })
+            .and_then(|metadata| {
+                self.publish_split(staging_file.as_deref().unwrap_or(staging_path.as_path()), output_path)?;
+                Ok(metadata)
+            });
+        
+        // The staging directory was either renamed into place or bundled into the split file
+        if result.is_err() || staging_file.is_some() {
+            let _ = fs::remove_dir_all(&staging_path);
+        }
+        if let Some(staging_file) = &staging_file {
+            let _ = fs::remove_file(staging_file);
         }
+        let metadata = result?;
         
-        // Create a new writer and commit to trigger merge
-        index_writer.commit()?;
+        if self.config.write_sidecar_metadata {
+            self.write_sidecar_metadata(output_path, &metadata)?;
+        }
+        self.report_progress(100);
         
-        // Return the first segment for now (simplified)
-        Ok(segment_ids[0])
+        log::debug!(
+            "Generated split {} with {} docs at {}",
+            metadata.split_id, metadata.num_docs, output_path.display()
+        );
+        Ok(metadata)
     }
     
-    /// Generates hotcache metadata for the segment
-    fn generate_hotcache(&self, segment_id: &SegmentId) -> Result<HotcacheInfo> {
-        let reader = self.index.reader()?;
-        let searcher = reader.searcher();
-        
-        // Get basic metrics from the index
-        let num_docs = searcher.num_docs() as u32;
-        let size_bytes = self.estimate_segment_size(segment_id)?;
-        
-        // Create simplified hotcache info
-        create_hotcache(segment_id.uuid_string(), num_docs, size_bytes)
+    /// Generates a split and streams it to `upload`, serialized
+    ///
+    /// Nothing is published locally; `upload` receives the split in the
+    /// format of `split_bytes`, chunked for multipart uploads, with the store
+    /// file carrying the hotcache footer last. See `stream_split` for chunk
+    /// sizes and ordering. Tantivy writes segments through a directory, so the
+    /// split files are still assembled in a temporary directory, removed once
+    /// streamed, but the serialized split is never held in memory or on disk.
+    /// Generation stops at the first upload error, which is returned: the
+    /// caller is expected to abort its multipart upload. The returned
+    /// metadata carries the checksum of the uploaded bytes, to compare
+    /// against the stored object.
+    pub fn generate_split_streaming(&self, upload: &UploadFn) -> Result<SplitMetadata> {
+        self.stage_split()?.stream(upload)
     }
     
-    /// Calculate the actual size of a segment by examining its files
-    fn estimate_segment_size(&self, segment_id: &SegmentId) -> Result<u64> {
-        let reader = self.index.reader()?;
-        let searcher = reader.searcher();
+    /// Generates a split into a temporary directory, to be streamed by `StagedSplit::stream`
+    ///
+    /// This is the first half of `generate_split_streaming`, letting callers
+    /// release the generator before the upload.
+    pub fn stage_split(&self) -> Result<StagedSplit> {
+        self.report_progress(0);
+        self.validate_config()?;
         
-        // Find the segment reader for this segment
-        let segment_reader = searcher.segment_readers()
-            .iter()
-            .find(|sr| sr.segment_id() == *segment_id);
-            
-        if let Some(sr) = segment_reader {
-            // Calculate size from segment reader statistics
-            let num_docs = sr.num_docs() as u64;
-            let alive_docs = sr.num_alive_docs() as u64;
-            let max_doc = sr.max_doc() as u64;
-            
-            // Estimate based on document count and field data
-            // This is a rough calculation - in practice you'd want to examine actual file sizes
-            let base_size_per_doc = 1024; // 1KB per document baseline
-            let field_overhead = num_docs * 512; // Additional field storage overhead
-            let index_overhead = max_doc * 256; // Index structures overhead
-            
-            let estimated_size = (alive_docs * base_size_per_doc) + field_overhead + index_overhead;
-            Ok(estimated_size)
-        } else {
-            // Fallback: estimate based on index-wide statistics
-            let total_docs = searcher.num_docs() as u64;
-            let estimated_size_per_doc = 2048; // 2KB per document
-            
-            Ok(total_docs * estimated_size_per_doc)
+        let staging_path = std::env::temp_dir()
+            .join(format!(".quickwit-split.{}.tmp", Uuid::new_v4().simple()));
+        fs::create_dir(&staging_path)?;
+        
+        let result = self.write_split(&staging_path, self.new_split_id())
+            .and_then(|metadata| self.run_post_process(&staging_path, metadata))
+            .and_then(|metadata| self.check_split_size(metadata));
+        match result {
+            Ok(metadata) => {
+                self.report_progress(90);
+                Ok(StagedSplit { staging_path, metadata, progress: self.progress.clone() })
+            }
+            Err(e) => {
+                let _ = fs::remove_dir_all(&staging_path);
+                Err(e)
+            }
         }
     }
     
-    /// Calculate actual file sizes for a segment (when access to file system is available)
-    fn calculate_actual_segment_size(&self, segment_id: &SegmentId, index_path: &Path) -> Result<u64> {
-        let segment_files = self.list_segment_files(segment_id)?;
-        let mut total_size = 0u64;
-        
-        for file_name in segment_files {
-            let file_path = index_path.join(&file_name);
-            if let Ok(metadata) = fs::metadata(&file_path) {
-                total_size += metadata.len();
-            }
+    /// Generates one split per distinct value of `partition_field`
+    ///
+    /// Each split is written to `output_dir/<split id>.split`, or the
+    /// `output_dir/<split id>` directory with the legacy layout, and tagged with
+    /// `partition_field:value`. The partition field must be an indexed text
+    /// field, typically a raw-tokenized tenant or partition key, and generation
+    /// fails if it has more than `SplitConfig::max_partitions` distinct values.
+    /// When `SplitConfig::split_id` is set, partitions are named
+    /// `<split_id>-<n>` in partition value order; otherwise each gets a fresh id.
+    /// Values only held by deleted documents produce no split. The post-process
+    /// hook, if any, runs on every partition's split.
+    ///
+    /// Tantivy can't split a segment, so every partition is re-indexed into its
+    /// own in-RAM index from the doc store: expect roughly the cost of indexing
+    /// the whole corpus again, plus holding the largest partition in memory.
+    /// Because documents are rebuilt from stored values, every field of the
+    /// schema must be stored.
+    pub fn generate_partitioned_splits(&self, partition_field: &str, output_dir: &Path) -> Result<Vec<SplitMetadata>> {
+        let schema = self.index.schema();
+        let field = schema.get_field(partition_field)
+            .map_err(|_| SplitsError::FieldError(format!("Field '{}' not found", partition_field)))?;
+        match schema.get_field_entry(field).field_type() {
+            FieldType::Str(options) if options.get_indexing_options().is_some() => {}
+            other => return Err(SplitsError::FieldError(format!(
+                "Partition field '{}' must be an indexed text field, got {:?}",
+                partition_field, other.value_type()
+            ))),
         }
         
-        if total_size > 0 {
-            Ok(total_size)
-        } else {
-            // Fallback to estimation if no files found
-            self.estimate_segment_size(segment_id)
+        let unstored: Vec<&str> = schema.fields()
+            .filter(|(_, entry)| !entry.is_stored())
+            .map(|(_, entry)| entry.name())
+            .collect();
+        if !unstored.is_empty() {
+            return Err(SplitsError::FieldError(format!(
+                "Partitioning re-indexes stored documents, but these fields aren't stored: {}",
+                unstored.join(", ")
+            )));
         }
-    }
-    
-    /// Copies segment files to the output directory
-    fn copy_segment_files(&self, segment_id: &SegmentId, output_path: &Path) -> Result<()> {
-        // For now, use a simple approach to get index path
-        // In a real implementation, this would extract the path from the directory
-        let index_path = std::path::PathBuf::from("."); // Placeholder
         
-        // Get all files for this segment
-        let segment_files = self.list_segment_files(segment_id)?;
+        self.report_progress(0);
+        let values = self.partition_values(partition_field)?;
+        let source_segments = self.source_segment_count()?;
+        let opstamp = self.opstamp()?;
+        fs::create_dir_all(output_dir)?;
         
-        for file_name in segment_files {
-            let src_path = index_path.join(&file_name);
-            let dst_path = output_path.join(&file_name);
+        let searcher = self.reader.searcher();
+        let mut splits = Vec::with_capacity(values.len());
+        for (done, value) in values.iter().enumerate() {
+            // Partitions count equally, whatever their size
+            self.report_progress((done * 100 / values.len()) as u8);
+            let query = TermQuery::new(Term::from_field_text(field, value), IndexRecordOption::Basic);
+            let mut doc_addresses: Vec<DocAddress> = searcher.search(&query, &DocSetCollector)?
+                .into_iter()
+                .collect();
+            if doc_addresses.is_empty() {
+                // Terms of deleted documents linger until their segment is merged
+                continue;
+            }
+            doc_addresses.sort();
+            
+            // Re-index the partition's documents with the same schema and tokenizers
+            let mut partition_index = Index::create_in_ram(schema.clone());
+            partition_index.set_tokenizers(self.index.tokenizers().clone());
+            let mut index_writer = partition_index.writer(50_000_000)?;
+            for doc_address in doc_addresses {
+                index_writer.add_document(searcher.doc::<TantivyDocument>(doc_address)?)?;
+            }
+            index_writer.commit()?;
             
-            if src_path.exists() {
-                fs::copy(&src_path, &dst_path)?;
+            let mut config = self.config.clone();
+            let split_id = match &self.config.split_id {
+                Some(prefix) => format!("{}-{}", prefix, splits.len()),
+                None => Uuid::now_v7().to_string(),
+            };
+            config.split_id = Some(split_id.clone());
+            if !config.tag_fields.iter().any(|tag_field| tag_field == partition_field) {
+                config.tag_fields.push(partition_field.to_string());
             }
+            
+            let mut generator = QuickwitSplitGenerator::new(partition_index, self.target_docs_per_split)?
+                .with_config(config);
+            generator.post_process = self.post_process.clone();
+            generator.source_opstamp = Some(opstamp);
+            let split_path = if self.config.legacy_directory_layout {
+                output_dir.join(&split_id)
+            } else {
+                output_dir.join(split_file_name(&split_id))
+            };
+            let mut metadata = generator.generate_split(&split_path)?;
+            // Report the fragmentation of this index, not of the re-indexed partition
+            metadata.source_segments = source_segments;
+            splits.push(metadata);
         }
+        self.report_progress(100);
         
-        Ok(())
+        Ok(splits)
     }
     
-    /// Lists all files belonging to a segment
-    fn list_segment_files(&self, segment_id: &SegmentId) -> Result<Vec<String>> {
-        let uuid = segment_id.uuid_string();
-        let extensions = vec![
-            "store", "term", "idx", "fast", "pos", "fieldnorm", "del"
-        ];
+    /// Lists the distinct values of a partition field, failing above `max_partitions`
+    fn partition_values(&self, partition_field: &str) -> Result<BTreeSet<String>> {
+        let field = self.index.schema().get_field(partition_field)
+            .map_err(|_| SplitsError::FieldError(format!("Field '{}' not found", partition_field)))?;
+        let searcher = self.reader.searcher();
         
-        let mut files = Vec::new();
-        for ext in extensions {
-            files.push(format!("{}.{}", uuid, ext));
+        let mut values = BTreeSet::new();
+        for segment_reader in searcher.segment_readers() {
+            let inverted_index = segment_reader.inverted_index(field)?;
+            let mut terms = inverted_index.terms().stream()?;
+            while terms.advance() {
+                values.insert(String::from_utf8_lossy(terms.key()).to_string());
+                if values.len() > self.config.max_partitions {
+                    return Err(SplitsError::InvalidOperation(format!(
+                        "Field '{}' has more than {} distinct values; raise max_partitions to allow more splits",
+                        partition_field, self.config.max_partitions
+                    )));
+                }
+            }
         }
         
-        Ok(files)
+        Ok(values)
+    }
+    
+    /// Returns the configured split id, or a fresh one
+    fn new_split_id(&self) -> String {
+        self.config.split_id.clone().unwrap_or_else(|| Uuid::now_v7().to_string())
     }
     
-    /// Embeds hotcache metadata as a footer in the appropriate file
-    fn embed_hotcache(&self, output_path: &Path, hotcache: &HotcacheInfo) -> Result<(u64, u64)> {
-        // Serialize hotcache
-        let hotcache_data = hotcache.to_bytes()?;
+    /// Writes all split files into `split_dir`
+    fn write_split(&self, split_dir: &Path, split_id: String) -> Result<SplitMetadata> {
+        let mut provenance = Provenance::current();
+        if let Some(created_at_millis) = self.config.created_at_millis {
+            provenance.created_at_millis = Some(created_at_millis);
+        }
         
-        // Find the store file to embed the footer
-        let store_files: Vec<_> = fs::read_dir(output_path)?
-            .filter_map(|entry| entry.ok())
-            .filter(|entry| {
-                entry.file_name()
-                    .to_string_lossy()
-                    .ends_with(".store")
-            })
-            .collect();
+        // Step 1: Get all segments from the index
+        let segment_ids = self.get_all_segments()?;
+        let opstamp = self.opstamp()?;
         
-        if store_files.is_empty() {
-            return Err(SplitsError::InvalidSplit(
-                "No store file found to embed hotcache".to_string()
-            ));
+        if segment_ids.is_empty() {
+            // Handle empty index case
+            return self.create_empty_split(split_dir, split_id, provenance, opstamp);
         }
         
-        let store_file_path = store_files[0].path();
+        // Step 2: Merge segments as the merge policy asks; Quickwit requires a single one
+        let split_segments = self.merge_segments(&segment_ids, split_dir)?;
+        self.report_progress(40);
         
-        // Get current file size (this will be the hotcache start position)
-        let metadata = fs::metadata(&store_file_path)?;
-        let hotcache_start = metadata.len();
-        
-        // Append hotcache data to the store file
-        use std::io::Write;
-        let mut file = fs::OpenOptions::new()
-            .append(true)
-            .open(&store_file_path)?;
+        // Step 3: Copy segment files to output location
+        let split_segment_metas = split_segments.metas();
+        let mut split_files = vec!["meta.json".to_string()];
+        for segment_meta in &split_segment_metas {
+            split_files.extend(self.list_segment_files(segment_meta));
+        }
+        check_case_collisions(&split_files)?;
+        for (segment_meta, directory) in &split_segments.segments {
+            self.copy_segment_files(directory.as_ref(), segment_meta, split_dir)?;
+        }
+        self.write_index_meta(&split_segment_metas, split_dir)?;
+        self.report_progress(60);
         
-        file.write_all(&hotcache_data)?;
-        file.sync_all()?;
+        // Step 4: Generate the hotcache and embed it as footer in the store file
+        let store_file_path = find_store_file(split_dir)?;
+        let (hotcache_start, hotcache_end) = if !self.config.embed_hotcache {
+            write_footer(&store_file_path, &[], None, false)?
+        } else if self.config.hotcache_format == HotcacheFormat::Quickwit {
+            self.embed_quickwit_hotcache(split_dir, &store_file_path)?
+        } else {
+            let mut hotcache = self.generate_hotcache(&split_segments, &split_id, split_dir)?;
+            hotcache.set_provenance(&provenance);
+            hotcache.set_opstamp(opstamp);
+            if let Some(key) = &self.config.encryption_key {
+                hotcache.set_encrypted_files(&encrypt_split_files(split_dir, key)?)?;
+            }
+            self.embed_hotcache(&store_file_path, hotcache)?
+        };
+        self.report_progress(80);
         
-        let hotcache_end = hotcache_start + hotcache_data.len() as u64;
+        // Step 5: Calculate final split size
+        let size_bytes = self.calculate_split_size(split_dir)?;
+        let num_docs = split_segment_metas.iter().map(SegmentMeta::num_docs).sum::<u32>();
+        let (time_start, time_end) = self.compute_time_range()?.unzip();
+        let exceeds_target_docs = num_docs as usize > self.target_docs_per_split;
+        let mut warnings = Vec::new();
+        if exceeds_target_docs {
+            warnings.push(format!(
+                "Split holds {} documents, more than the target of {} per split: indices aren't split by document count",
+                num_docs, self.target_docs_per_split
+            ));
+        }
+        warnings.extend(self.hotcache_fields_warning(split_segment_metas.len()));
+        warnings.extend(self.merge_threads_warning());
+        let tags = self.collect_tags(&mut warnings)?;
+        for warning in &warnings {
+            log::warn!("Split {}: {}", split_id, warning);
+        }
         
-        Ok((hotcache_start, hotcache_end))
+        Ok(SplitMetadata {
+            split_id,
+            num_docs,
+            size_bytes,
+            hotcache_start,
+            hotcache_end,
+            time_start,
+            time_end,
+            tags,
+            files: self.list_output_files(split_dir)?,
+            provenance,
+            source_segments: segment_ids.len(),
+            opstamp,
+            checksum: None,
+            exceeds_target_docs,
+            warnings,
+        })
     }
     
-    /// Calculates the total size of all split files
-    fn calculate_split_size(&self, output_path: &Path) -> Result<u64> {
-        let mut total_size = 0;
-        
-        for entry in fs::read_dir(output_path)? {
-            let entry = entry?;
-            let metadata = entry.metadata()?;
-            if metadata.is_file() {
-                total_size += metadata.len();
-            }
+    /// Runs the post-process hook, if any, refreshing the metadata it may affect
+    fn run_post_process(&self, split_dir: &Path, mut metadata: SplitMetadata) -> Result<SplitMetadata> {
+        if let Some(hook) = &self.post_process {
+            hook(split_dir)?;
+            metadata.size_bytes = self.calculate_split_size(split_dir)?;
+            metadata.files = self.list_output_files(split_dir)?;
         }
-        
-        Ok(total_size)
+        Ok(metadata)
     }
     
-    /// Counts documents in a segment
-    fn count_documents(&self, segment_id: &SegmentId) -> Result<u32> {
-        let reader = self.index.reader()?;
-        let searcher = reader.searcher();
-        
-        let segment_reader = searcher.segment_readers()
-            .iter()
-            .find(|sr| sr.segment_id() == *segment_id)
-            .ok_or_else(|| SplitsError::InvalidOperation(
-                "Segment not found in searcher".to_string()
-            ))?;
-        
-        Ok(segment_reader.num_docs())
+    /// Rejects a split larger than `SplitConfig::max_split_size_bytes`
+    fn check_split_size(&self, metadata: SplitMetadata) -> Result<SplitMetadata> {
+        match self.config.max_split_size_bytes {
+            Some(max_size) if metadata.size_bytes > max_size => Err(SplitsError::InvalidOperation(format!(
+                "Split {} exceeds max size: {} bytes, over the limit of {}",
+                metadata.split_id, metadata.size_bytes, max_size
+            ))),
+            _ => Ok(metadata),
+        }
     }
     
-    /// Creates an empty split for indices with no documents
-    fn create_empty_split(&self, output_path: &Path) -> Result<SplitMetadata> {
-        // Create a minimal hotcache for empty split
-        let hotcache = Hotcache::empty(self.index.schema());
-        let hotcache_data = hotcache.serialize()?;
-        
-        // Create a minimal store file with just the hotcache
-        let store_file_path = output_path.join(format!("{}.store", Uuid::new_v4()));
-        fs::write(&store_file_path, &hotcache_data)?;
-        
-        Ok(SplitMetadata {
-            split_id: Uuid::new_v4().to_string(),
-            num_docs: 0,
-            size_bytes: hotcache_data.len() as u64,
-            hotcache_start: 0,
-            hotcache_end: hotcache_data.len() as u64,
-        })
+    /// Fails fast on a configuration generation would reject midway
+    fn validate_config(&self) -> Result<()> {
+        self.validate_config_fields()?;
+        if let Some(split_id) = &self.config.split_id {
+            validate_split_id(split_id)?;
+        }
+        if let Some(alignment) = self.config.align_sections {
+            validate_alignment(alignment)?;
+        }
+        if self.config.merge_policy == MergePolicy::MaxSegments(0) {
+            return Err(SplitsError::InvalidOperation(
+                "Merge policy max segments must be greater than 0".to_string()
+            ));
+        }
+        if self.config.merge_threads == Some(0) {
+            return Err(SplitsError::InvalidOperation(
+                "Merge threads must be greater than 0".to_string()
+            ));
+        }
+        self.validate_hotcache_format()?;
+        self.validate_encryption()?;
+        for config in self.config.tokenizers.values() {
+            config.build()?;
+        }
+        Ok(())
+    }
+    
+    /// Fails on a hotcache format this build or configuration can't produce
+    fn validate_hotcache_format(&self) -> Result<()> {
+        if !self.config.hotcache_format.is_supported() {
+            return Err(SplitsError::InvalidOperation(
+                "The Quickwit hotcache format requires the quickwit-compat feature".to_string()
+            ));
+        }
+        if self.config.hotcache_format == HotcacheFormat::Quickwit && self.config.align_sections.is_some() {
+            return Err(SplitsError::InvalidOperation(
+                "Section alignment isn't supported with the Quickwit hotcache format".to_string()
+            ));
+        }
+        if self.config.hotcache_format == HotcacheFormat::Quickwit && self.config.dual_footer {
+            return Err(SplitsError::InvalidOperation(
+                "Dual footers aren't supported with the Quickwit hotcache format".to_string()
+            ));
+        }
+        Ok(())
+    }
+    
+    /// Fails on encryption this build or configuration can't provide
+    fn validate_encryption(&self) -> Result<()> {
+        if self.config.encryption_key.is_none() {
+            return Ok(());
+        }
+        if !cfg!(feature = "encryption") {
+            return Err(SplitsError::InvalidOperation(
+                "Split encryption requires the encryption feature".to_string()
+            ));
+        }
+        if !self.config.embed_hotcache || self.config.hotcache_format != HotcacheFormat::Simple {
+            return Err(SplitsError::InvalidOperation(
+                "Split encryption requires an embedded hotcache in the Simple format".to_string()
+            ));
+        }
+        Ok(())
+    }
+    
+    /// Fails if `output_path` already holds a split and overwriting is disabled
+    fn ensure_output_available(&self, output_path: &Path) -> Result<()> {
+        if !self.config.overwrite && !is_missing_or_empty_dir(output_path)? {
+            return Err(SplitsError::InvalidOperation(format!(
+                "Split output path already exists: {}",
+                output_path.display()
+            )));
+        }
+        Ok(())
+    }
+    
+    /// Moves a fully written staging directory or split file to its final location
+    ///
+    /// A staged split file is linked rather than renamed, which unlike a
+    /// rename never replaces a file published meanwhile; the caller removes
+    /// the staged file afterwards. A split being overwritten is moved aside
+    /// and only deleted once the new one is published, or moved back if
+    /// publishing fails, so the output path never loses its split.
+    fn publish_split(&self, staging_path: &Path, output_path: &Path) -> Result<()> {
+        let mut replaced_path = None;
+        if output_path.exists() {
+            if self.config.overwrite {
+                let aside_path = staging_path_for(output_path);
+                fs::rename(output_path, &aside_path)?;
+                replaced_path = Some(aside_path);
+            } else if is_missing_or_empty_dir(output_path)? {
+                // An empty directory may have been pre-created by the caller
+                fs::remove_dir(output_path)?;
+            }
+        }
+        
+        let published = if staging_path.is_dir() {
+            fs::rename(staging_path, output_path)
+        } else {
+            fs::hard_link(staging_path, output_path)
+        };
+        if let Some(replaced_path) = replaced_path {
+            // Restoring never replaces a split another generation published meanwhile
+            let removed = if replaced_path.is_dir() {
+                if published.is_ok() || fs::rename(&replaced_path, output_path).is_err() {
+                    fs::remove_dir_all(&replaced_path)
+                } else {
+                    Ok(())
+                }
+            } else {
+                if published.is_err() {
+                    let _ = fs::hard_link(&replaced_path, output_path);
+                }
+                fs::remove_file(&replaced_path)
+            };
+            if let Err(e) = removed {
+                log::warn!("Failed to remove replaced split {}: {}", replaced_path.display(), e);
+            }
+        }
+        published.map_err(|e| {
+            if output_path.exists() {
+                // Another generation published to the same path first
+                SplitsError::InvalidOperation(format!(
+                    "Split output path already exists: {}",
+                    output_path.display()
+                ))
+            } else {
+                SplitsError::Io(e)
+            }
+        })
+    }
+    
+    /// Checks that every field named in the configuration exists in the schema
+    fn validate_config_fields(&self) -> Result<()> {
+        let schema = self.index.schema();
+        
+        let missing: Vec<&str> = self.config.referenced_fields()
+            .filter(|field_name| schema.get_field(field_name).is_err())
+            .collect();
+        
+        if !missing.is_empty() {
+            return Err(SplitsError::FieldError(format!(
+                "Configured fields not found in index schema: {}",
+                missing.join(", ")
+            )));
+        }
+        
+        Ok(())
+    }
+    
+    /// Estimates the size of the split the index would currently generate, without generating it
+    ///
+    /// Sums the real lengths of the source segments' files, read through the
+    /// index directory, so it costs a few metadata lookups per segment. The
+    /// upper bound is that sum; the lower bound scales each segment by its
+    /// share of live documents, as merging drops deleted documents.
+    ///
+    /// Merging recompresses data about as well as the source segments did,
+    /// so for an index without deletes the real split usually lands within a
+    /// few percent of the upper bound. With deletes, it lands between the
+    /// bounds: term dictionaries and doc store blocks don't shrink in
+    /// proportion to deletions, so expect it above the lower bound. Neither
+    /// bound includes the split's `meta.json` and hotcache footer, which add
+    /// a few KiB, plus a little per hotcached field.
+    pub fn plan_split(&self) -> Result<SplitPlan> {
+        let mut plan = SplitPlan::default();
+        for segment_meta in self.snapshot_segment_metas() {
+            let size = self.segment_files_size(self.index.directory(), &segment_meta)?;
+            let num_docs = segment_meta.num_docs() as u64;
+            let max_doc = segment_meta.max_doc() as u64;
+            
+            plan.num_docs += num_docs;
+            plan.source_segments += 1;
+            plan.max_size_bytes += size;
+            plan.min_size_bytes += if max_doc == 0 { 0 } else { (size as u128 * num_docs as u128 / max_doc as u128) as u64 };
+        }
+        Ok(plan)
+    }
+    
+    /// Gets the opstamp the split reflects: the source index's last commit
+    fn opstamp(&self) -> Result<u64> {
+        match self.source_opstamp {
+            Some(opstamp) => Ok(opstamp),
+            None => Ok(self.reader_opstamp),
+        }
+    }
+    
+    /// Gets all segment IDs from the index
+    fn get_all_segments(&self) -> Result<Vec<SegmentId>> {
+        let searcher = self.reader.searcher();
+        let segment_readers = searcher.segment_readers();
+        
+        Ok(segment_readers.iter()
+           .map(|sr| sr.segment_id())
+           .collect())
+    }
+    
+    /// Merges segments as `SplitConfig::merge_policy` asks, returning those the split is made of
+    ///
+    /// When there are too many segments, the ones with the fewest documents
+    /// are merged into one, so large segments aren't rewritten. Segments kept
+    /// as they are come first, in index order, then the merged one. An index
+    /// already within the policy, e.g. of a single segment, is used as is.
+    ///
+    /// Merges run in a scratch index next to `split_dir`, never in the source
+    /// index: its writer lock isn't taken, so the application can keep its
+    /// writer open, and the index and the generator's snapshot of it are
+    /// left untouched. This costs a copy of the merged segments' files.
+    fn merge_segments(&self, segment_ids: &[SegmentId], split_dir: &Path) -> Result<SplitSegments> {
+        let segment_metas: Vec<SegmentMeta> = self.snapshot_segment_metas()
+            .into_iter()
+            .filter(|segment_meta| segment_ids.contains(&segment_meta.id()))
+            .collect();
+        let max_segments = match self.config.merge_policy {
+            MergePolicy::ForceSingle => 1,
+            MergePolicy::MaxSegments(max_segments) => max_segments,
+            MergePolicy::NoMerge => usize::MAX,
+        };
+        if segment_metas.len() <= max_segments {
+            return Ok(SplitSegments {
+                segments: segment_metas.into_iter()
+                    .map(|segment_meta| (segment_meta, self.index.directory().box_clone()))
+                    .collect(),
+                _scratch_dir: None,
+            });
+        }
+        
+        let mut by_num_docs = segment_metas.clone();
+        by_num_docs.sort_by_key(|segment_meta| segment_meta.num_docs());
+        let to_merge: Vec<SegmentMeta> = by_num_docs.into_iter()
+            .take(segment_metas.len() - max_segments + 1)
+            .collect();
+        let merged_ids: Vec<SegmentId> = to_merge.iter().map(|segment_meta| segment_meta.id()).collect();
+        
+        let scratch_dir = ScratchDir::next_to(split_dir)?;
+        let scratch_index = self.create_scratch_index(&scratch_dir.path, &to_merge)?;
+        
+        let merged_segment = thread_pool::install(|| self.merge_segments_in_pool(&scratch_index, &merged_ids))?;
+        if self.config.verify_merge {
+            verify_merge(&scratch_index, &merged_ids, &merged_segment.id())?;
+        }
+        
+        let mut segments: Vec<(SegmentMeta, Box<dyn Directory>)> = segment_metas.into_iter()
+            .filter(|segment_meta| !merged_ids.contains(&segment_meta.id()))
+            .map(|segment_meta| (segment_meta, self.index.directory().box_clone()))
+            .collect();
+        segments.push((merged_segment, scratch_index.directory().box_clone()));
+        Ok(SplitSegments { segments, _scratch_dir: Some(scratch_dir) })
+    }
+    
+    /// Creates an index in `scratch_path` holding copies of the segments to merge
+    ///
+    /// The segment files are copied with their Tantivy footer, and the
+    /// scratch `meta.json` lists the segments with their deletes.
+    fn create_scratch_index(&self, scratch_path: &Path, segment_metas: &[SegmentMeta]) -> Result<Index> {
+        for segment_meta in segment_metas {
+            self.copy_segment_files(self.index.directory(), segment_meta, scratch_path)?;
+        }
+        
+        let mut index_meta = self.index.load_metas()?;
+        index_meta.segments = segment_metas.to_vec();
+        index_meta.opstamp = self.opstamp()?;
+        fs::write(scratch_path.join("meta.json"), serde_json::to_string_pretty(&index_meta)?)?;
+        
+        let directory = MmapDirectory::open(scratch_path).map_err(|e| SplitsError::InvalidOperation(format!(
+            "Failed to open merge scratch directory {}: {}", scratch_path.display(), e
+        )))?;
+        Ok(Index::open(directory)?)
+    }
+    
+    /// Performs the merge in the scratch index; called from within the shared thread pool
+    fn merge_segments_in_pool(&self, scratch_index: &Index, segment_ids: &[SegmentId]) -> Result<SegmentMeta> {
+        let mut index_writer = match self.config.merge_threads {
+            Some(threads) => scratch_index.writer_with_num_threads::<TantivyDocument>(
+                threads,
+                MERGE_WRITER_MEMORY_BYTES.max(threads * WRITER_THREAD_MIN_MEMORY_BYTES),
+            )?,
+            None => scratch_index.writer::<TantivyDocument>(MERGE_WRITER_MEMORY_BYTES)?,
+        };
+        // Only the requested merge runs, none the default policy would pick after it
+        index_writer.set_merge_policy(Box::new(NoMergePolicy));
+        let merged_segment = index_writer.merge(segment_ids).wait()?;
+        index_writer.wait_merging_threads()?;
+        
+        merged_segment.ok_or_else(|| SplitsError::InvalidOperation(
+            "Merged segments hold no live documents".to_string()
+        ))
+    }
+    
+    /// Generates hotcache metadata for the split's segments
+    ///
+    /// Field ranges are only recorded for a single segment, as readers look
+    /// them up in the split's one set of segment files.
+    fn generate_hotcache(&self, split_segments: &SplitSegments, split_id: &str, split_dir: &Path) -> Result<HotcacheInfo> {
+        let searcher = self.reader.searcher();
+        
+        // Get basic metrics from the index
+        let num_docs = searcher.num_docs() as u32;
+        let size_bytes = split_segments.segments.iter()
+            .map(|(segment_meta, directory)| self.segment_files_size(directory.as_ref(), segment_meta))
+            .sum::<Result<u64>>()?;
+        
+        // Create simplified hotcache info
+        let mut hotcache = create_hotcache(split_id.to_string(), num_docs, size_bytes)?;
+        hotcache.set_tokenizers(&self.config.tokenizers)?;
+        hotcache.set_custom_metadata(&self.config.custom_metadata)?;
+        // Field ranges hold term and value bounds, which would leak the encrypted data
+        if let ([(segment_meta, _)], None) = (split_segments.segments.as_slice(), &self.config.encryption_key) {
+            hotcache.set_field_metadata(&record_field_ranges(split_dir, &segment_meta.id(), &self.hotcache_field_names())?)?;
+        }
+        Ok(hotcache)
+    }
+    
+    /// Lists the fields to hotcache, in schema order
+    fn hotcache_field_names(&self) -> Vec<String> {
+        let schema = self.index.schema();
+        schema.fields()
+            .map(|(_, field_entry)| field_entry.name().to_string())
+            .filter(|name| self.config.hotcache_fields.as_ref()
+                .is_none_or(|included| included.contains(name)))
+            .filter(|name| !self.config.hotcache_exclude.contains(name))
+            .collect()
+    }
+    
+    /// Gets the metas of the segments in the generator's snapshot of the index
+    ///
+    /// Built from the reader rather than read from `meta.json`, which may
+    /// have moved on since the last refresh.
+    fn snapshot_segment_metas(&self) -> Vec<SegmentMeta> {
+        let searcher = self.reader.searcher();
+        let delete_opstamps = searcher.generation().segments();
+        searcher.segment_readers().iter()
+            .map(|segment_reader| {
+                let segment_id = segment_reader.segment_id();
+                let segment_meta = self.index.new_segment_meta(segment_id, segment_reader.max_doc());
+                match delete_opstamps.get(&segment_id).copied().flatten() {
+                    Some(opstamp) => segment_meta.with_delete_meta(segment_reader.num_deleted_docs(), opstamp),
+                    None => segment_meta,
+                }
+            })
+            .collect()
+    }
+    
+    /// Sums the lengths of a segment's files, deletes included, as stored in `directory`
+    fn segment_files_size(&self, directory: &dyn Directory, segment_meta: &SegmentMeta) -> Result<u64> {
+        let mut size = 0u64;
+        for file_path in segment_meta.list_files() {
+            // Components a segment doesn't use, e.g. positions without indexed text, aren't written
+            if !directory.exists(&file_path).map_err(tantivy::TantivyError::from)? {
+                continue;
+            }
+            size += directory.open_read(&file_path).map_err(tantivy::TantivyError::from)?.len() as u64;
+        }
+        Ok(size)
+    }
+    
+    /// Copies segment files from `directory` to the output directory
+    ///
+    /// Files are read with `atomic_read`, which unlike `open_read` keeps
+    /// Tantivy's footer, so the copies remain valid segment files.
+    fn copy_segment_files(&self, directory: &dyn Directory, segment_meta: &SegmentMeta, output_path: &Path) -> Result<()> {
+        // Get all files for this segment
+        let segment_files = self.list_segment_files(segment_meta);
+        
+        // Copy files in parallel on the shared pool
+        thread_pool::global_pool()?.install(|| {
+            segment_files.par_iter().try_for_each(|file_name| -> Result<()> {
+                let src_path = Path::new(file_name);
+                
+                // Components a segment doesn't use, e.g. positions without indexed text, aren't written
+                if !directory.exists(src_path).map_err(tantivy::TantivyError::from)? {
+                    return Ok(());
+                }
+                let file_bytes = directory.atomic_read(src_path).map_err(tantivy::TantivyError::from)?;
+                fs::write(output_path.join(file_name), file_bytes)?;
+                Ok(())
+            })
+        })
+    }
+    
+    /// Writes a `meta.json` restricted to the split's segments
+    ///
+    /// This embeds the schema and segment metas so the split directory can be
+    /// opened directly as a Tantivy index.
+    fn write_index_meta(&self, segment_metas: &[SegmentMeta], output_path: &Path) -> Result<()> {
+        let mut index_meta = self.index.load_metas()?;
+        index_meta.segments = segment_metas.to_vec();
+        index_meta.opstamp = self.opstamp()?;
+        
+        let meta_json = serde_json::to_string_pretty(&index_meta)?;
+        fs::write(output_path.join("meta.json"), meta_json)?;
+        
+        Ok(())
+    }
+    
+    /// Lists all files belonging to a segment, sorted by name
+    ///
+    /// The delete file is only listed for a segment with deletes, under the
+    /// `<segment_id>.<opstamp>.del` name of its latest delete opstamp.
+    fn list_segment_files(&self, segment_meta: &SegmentMeta) -> Vec<String> {
+        let mut files: Vec<String> = SegmentComponent::iterator()
+            .filter(|component| match component {
+                SegmentComponent::TempStore => false,
+                SegmentComponent::Delete => segment_meta.has_deletes(),
+                _ => true,
+            })
+            .map(|component| segment_meta.relative_path(*component).to_string_lossy().to_string())
+            .collect();
+        files.sort();
+        files
+    }
+    
+    /// Embeds hotcache metadata as a footer in the split's store file
+    fn embed_hotcache(&self, store_file_path: &Path, mut hotcache: HotcacheInfo) -> Result<(u64, u64)> {
+        let store_data_len = fs::metadata(store_file_path)?.len();
+        if let Some(alignment) = self.config.align_sections {
+            hotcache.set_section_alignment(alignment, store_data_len);
+        } else if self.config.dual_footer {
+            // Legacy readers would otherwise take the versioned footer for store data
+            hotcache.set_store_data_len(store_data_len);
+        }
+        
+        write_footer(store_file_path, &hotcache.to_bytes()?, self.config.align_sections, self.config.dual_footer)
+    }
+    
+    /// Embeds a Quickwit `StaticDirectoryCache` hotcache as a footer in the split's store file
+    #[cfg(feature = "quickwit-compat")]
+    fn embed_quickwit_hotcache(&self, split_dir: &Path, store_file_path: &Path) -> Result<(u64, u64)> {
+        // Built before the footer is appended, so recorded ranges match the segment files
+        let hotcache_bytes = crate::hotcache::build_quickwit_hotcache(split_dir)?;
+        write_footer(store_file_path, &hotcache_bytes, None, false)
+    }
+    
+    #[cfg(not(feature = "quickwit-compat"))]
+    fn embed_quickwit_hotcache(&self, _split_dir: &Path, _store_file_path: &Path) -> Result<(u64, u64)> {
+        Err(SplitsError::InvalidOperation(
+            "The Quickwit hotcache format requires the quickwit-compat feature".to_string()
+        ))
+    }
+    
+    /// Computes the min/max of the configured timestamp field across all segments
+    ///
+    /// Date fields are reported in Unix seconds, i64 fields as raw values.
+    /// Only alive documents count: a segment with deletes is scanned, as its
+    /// column bounds still include the deleted documents' values.
+    fn compute_time_range(&self) -> Result<Option<(i64, i64)>> {
+        let field_name = match &self.config.timestamp_field {
+            Some(field_name) => field_name,
+            None => return Ok(None),
+        };
+        
+        let schema = self.index.schema();
+        let field = schema.get_field(field_name)
+            .map_err(|_| SplitsError::FieldError(format!("Field '{}' not found", field_name)))?;
+        let is_date = match schema.get_field_entry(field).field_type() {
+            FieldType::Date(_) => true,
+            FieldType::I64(_) => false,
+            other => return Err(SplitsError::FieldError(format!(
+                "Timestamp field '{}' must be a date or i64 field, got {:?}",
+                field_name, other.value_type()
+            ))),
+        };
+        
+        let searcher = self.reader.searcher();
+        
+        let mut time_range: Option<(i64, i64)> = None;
+        for segment_reader in searcher.segment_readers() {
+            if segment_reader.num_docs() == 0 {
+                continue;
+            }
+            let fast_fields = segment_reader.fast_fields();
+            let bounds = if is_date {
+                alive_column_bounds(segment_reader, &fast_fields.date(field_name)?)
+                    .map(|(min, max)| (min.into_timestamp_secs(), max.into_timestamp_secs()))
+            } else {
+                alive_column_bounds(segment_reader, &fast_fields.i64(field_name)?)
+            };
+            let Some((min, max)) = bounds else {
+                continue;
+            };
+            
+            time_range = Some(match time_range {
+                Some((start, end)) => (start.min(min), end.max(max)),
+                None => (min, max),
+            });
+        }
+        
+        Ok(time_range)
+    }
+    
+    /// Collects `field:value` tags from the terms of the configured tag fields
+    ///
+    /// A field with more than `MAX_VALUES_PER_TAG_FIELD` distinct values
+    /// records none of them, as a partial set would wrongly rule the split out
+    /// for the missing values, and adds a warning instead.
+    fn collect_tags(&self, warnings: &mut Vec<String>) -> Result<BTreeSet<String>> {
+        let mut tags = BTreeSet::new();
+        if self.config.tag_fields.is_empty() {
+            return Ok(tags);
+        }
+        
+        let schema = self.index.schema();
+        let searcher = self.reader.searcher();
+        
+        'fields: for field_name in &self.config.tag_fields {
+            let field = schema.get_field(field_name)
+                .map_err(|_| SplitsError::FieldError(format!("Field '{}' not found", field_name)))?;
+            
+            let mut field_tags = BTreeSet::new();
+            for segment_reader in searcher.segment_readers() {
+                let inverted_index = segment_reader.inverted_index(field)?;
+                let mut terms = inverted_index.terms().stream()?;
+                while terms.advance() {
+                    field_tags.insert(format!("{}:{}", field_name, String::from_utf8_lossy(terms.key())));
+                    if field_tags.len() > MAX_VALUES_PER_TAG_FIELD {
+                        warnings.push(format!(
+                            "Tag field '{}' has more than {} distinct values; none were recorded as tags",
+                            field_name, MAX_VALUES_PER_TAG_FIELD
+                        ));
+                        continue 'fields;
+                    }
+                }
+            }
+            tags.append(&mut field_tags);
+        }
+        
+        Ok(tags)
+    }
+    
+    /// Warns when `merge_threads` exceeds the cores available to the process
+    fn merge_threads_warning(&self) -> Option<String> {
+        let threads = self.config.merge_threads?;
+        let available = std::thread::available_parallelism().ok()?.get();
+        (threads > available).then(|| format!(
+            "Merge threads ({}) exceed the available parallelism ({})", threads, available
+        ))
+    }
+    
+    /// Warns when the configured hotcache fields can't be applied to a split of `num_segments` segments
+    fn hotcache_fields_warning(&self, num_segments: usize) -> Option<String> {
+        if self.config.hotcache_fields.is_none() && self.config.hotcache_exclude.is_empty() {
+            return None;
+        }
+        let reason = if !self.config.embed_hotcache {
+            "the hotcache isn't embedded"
+        } else if self.config.hotcache_format != HotcacheFormat::Simple {
+            "they only apply to the Simple hotcache format"
+        } else if self.config.encryption_key.is_some() {
+            "encrypted splits record no field ranges"
+        } else if num_segments > 1 {
+            "field ranges are only recorded for single-segment splits"
+        } else {
+            return None;
+        };
+        Some(format!("Configured hotcache fields were ignored: {}", reason))
+    }
+    
+    /// Lists the names of the files written to the split directory, sorted
+    fn list_output_files(&self, output_path: &Path) -> Result<Vec<String>> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(output_path)? {
+            let entry = entry?;
+            if entry.metadata()?.is_file() {
+                files.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+        files.sort();
+        Ok(files)
+    }
+    
+    /// Writes the split metadata as JSON next to the split
+    fn write_sidecar_metadata(&self, output_path: &Path, metadata: &SplitMetadata) -> Result<()> {
+        let json = serde_json::to_string_pretty(metadata)?;
+        fs::write(sidecar_metadata_path(output_path), json)?;
+        Ok(())
+    }
+    
+    /// Calculates the total size of all split files
+    fn calculate_split_size(&self, output_path: &Path) -> Result<u64> {
+        let mut total_size = 0;
+        
+        for file_name in self.list_output_files(output_path)? {
+            total_size += fs::metadata(output_path.join(file_name))?.len();
+        }
+        
+        Ok(total_size)
+    }
+    
+    /// Creates an empty split for indices with no documents
+    fn create_empty_split(
+        &self,
+        output_path: &Path,
+        split_id: String,
+        provenance: Provenance,
+        opstamp: u64,
+    ) -> Result<SplitMetadata> {
+        // Create a minimal store file holding only the footer, named after a
+        // configured split id so that regenerating it yields the same file
+        let segment_uuid = match &self.config.split_id {
+            Some(split_id) => Uuid::from_slice(&blake3::hash(split_id.as_bytes()).as_bytes()[..16])
+                .map_err(|e| SplitsError::InvalidOperation(format!("Failed to derive segment id: {}", e)))?,
+            None => Uuid::new_v4(),
+        };
+        let store_file_path = output_path.join(format!("{}.store", segment_uuid));
+        fs::write(&store_file_path, [])?;
+        
+        let (hotcache_start, hotcache_end) = if self.config.embed_hotcache {
+            let mut hotcache = create_hotcache(split_id.clone(), 0, 0)?;
+            hotcache.set_tokenizers(&self.config.tokenizers)?;
+            hotcache.set_custom_metadata(&self.config.custom_metadata)?;
+            hotcache.set_provenance(&provenance);
+            hotcache.set_opstamp(opstamp);
+            self.embed_hotcache(&store_file_path, hotcache)?
+        } else {
+            write_footer(&store_file_path, &[], None, false)?
+        };
+        
+        Ok(SplitMetadata {
+            split_id,
+            num_docs: 0,
+            size_bytes: self.calculate_split_size(output_path)?,
+            hotcache_start,
+            hotcache_end,
+            time_start: None,
+            time_end: None,
+            tags: BTreeSet::new(),
+            files: self.list_output_files(output_path)?,
+            provenance,
+            source_segments: 0,
+            opstamp,
+            checksum: None,
+            exceeds_target_docs: false,
+            warnings: Vec::new(),
+        })
     }
     
     /// Gets the target documents per split
     pub fn target_docs_per_split(&self) -> usize {
         self.target_docs_per_split
     }
-}
\ No newline at end of file
+    
+    /// Gets the number of segments in the source index, which generation merges into one
+    ///
+    /// Many small segments point at a commit cadence that outpaces merging.
+    pub fn source_segment_count(&self) -> Result<usize> {
+        Ok(self.get_all_segments()?.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tantivy::schema::{SchemaBuilder, FAST, STORED, TEXT};
+    use tempfile::TempDir;
+    
+    fn test_index() -> Index {
+        let mut schema_builder = SchemaBuilder::default();
+        schema_builder.add_text_field("title", TEXT | STORED);
+        schema_builder.add_u64_field("id", FAST | STORED);
+        Index::create_in_ram(schema_builder.build())
+    }
+    
+    /// Creates a `test_index` with a commit, so a segment, per slice of titles
+    fn index_with_docs(commits: &[&[&str]]) -> Index {
+        let index = test_index();
+        let title = index.schema().get_field("title").unwrap();
+        let mut index_writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for titles in commits {
+            for value in *titles {
+                index_writer.add_document(tantivy::doc!(title => *value)).unwrap();
+            }
+            index_writer.commit().unwrap();
+        }
+        index
+    }
+    
+    #[test]
+    fn test_generate_split_rejects_unknown_configured_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        let generator = QuickwitSplitGenerator::new(test_index(), 1000)
+            .unwrap()
+            .with_config(SplitConfig {
+                timestamp_field: Some("missing_timestamp".to_string()),
+                tag_fields: vec!["title".to_string(), "missing_tag".to_string()],
+                ..SplitConfig::default()
+            });
+        
+        match generator.generate_split(&temp_dir.path().join("split")) {
+            Err(SplitsError::FieldError(msg)) => {
+                assert!(msg.contains("missing_timestamp"));
+                assert!(msg.contains("missing_tag"));
+                assert!(!msg.contains("title"));
+            }
+            other => panic!("Expected FieldError, got {:?}", other.map(|m| m.split_id)),
+        }
+        assert!(!temp_dir.path().join("split").exists());
+    }
+    
+    #[test]
+    fn test_concurrent_generation_to_same_path_publishes_once() {
+        // Splits of a populated index take the merge and hotcache steps an empty one skips
+        let populated_index = index_with_docs(&[&["first"], &["second"]]);
+        
+        for index in [test_index(), populated_index] {
+            let temp_dir = TempDir::new().unwrap();
+            let output_path = temp_dir.path().join("split");
+            let generator = QuickwitSplitGenerator::new(index, 1000).unwrap();
+            
+            let results: Vec<Result<SplitMetadata>> = std::thread::scope(|scope| {
+                let handles: Vec<_> = (0..2)
+                    .map(|_| scope.spawn(|| generator.generate_split(&output_path)))
+                    .collect();
+                handles.into_iter().map(|handle| handle.join().unwrap()).collect()
+            });
+            
+            let successes = results.iter().filter(|result| result.is_ok()).count();
+            assert_eq!(successes, 1);
+            assert!(results.iter().any(|result| matches!(result, Err(SplitsError::InvalidOperation(_)))));
+            crate::split_reader::QuickwitSplitReader::open(&output_path).unwrap();
+            
+            // No staging directories are left behind
+            let leftovers: Vec<_> = fs::read_dir(temp_dir.path()).unwrap()
+                .map(|entry| entry.unwrap().file_name())
+                .filter(|name| name != "split")
+                .collect();
+            assert!(leftovers.is_empty(), "unexpected files: {:?}", leftovers);
+        }
+    }
+    
+    #[test]
+    fn test_generate_split_without_hotcache() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_path = temp_dir.path().join("split");
+        let generator = QuickwitSplitGenerator::new(test_index(), 1000)
+            .unwrap()
+            .with_config(SplitConfig {
+                embed_hotcache: false,
+                ..SplitConfig::default()
+            });
+        
+        let metadata = generator.generate_split(&output_path).unwrap();
+        assert_eq!(metadata.hotcache_start, metadata.hotcache_end);
+        
+        let reader = crate::split_reader::QuickwitSplitReader::open(&output_path).unwrap();
+        assert!(reader.get_hotcache_info().is_none());
+    }
+    
+    #[test]
+    fn test_open_in_dir() {
+        let index_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT | STORED);
+        let index = Index::create_in_dir(index_dir.path(), schema_builder.build()).unwrap();
+        let mut index_writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "quickwit")).unwrap();
+        index_writer.commit().unwrap();
+        drop(index_writer);
+        
+        let output_dir = TempDir::new().unwrap();
+        let generator = QuickwitSplitGenerator::open_in_dir(index_dir.path(), 1000).unwrap();
+        assert_eq!(generator.generate_split(&output_dir.path().join("split")).unwrap().num_docs, 1);
+        
+        let not_an_index = TempDir::new().unwrap();
+        match QuickwitSplitGenerator::open_in_dir(not_an_index.path(), 1000) {
+            Err(SplitsError::Io(e)) => {
+                assert_eq!(e.kind(), io::ErrorKind::InvalidInput);
+                assert!(e.to_string().contains("is not a valid Tantivy index"));
+            }
+            other => panic!("Expected an IO error, got {:?}", other.map(|generator| generator.target_docs_per_split())),
+        }
+    }
+    
+    #[test]
+    fn test_source_segment_count() {
+        let temp_dir = TempDir::new().unwrap();
+        let index = test_index();
+        let mut generator = QuickwitSplitGenerator::new(index.clone(), 1000).unwrap();
+        assert_eq!(generator.source_segment_count().unwrap(), 0);
+        assert_eq!(generator.generate_split(&temp_dir.path().join("empty")).unwrap().source_segments, 0);
+        
+        let title = index.schema().get_field("title").unwrap();
+        let mut index_writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "first")).unwrap();
+        index_writer.commit().unwrap();
+        index_writer.add_document(tantivy::doc!(title => "second")).unwrap();
+        index_writer.commit().unwrap();
+        drop(index_writer);
+        
+        generator.refresh().unwrap();
+        assert_eq!(generator.source_segment_count().unwrap(), 2);
+        let metadata = generator.generate_split(&temp_dir.path().join("split")).unwrap();
+        assert_eq!(metadata.source_segments, 2);
+        assert_eq!(metadata.num_docs, 2);
+    }
+    
+    #[test]
+    fn test_verify_merge_rejects_leftover_segments() {
+        let index = index_with_docs(&[&["first"], &["second"]]);
+        
+        let generator = QuickwitSplitGenerator::new(index, 1000).unwrap();
+        let segment_ids = generator.get_all_segments().unwrap();
+        assert_eq!(segment_ids.len(), 2);
+        
+        // A merge that silently did nothing leaves both source segments behind
+        match verify_merge(&generator.index, &segment_ids, &segment_ids[0]) {
+            Err(SplitsError::InvalidOperation(msg)) => {
+                assert_eq!(msg, "Merging 2 segments left 2 segments in the index instead of one");
+            }
+            other => panic!("Expected InvalidOperation, got {:?}", other),
+        }
+        
+        // Segments outside the merge don't count
+        verify_merge(&generator.index, &segment_ids[..1], &segment_ids[0]).unwrap();
+    }
+    
+    #[test]
+    fn test_case_collisions_are_rejected() {
+        let names = |names: &[&str]| names.iter().map(|name| name.to_string()).collect::<Vec<_>>();
+        check_case_collisions(&names(&["meta.json", "abc.idx", "abc.store", "abd.idx"])).unwrap();
+        
+        match check_case_collisions(&names(&["meta.json", "abc.idx", "ABC.idx"])) {
+            Err(SplitsError::InvalidOperation(msg)) => assert_eq!(
+                msg,
+                "Split files abc.idx and ABC.idx differ only in case and would collide on case-insensitive filesystems"
+            ),
+            other => panic!("Expected InvalidOperation, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_merge_policy_bounds_split_segments() {
+        let temp_dir = TempDir::new().unwrap();
+        let index = index_with_docs(&[&["first"], &["second"], &["third"]]);
+        
+        let split_segments = |merge_policy: MergePolicy| {
+            let split_path = temp_dir.path().join(format!("{:?}", merge_policy));
+            let metadata = QuickwitSplitGenerator::new(index.clone(), 1000)
+                .unwrap()
+                .with_config(SplitConfig { merge_policy, legacy_directory_layout: true, ..SplitConfig::default() })
+                .generate_split(&split_path)
+                .unwrap();
+            assert_eq!(metadata.source_segments, 3);
+            Index::open_in_dir(&split_path).unwrap().searchable_segment_ids().unwrap().len()
+        };
+        assert_eq!(split_segments(MergePolicy::ForceSingle), 1);
+        assert_eq!(split_segments(MergePolicy::MaxSegments(2)), 2);
+        assert_eq!(split_segments(MergePolicy::MaxSegments(5)), 3);
+        assert_eq!(split_segments(MergePolicy::NoMerge), 3);
+        
+        let config = SplitConfig::from_json(r#"{"merge_policy": {"max_segments": 0}}"#).unwrap();
+        assert_eq!(config.merge_policy, MergePolicy::MaxSegments(0));
+        let generator = QuickwitSplitGenerator::new(index, 1000).unwrap().with_config(config);
+        assert!(matches!(
+            generator.generate_split(&temp_dir.path().join("none")),
+            Err(SplitsError::InvalidOperation(_))
+        ));
+    }
+    
+    #[test]
+    fn test_merging_leaves_the_source_index_untouched() {
+        let temp_dir = TempDir::new().unwrap();
+        let index = test_index();
+        let title = index.schema().get_field("title").unwrap();
+        let mut index_writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for value in ["first", "second", "third"] {
+            index_writer.add_document(tantivy::doc!(title => value)).unwrap();
+            index_writer.commit().unwrap();
+        }
+        
+        // The application's writer stays open across the generation
+        let generator = QuickwitSplitGenerator::new(index.clone(), 1000).unwrap();
+        let segment_ids = generator.get_all_segments().unwrap();
+        let split_path = temp_dir.path().join("split");
+        let metadata = generator
+            .with_config(SplitConfig { legacy_directory_layout: true, ..SplitConfig::default() })
+            .generate_split(&split_path)
+            .unwrap();
+        assert_eq!(metadata.num_docs, 3);
+        assert_eq!(Index::open_in_dir(&split_path).unwrap().searchable_segment_ids().unwrap().len(), 1);
+        
+        assert_eq!(index.searchable_segment_ids().unwrap(), segment_ids);
+        let generator = QuickwitSplitGenerator::new(index, 1000).unwrap();
+        assert_eq!(generator.get_all_segments().unwrap(), segment_ids);
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+        index_writer.add_document(tantivy::doc!(title => "fourth")).unwrap();
+        index_writer.commit().unwrap();
+    }
+    
+    #[test]
+    fn test_refresh_picks_up_new_commits() {
+        let temp_dir = TempDir::new().unwrap();
+        let index = test_index();
+        let title = index.schema().get_field("title").unwrap();
+        let mut index_writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "first")).unwrap();
+        let first_opstamp = index_writer.commit().unwrap();
+        
+        let mut generator = QuickwitSplitGenerator::new(index, 1000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "second")).unwrap();
+        let second_opstamp = index_writer.commit().unwrap();
+        drop(index_writer);
+        
+        // Commits made after creation are invisible until a refresh
+        let metadata = generator.generate_split(&temp_dir.path().join("before")).unwrap();
+        assert_eq!(metadata.num_docs, 1);
+        assert_eq!(metadata.opstamp, first_opstamp);
+        
+        generator.refresh().unwrap();
+        let metadata = generator.generate_split(&temp_dir.path().join("after")).unwrap();
+        assert_eq!(metadata.source_segments, 2);
+        assert_eq!(metadata.opstamp, second_opstamp);
+    }
+    
+    #[test]
+    fn test_progress_can_be_polled_during_generation() {
+        let temp_dir = TempDir::new().unwrap();
+        let titles: Vec<String> = (0..100).map(|i| format!("document number {}", i)).collect();
+        let titles: Vec<&str> = titles.iter().map(String::as_str).collect();
+        let commits: Vec<&[&str]> = titles.chunks(25).collect();
+        let index = index_with_docs(&commits);
+        
+        let generator = QuickwitSplitGenerator::new(index, 1000).unwrap();
+        let progress = generator.progress_handle();
+        let progress_in_hook = generator.progress_handle();
+        let generator = generator.with_post_process(move |_| {
+            // Segments are merged and copied, and the hotcache is embedded
+            assert_eq!(progress_in_hook.load(Ordering::Relaxed), 80);
+            Ok(())
+        });
+        assert_eq!(generator.progress(), 0);
+        
+        // Stops the poller even if generation panics, which would otherwise hang the scope
+        struct SetOnDrop<'a>(&'a std::sync::atomic::AtomicBool);
+        impl Drop for SetOnDrop<'_> {
+            fn drop(&mut self) {
+                self.0.store(true, Ordering::Release);
+            }
+        }
+        
+        let done = std::sync::atomic::AtomicBool::new(false);
+        let (result, samples) = std::thread::scope(|scope| {
+            let poller = scope.spawn(|| {
+                let mut samples = vec![];
+                while !done.load(Ordering::Acquire) {
+                    samples.push(progress.load(Ordering::Relaxed));
+                    std::thread::yield_now();
+                }
+                samples
+            });
+            let result = {
+                let _stop_poller = SetOnDrop(&done);
+                generator.generate_split(&temp_dir.path().join("split"))
+            };
+            (result, poller.join().unwrap())
+        });
+        
+        result.unwrap();
+        assert!(samples.windows(2).all(|pair| pair[0] <= pair[1]), "progress went backwards: {:?}", samples);
+        assert!(samples.iter().all(|&percent| percent <= 100));
+        assert_eq!(generator.progress(), 100);
+    }
+    
+    #[test]
+    fn test_time_range_skips_deleted_documents() {
+        use tantivy::schema::{SchemaBuilder, FAST, INDEXED};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let timestamp = schema_builder.add_i64_field("timestamp", INDEXED | FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for value in [10i64, 20, 30] {
+            index_writer.add_document(tantivy::doc!(timestamp => value)).unwrap();
+        }
+        index_writer.commit().unwrap();
+        index_writer.delete_term(Term::from_field_i64(timestamp, 30));
+        index_writer.commit().unwrap();
+        drop(index_writer);
+        
+        let config = SplitConfig {
+            timestamp_field: Some("timestamp".to_string()),
+            merge_policy: MergePolicy::NoMerge,
+            legacy_directory_layout: true,
+            ..SplitConfig::default()
+        };
+        let generator = QuickwitSplitGenerator::new(index, 1000).unwrap().with_config(config);
+        assert_eq!(generator.compute_time_range().unwrap(), Some((10, 20)));
+        let metadata = generator.generate_split(&temp_dir.path().join("split")).unwrap();
+        assert_eq!((metadata.time_start, metadata.time_end), (Some(10), Some(20)));
+    }
+    
+    #[test]
+    fn test_split_id_from_config() {
+        let temp_dir = TempDir::new().unwrap();
+        let generator = QuickwitSplitGenerator::new(test_index(), 1000)
+            .unwrap()
+            .with_config(SplitConfig {
+                split_id: Some("tenant-42_2024-01".to_string()),
+                ..SplitConfig::default()
+            });
+        
+        let metadata = generator.generate_split(&temp_dir.path().join("split")).unwrap();
+        assert_eq!(metadata.split_id, "tenant-42_2024-01");
+        
+        // Default ids are time-ordered UUID v7
+        let metadata = QuickwitSplitGenerator::new(test_index(), 1000).unwrap()
+            .generate_split(&temp_dir.path().join("other")).unwrap();
+        assert_eq!(Uuid::parse_str(&metadata.split_id).unwrap().get_version_num(), 7);
+    }
+    
+    #[test]
+    fn test_plan_split() {
+        let index = test_index();
+        let mut generator = QuickwitSplitGenerator::new(index.clone(), 1000).unwrap();
+        assert_eq!(generator.plan_split().unwrap(), SplitPlan::default());
+        
+        let title = index.schema().get_field("title").unwrap();
+        let mut index_writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for i in 0..100 {
+            index_writer.add_document(tantivy::doc!(title => format!("document number {}", i))).unwrap();
+        }
+        index_writer.commit().unwrap();
+        
+        generator.refresh().unwrap();
+        let plan = generator.plan_split().unwrap();
+        assert_eq!(plan.num_docs, 100);
+        assert_eq!(plan.source_segments, 1);
+        assert!(plan.max_size_bytes > 0);
+        assert_eq!(plan.min_size_bytes, plan.max_size_bytes);
+        
+        // Deleting half the documents only lowers the lower bound
+        for i in 0..50 {
+            index_writer.delete_term(Term::from_field_text(title, &i.to_string()));
+        }
+        index_writer.commit().unwrap();
+        drop(index_writer);
+        
+        generator.refresh().unwrap();
+        let with_deletes = generator.plan_split().unwrap();
+        assert_eq!(with_deletes.num_docs, 50);
+        assert!(with_deletes.max_size_bytes >= plan.max_size_bytes);
+        assert_eq!(with_deletes.min_size_bytes, with_deletes.max_size_bytes / 2);
+    }
+    
+    #[test]
+    fn test_list_segment_files_names_the_delete_file() {
+        let index = test_index();
+        let title = index.schema().get_field("title").unwrap();
+        let mut index_writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "kept")).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "deleted")).unwrap();
+        index_writer.commit().unwrap();
+        
+        let generator = QuickwitSplitGenerator::new(index.clone(), 1000).unwrap();
+        let segment_meta = generator.snapshot_segment_metas()[0].clone();
+        let uuid = segment_meta.id().uuid_string();
+        let files = generator.list_segment_files(&segment_meta);
+        assert!(files.contains(&format!("{}.store", uuid)));
+        assert!(!files.iter().any(|file_name| file_name.ends_with(".del")));
+        
+        index_writer.delete_term(Term::from_field_text(title, "deleted"));
+        let opstamp = index_writer.commit().unwrap();
+        drop(index_writer);
+        
+        let generator = QuickwitSplitGenerator::new(index, 1000).unwrap();
+        let files = generator.list_segment_files(&generator.snapshot_segment_metas()[0]);
+        assert!(files.contains(&format!("{}.{}.del", uuid, opstamp)));
+        assert!(!files.iter().any(|file_name| file_name.ends_with(".temp")));
+    }
+    
+    #[test]
+    fn test_max_split_size_rejects_larger_splits() {
+        let temp_dir = TempDir::new().unwrap();
+        let index = index_with_docs(&[&["a split too large for its limit"]]);
+        
+        let generator = QuickwitSplitGenerator::new(index, 1000).unwrap()
+            .with_config(SplitConfig { max_split_size_bytes: Some(64), ..SplitConfig::default() });
+        let output_path = temp_dir.path().join("split");
+        match generator.generate_split(&output_path) {
+            Err(SplitsError::InvalidOperation(msg)) => assert!(msg.contains("exceeds max size")),
+            other => panic!("Expected InvalidOperation, got {:?}", other),
+        }
+        assert!(!output_path.exists());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 0);
+        
+        let generator = generator.with_config(SplitConfig { max_split_size_bytes: Some(1 << 30), ..SplitConfig::default() });
+        assert!(generator.generate_split(&output_path).unwrap().size_bytes <= 1 << 30);
+    }
+    
+    #[test]
+    fn test_generation_is_reproducible() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = SplitConfig {
+            split_id: Some("reproducible".to_string()),
+            created_at_millis: Some(1_700_000_000_000),
+            ..SplitConfig::default()
+        };
+        
+        let mut bundles = Vec::new();
+        for name in ["first", "second"] {
+            let generator = QuickwitSplitGenerator::new(test_index(), 1000).unwrap().with_config(config.clone());
+            let metadata = generator.generate_split(&temp_dir.path().join(name)).unwrap();
+            assert_eq!(metadata.provenance.created_at_millis, Some(1_700_000_000_000));
+            bundles.push(fs::read(temp_dir.path().join(name)).unwrap());
+        }
+        
+        assert_eq!(bundles[0], bundles[1]);
+    }
+    
+    #[test]
+    fn test_validate_split_id() {
+        assert!(validate_split_id("split-01.a").is_ok());
+        for split_id in ["", ".", "..", "a/b", "a\\b", "a\0b", "x".repeat(256).as_str()] {
+            assert!(
+                matches!(validate_split_id(split_id), Err(SplitsError::InvalidOperation(_))),
+                "accepted {:?}", split_id
+            );
+        }
+    }
+    
+    #[test]
+    fn test_single_segment_index_is_not_merged() {
+        let temp_dir = TempDir::new().unwrap();
+        let index = test_index();
+        let title = index.schema().get_field("title").unwrap();
+        let mut index_writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "merged")).unwrap();
+        index_writer.commit().unwrap();
+        
+        // The writer holds the index lock: creating another one would fail
+        let metadata = QuickwitSplitGenerator::new(index.clone(), 1000).unwrap()
+            .generate_split(&temp_dir.path().join("split"))
+            .unwrap();
+        assert_eq!(metadata.num_docs, 1);
+        assert_eq!(metadata.source_segments, 1);
+        drop(index_writer);
+    }
+    
+    #[test]
+    fn test_merge_threads() {
+        let temp_dir = TempDir::new().unwrap();
+        let index = index_with_docs(&[&["first"], &["second"]]);
+        
+        let config = SplitConfig { merge_threads: Some(1), ..SplitConfig::default() };
+        let metadata = QuickwitSplitGenerator::new(index.clone(), 1000).unwrap()
+            .with_config(config)
+            .generate_split(&temp_dir.path().join("split"))
+            .unwrap();
+        assert_eq!(metadata.source_segments, 2);
+        assert_eq!(metadata.num_docs, 2);
+        assert!(metadata.warnings.is_empty());
+        
+        let config = SplitConfig { merge_threads: Some(0), ..SplitConfig::default() };
+        let result = QuickwitSplitGenerator::new(index, 1000).unwrap()
+            .with_config(config)
+            .generate_split(&temp_dir.path().join("no-threads"));
+        assert!(matches!(result, Err(SplitsError::InvalidOperation(_))));
+    }
+    
+    #[test]
+    fn test_split_reports_exceeding_target_docs() {
+        let temp_dir = TempDir::new().unwrap();
+        let index = index_with_docs(&[&["a", "b", "c"]]);
+        
+        // The target never splits the index, whatever its value
+        for (target_docs_per_split, exceeds_target_docs) in [(2, true), (3, false), (1000, false)] {
+            let metadata = QuickwitSplitGenerator::new(index.clone(), target_docs_per_split).unwrap()
+                .generate_split(&temp_dir.path().join(format!("split-{}", target_docs_per_split)))
+                .unwrap();
+            assert_eq!(metadata.num_docs, 3);
+            assert_eq!(metadata.exceeds_target_docs, exceeds_target_docs);
+            assert_eq!(metadata.warnings.len(), exceeds_target_docs as usize);
+        }
+    }
+    
+    #[test]
+    fn test_split_warns_of_truncated_tags() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let kind = schema_builder.add_text_field("kind", tantivy::schema::STRING);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        for i in 0..=MAX_VALUES_PER_TAG_FIELD {
+            let kind_value = if i % 2 == 0 { "even" } else { "odd" };
+            index_writer.add_document(tantivy::doc!(title => format!("t{}", i), kind => kind_value)).unwrap();
+        }
+        index_writer.commit().unwrap();
+        
+        let config = SplitConfig {
+            tag_fields: vec!["title".to_string(), "kind".to_string()],
+            ..SplitConfig::default()
+        };
+        let metadata = QuickwitSplitGenerator::new(index, 10_000).unwrap()
+            .with_config(config)
+            .generate_split(&temp_dir.path().join("split"))
+            .unwrap();
+        
+        // Only the field over the cap loses its tags
+        assert_eq!(metadata.tags, BTreeSet::from(["kind:even".to_string(), "kind:odd".to_string()]));
+        assert_eq!(metadata.warnings.len(), 1);
+        assert!(metadata.warnings[0].contains("'title'"));
+        
+        let json: serde_json::Value = serde_json::to_value(&metadata).unwrap();
+        assert_eq!(json["warnings"], serde_json::json!(metadata.warnings));
+    }
+    
+    #[test]
+    fn test_generate_batch() {
+        let temp_dir = TempDir::new().unwrap();
+        let index_dir = temp_dir.path().join("index");
+        fs::create_dir(&index_dir).unwrap();
+        let schema = test_index().schema();
+        let title = schema.get_field("title").unwrap();
+        let index = Index::create_in_dir(&index_dir, schema).unwrap();
+        let mut index_writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "batched")).unwrap();
+        index_writer.commit().unwrap();
+        
+        let inputs = vec![
+            (index_dir.clone(), temp_dir.path().join("first.split")),
+            (temp_dir.path().join("missing"), temp_dir.path().join("second.split")),
+            (index_dir, temp_dir.path().join("third.split")),
+        ];
+        let results = QuickwitSplitGenerator::generate_batch(&inputs, &SplitConfig::default());
+        
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap().num_docs, 1);
+        assert!(matches!(&results[1], Err(SplitsError::Io(e)) if e.kind() == io::ErrorKind::InvalidInput));
+        assert_eq!(results[2].as_ref().unwrap().num_docs, 1);
+        assert!(temp_dir.path().join("third.split").is_file());
+        assert!(!temp_dir.path().join("second.split").exists());
+    }
+    
+    #[test]
+    fn test_generated_split_records_provenance() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_path = temp_dir.path().join("split");
+        let generator = QuickwitSplitGenerator::new(test_index(), 1000)
+            .unwrap()
+            .with_config(SplitConfig {
+                write_sidecar_metadata: true,
+                ..SplitConfig::default()
+            });
+        
+        let metadata = generator.generate_split(&output_path).unwrap();
+        assert!(metadata.provenance.created_at_millis.unwrap() > 0);
+        assert_eq!(metadata.provenance.crate_version.as_deref(), Some(env!("CARGO_PKG_VERSION")));
+        
+        let reader = crate::split_reader::QuickwitSplitReader::open(&output_path).unwrap();
+        assert_eq!(reader.provenance(), metadata.provenance);
+        assert_eq!(reader.tantivy_version().as_deref(), Some(tantivy::version_string()));
+        
+        let sidecar = crate::split_reader::QuickwitSplitReader::load_sidecar_metadata(&output_path).unwrap();
+        assert_eq!(sidecar.provenance, metadata.provenance);
+    }
+    
+    #[test]
+    fn test_custom_metadata_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let custom_metadata: BTreeMap<String, String> = [("source", "kafka"), ("pipeline_id", "ingest-7")]
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        let config = SplitConfig::from_json(r#"{"custom_metadata": {"source": "kafka", "pipeline_id": "ingest-7"}}"#).unwrap();
+        assert_eq!(config.custom_metadata, custom_metadata);
+        
+        let output_path = temp_dir.path().join("split");
+        QuickwitSplitGenerator::new(test_index(), 1000).unwrap()
+            .with_config(config)
+            .generate_split(&output_path)
+            .unwrap();
+        let reader = crate::split_reader::QuickwitSplitReader::open(&output_path).unwrap();
+        assert_eq!(reader.custom_metadata().unwrap(), custom_metadata);
+        
+        // Splits generated without custom metadata have none
+        let output_path = temp_dir.path().join("plain");
+        QuickwitSplitGenerator::new(test_index(), 1000).unwrap().generate_split(&output_path).unwrap();
+        let reader = crate::split_reader::QuickwitSplitReader::open(&output_path).unwrap();
+        assert!(reader.custom_metadata().unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_update_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        let index = index_with_docs(&[&["quarterly report"]]);
+        
+        let output_path = temp_dir.path().join("a.split");
+        let config = SplitConfig::from_json(
+            r#"{"custom_metadata": {"retention": "30d", "team": "a"}, "write_sidecar_metadata": true}"#
+        ).unwrap();
+        let generated = QuickwitSplitGenerator::new(index, 1000).unwrap()
+            .with_config(config)
+            .generate_split(&output_path)
+            .unwrap();
+        
+        let file_bytes = |path: &Path| -> BTreeMap<String, Vec<u8>> {
+            let bytes = fs::read(path).unwrap();
+            split_file_ranges(path).unwrap().into_iter()
+                .filter(|(name, _)| !name.ends_with(".store"))
+                .map(|(name, range)| (name, bytes[range.start as usize..range.end as usize].to_vec()))
+                .collect()
+        };
+        let segment_files = file_bytes(&output_path);
+        let store_data = crate::split_reader::QuickwitSplitReader::open(&output_path).unwrap().read_store_data().unwrap();
+        
+        let updates = HashMap::from([("retention".to_string(), "90d".to_string())]);
+        let metadata = QuickwitSplitGenerator::update_metadata(&output_path, updates).unwrap();
+        
+        let reader = crate::split_reader::QuickwitSplitReader::open(&output_path).unwrap();
+        let expected: BTreeMap<String, String> = [("retention", "90d"), ("team", "a")]
+            .into_iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        assert_eq!(reader.custom_metadata().unwrap(), expected);
+        assert_eq!(reader.read_store_data().unwrap(), store_data);
+        assert!(!segment_files.is_empty());
+        assert_eq!(file_bytes(&output_path), segment_files);
+        
+        assert_eq!(metadata.split_id, generated.split_id);
+        assert_eq!(metadata.num_docs, 1);
+        assert_eq!(metadata.files, generated.files);
+        assert_eq!(metadata.size_bytes, fs::metadata(&output_path).unwrap().len());
+        assert_eq!(metadata.checksum, Some(split_file_checksum(&output_path).unwrap()));
+        let sidecar = crate::split_reader::QuickwitSplitReader::load_sidecar_metadata(&output_path).unwrap();
+        assert_eq!(sidecar.checksum, metadata.checksum);
+        assert_eq!(sidecar.hotcache_end, metadata.hotcache_end);
+        assert!(!staging_path_for(&output_path).exists());
+        
+        // Without a sidecar the files are listed from the split, in the same order
+        fs::remove_file(sidecar_metadata_path(&output_path)).unwrap();
+        let metadata = QuickwitSplitGenerator::update_metadata(&output_path, HashMap::new()).unwrap();
+        assert_eq!(metadata.files, generated.files);
+        
+        // Splits without an embedded hotcache have nowhere to record metadata
+        let output_path = temp_dir.path().join("bare.split");
+        let config = SplitConfig { embed_hotcache: false, ..SplitConfig::default() };
+        QuickwitSplitGenerator::new(test_index(), 1000).unwrap()
+            .with_config(config)
+            .generate_split(&output_path)
+            .unwrap();
+        let result = QuickwitSplitGenerator::update_metadata(&output_path, HashMap::new());
+        assert!(matches!(result, Err(SplitsError::InvalidOperation(_))));
+    }
+    
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_encrypted_split_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let index = index_with_docs(&[&["confidential report"]]);
+        
+        let key = "2a".repeat(crate::encryption::ENCRYPTION_KEY_LEN);
+        let config = SplitConfig::from_json(&format!(
+            r#"{{"encryption_key": "{}", "custom_metadata": {{"source": "kafka"}}, "legacy_directory_layout": true}}"#, key
+        )).unwrap();
+        let output_path = temp_dir.path().join("split");
+        QuickwitSplitGenerator::new(index, 1000).unwrap()
+            .with_config(config)
+            .generate_split(&output_path)
+            .unwrap();
+        
+        // Segment files hold no cleartext, the hotcache summary stays readable
+        let store_file = find_store_file(&output_path).unwrap();
+        let store_bytes = fs::read(&store_file).unwrap();
+        assert!(!store_bytes.windows(12).any(|window| window == b"confidential"));
+        let mut reader = crate::split_reader::QuickwitSplitReader::open(&output_path).unwrap();
+        assert!(reader.is_encrypted().unwrap());
+        assert_eq!(reader.custom_metadata().unwrap()["source"], "kafka");
+        assert!(matches!(reader.field_terms("title"), Err(SplitsError::InvalidOperation(_))));
+        
+        reader.set_encryption_key(&[0x2b; 32]).unwrap();
+        assert!(matches!(reader.field_terms("title"), Err(SplitsError::Decryption(_))));
+        
+        reader.set_encryption_key(&[0x2a; 32]).unwrap();
+        assert_eq!(reader.field_terms("title").unwrap(), vec!["confidential", "report"]);
+    }
+    
+    #[test]
+    fn test_encryption_requires_simple_embedded_hotcache() {
+        let temp_dir = TempDir::new().unwrap();
+        let key = "2a".repeat(crate::encryption::ENCRYPTION_KEY_LEN);
+        let config = SplitConfig::from_json(&format!(
+            r#"{{"encryption_key": "{}", "embed_hotcache": false}}"#, key
+        )).unwrap();
+        
+        let result = QuickwitSplitGenerator::new(test_index(), 1000).unwrap()
+            .with_config(config)
+            .generate_split(&temp_dir.path().join("split"));
+        assert!(matches!(result, Err(SplitsError::InvalidOperation(_))));
+        assert!(!temp_dir.path().join("split").exists());
+    }
+    
+    #[test]
+    fn test_generate_split_fails_if_output_exists() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_path = temp_dir.path().join("split");
+        fs::create_dir(&output_path).unwrap();
+        fs::write(output_path.join("stale.store"), b"old split").unwrap();
+        
+        let generator = QuickwitSplitGenerator::new(test_index(), 1000)
+            .unwrap()
+            .with_config(SplitConfig { legacy_directory_layout: true, ..SplitConfig::default() });
+        assert!(matches!(generator.generate_split(&output_path), Err(SplitsError::InvalidOperation(_))));
+        
+        // The existing content is left untouched
+        assert_eq!(fs::read(output_path.join("stale.store")).unwrap(), b"old split");
+        
+        // Likewise for an existing split file
+        let output_path = temp_dir.path().join("stale.split");
+        fs::write(&output_path, b"old split").unwrap();
+        let generator = generator.with_config(SplitConfig::default());
+        assert!(matches!(generator.generate_split(&output_path), Err(SplitsError::InvalidOperation(_))));
+        assert_eq!(fs::read(&output_path).unwrap(), b"old split");
+    }
+    
+    #[test]
+    fn test_generate_split_overwrite_replaces_existing_split() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_path = temp_dir.path().join("split");
+        fs::create_dir(&output_path).unwrap();
+        fs::write(output_path.join("stale.store"), b"old split").unwrap();
+        
+        let generator = QuickwitSplitGenerator::new(test_index(), 1000)
+            .unwrap()
+            .with_config(SplitConfig::from_json(r#"{"overwrite": true, "legacy_directory_layout": true}"#).unwrap());
+        
+        let metadata = generator.generate_split(&output_path).unwrap();
+        assert!(!output_path.join("stale.store").exists());
+        assert!(!metadata.files.contains(&"stale.store".to_string()));
+        
+        // A split directory can be replaced by a split file, and the other way around
+        let generator = generator.with_config(SplitConfig::from_json(r#"{"overwrite": true}"#).unwrap());
+        generator.generate_split(&output_path).unwrap();
+        assert!(output_path.is_file());
+        crate::split_reader::QuickwitSplitReader::open(&output_path).unwrap();
+        
+        // The replaced splits were moved aside, then removed
+        generator.generate_split(&output_path).unwrap();
+        let entries: Vec<_> = fs::read_dir(temp_dir.path()).unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(entries, vec!["split"]);
+    }
+    
+    #[test]
+    fn test_split_file_layout() {
+        use crate::split_reader::QuickwitSplitReader;
+        
+        let temp_dir = TempDir::new().unwrap();
+        let index = index_with_docs(&[&["quickwit"]]);
+        
+        let generator = QuickwitSplitGenerator::new(index, 1000)
+            .unwrap()
+            .with_config(SplitConfig {
+                split_id: Some("abc".to_string()),
+                created_at_millis: Some(1_700_000_000_000),
+                ..SplitConfig::default()
+            });
+        
+        // An existing directory receives `<split_id>.split`
+        let metadata = generator.generate_split(temp_dir.path()).unwrap();
+        let split_path = temp_dir.path().join("abc.split");
+        assert!(split_path.is_file());
+        assert_eq!(metadata.size_bytes, fs::metadata(&split_path).unwrap().len());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+        
+        // Any other path is the split file itself
+        let named_path = temp_dir.path().join("named.split");
+        generator.generate_split(&named_path).unwrap();
+        assert_eq!(fs::read(&named_path).unwrap(), fs::read(&split_path).unwrap());
+        
+        let reader = QuickwitSplitReader::open(&split_path).unwrap();
+        assert_eq!(reader.read_posting_list("title", "quickwit").unwrap(), vec![0]);
+        
+        // The legacy layout writes the split's files into a directory
+        let legacy_path = temp_dir.path().join("legacy");
+        let generator = generator.with_config(SplitConfig { legacy_directory_layout: true, ..SplitConfig::default() });
+        let metadata = generator.generate_split(&legacy_path).unwrap();
+        assert!(legacy_path.is_dir());
+        assert!(metadata.files.iter().all(|file| legacy_path.join(file).is_file()));
+        let reader = QuickwitSplitReader::open(&legacy_path).unwrap();
+        assert_eq!(reader.read_posting_list("title", "quickwit").unwrap(), vec![0]);
+    }
+    
+    #[test]
+    fn test_split_config_from_json() {
+        let config = SplitConfig::from_json(r#"{"tag_fields": ["title"], "split_id": "a"}"#).unwrap();
+        assert_eq!(config.tag_fields, vec!["title".to_string()]);
+        assert_eq!(config.split_id.as_deref(), Some("a"));
+        assert!(config.embed_hotcache);
+        assert!(!config.overwrite);
+        
+        assert!(matches!(SplitConfig::from_json(r#"{"overwrtie": true}"#), Err(SplitsError::Serialization(_))));
+    }
+    
+    /// Builds an index whose `tenant` field takes each of `tenants` in turn
+    fn partitioned_index(tenants: &[&str]) -> Index {
+        use tantivy::schema::STRING;
+        
+        let mut schema_builder = SchemaBuilder::default();
+        let tenant = schema_builder.add_text_field("tenant", STRING | STORED);
+        let title = schema_builder.add_text_field("title", TEXT | STORED);
+        let index = Index::create_in_ram(schema_builder.build());
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        for value in tenants {
+            index_writer.add_document(tantivy::doc!(tenant => *value, title => "quickwit")).unwrap();
+        }
+        index_writer.commit().unwrap();
+        index
+    }
+    
+    #[test]
+    fn test_partition_values_are_bounded() {
+        let generator = QuickwitSplitGenerator::new(partitioned_index(&["b", "a", "c", "a"]), 1000)
+            .unwrap()
+            .with_config(SplitConfig { max_partitions: 3, ..SplitConfig::default() });
+        let values: Vec<String> = generator.partition_values("tenant").unwrap().into_iter().collect();
+        assert_eq!(values, vec!["a", "b", "c"]);
+        
+        let generator = generator.with_config(SplitConfig { max_partitions: 2, ..SplitConfig::default() });
+        let temp_dir = TempDir::new().unwrap();
+        match generator.generate_partitioned_splits("tenant", temp_dir.path()) {
+            Err(SplitsError::InvalidOperation(msg)) => assert!(msg.contains("max_partitions")),
+            other => panic!("Expected InvalidOperation, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_generate_partitioned_splits_validates_schema() {
+        let temp_dir = TempDir::new().unwrap();
+        
+        // `id` is not a text field
+        let generator = QuickwitSplitGenerator::new(test_index(), 1000).unwrap();
+        assert!(matches!(
+            generator.generate_partitioned_splits("id", temp_dir.path()),
+            Err(SplitsError::FieldError(_))
+        ));
+        
+        // Documents can't be rebuilt without every field stored
+        let mut schema_builder = SchemaBuilder::default();
+        schema_builder.add_text_field("tenant", tantivy::schema::STRING | STORED);
+        schema_builder.add_text_field("body", TEXT);
+        let generator = QuickwitSplitGenerator::new(Index::create_in_ram(schema_builder.build()), 1000).unwrap();
+        match generator.generate_partitioned_splits("tenant", temp_dir.path()) {
+            Err(SplitsError::FieldError(msg)) => assert!(msg.contains("body")),
+            other => panic!("Expected FieldError, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_post_process_runs_before_publish() {
+        let temp_dir = TempDir::new().unwrap();
+        let output_path = temp_dir.path().join("signed_split");
+        let key = [1u8; crate::signing::SIGNING_KEY_LEN];
+        
+        let generator = QuickwitSplitGenerator::new(test_index(), 1000)
+            .unwrap()
+            .with_config(SplitConfig { legacy_directory_layout: true, ..SplitConfig::default() })
+            .with_post_process(move |split_dir| {
+                assert!(find_store_file(split_dir).is_ok());
+                crate::signing::sign_split(split_dir, &key).map(|_| ())
+            });
+        let metadata = generator.generate_split(&output_path).unwrap();
+        
+        assert!(metadata.files.contains(&crate::signing::SIGNATURE_FILE_NAME.to_string()));
+        crate::signing::verify_split(&output_path, &key).unwrap();
+        
+        // A failing hook publishes nothing
+        let failing_path = temp_dir.path().join("failed_split");
+        let generator = QuickwitSplitGenerator::new(test_index(), 1000)
+            .unwrap()
+            .with_post_process(|_| Err(SplitsError::InvalidOperation("rejected".to_string())));
+        assert!(matches!(generator.generate_split(&failing_path), Err(SplitsError::InvalidOperation(_))));
+        assert!(!failing_path.exists());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+    
+    #[test]
+    fn test_write_footer_aligns_payload() {
+        let temp_dir = TempDir::new().unwrap();
+        let store_file_path = temp_dir.path().join("split.store");
+        fs::write(&store_file_path, vec![1u8; 100]).unwrap();
+        
+        let (start, end) = write_footer(&store_file_path, b"hotcache", Some(4096), false).unwrap();
+        assert_eq!((start, end), (4096, 4104));
+        
+        let data = fs::read(&store_file_path).unwrap();
+        assert_eq!(data.len(), 4104 + 8);
+        assert!(data[100..4096].iter().all(|byte| *byte == 0));
+        assert_eq!(&data[4096..4104], b"hotcache");
+        
+        // The trailer still locates the payload from the end of the file
+        let footer_len = u64::from_le_bytes(data[data.len() - 8..].try_into().unwrap());
+        assert_eq!(data.len() as u64 - footer_len, start);
+        
+        // Already aligned data gets no padding
+        let (start, _) = write_footer(&store_file_path, b"", Some(8), false).unwrap();
+        assert_eq!(start, 4112);
+    }
+    
+    #[test]
+    fn test_locate_dual_footer() {
+        let mut store = vec![1u8; 100];
+        store.extend_from_slice(&encode_footers(b"hotcache", true));
+        let read = |range: ByteRange| Ok(store[range.start as usize..range.end as usize].to_vec());
+        
+        // The versioned footer is read, the legacy one still ends the file
+        let footer = locate_footer(store.len() as u64, read).unwrap();
+        assert_eq!(footer, StoreFooter { start: 100, payload: ByteRange { start: 100, end: 108 }, dual: true });
+        let legacy_len = u64::from_le_bytes(store[store.len() - 8..].try_into().unwrap());
+        assert_eq!(&store[store.len() - legacy_len as usize..store.len() - 8], b"hotcache");
+        
+        // A versioned footer of an unknown version leaves the legacy one to read
+        store[108..112].copy_from_slice(&(FOOTER_VERSION + 1).to_le_bytes());
+        let read = |range: ByteRange| Ok(store[range.start as usize..range.end as usize].to_vec());
+        let footer = locate_footer(store.len() as u64, read).unwrap();
+        assert_eq!(footer, StoreFooter { start: 124, payload: ByteRange { start: 124, end: 132 }, dual: false });
+        
+        // As does a legacy footer alone
+        let mut store = vec![1u8; 100];
+        store.extend_from_slice(&encode_footers(b"hotcache", false));
+        let read = |range: ByteRange| Ok(store[range.start as usize..range.end as usize].to_vec());
+        assert!(!locate_footer(store.len() as u64, read).unwrap().dual);
+    }
+    
+    #[test]
+    fn test_align_sections_must_be_power_of_two() {
+        let temp_dir = TempDir::new().unwrap();
+        let generator = QuickwitSplitGenerator::new(test_index(), 1000)
+            .unwrap()
+            .with_config(SplitConfig { align_sections: Some(3000), ..SplitConfig::default() });
+        assert!(matches!(
+            generator.generate_split(&temp_dir.path().join("split")),
+            Err(SplitsError::InvalidOperation(_))
+        ));
+        
+        let generator = generator.with_config(SplitConfig { align_sections: Some(4096), ..SplitConfig::default() });
+        let metadata = generator.generate_split(&temp_dir.path().join("split")).unwrap();
+        assert_eq!(metadata.hotcache_start % 4096, 0);
+    }
+    
+    #[test]
+    fn test_hotcache_records_json_field_ranges() {
+        use tantivy::schema::{JsonObjectOptions, SchemaBuilder, STRING};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        schema_builder.add_json_field("attributes", JsonObjectOptions::from(STRING).set_fast(None));
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        let schema = index.schema();
+        let mut index_writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+        let doc = TantivyDocument::parse_json(&schema, r#"{"attributes": {"color": "red", "size": 3}}"#).unwrap();
+        index_writer.add_document(doc).unwrap();
+        index_writer.commit().unwrap();
+        drop(index_writer);
+        let segment_id = index.searchable_segment_ids().unwrap()[0];
+        
+        let generator = QuickwitSplitGenerator::new(index, 1000).unwrap();
+        let split_segments = generator.merge_segments(&[segment_id], temp_dir.path()).unwrap();
+        let field_metadata = generator.generate_hotcache(&split_segments, "split", temp_dir.path())
+            .unwrap()
+            .field_metadata()
+            .unwrap();
+        let fast_field_range = field_metadata["attributes"].fast_field_range.unwrap();
+        assert!(fast_field_range.size() > 0);
+        assert!(field_metadata["attributes"].term_dict_range.is_some());
+    }
+    
+    #[test]
+    fn test_hotcache_field_selection() {
+        use tantivy::collector::Count;
+        use tantivy::schema::{SchemaBuilder, FAST, INDEXED, STORED, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT | STORED);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let id = schema_builder.add_u64_field("id", INDEXED | FAST);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "first", body => "quickwit split", id => 1u64)).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "second", body => "tantivy index", id => 2u64)).unwrap();
+        index_writer.commit().unwrap();
+        drop(index_writer);
+        let segment_id = index.searchable_segment_ids().unwrap()[0];
+        
+        let generator = QuickwitSplitGenerator::new(index.clone(), 1000)
+            .unwrap()
+            .with_config(SplitConfig { hotcache_exclude: vec!["body".to_string()], ..SplitConfig::default() });
+        let split_segments = generator.merge_segments(&[segment_id], temp_dir.path()).unwrap();
+        let field_metadata = generator.generate_hotcache(&split_segments, "split", temp_dir.path())
+            .unwrap()
+            .field_metadata()
+            .unwrap();
+        assert!(field_metadata["title"].posting_range.is_some());
+        assert!(field_metadata["title"].fast_field_range.is_none());
+        assert!(field_metadata["id"].fast_field_range.is_some());
+        assert!(!field_metadata.contains_key("body"));
+        
+        let generator = generator.with_config(SplitConfig {
+            hotcache_fields: Some(vec!["title".to_string(), "body".to_string()]),
+            hotcache_exclude: vec!["body".to_string()],
+            ..SplitConfig::default()
+        });
+        let split_segments = generator.merge_segments(&[segment_id], temp_dir.path()).unwrap();
+        let field_metadata = generator.generate_hotcache(&split_segments, "split", temp_dir.path())
+            .unwrap()
+            .field_metadata()
+            .unwrap();
+        assert_eq!(field_metadata.keys().collect::<Vec<_>>(), vec!["title"]);
+        
+        // Fields left out of the hotcache are still queryable
+        let searcher = index.reader().unwrap().searcher();
+        let query = TermQuery::new(Term::from_field_text(body, "quickwit"), IndexRecordOption::Basic);
+        assert_eq!(searcher.search(&query, &Count).unwrap(), 1);
+    }
+    
+    #[test]
+    fn test_quickwit_hotcache_format_validation() {
+        let temp_dir = TempDir::new().unwrap();
+        let generator = QuickwitSplitGenerator::new(test_index(), 1000)
+            .unwrap()
+            .with_config(SplitConfig {
+                hotcache_format: HotcacheFormat::Quickwit,
+                align_sections: Some(4096),
+                ..SplitConfig::default()
+            });
+        
+        // Unsupported without the feature, and never combinable with alignment
+        assert!(matches!(
+            generator.generate_split(&temp_dir.path().join("split")),
+            Err(SplitsError::InvalidOperation(_))
+        ));
+        assert!(!temp_dir.path().join("split").exists());
+        
+        let generator = generator.with_config(SplitConfig {
+            hotcache_format: HotcacheFormat::Quickwit,
+            dual_footer: true,
+            ..SplitConfig::default()
+        });
+        assert!(matches!(
+            generator.generate_split(&temp_dir.path().join("split")),
+            Err(SplitsError::InvalidOperation(_))
+        ));
+    }
+    
+    #[test]
+    fn test_generate_split_streaming() {
+        use crate::split_bytes::split_files_from_bytes;
+        use std::cell::RefCell;
+        
+        let generator = QuickwitSplitGenerator::new(test_index(), 1000).unwrap();
+        let uploaded = RefCell::new(Vec::new());
+        let metadata = generator.generate_split_streaming(&|chunk: &[u8], offset: u64| {
+            assert_eq!(offset, uploaded.borrow().len() as u64);
+            uploaded.borrow_mut().extend_from_slice(chunk);
+            Ok(())
+        }).unwrap();
+        
+        let uploaded = uploaded.into_inner();
+        let files = split_files_from_bytes(&uploaded).unwrap();
+        let (last_name, last_data) = files.last().unwrap();
+        assert!(last_name.ends_with(".store"));
+        
+        // The hotcache footer is uploaded last
+        let footer_size = u64::from_le_bytes(last_data[last_data.len() - 8..].try_into().unwrap());
+        assert_eq!(footer_size, metadata.hotcache_end - metadata.hotcache_start + 8);
+    }
+    
+    #[test]
+    fn test_resolve_relative_output_path() {
+        let current_dir = fs::canonicalize(std::env::current_dir().unwrap()).unwrap();
+        assert_eq!(
+            resolve_output_path(Path::new("not-yet/../splits/./split"), None).unwrap(),
+            current_dir.join("splits").join("split")
+        );
+    }
+    
+    #[test]
+    fn test_generate_split_with_trailing_slash() {
+        let temp_dir = TempDir::new().unwrap();
+        let generator = QuickwitSplitGenerator::new(test_index(), 1000)
+            .unwrap()
+            .with_config(SplitConfig { legacy_directory_layout: true, ..SplitConfig::default() });
+        
+        let output = format!("{}/split/", temp_dir.path().display());
+        generator.generate_split(Path::new(&output)).unwrap();
+        assert!(temp_dir.path().join("split").is_dir());
+        
+        // The same split either way: without overwrite, the second generation collides
+        let result = generator.generate_split(&temp_dir.path().join("split"));
+        assert!(matches!(result, Err(SplitsError::InvalidOperation(_))));
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+    }
+    
+    #[cfg(unix)]
+    #[test]
+    fn test_generate_split_through_symlinks() {
+        use std::os::unix::fs::symlink;
+        
+        let temp_dir = TempDir::new().unwrap();
+        let base_dir = temp_dir.path().join("base");
+        let real_dir = base_dir.join("real");
+        fs::create_dir_all(real_dir.join("target")).unwrap();
+        symlink(&real_dir, base_dir.join("link")).unwrap();
+        symlink(real_dir.join("target"), base_dir.join("split-link")).unwrap();
+        symlink(temp_dir.path(), base_dir.join("escape")).unwrap();
+        
+        let generator = QuickwitSplitGenerator::new(test_index(), 1000)
+            .unwrap()
+            .with_config(SplitConfig { output_base_dir: Some(base_dir.clone()), ..SplitConfig::default() });
+        
+        // A symlinked parent directory
+        generator.generate_split(&base_dir.join("link").join("split")).unwrap();
+        assert!(real_dir.join("split").is_file());
+        
+        // A symlinked output directory is written into at its target and stays a symlink
+        generator.generate_split(&base_dir.join("split-link")).unwrap();
+        assert!(fs::read_dir(real_dir.join("target")).unwrap().next().is_some());
+        assert!(fs::symlink_metadata(base_dir.join("split-link")).unwrap().file_type().is_symlink());
+        
+        // Symlinks can't escape the base directory
+        let result = generator.generate_split(&base_dir.join("escape").join("split"));
+        assert!(matches!(result, Err(SplitsError::InvalidOperation(_))));
+        assert!(!temp_dir.path().join("split").exists());
+    }
+    
+    #[test]
+    fn test_exclusive_generation_waits_for_lock() {
+        let temp_dir = TempDir::new().unwrap();
+        
+        // Another producer holds the lock
+        let lock = lock_directory(temp_dir.path(), Duration::from_secs(5)).unwrap();
+        
+        let impatient = QuickwitSplitGenerator::new(test_index(), 1000)
+            .unwrap()
+            .with_config(SplitConfig { exclusive: true, lock_timeout_millis: 0, ..SplitConfig::default() });
+        match impatient.generate_split(&temp_dir.path().join("first")) {
+            Err(SplitsError::InvalidOperation(msg)) => assert!(msg.contains("generation lock")),
+            other => panic!("Expected InvalidOperation, got {:?}", other),
+        }
+        
+        let patient = impatient.with_config(SplitConfig { exclusive: true, lock_timeout_millis: 60_000, ..SplitConfig::default() });
+        let output_path = temp_dir.path().join("second");
+        let generation = std::thread::spawn(move || patient.generate_split(&output_path));
+        // The generation can't complete until the lock is released
+        assert!(!generation.is_finished());
+        drop(lock);
+        generation.join().unwrap().unwrap();
+    }
+    
+    #[test]
+    fn test_sidecar_metadata_path() {
+        assert_eq!(
+            sidecar_metadata_path(Path::new("/data/splits/abc")),
+            PathBuf::from("/data/splits/abc.json")
+        );
+    }
+}
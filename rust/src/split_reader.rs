@@ -19,22 +19,324 @@
 
 //! Quickwit split reading functionality
 
+use crate::compression::{decompress_split, Compression, DecompressedSplit, TempFile};
+use crate::encryption::{decrypt_split, EncryptionKey};
 use crate::error::{Result, SplitsError};
-use crate::hotcache::Hotcache;
+use crate::hotcache::{term_dict_range, ByteRange, FieldMetadata, HotcacheFormat, HotcacheInfo, Provenance};
+#[cfg(feature = "quickwit-compat")]
+use crate::hotcache::{parse_quickwit_hotcache, StaticDirectoryCache};
+use crate::memory_budget::CacheSlot;
+use crate::range_read::ReadOptions;
+use crate::split_bytes::{split_dir_checksum, split_file_checksum, split_files_from_bytes};
+use crate::split_generator::{locate_footer, sidecar_metadata_path, SplitMetadata};
+use crate::split_storage::{DirectoryStorage, LocalStorage, SplitFileStorage, SplitStorage, TruncatedFileDirectory};
+use crate::streaming::COPY_BUFFER_SIZE;
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque};
 use std::path::{Path, PathBuf};
 use std::fs;
-use std::io::{Read, Seek, SeekFrom};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use tantivy::{DocAddress, DocSet, Document, HasLen, Index, Searcher, SegmentReader, TantivyDocument, Term, TERMINATED};
+use tantivy::collector::FacetCollector;
+use tantivy::columnar::{Column, ColumnValues, DynamicColumn};
+use tantivy::directory::{Directory, OwnedBytes, RamDirectory};
+use tantivy::directory::footer::Footer;
+use tantivy::fastfield::{AliveBitSet, FastFieldReaders};
+use tantivy::index::{SegmentComponent, SegmentMeta};
+use tantivy::postings::{Postings, SegmentPostings};
+use tantivy::query::{AllQuery, BooleanQuery, Occur, PhraseQuery, Query, QueryParser, TermQuery};
+use tantivy::schema::{Facet, Field, FieldType, IndexRecordOption, OwnedValue, Type, ValueBytes};
+use tantivy::snippet::SnippetGenerator;
+use tantivy::tokenizer::TextAnalyzer;
+
+/// Name of the Tantivy index meta file, when embedded in a split
+const META_FILE_NAME: &str = "meta.json";
+
+/// Segment components every split segment must have on disk
+const REQUIRED_COMPONENTS: [SegmentComponent; 6] = [
+    SegmentComponent::Postings,
+    SegmentComponent::Positions,
+    SegmentComponent::FastFields,
+    SegmentComponent::FieldNorms,
+    SegmentComponent::Terms,
+    SegmentComponent::Store,
+];
+
+/// Separates the segments of a JSON path in indexed terms
+const JSON_PATH_SEGMENT_SEP: char = '\u{1}';
+
+/// Ends the JSON path of an indexed term, before the value's type code
+const JSON_END_OF_PATH: u8 = 0;
+
+/// Smallest valid segment file: Tantivy's footer ends with a u32 length and a u32 magic number
+const MIN_SEGMENT_FILE_SIZE: u64 = 8;
+
+/// Magic number ending Tantivy's file footer, as a little-endian u32
+const TANTIVY_FOOTER_MAGIC: u32 = 1337;
+
+/// Size of tar blocks: headers and file contents are padded to a multiple of it
+const TAR_BLOCK_SIZE: usize = 512;
+
+/// Size of the footer ending Tantivy's doc store: version, skip index offset, compressor id and reserved bytes
+const DOC_STORE_FOOTER_LEN: u64 = 28;
+
+/// Smallest non-empty store data: the doc store footer followed by Tantivy's file footer
+const MIN_STORE_DATA_SIZE: u64 = DOC_STORE_FOOTER_LEN + MIN_SEGMENT_FILE_SIZE;
+
+/// Reads a Tantivy variable-length integer, 7 bits per byte with the high bit set on the last byte
+fn read_vint(data: &mut &[u8]) -> Option<u64> {
+    let mut value = 0u64;
+    for (i, byte) in data.iter().enumerate().take(10) {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 != 0 {
+            *data = &data[i + 1..];
+            return Some(value);
+        }
+    }
+    None
+}
 
 /// Reader for accessing Quickwit split data and metadata
 pub struct QuickwitSplitReader {
-    /// Path to the split directory
+    /// Path identifying the split, its directory for splits on local storage
     split_path: PathBuf,
+    /// Where the split files are read from
+    storage: Arc<dyn SplitStorage>,
     /// Loaded hotcache metadata
-    hotcache: Option<Hotcache>,
+    hotcache: Option<HotcacheInfo>,
+    /// Loaded hotcache, for splits generated with the Quickwit hotcache format
+    #[cfg(feature = "quickwit-compat")]
+    quickwit_hotcache: Option<StaticDirectoryCache>,
+    /// Fingerprint of the store file at load time, used to detect changes
+    fingerprint: Option<SplitFingerprint>,
+    /// Tantivy index over the split, opened on first use and evictable
+    /// under the reader memory budget
+    index: Arc<CacheSlot<Index>>,
+    /// Tokenizers registered by the caller, applied over the persisted ones
+    registered_tokenizers: Mutex<HashMap<String, TextAnalyzer>>,
+    /// How byte ranges are read from the split files
+    read_options: ReadOptions,
+    /// Fast field columns handed out by address, kept mapped for the reader's lifetime
+    pinned_columns: Mutex<HashMap<String, OwnedBytes>>,
+    /// Key to decrypt the split files with, see `set_encryption_key`
+    encryption_key: Option<EncryptionKey>,
+    /// Temporary file a compressed split was decompressed into, removed with the reader
+    decompressed_file: Option<TempFile>,
+    /// Compression of the split file, for splits decompressed on open
+    compression: Option<Compression>,
+}
+
+/// Size and modification time of a split's store file, or of a compressed split's file
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SplitFingerprint {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+/// SplitMix64, a small seeded generator for reproducible sampling
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+    
+    /// Draws a number in `0..bound`, with negligible bias for bounds far below 2^64
+    fn next_below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+}
+
+/// Counts distinct values, by their bytes, as `QuickwitSplitReader::field_cardinality` asks
+enum DistinctCounter {
+    Exact(HashSet<Vec<u8>>),
+    Estimate(HyperLogLog),
+}
+
+impl DistinctCounter {
+    fn insert(&mut self, value: &[u8]) {
+        match self {
+            DistinctCounter::Exact(values) => {
+                if !values.contains(value) {
+                    values.insert(value.to_vec());
+                }
+            }
+            DistinctCounter::Estimate(hyperloglog) => hyperloglog.insert(value),
+        }
+    }
+    
+    fn count(&self) -> u64 {
+        match self {
+            DistinctCounter::Exact(values) => values.len() as u64,
+            DistinctCounter::Estimate(hyperloglog) => hyperloglog.estimate(),
+        }
+    }
+}
+
+/// Number of index bits of `HyperLogLog`, for 2^14 registers and a standard error of 0.8%
+const HYPERLOGLOG_PRECISION: u32 = 14;
+
+/// HyperLogLog cardinality estimator over BLAKE3 hashes of the values
+struct HyperLogLog {
+    registers: Vec<u8>,
+}
+
+impl HyperLogLog {
+    fn new() -> Self {
+        HyperLogLog { registers: vec![0; 1 << HYPERLOGLOG_PRECISION] }
+    }
+    
+    fn insert(&mut self, value: &[u8]) {
+        let hash_bytes: [u8; 8] = blake3::hash(value).as_bytes()[..8].try_into().expect("BLAKE3 hashes are 32 bytes");
+        let hash = u64::from_le_bytes(hash_bytes);
+        let register = (hash >> (64 - HYPERLOGLOG_PRECISION)) as usize;
+        // Position of the first set bit in the remaining bits, capped past their end
+        let rank = ((hash << HYPERLOGLOG_PRECISION).leading_zeros() + 1).min(64 - HYPERLOGLOG_PRECISION + 1) as u8;
+        self.registers[register] = self.registers[register].max(rank);
+    }
+    
+    fn estimate(&self) -> u64 {
+        let m = self.registers.len() as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|&rank| 2f64.powi(-(rank as i32))).sum();
+        let estimate = alpha * m * m / sum;
+        
+        // Linear counting is more accurate while many registers are still empty
+        let empty = self.registers.iter().filter(|&&rank| rank == 0).count();
+        if estimate <= 2.5 * m && empty > 0 {
+            return (m * (m / empty as f64).ln()).round() as u64;
+        }
+        estimate.round() as u64
+    }
+}
+
+/// Checks that a split written by Tantivy `version` can be read by this build
+///
+/// Index formats this build's Tantivy can't read fail with `InvalidSplit`,
+/// rather than deep in the directory layer; other Tantivy versions, and
+/// versions that can't be parsed, are only logged as a warning.
+fn check_tantivy_version(split_path: &Path, version: &str) -> Result<()> {
+    let index_format = version.split(", ")
+        .find_map(|part| part.strip_prefix("index_format v"))
+        .and_then(|format| format.parse::<u32>().ok());
+    
+    match index_format {
+        Some(format) if !(tantivy::INDEX_FORMAT_OLDEST_SUPPORTED_VERSION..=tantivy::INDEX_FORMAT_VERSION).contains(&format) => {
+            Err(SplitsError::InvalidSplit(format!(
+                "Split {} was written by {}, whose index format this build can't read ({})",
+                split_path.display(), version, tantivy::version_string()
+            )))
+        }
+        Some(_) if version != tantivy::version_string() => {
+            log::warn!(
+                "Split {} was written by {}, reading it with {}",
+                split_path.display(), version, tantivy::version_string()
+            );
+            Ok(())
+        }
+        Some(_) => Ok(()),
+        None => {
+            log::warn!("Split {} records an unrecognized Tantivy version: {}", split_path.display(), version);
+            Ok(())
+        }
+    }
+}
+
+/// Checks if a file name prefix is a segment id
+///
+/// Tantivy names segment files after the simple, 32 hex digit form of the
+/// segment UUID; the hyphenated form is accepted too.
+fn is_segment_id(prefix: &str) -> bool {
+    match prefix.len() {
+        32 => prefix.bytes().all(|b| b.is_ascii_hexdigit()),
+        36 => prefix.chars().filter(|&c| c == '-').count() == 4,
+        _ => false,
+    }
+}
+
+/// Converts a path relative to the split root into a storage file name
+fn file_name(relative_path: &Path) -> String {
+    relative_path.to_string_lossy().to_string()
+}
+
+/// Pairs each segment of a searcher with the split-wide id of its first document
+///
+/// Doc ids run across the segments in order, each segment's starting after
+/// the previous one's last document, deleted or not.
+fn segment_doc_bases(searcher: &Searcher) -> impl Iterator<Item = (&SegmentReader, u32)> {
+    searcher.segment_readers().iter().scan(0u32, |doc_base, segment_reader| {
+        let segment_doc_base = *doc_base;
+        *doc_base += segment_reader.max_doc();
+        Some((segment_reader, segment_doc_base))
+    })
+}
+
+/// Maps a split-wide doc id to its segment and the doc id within it, None past the end of the split
+fn split_doc_address(searcher: &Searcher, doc_id: u32) -> Option<DocAddress> {
+    segment_doc_bases(searcher).enumerate().find_map(|(segment_ord, (segment_reader, doc_base))| {
+        doc_id.checked_sub(doc_base)
+            .filter(|local_doc_id| *local_doc_id < segment_reader.max_doc())
+            .map(|local_doc_id| DocAddress::new(segment_ord as u32, local_doc_id))
+    })
+}
+
+/// Counts the documents of every segment, deleted or not
+fn split_max_doc(searcher: &Searcher) -> u32 {
+    searcher.segment_readers().iter().map(|segment_reader| segment_reader.max_doc()).sum()
+}
+
+/// Builds the POSIX ustar header of a regular file
+///
+/// Sizes beyond the 8 GiB octal limit use the GNU base-256 encoding, which
+/// every common tar implementation reads.
+fn tar_header(file_name: &str, len: u64, modified: u64) -> Result<[u8; TAR_BLOCK_SIZE]> {
+    fn put_octal(field: &mut [u8], value: u64) {
+        let digits = format!("{:0width$o}\0", value, width = field.len() - 1);
+        field.copy_from_slice(digits.as_bytes());
+    }
+    
+    if file_name.len() > 100 {
+        return Err(SplitsError::InvalidOperation(format!(
+            "File name too long for a tar archive: {}", file_name
+        )));
+    }
+    
+    let mut header = [0u8; TAR_BLOCK_SIZE];
+    header[..file_name.len()].copy_from_slice(file_name.as_bytes());
+    put_octal(&mut header[100..108], 0o644);
+    put_octal(&mut header[108..116], 0);
+    put_octal(&mut header[116..124], 0);
+    if len < 1 << 33 {
+        put_octal(&mut header[124..136], len);
+    } else {
+        header[124] = 0x80;
+        header[128..136].copy_from_slice(&len.to_be_bytes());
+    }
+    put_octal(&mut header[136..148], modified.min((1 << 33) - 1));
+    header[156] = b'0';
+    header[257..263].copy_from_slice(b"ustar\0");
+    header[263..265].copy_from_slice(b"00");
+    
+    // The checksum is computed with its own field filled with spaces
+    header[148..156].fill(b' ');
+    let checksum: u64 = header.iter().map(|byte| *byte as u64).sum();
+    put_octal(&mut header[148..155], checksum);
+    Ok(header)
 }
 
 impl QuickwitSplitReader {
     /// Opens a Quickwit split for reading
+    ///
+    /// `split_path` is either a single-file split, such as
+    /// `<split_id>.split`, read in place, or a split directory of the legacy
+    /// layout. Split files compressed as a whole, ending in `.gz` or `.zst`,
+    /// are decompressed first, see the `compression` module for the cost.
     pub fn open(split_path: &Path) -> Result<Self> {
         if !split_path.exists() {
             return Err(SplitsError::InvalidSplit(
@@ -42,24 +344,127 @@ impl QuickwitSplitReader {
             ));
         }
         
+        if let Some(compression) = Compression::from_path(split_path) {
+            return Self::open_compressed(split_path, compression);
+        }
+        
+        if split_path.is_file() {
+            return Self::open_storage(split_path, Arc::new(SplitFileStorage::open(split_path)?));
+        }
+        
         if !split_path.is_dir() {
             return Err(SplitsError::InvalidSplit(
-                format!("Split path is not a directory: {}", split_path.display())
+                format!("Split path is neither a file nor a directory: {}", split_path.display())
             ));
         }
         
+        Self::open_storage(split_path, Arc::new(LocalStorage::new(split_path)))
+    }
+    
+    /// Opens a Quickwit split whose files are read from `storage`
+    ///
+    /// `split_path` only identifies the split, in error messages and
+    /// `get_split_path`; sidecar metadata is only looked up for storages
+    /// with a local path.
+    pub fn open_with_storage(split_path: &Path, storage: Box<dyn SplitStorage>) -> Result<Self> {
+        Self::open_storage(split_path, Arc::from(storage))
+    }
+    
+    /// Opens a split file compressed as a whole, decompressing it first
+    fn open_compressed(split_path: &Path, compression: Compression) -> Result<Self> {
+        let mut reader = match decompress_split(split_path, compression)? {
+            DecompressedSplit::InMemory(bytes) => {
+                let directory = RamDirectory::create();
+                let mut files = Vec::new();
+                for (name, data) in split_files_from_bytes(&bytes)? {
+                    directory.atomic_write(Path::new(&name), data)?;
+                    files.push(name);
+                }
+                let storage = DirectoryStorage::new(Box::new(directory), files);
+                Self::open_storage(split_path, Arc::new(storage))?
+            }
+            DecompressedSplit::TempFile(temp_file) => {
+                let storage = SplitFileStorage::open(temp_file.path())?;
+                let mut reader = Self::open_storage(split_path, Arc::new(storage))?;
+                reader.decompressed_file = Some(temp_file);
+                reader
+            }
+        };
+        reader.compression = Some(compression);
+        reader.fingerprint = Some(reader.compute_fingerprint()?);
+        Ok(reader)
+    }
+    
+    fn open_storage(split_path: &Path, storage: Arc<dyn SplitStorage>) -> Result<Self> {
         let mut reader = QuickwitSplitReader {
             split_path: split_path.to_path_buf(),
+            storage,
             hotcache: None,
+            #[cfg(feature = "quickwit-compat")]
+            quickwit_hotcache: None,
+            fingerprint: None,
+            index: CacheSlot::new(),
+            registered_tokenizers: Mutex::default(),
+            read_options: ReadOptions::default(),
+            pinned_columns: Mutex::default(),
+            encryption_key: None,
+            decompressed_file: None,
+            compression: None,
         };
         
+        // Catch interrupted writes before they surface as low-level IO errors
+        reader.check_not_truncated()?;
+        
         // Load hotcache on open
         reader.load_hotcache()?;
+        if let Some(version) = reader.tantivy_version() {
+            check_tantivy_version(split_path, &version)?;
+        }
+        reader.fingerprint = Some(reader.compute_fingerprint()?);
         
+        log::debug!("Opened split {}", split_path.display());
         Ok(reader)
     }
     
+    /// Reloads the split if its files changed since it was opened
+    ///
+    /// Changes are detected from the store file size and modification time,
+    /// so a split path that is a symlink flipped to a new split is picked up.
+    /// Compressed splits are checked, and decompressed anew, as a whole file.
+    /// The new state is fully loaded before being swapped in: callers holding
+    /// the reader see either the old or the new split, never a mix, and a
+    /// failed reload leaves the reader unchanged.
+    pub fn reload(&mut self) -> Result<()> {
+        let current = self.compute_fingerprint()?;
+        if self.fingerprint.as_ref() == Some(&current) {
+            return Ok(());
+        }
+        
+        let mut reloaded = match self.compression {
+            Some(compression) => QuickwitSplitReader::open_compressed(&self.split_path, compression)?,
+            None => {
+                // A split file's layout is only read on open, and may have changed with it
+                let storage: Arc<dyn SplitStorage> = match self.storage.local_path() {
+                    Some(path) if path.is_file() => Arc::new(SplitFileStorage::open(path)?),
+                    _ => self.storage.clone(),
+                };
+                QuickwitSplitReader::open_storage(&self.split_path, storage)?
+            }
+        };
+        reloaded.registered_tokenizers = std::mem::take(&mut self.registered_tokenizers);
+        reloaded.read_options = self.read_options;
+        reloaded.encryption_key = self.encryption_key.take();
+        // Addresses already handed out stay valid, over the previous split's files
+        reloaded.pinned_columns = std::mem::take(&mut self.pinned_columns);
+        *self = reloaded;
+        
+        Ok(())
+    }
+    
     /// Loads hotcache metadata from the split
+    ///
+    /// Splits generated without a hotcache carry an empty footer payload and
+    /// leave the hotcache unset.
     pub fn load_hotcache(&mut self) -> Result<()> {
         // Step 1: Find the file containing the hotcache footer
         let store_file = self.find_store_file()?;
@@ -67,27 +472,147 @@ impl QuickwitSplitReader {
         // Step 2: Read the hotcache data from the footer
         let hotcache_data = self.read_hotcache_from_footer(&store_file)?;
         
-        // Step 3: Parse the hotcache
-        self.hotcache = Some(Hotcache::deserialize(&hotcache_data)?);
+        // Step 3: Parse the hotcache, if any, in either format
+        self.hotcache = None;
+        #[cfg(feature = "quickwit-compat")]
+        {
+            self.quickwit_hotcache = None;
+        }
+        if hotcache_data.is_empty() {
+            return Ok(());
+        }
+        
+        match HotcacheInfo::from_bytes(&hotcache_data) {
+            Ok(hotcache) => self.hotcache = Some(hotcache),
+            Err(e) => self.load_quickwit_hotcache(hotcache_data, e)?,
+        }
         
         Ok(())
     }
     
+    /// Falls back to parsing a hotcache our format rejected as a Quickwit one
+    ///
+    /// A Quickwit hotcache never decodes as ours: its leading version and
+    /// length fields read as an oversized split id length. If both parsers
+    /// reject the payload, the error of ours is reported.
+    #[cfg(feature = "quickwit-compat")]
+    fn load_quickwit_hotcache(&mut self, hotcache_data: Vec<u8>, error: SplitsError) -> Result<()> {
+        self.quickwit_hotcache = Some(parse_quickwit_hotcache(hotcache_data).map_err(|_| error)?);
+        Ok(())
+    }
+    
+    #[cfg(not(feature = "quickwit-compat"))]
+    fn load_quickwit_hotcache(&mut self, _hotcache_data: Vec<u8>, error: SplitsError) -> Result<()> {
+        Err(error)
+    }
+    
     /// Gets the hotcache information
-    pub fn get_hotcache_info(&self) -> Option<&Hotcache> {
+    ///
+    /// Returns `None` for splits generated without a hotcache or with a
+    /// Quickwit one.
+    pub fn get_hotcache_info(&self) -> Option<&HotcacheInfo> {
+        self.hotcache.as_ref()
+    }
+    
+    /// Gets the format of the split's hotcache, `None` if it has none
+    pub fn hotcache_format(&self) -> Option<HotcacheFormat> {
+        if self.hotcache.is_some() {
+            return Some(HotcacheFormat::Simple);
+        }
+        #[cfg(feature = "quickwit-compat")]
+        if self.quickwit_hotcache.is_some() {
+            return Some(HotcacheFormat::Quickwit);
+        }
+        None
+    }
+    
+    /// Gets the Quickwit hotcache of a split generated with `HotcacheFormat::Quickwit`
+    #[cfg(feature = "quickwit-compat")]
+    pub fn quickwit_hotcache(&self) -> Option<&StaticDirectoryCache> {
+        self.quickwit_hotcache.as_ref()
+    }
+    
+    /// Gets when and by what the split was produced
+    ///
+    /// Fields are `None` when the split doesn't record them, e.g. splits
+    /// generated without a hotcache.
+    pub fn provenance(&self) -> Provenance {
         self.hotcache.as_ref()
+            .map(|hotcache| hotcache.provenance())
+            .unwrap_or_default()
+    }
+    
+    /// Gets the Tantivy version and index format the split was written with
+    ///
+    /// As recorded in the provenance, e.g. `tantivy v0.24.2, index_format v7`;
+    /// `None` for splits that don't record it.
+    pub fn tantivy_version(&self) -> Option<String> {
+        self.provenance().tantivy_version
+    }
+    
+    /// Gets the key/value metadata the split was generated with, see `SplitConfig::custom_metadata`
+    ///
+    /// Empty for splits generated without any, or without a hotcache.
+    pub fn custom_metadata(&self) -> Result<BTreeMap<String, String>> {
+        match &self.hotcache {
+            Some(hotcache) => hotcache.custom_metadata(),
+            None => Ok(BTreeMap::new()),
+        }
+    }
+    
+    /// Sets the key to decrypt an encrypted split with, see `SplitConfig::encryption_key`
+    ///
+    /// Fails with `InvalidOperation` unless the key is 32 bytes long. The
+    /// index is reopened under the new key on next use; a wrong key then
+    /// fails with `Decryption`. Methods returning raw split bytes, such as
+    /// store blocks or tar exports, return them encrypted regardless.
+    pub fn set_encryption_key(&mut self, key: &[u8]) -> Result<()> {
+        self.encryption_key = Some(EncryptionKey::new(key)?);
+        self.index = CacheSlot::new();
+        Ok(())
+    }
+    
+    /// Whether the split files are encrypted, requiring a key to be read
+    pub fn is_encrypted(&self) -> Result<bool> {
+        match &self.hotcache {
+            Some(hotcache) => Ok(!hotcache.encrypted_files()?.is_empty()),
+            None => Ok(false),
+        }
+    }
+    
+    /// Gets the opstamp of the source index's last commit the split reflects
+    ///
+    /// Every indexing operation with a lower opstamp is included in the
+    /// split. Splits without a recorded opstamp fall back to their embedded
+    /// `meta.json`, and fail with `InvalidSplit` if they have none.
+    pub fn opstamp(&self) -> Result<u64> {
+        if let Some(opstamp) = self.hotcache.as_ref().and_then(|hotcache| hotcache.opstamp()) {
+            return Ok(opstamp);
+        }
+        Ok(self.index()?.load_metas()?.opstamp)
     }
     
     /// Lists all segment files in the split
     pub fn list_segment_files(&self) -> Result<Vec<String>> {
+        Ok(self.segment_files_with_sizes()?
+            .into_iter()
+            .map(|(file_name, _)| file_name)
+            .collect())
+    }
+    
+    /// Lists all segment files in the split with their length in bytes
+    ///
+    /// Files are sorted by name. The store file's length includes the
+    /// hotcache footer.
+    pub fn segment_files_with_sizes(&self) -> Result<Vec<(String, u64)>> {
         let mut files = Vec::new();
         
-        for entry in fs::read_dir(&self.split_path)? {
-            let entry = entry?;
-            let file_name = entry.file_name().to_string_lossy().to_string();
-            
-            if self.is_segment_file(&file_name) {
-                files.push(file_name);
+        for file_name in self.storage.list_files()? {
+            if !self.is_segment_file(&file_name) {
+                continue;
+            }
+            if let Some(len) = self.storage.file_len(&file_name)? {
+                files.push((file_name, len));
             }
         }
         
@@ -97,223 +622,4497 @@ impl QuickwitSplitReader {
     }
     
     /// Reads the posting list for a given field and term
+    ///
+    /// Postings are decoded by Tantivy, whatever ranges the hotcache records
+    /// for the field, so a stale range can't corrupt the result. Deleted
+    /// documents are skipped. Returns an empty list for a split generated
+    /// from an empty index.
     pub fn read_posting_list(&self, field: &str, term: &str) -> Result<Vec<u32>> {
-        let hotcache = self.hotcache.as_ref()
-            .ok_or_else(|| SplitsError::InvalidOperation("Hotcache not loaded".to_string()))?;
-        
-        // Get field metadata
-        let field_metadata = hotcache.field_metadata.get(field)
-            .ok_or_else(|| SplitsError::FieldError(format!("Field '{}' not found", field)))?;
-        
-        let posting_range = field_metadata.posting_range.as_ref()
-            .ok_or_else(|| SplitsError::FieldError(format!("No posting data for field '{}'", field)))?;
-        
-        // Find the term file
-        let term_file = self.find_file_with_extension("term")?;
+        if self.is_empty_split()? {
+            return Ok(Vec::new());
+        }
         
-        // In a real implementation, this would:
-        // 1. Use the term dictionary to find the exact byte range for this term
-        // 2. Read and decode the posting list for the specific term
-        // For now, we'll return a placeholder
+        if self.hotcache.is_none() {
+            return Err(SplitsError::InvalidOperation("Hotcache not loaded".to_string()));
+        }
         
-        self.read_posting_list_from_range(&term_file, posting_range, term)
+        self.read_posting_list_from_index(field, term)
     }
     
-    /// Gets fast field data for a document range
-    pub fn get_fast_field_data(&self, field: &str, doc_range: std::ops::Range<u32>) -> Result<Vec<u8>> {
-        let hotcache = self.hotcache.as_ref()
-            .ok_or_else(|| SplitsError::InvalidOperation("Hotcache not loaded".to_string()))?;
+    /// Reads a whole posting list through a cursor, skipping deleted documents
+    fn read_posting_list_from_index(&self, field: &str, term: &str) -> Result<Vec<u32>> {
+        let mut cursor = self.posting_cursor(field, term)?;
+        let mut doc_ids = Vec::new();
+        loop {
+            let batch = cursor.next_batch(4096)?;
+            if batch.is_empty() {
+                return Ok(doc_ids);
+            }
+            doc_ids.extend(batch);
+        }
+    }
+    
+    /// Opens a cursor over the posting list for a given field and term
+    ///
+    /// Unlike `read_posting_list`, doc ids are decoded lazily in batches, which
+    /// keeps memory bounded when paging through common terms. Deleted documents
+    /// are skipped. In a split of several segments, doc ids run across the
+    /// segments in order, each segment's starting after the previous one's
+    /// last document, deleted or not.
+    pub fn posting_cursor(&self, field: &str, term: &str) -> Result<PostingCursor> {
+        let field_handle = self.tantivy_field(field)?;
+        let term = Term::from_field_text(field_handle, term);
         
-        // Get field metadata
-        let field_metadata = hotcache.field_metadata.get(field)
-            .ok_or_else(|| SplitsError::FieldError(format!("Field '{}' not found", field)))?;
+        let searcher = self.searcher()?;
+        let mut segments = VecDeque::new();
+        for (segment_reader, doc_base) in segment_doc_bases(&searcher) {
+            let inverted_index = segment_reader.inverted_index(field_handle)?;
+            if let Some(postings) = inverted_index.read_postings(&term, IndexRecordOption::Basic)? {
+                segments.push_back(SegmentPostingCursor {
+                    postings,
+                    alive_bitset: segment_reader.alive_bitset().cloned(),
+                    doc_base,
+                });
+            }
+        }
         
-        let fast_field_range = field_metadata.fast_field_range.as_ref()
-            .ok_or_else(|| SplitsError::FieldError(format!("No fast field data for field '{}'", field)))?;
+        Ok(PostingCursor { segments })
+    }
+    
+    /// Returns how many times `term` occurs in `field` of document `doc_id`
+    ///
+    /// Doc ids are split-wide, as `posting_cursor` numbers them. Returns 0
+    /// when the document doesn't contain the term, is deleted or is past the
+    /// end of the split. Fails with a `FieldError` if the field isn't indexed
+    /// with term frequencies.
+    pub fn term_freq(&self, field: &str, term: &str, doc_id: u32) -> Result<u32> {
+        let field_handle = self.tantivy_field(field)?;
+        if !self.index_record_option(field, field_handle)?.has_freq() {
+            return Err(SplitsError::FieldError(format!(
+                "Field '{}' is not indexed with term frequencies", field
+            )));
+        }
         
-        // Find the fast field file
-        let fast_file = self.find_file_with_extension("fast")?;
+        let searcher = self.searcher()?;
+        let doc_address = match split_doc_address(&searcher, doc_id) {
+            Some(doc_address) => doc_address,
+            None => return Ok(0),
+        };
+        let segment_reader = searcher.segment_reader(doc_address.segment_ord);
+        let doc_id = doc_address.doc_id;
         
-        // Calculate the specific byte range for the requested document range
-        let doc_byte_range = self.calculate_doc_range_bytes(fast_field_range, doc_range)?;
+        let inverted_index = segment_reader.inverted_index(field_handle)?;
+        let mut postings = match inverted_index.read_postings(
+            &Term::from_field_text(field_handle, term),
+            IndexRecordOption::WithFreqs,
+        )? {
+            Some(postings) => postings,
+            None => return Ok(0),
+        };
         
-        self.read_byte_range(&fast_file, &doc_byte_range)
-    }
-    
-    /// Gets the split path
-    pub fn get_split_path(&self) -> &Path {
-        &self.split_path
+        // Postings only seek forward, from the term's first document
+        if postings.doc() > doc_id || segment_reader.is_deleted(doc_id) {
+            return Ok(0);
+        }
+        if postings.seek(doc_id) == doc_id {
+            Ok(postings.term_freq())
+        } else {
+            Ok(0)
+        }
     }
     
-    /// Finds the store file in the split directory
-    fn find_store_file(&self) -> Result<PathBuf> {
-        for entry in fs::read_dir(&self.split_path)? {
-            let entry = entry?;
-            let file_name = entry.file_name().to_string_lossy().to_string();
+    /// Reads the posting list for a given field and term, with the term's frequency in each document
+    ///
+    /// Returns `(doc_id, term_freq)` pairs in split-wide doc id order, as
+    /// `posting_cursor` numbers them, skipping deleted documents. Fails with
+    /// a `FieldError` if the field isn't indexed with term frequencies;
+    /// `read_posting_list` reads doc ids of any field.
+    pub fn read_posting_list_with_freqs(&self, field: &str, term: &str) -> Result<Vec<(u32, u32)>> {
+        let field_handle = self.tantivy_field(field)?;
+        if !self.index_record_option(field, field_handle)?.has_freq() {
+            return Err(SplitsError::FieldError(format!(
+                "Field '{}' is not indexed with term frequencies", field
+            )));
+        }
+        
+        let searcher = self.searcher()?;
+        let term = Term::from_field_text(field_handle, term);
+        let mut doc_freqs = Vec::new();
+        for (segment_reader, doc_base) in segment_doc_bases(&searcher) {
+            let inverted_index = segment_reader.inverted_index(field_handle)?;
+            let mut postings = match inverted_index.read_postings(&term, IndexRecordOption::WithFreqs)? {
+                Some(postings) => postings,
+                None => continue,
+            };
             
-            if file_name.ends_with(".store") {
-                return Ok(entry.path());
+            let alive_bitset = segment_reader.alive_bitset();
+            doc_freqs.reserve(postings.size_hint() as usize);
+            let mut doc = postings.doc();
+            while doc != TERMINATED {
+                if alive_bitset.is_none_or(|alive_bitset| alive_bitset.is_alive(doc)) {
+                    doc_freqs.push((doc_base + doc, postings.term_freq()));
+                }
+                doc = postings.advance();
             }
         }
+        Ok(doc_freqs)
+    }
+    
+    /// Returns the field norm of `field` in document `doc_id`, its length in tokens
+    ///
+    /// This is the value BM25 scoring uses: Tantivy stores norms in one byte,
+    /// so lengths are exact up to 40 and rounded down beyond. Fails with a
+    /// `FieldError` if the field doesn't record norms, and `InvalidOperation`
+    /// for doc ids past the end of the split.
+    pub fn fieldnorm(&self, field: &str, doc_id: u32) -> Result<u32> {
+        let field_handle = self.tantivy_field(field)?;
+        if !self.index()?.schema().get_field_entry(field_handle).has_fieldnorms() {
+            return Err(SplitsError::FieldError(format!(
+                "Field '{}' is not indexed with field norms", field
+            )));
+        }
         
-        Err(SplitsError::InvalidSplit(
-            "No store file found in split directory".to_string()
-        ))
+        let searcher = self.searcher()?;
+        let doc_address = split_doc_address(&searcher, doc_id)
+            .ok_or_else(|| SplitsError::InvalidOperation(format!("Document ID {} out of range", doc_id)))?;
+        let segment_reader = searcher.segment_reader(doc_address.segment_ord);
+        Ok(segment_reader.get_fieldnorms_reader(field_handle)?.fieldnorm(doc_address.doc_id))
     }
     
-    /// Reads hotcache data from the footer of a store file
-    fn read_hotcache_from_footer(&self, store_file: &Path) -> Result<Vec<u8>> {
-        let file_size = fs::metadata(store_file)?.len();
+    /// Lists the distinct terms of an indexed text or JSON field, in term order
+    ///
+    /// Terms of a JSON field are listed as `<path>:<value>`, the path dotted
+    /// from the field's root, e.g. `user.age:42`, grouped by path. Values of
+    /// any JSON type are rendered as text, dates in RFC 3339. Terms only held
+    /// by deleted documents are listed until the split is merged. A split
+    /// generated from an empty index has no schema, so every field yields an
+    /// empty list.
+    pub fn field_terms(&self, field: &str) -> Result<Vec<String>> {
+        if self.is_empty_split()? {
+            return Ok(Vec::new());
+        }
         
-        if file_size < 8 {
-            return Err(SplitsError::InvalidSplit(
-                "Store file too small to contain hotcache footer".to_string()
-            ));
+        let field_handle = self.tantivy_field(field)?;
+        let is_json = matches!(
+            self.index()?.schema().get_field_entry(field_handle).field_type(),
+            FieldType::JsonObject(_)
+        );
+        if is_json {
+            self.index_record_option(field, field_handle)?;
+        } else {
+            self.indexed_text_field(field)?;
         }
         
-        let mut file = fs::File::open(store_file)?;
+        let searcher = self.searcher()?;
+        let mut keys = BTreeSet::new();
+        for segment_reader in searcher.segment_readers() {
+            let inverted_index = segment_reader.inverted_index(field_handle)?;
+            let mut stream = inverted_index.terms().stream()?;
+            while stream.advance() {
+                keys.insert(stream.key().to_vec());
+            }
+        }
         
-        // In a real implementation, the hotcache would be embedded with a footer
-        // that contains the size and position information. For now, we'll simulate
-        // reading from the end of the file.
+        Ok(keys.iter()
+            .map(|key| {
+                let json_term = if is_json { json_term_to_string(key) } else { None };
+                json_term.unwrap_or_else(|| String::from_utf8_lossy(key).to_string())
+            })
+            .collect())
+    }
+    
+    /// Lists the distinct terms of an indexed text field starting with `prefix`, for typeahead
+    ///
+    /// Terms are returned in term order, i.e. sorted by their UTF-8 bytes,
+    /// and at most `limit` of them: the first ones in that order. Each
+    /// segment's term dictionary is only scanned from the prefix on, until a
+    /// term no longer matches it or enough terms were found. An empty prefix
+    /// matches every term. Fails with a `FieldError` like `field_terms`.
+    pub fn terms_with_prefix(&self, field: &str, prefix: &str, limit: usize) -> Result<Vec<String>> {
+        if limit == 0 || self.is_empty_split()? {
+            return Ok(Vec::new());
+        }
         
-        // Read the last 8 bytes to get the hotcache size
-        file.seek(SeekFrom::End(-8))?;
-        let mut size_bytes = [0u8; 8];
-        file.read_exact(&mut size_bytes)?;
-        let hotcache_size = u64::from_le_bytes(size_bytes);
+        let field_handle = self.indexed_text_field(field)?;
+        let searcher = self.searcher()?;
+        let mut terms = BTreeSet::new();
+        for segment_reader in searcher.segment_readers() {
+            let inverted_index = segment_reader.inverted_index(field_handle)?;
+            let mut stream = inverted_index.terms().range().ge(prefix.as_bytes()).into_stream()?;
+            // The first `limit` terms overall are among each segment's first `limit`
+            let mut found = 0;
+            while found < limit && stream.advance() {
+                if !stream.key().starts_with(prefix.as_bytes()) {
+                    break;
+                }
+                terms.insert(String::from_utf8_lossy(stream.key()).to_string());
+                found += 1;
+            }
+        }
         
-        if hotcache_size > file_size || hotcache_size < 8 {
-            return Err(SplitsError::InvalidSplit(
-                "Invalid hotcache size in footer".to_string()
-            ));
+        Ok(terms.into_iter().take(limit).collect())
+    }
+    
+    /// Resolves an indexed text field, failing with a `FieldError` for any other field
+    fn indexed_text_field(&self, field: &str) -> Result<Field> {
+        let field_handle = self.tantivy_field(field)?;
+        self.index_record_option(field, field_handle)?;
+        let field_type = self.index()?.schema().get_field_entry(field_handle).field_type().clone();
+        if !matches!(field_type, FieldType::Str(_)) {
+            return Err(SplitsError::FieldError(format!(
+                "Field '{}' of type {} is not a text field", field, FieldKind::of(&field_type).name()
+            )));
+        }
+        Ok(field_handle)
+    }
+    
+    /// Returns the serialized term dictionary of an indexed field
+    ///
+    /// The bytes are the field's section of the segment's `.term` file, in
+    /// Tantivy's FST term dictionary format, version 1: a `tantivy-fst` map
+    /// from each term to its ordinal, then the term info store, then the
+    /// store's length as a little-endian u64 and the format version as a
+    /// little-endian u32. The section is read at the range recorded in the
+    /// hotcache, or else located from the file's footer with a few small
+    /// reads. A split generated from an empty index, or a field without any
+    /// term, yields no bytes. Fails with a `FieldError` if the field isn't
+    /// indexed.
+    pub fn term_dict_bytes(&self, field: &str) -> Result<Vec<u8>> {
+        if self.is_empty_split()? {
+            return Ok(Vec::new());
         }
         
-        // Read the hotcache data
-        let hotcache_start = file_size - hotcache_size;
-        file.seek(SeekFrom::Start(hotcache_start))?;
+        let field_handle = self.tantivy_field(field)?;
+        self.index_record_option(field, field_handle)?;
         
-        let mut hotcache_data = vec![0u8; (hotcache_size - 8) as usize];
-        file.read_exact(&mut hotcache_data)?;
+        let term_file = self.component_file(SegmentComponent::Terms)?;
+        let term_dict_range = match self.hotcached_range(field, &term_file, |metadata| metadata.term_dict_range)? {
+            Some(range) => Some(range),
+            None => {
+                let file_len = self.storage.file_len(&term_file)?.unwrap_or(0);
+                term_dict_range(file_len, field_handle.field_id(), |range| self.read_byte_range(&term_file, &range))?
+            }
+        };
         
-        Ok(hotcache_data)
+        match term_dict_range {
+            Some(range) => self.read_byte_range(&term_file, &range),
+            None => Ok(Vec::new()),
+        }
     }
     
-    /// Finds a file with the given extension in the split directory
-    fn find_file_with_extension(&self, extension: &str) -> Result<PathBuf> {
-        for entry in fs::read_dir(&self.split_path)? {
-            let entry = entry?;
-            let file_name = entry.file_name().to_string_lossy().to_string();
+    /// Returns how many times `term` occurs in `field` across all live documents
+    ///
+    /// Tantivy records document frequencies per term but no total term
+    /// frequency, so the term's posting list is decoded once, frequencies only.
+    /// This costs a pass over the term's postings, not the whole field's.
+    /// Returns 0 for a missing term. Fails with a `FieldError` if the field
+    /// isn't indexed with term frequencies.
+    pub fn total_term_freq(&self, field: &str, term: &str) -> Result<u64> {
+        let field_handle = self.tantivy_field(field)?;
+        if !self.index_record_option(field, field_handle)?.has_freq() {
+            return Err(SplitsError::FieldError(format!(
+                "Field '{}' is not indexed with term frequencies", field
+            )));
+        }
+        
+        let searcher = self.searcher()?;
+        let term = Term::from_field_text(field_handle, term);
+        let mut total = 0u64;
+        for segment_reader in searcher.segment_readers() {
+            let inverted_index = segment_reader.inverted_index(field_handle)?;
+            let mut postings = match inverted_index.read_postings(&term, IndexRecordOption::WithFreqs)? {
+                Some(postings) => postings,
+                None => continue,
+            };
             
-            if file_name.ends_with(&format!(".{}", extension)) {
-                return Ok(entry.path());
+            let alive_bitset = segment_reader.alive_bitset();
+            let mut doc = postings.doc();
+            while doc != TERMINATED {
+                if alive_bitset.is_none_or(|alive_bitset| alive_bitset.is_alive(doc)) {
+                    total += postings.term_freq() as u64;
+                }
+                doc = postings.advance();
             }
         }
         
-        Err(SplitsError::InvalidSplit(
-            format!("No {} file found in split directory", extension)
-        ))
+        Ok(total)
     }
     
-    /// Checks if a filename is a segment file
-    fn is_segment_file(&self, filename: &str) -> bool {
-        // UUID-based filenames with known extensions
-        let extensions = ["store", "term", "idx", "fast", "pos", "fieldnorm", "del"];
-        
-        for ext in &extensions {
-            if filename.ends_with(&format!(".{}", ext)) {
-                // Check if the prefix looks like a UUID
-                if let Some(prefix) = filename.strip_suffix(&format!(".{}", ext)) {
-                    if prefix.len() == 36 && prefix.chars().filter(|&c| c == '-').count() == 4 {
-                        return true;
-                    }
-                }
+    /// Checks whether two splits hold the same schema and live documents
+    ///
+    /// Documents are compared by stored content, as a multiset: doc ids,
+    /// split ids, provenance and file-level bytes don't matter, so generating
+    /// the same index twice yields equal splits. Fields that aren't stored are
+    /// only compared through the schema. Splits generated from empty indexes
+    /// have no schema and only equal each other.
+    pub fn content_equals(&self, other: &QuickwitSplitReader) -> Result<bool> {
+        match (self.is_empty_split()?, other.is_empty_split()?) {
+            (true, true) => return Ok(true),
+            (false, false) => {}
+            _ => return Ok(false),
+        }
+        
+        let schema = self.index()?.schema();
+        let other_schema = other.index()?.schema();
+        if serde_json::to_value(&schema)? != serde_json::to_value(&other_schema)? {
+            return Ok(false);
+        }
+        
+        Ok(self.live_documents_json()? == other.live_documents_json()?)
+    }
+    
+    /// Computes a digest of the split's logical content, for deduplication
+    ///
+    /// The digest is the hex BLAKE3 hash of the schema and the stored content
+    /// of every live document, taken as a multiset, so it depends on exactly
+    /// what `content_equals` compares: splits generated from the same data
+    /// share a digest whatever their split id, provenance, doc order or file
+    /// bytes. Unlike a hash of the split files, it reads every live document.
+    pub fn content_digest(&self) -> Result<String> {
+        let mut hasher = blake3::Hasher::new();
+        hasher.update(b"quickwit-splits4java content digest v1\0");
+        
+        // Length prefixes keep the schema and documents from running into each other
+        let mut update_framed = |bytes: &[u8]| {
+            hasher.update(&(bytes.len() as u64).to_le_bytes());
+            hasher.update(bytes);
+        };
+        if !self.is_empty_split()? {
+            update_framed(serde_json::to_string(&self.index()?.schema())?.as_bytes());
+            for document in self.live_documents_json()? {
+                update_framed(document.as_bytes());
             }
         }
         
-        false
+        Ok(hasher.finalize().to_hex().to_string())
+    }
+    
+    /// Computes the checksum of the split's raw bytes
+    ///
+    /// The checksum is the hex BLAKE3 hash of the split file, hotcache footer
+    /// included, or for a split directory of the legacy layout, of the split
+    /// file it would bundle into. It matches `SplitMetadata::checksum`
+    /// recorded at generation, so a re-downloaded split can be checked
+    /// against it. Unlike `content_digest`, it covers bytes, not documents:
+    /// any rewrite of the split changes it. Reads the whole split; fails with
+    /// `InvalidOperation` for storages without a local path.
+    pub fn split_checksum(&self) -> Result<String> {
+        match self.storage.local_path() {
+            Some(path) if path.is_file() => split_file_checksum(path),
+            Some(path) => split_dir_checksum(path),
+            None => Err(SplitsError::InvalidOperation(format!(
+                "Split {} has no local path to compute a checksum over", self.split_path.display()
+            ))),
+        }
     }
     
-    /// Reads a posting list from a byte range (simplified implementation)
-    fn read_posting_list_from_range(&self, term_file: &Path, posting_range: &crate::hotcache::ByteRange, term: &str) -> Result<Vec<u32>> {
-        // This is a simplified implementation
-        // In reality, this would:
-        // 1. Use the term dictionary to locate the exact posting list for the term
-        // 2. Decode the compressed posting list
-        // 3. Return the document IDs
+    /// Collects the stored content of every live document as JSON, sorted
+    fn live_documents_json(&self) -> Result<Vec<String>> {
+        let schema = self.index()?.schema();
+        let searcher = self.searcher()?;
         
-        // For now, return empty list for non-existent terms or placeholder data
-        if term == "test" || term == "quickwit" {
-            Ok(vec![1, 5, 10, 15]) // Placeholder document IDs
-        } else {
-            Ok(vec![]) // No matches
+        let mut documents = Vec::new();
+        for segment_reader in searcher.segment_readers() {
+            let store_reader = segment_reader.get_store_reader(1)?;
+            for doc in store_reader.iter::<TantivyDocument>(segment_reader.alive_bitset()) {
+                documents.push(doc?.to_json(&schema));
+            }
         }
+        documents.sort_unstable();
+        
+        Ok(documents)
     }
     
-    /// Calculates byte range for a specific document range within fast field data
-    fn calculate_doc_range_bytes(&self, base_range: &crate::hotcache::ByteRange, doc_range: std::ops::Range<u32>) -> Result<crate::hotcache::ByteRange> {
-        // This is simplified - real implementation would depend on the fast field encoding
-        let doc_count = doc_range.end - doc_range.start;
-        let bytes_per_doc = 8; // Assume 8 bytes per document (e.g., for u64 values)
+    /// Returns which documents of the split are alive, as a packed bitset
+    ///
+    /// Bit `doc_id % 8` of byte `doc_id / 8` is set when document `doc_id` is
+    /// alive, least significant bit first, covering the `max_doc` documents of
+    /// every segment, with split-wide doc ids as `posting_cursor` numbers
+    /// them; the unused high bits of the last byte are zero. Without a `.del`
+    /// file every document is alive. A split generated from an empty index
+    /// yields an empty array.
+    pub fn alive_bitset(&self) -> Result<Vec<u8>> {
+        if self.is_empty_split()? {
+            return Ok(Vec::new());
+        }
         
-        let start_offset = (doc_range.start as u64) * bytes_per_doc;
-        let size = (doc_count as u64) * bytes_per_doc;
+        let searcher = self.searcher()?;
+        let mut bytes = vec![0u8; (split_max_doc(&searcher) as usize).div_ceil(8)];
+        for (segment_reader, doc_base) in segment_doc_bases(&searcher) {
+            let alive_bitset = segment_reader.alive_bitset();
+            for doc in 0..segment_reader.max_doc() {
+                if alive_bitset.is_none_or(|alive_bitset| alive_bitset.is_alive(doc)) {
+                    let doc = doc_base + doc;
+                    bytes[doc as usize / 8] |= 1 << (doc % 8);
+                }
+            }
+        }
         
-        Ok(crate::hotcache::ByteRange {
-            start: base_range.start + start_offset,
-            end: base_range.start + start_offset + size,
-        })
+        Ok(bytes)
     }
     
-    /// Reads data from a specific byte range in a file
-    fn read_byte_range(&self, file_path: &Path, range: &crate::hotcache::ByteRange) -> Result<Vec<u8>> {
-        let mut file = fs::File::open(file_path)?;
-        file.seek(SeekFrom::Start(range.start))?;
+    /// Returns the document frequency of each of `terms` in `field`
+    ///
+    /// Frequencies are aligned with `terms`, 0 for absent terms. Like
+    /// Tantivy's own statistics, they count deleted documents until the split
+    /// is merged. Distinct terms are looked up once each, in term order, so
+    /// the dictionary is walked forward a single time.
+    pub fn doc_freqs(&self, field: &str, terms: &[&str]) -> Result<Vec<u64>> {
+        let field_handle = self.tantivy_field(field)?;
+        let mut doc_freqs = vec![0u64; terms.len()];
+        if self.is_empty_split()? {
+            return Ok(doc_freqs);
+        }
+        
+        let mut by_term: BTreeMap<&str, Vec<usize>> = BTreeMap::new();
+        for (idx, term) in terms.iter().enumerate() {
+            by_term.entry(*term).or_default().push(idx);
+        }
         
-        let mut data = vec![0u8; range.size() as usize];
-        file.read_exact(&mut data)?;
+        let searcher = self.searcher()?;
+        for segment_reader in searcher.segment_readers() {
+            let inverted_index = segment_reader.inverted_index(field_handle)?;
+            let term_dictionary = inverted_index.terms();
+            for (term, indices) in &by_term {
+                let key = Term::from_field_text(field_handle, term);
+                if let Some(term_info) = term_dictionary.get(key.serialized_value_bytes())? {
+                    for idx in indices {
+                        doc_freqs[*idx] += term_info.doc_freq as u64;
+                    }
+                }
+            }
+        }
         
-        Ok(data)
+        Ok(doc_freqs)
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use std::fs;
-    use tempfile::TempDir;
     
-    #[test]
-    fn test_is_segment_file() {
-        let temp_dir = TempDir::new().unwrap();
-        let reader = QuickwitSplitReader {
-            split_path: temp_dir.path().to_path_buf(),
-            hotcache: None,
+    /// Returns the positions of `term` in `field` of document `doc_id`
+    ///
+    /// Positions are token offsets within the field, in increasing order,
+    /// decoded from the segment's `.pos` file. Doc ids are split-wide, as
+    /// `posting_cursor` numbers them. Returns an empty list when the document
+    /// doesn't contain the term or is past the end of the split. Fails with a
+    /// `FieldError` if the field isn't indexed with positions.
+    pub fn term_positions(&self, field: &str, term: &str, doc_id: u32) -> Result<Vec<u32>> {
+        let field_handle = self.tantivy_field(field)?;
+        if !self.index_record_option(field, field_handle)?.has_positions() {
+            return Err(SplitsError::FieldError(format!(
+                "Field '{}' is not indexed with positions", field
+            )));
+        }
+        
+        let searcher = self.searcher()?;
+        let doc_address = match split_doc_address(&searcher, doc_id) {
+            Some(doc_address) => doc_address,
+            None => return Ok(Vec::new()),
         };
+        let segment_reader = searcher.segment_reader(doc_address.segment_ord);
+        let doc_id = doc_address.doc_id;
         
-        assert!(reader.is_segment_file("12345678-1234-1234-1234-123456789abc.store"));
-        assert!(reader.is_segment_file("87654321-4321-4321-4321-cba987654321.term"));
-        assert!(!reader.is_segment_file("not-a-uuid.store"));
-        assert!(!reader.is_segment_file("12345678-1234-1234-1234-123456789abc.unknown"));
+        let inverted_index = segment_reader.inverted_index(field_handle)?;
+        let mut postings = match inverted_index.read_postings(
+            &Term::from_field_text(field_handle, term),
+            IndexRecordOption::WithFreqsAndPositions,
+        )? {
+            Some(postings) => postings,
+            None => return Ok(Vec::new()),
+        };
+        
+        // Postings only seek forward, from the term's first document
+        let mut positions = Vec::new();
+        if postings.doc() <= doc_id && postings.seek(doc_id) == doc_id {
+            postings.positions(&mut positions);
+        }
+        Ok(positions)
     }
     
-    #[test]
-    fn test_calculate_doc_range_bytes() {
-        let temp_dir = TempDir::new().unwrap();
-        let reader = QuickwitSplitReader {
-            split_path: temp_dir.path().to_path_buf(),
-            hotcache: None,
+    /// Estimates how many documents match `query` without executing it
+    ///
+    /// The query uses Tantivy's query syntax, with all indexed text fields as
+    /// default fields. The estimate combines term document frequencies along
+    /// the boolean structure, assuming clauses are independent: `Must` clauses
+    /// take the smallest estimate, `Should` clauses add up, and `MustNot`
+    /// clauses scale the result down by the fraction of documents they exclude.
+    /// Phrases are bounded by their rarest term. Query types it can't reason
+    /// about count as matching every document, so complex queries may be
+    /// over- or under-estimated.
+    pub fn estimate_hits(&self, query: &str) -> Result<u64> {
+        let query = self.parse_query(query)?;
+        let searcher = self.searcher()?;
+        Self::estimate_query_hits(&searcher, query.as_ref())
+    }
+    
+    /// Returns the `top_k` most frequent facets directly under `prefix` in `field`
+    ///
+    /// Facets are paths like `/category/books`, and `prefix` is one too: an
+    /// empty prefix or `/` counts the top-level facets. Counts are numbers of
+    /// live documents holding the facet or one of its descendants, sorted
+    /// highest first. Fails with a `FieldError` if the field isn't a facet
+    /// field, and with `Query` if the prefix isn't a valid facet path.
+    pub fn facet_counts(&self, field: &str, prefix: &str, top_k: usize) -> Result<Vec<(String, u64)>> {
+        let field_handle = self.tantivy_field(field)?;
+        if !matches!(self.index()?.schema().get_field_entry(field_handle).field_type(), FieldType::Facet(_)) {
+            return Err(SplitsError::FieldError(format!("Field '{}' is not a facet field", field)));
+        }
+        let prefix = if prefix.is_empty() {
+            Facet::root()
+        } else {
+            Facet::from_text(prefix)
+                .map_err(|e| SplitsError::Query(format!("Invalid facet prefix: {}", e)))?
         };
+        if self.is_empty_split()? {
+            return Ok(Vec::new());
+        }
         
-        let base_range = crate::hotcache::ByteRange {
-            start: 1000,
-            end: 2000,
+        let mut collector = FacetCollector::for_field(field);
+        collector.add_facet(prefix.clone());
+        let facet_counts = self.searcher()?.search(&AllQuery, &collector)?;
+        
+        Ok(facet_counts.top_k(prefix, top_k)
+            .into_iter()
+            .map(|(facet, count)| (facet.to_path_string(), count))
+            .collect())
+    }
+    
+    /// Returns a highlighted excerpt of `field` for each of `doc_ids`, for `query`
+    ///
+    /// Excerpts are built by Tantivy's `SnippetGenerator` from the stored
+    /// field value: the fragment of at most `max_chars` characters with the
+    /// most matching terms, matches wrapped in `<b>` tags and the text HTML
+    /// escaped. Documents without a value for the field, or whose value
+    /// matches none of the query terms, get an empty string. Fails with a
+    /// `FieldError` if the field isn't a stored text field, and with
+    /// `InvalidOperation` for doc ids past the end of the split.
+    pub fn snippets(&self, query: &str, field: &str, doc_ids: &[u32], max_chars: usize) -> Result<Vec<String>> {
+        let max_doc = if self.is_empty_split()? {
+            0
+        } else {
+            split_max_doc(&self.searcher()?)
         };
+        if let Some(doc_id) = doc_ids.iter().find(|doc_id| **doc_id >= max_doc) {
+            return Err(SplitsError::InvalidOperation(format!(
+                "Document ID {} out of range, split has {} documents", doc_id, max_doc
+            )));
+        }
+        if doc_ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        let field_handle = self.tantivy_field(field)?;
+        let field_entry = self.index()?.schema().get_field_entry(field_handle).clone();
+        if !matches!(field_entry.field_type(), FieldType::Str(_)) || !field_entry.is_stored() {
+            return Err(SplitsError::FieldError(format!("Field '{}' is not a stored text field", field)));
+        }
+        
+        let query = self.parse_query(query)?;
+        let searcher = self.searcher()?;
+        let mut snippet_generator = SnippetGenerator::create(&searcher, query.as_ref(), field_handle)?;
+        snippet_generator.set_max_num_chars(max_chars);
+        
+        doc_ids.iter()
+            .map(|doc_id| {
+                let doc_address = split_doc_address(&searcher, *doc_id)
+                    .ok_or_else(|| SplitsError::InvalidOperation(format!("Document ID {} out of range", doc_id)))?;
+                let doc: TantivyDocument = searcher.doc(doc_address)?;
+                Ok(snippet_generator.snippet_from_doc(&doc).to_html())
+            })
+            .collect()
+    }
+    
+    /// Counts the distinct values a fast field takes over the live documents
+    ///
+    /// With `exact`, every distinct value is held in a hash set while the
+    /// column is scanned, so memory grows with the cardinality. Otherwise the
+    /// count is a HyperLogLog estimate in a fixed 16 KiB, within about 1% of
+    /// the exact count, which suits fields with millions of distinct values.
+    /// Either way each value of the column is read once. Every value of a
+    /// multi-valued field counts. Fails with a `FieldError` if the field
+    /// isn't fast.
+    pub fn field_cardinality(&self, field: &str, exact: bool) -> Result<u64> {
+        let field_handle = self.tantivy_field(field)?;
+        let schema = self.index()?.schema();
+        let field_type = schema.get_field_entry(field_handle).field_type();
+        if !schema.get_field_entry(field_handle).is_fast() || !Self::has_fast_column(field_type) {
+            return Err(SplitsError::FieldError(format!("Field '{}' is not a fast field", field)));
+        }
+        
+        let mut counter = if exact { DistinctCounter::Exact(HashSet::new()) } else { DistinctCounter::Estimate(HyperLogLog::new()) };
+        for segment_reader in self.searcher()?.segment_readers() {
+            let fast_fields = segment_reader.fast_fields();
+            match field_type {
+                FieldType::U64(_) => Self::count_numeric_column(segment_reader, fast_fields.u64(field)?, |value| value, &mut counter),
+                FieldType::I64(_) => Self::count_numeric_column(segment_reader, fast_fields.i64(field)?, |value| value as u64, &mut counter),
+                FieldType::F64(_) => Self::count_numeric_column(segment_reader, fast_fields.f64(field)?, f64::to_bits, &mut counter),
+                FieldType::Bool(_) => Self::count_numeric_column(segment_reader, fast_fields.bool(field)?, u64::from, &mut counter),
+                FieldType::Date(_) => Self::count_numeric_column(
+                    segment_reader,
+                    fast_fields.date(field)?,
+                    |value| value.into_timestamp_nanos() as u64,
+                    &mut counter,
+                ),
+                FieldType::Str(_) => {
+                    let str_column = match fast_fields.str(field)? {
+                        Some(str_column) => str_column,
+                        None => continue,
+                    };
+                    // Ords are per segment: resolve each distinct one to its value once
+                    let ords: BTreeSet<u64> = segment_reader.doc_ids_alive()
+                        .flat_map(|doc| str_column.term_ords(doc))
+                        .collect();
+                    let mut buffer = String::new();
+                    for ord in ords {
+                        buffer.clear();
+                        str_column.ord_to_str(ord, &mut buffer)?;
+                        counter.insert(buffer.as_bytes());
+                    }
+                }
+                _ => unreachable!("has_fast_column accepted the field type"),
+            }
+        }
+        
+        Ok(counter.count())
+    }
+    
+    /// Feeds the values of a numeric fast field column of every live document to `counter`
+    fn count_numeric_column<T>(
+        segment_reader: &SegmentReader,
+        column: Column<T>,
+        to_bits: impl Fn(T) -> u64,
+        counter: &mut DistinctCounter,
+    )
+    where
+        T: PartialOrd + Copy + std::fmt::Debug + Send + Sync + 'static,
+    {
+        for doc in segment_reader.doc_ids_alive() {
+            for value in column.values_for_doc(doc) {
+                counter.insert(&to_bits(value).to_le_bytes());
+            }
+        }
+    }
+    
+    /// Returns the smallest and largest values of a numeric fast field
+    ///
+    /// The bounds come from the min and max each column records when written,
+    /// so no document is read. They include the values of deleted documents,
+    /// which only makes them looser, never wrong, for pruning. Both bounds are
+    /// returned as `i64` bits: u64 values reinterpreted, compared as u64
+    /// first; f64 values as `f64::to_bits`; dates as nanoseconds since the
+    /// epoch, at the field's precision. Fails with a `FieldError` if the field
+    /// isn't a u64, i64, f64 or date fast field, and `InvalidOperation` if no
+    /// document has a value for it.
+    pub fn numeric_bounds(&self, field: &str) -> Result<(i64, i64)> {
+        let field_handle = self.tantivy_field(field)?;
+        let schema = self.index()?.schema();
+        let field_entry = schema.get_field_entry(field_handle);
+        if !field_entry.is_fast() {
+            return Err(SplitsError::FieldError(format!("Field '{}' is not a fast field", field)));
+        }
+        
+        let searcher = self.searcher()?;
+        let bounds = match field_entry.field_type() {
+            FieldType::U64(_) => Self::column_bounds(&searcher, |fast_fields| fast_fields.column_opt::<u64>(field))?
+                .map(|(min, max)| (min as i64, max as i64)),
+            FieldType::I64(_) => Self::column_bounds(&searcher, |fast_fields| fast_fields.column_opt::<i64>(field))?,
+            FieldType::F64(_) => Self::column_bounds(&searcher, |fast_fields| fast_fields.column_opt::<f64>(field))?
+                .map(|(min, max)| (min.to_bits() as i64, max.to_bits() as i64)),
+            FieldType::Date(_) => Self::column_bounds(&searcher, |fast_fields| fast_fields.column_opt::<tantivy::DateTime>(field))?
+                .map(|(min, max)| (min.into_timestamp_nanos(), max.into_timestamp_nanos())),
+            other => return Err(SplitsError::FieldError(format!(
+                "Field '{}' is not numeric, got {:?}", field, other.value_type()
+            ))),
+        };
+        
+        bounds.ok_or_else(|| SplitsError::InvalidOperation(format!("No document has a value for field '{}'", field)))
+    }
+    
+    /// Combines the recorded min and max of a fast field column over every segment
+    fn column_bounds<T>(
+        searcher: &Searcher,
+        open_column: impl Fn(&FastFieldReaders) -> tantivy::Result<Option<Column<T>>>,
+    ) -> Result<Option<(T, T)>>
+    where
+        T: PartialOrd + Copy + std::fmt::Debug + Send + Sync + 'static,
+    {
+        let mut bounds: Option<(T, T)> = None;
+        for segment_reader in searcher.segment_readers() {
+            // Segments where no document has a value have no column
+            let column = match open_column(segment_reader.fast_fields())? {
+                Some(column) if column.values.num_vals() > 0 => column,
+                _ => continue,
+            };
+            let (min, max) = (column.min_value(), column.max_value());
+            bounds = Some(match bounds {
+                Some((start, end)) => (if min < start { min } else { start }, if max > end { max } else { end }),
+                None => (min, max),
+            });
+        }
+        Ok(bounds)
+    }
+    
+    /// Returns the values of a single field for every live document
+    ///
+    /// Fast fields are read straight from their column; other fields are read
+    /// from the doc store, decoding only the requested field into the result.
+    /// Each entry is `null` when the document has no value, the value itself
+    /// when it has one, or an array for multi-valued fields. A dotted path
+    /// into a JSON field, e.g. `attributes.color`, reads the values at that
+    /// path, of whatever types documents hold there, arrays being flattened.
+    /// Fails with a `FieldError` if the field is neither stored nor fast.
+    pub fn column(&self, field: &str) -> Result<Vec<serde_json::Value>> {
+        let schema = self.index()?.schema();
+        if let Some((json_field, json_path)) = schema.find_field(field) {
+            if !json_path.is_empty() && matches!(schema.get_field_entry(json_field).field_type(), FieldType::JsonObject(_)) {
+                return self.json_path_column(field, json_field, json_path);
+            }
+        }
+        let field_handle = self.tantivy_field(field)?;
+        let field_entry = schema.get_field_entry(field_handle);
+        
+        let searcher = self.searcher()?;
+        let mut values = Vec::new();
+        
+        if field_entry.is_fast() && Self::has_fast_column(field_entry.field_type()) {
+            for segment_reader in searcher.segment_readers() {
+                Self::read_fast_column(segment_reader, field, field_entry.field_type(), &mut values)?;
+            }
+        } else if field_entry.is_stored() {
+            for segment_reader in searcher.segment_readers() {
+                let store_reader = segment_reader.get_store_reader(1)?;
+                for doc in store_reader.iter::<TantivyDocument>(segment_reader.alive_bitset()) {
+                    let field_values: Vec<OwnedValue> = doc?.get_all(field_handle)
+                        .map(OwnedValue::from)
+                        .collect();
+                    values.push(Self::values_to_json(field_values)?);
+                }
+            }
+        } else {
+            return Err(SplitsError::FieldError(format!(
+                "Field '{}' is neither stored nor fast", field
+            )));
+        }
+        
+        Ok(values)
+    }
+    
+    /// Reads the values at a path into a JSON field for every live document, see `column`
+    fn json_path_column(&self, full_path: &str, json_field: Field, json_path: &str) -> Result<Vec<serde_json::Value>> {
+        let schema = self.index()?.schema();
+        let field_entry = schema.get_field_entry(json_field);
+        let searcher = self.searcher()?;
+        let mut values = Vec::new();
+        
+        if field_entry.is_fast() {
+            for segment_reader in searcher.segment_readers() {
+                Self::read_json_fast_columns(segment_reader, full_path, &mut values)?;
+            }
+        } else if field_entry.is_stored() {
+            let path: Vec<&str> = json_path.split('.').collect();
+            for segment_reader in searcher.segment_readers() {
+                let store_reader = segment_reader.get_store_reader(1)?;
+                for doc in store_reader.iter::<TantivyDocument>(segment_reader.alive_bitset()) {
+                    let mut path_values = Vec::new();
+                    for value in doc?.get_all(json_field) {
+                        collect_json_path_values(&OwnedValue::from(value), &path, &mut path_values);
+                    }
+                    values.push(Self::values_to_json(path_values)?);
+                }
+            }
+        } else {
+            return Err(SplitsError::FieldError(format!(
+                "Field '{}' is neither stored nor fast", schema.get_field_name(json_field)
+            )));
+        }
+        
+        Ok(values)
+    }
+    
+    /// Appends the values of every column at a JSON path for every live document in a segment
+    ///
+    /// A path holding values of several types has one column per type; a
+    /// document's values are gathered from all of them. Bytes and IP address
+    /// columns, which JSON values never produce, are skipped.
+    fn read_json_fast_columns(segment_reader: &SegmentReader, full_path: &str, values: &mut Vec<serde_json::Value>) -> Result<()> {
+        let columns = segment_reader.fast_fields().dynamic_column_handles(full_path)?
+            .iter()
+            .map(|column_handle| column_handle.open())
+            .collect::<std::io::Result<Vec<DynamicColumn>>>()?;
+        
+        let mut buffer = String::new();
+        for doc in segment_reader.doc_ids_alive() {
+            let mut field_values = Vec::new();
+            for column in &columns {
+                match column {
+                    DynamicColumn::U64(column) => field_values.extend(column.values_for_doc(doc).map(OwnedValue::U64)),
+                    DynamicColumn::I64(column) => field_values.extend(column.values_for_doc(doc).map(OwnedValue::I64)),
+                    DynamicColumn::F64(column) => field_values.extend(column.values_for_doc(doc).map(OwnedValue::F64)),
+                    DynamicColumn::Bool(column) => field_values.extend(column.values_for_doc(doc).map(OwnedValue::Bool)),
+                    DynamicColumn::DateTime(column) => field_values.extend(column.values_for_doc(doc).map(OwnedValue::Date)),
+                    DynamicColumn::Str(column) => {
+                        for ord in column.term_ords(doc) {
+                            buffer.clear();
+                            column.ord_to_str(ord, &mut buffer)?;
+                            field_values.push(OwnedValue::Str(buffer.clone()));
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            values.push(Self::values_to_json(field_values)?);
+        }
+        Ok(())
+    }
+    
+    /// Returns the stored fields of a live document as a JSON object
+    ///
+    /// Doc ids are split-wide, as `posting_cursor` numbers them. Each stored
+    /// field the document has a value for maps to that value, or to an array
+    /// for multi-valued fields, as in `column`. JSON fields keep their nested
+    /// objects and arrays as indexed. Fails with `InvalidOperation` for doc
+    /// ids past the end of the split or of deleted documents.
+    pub fn get_document(&self, doc_id: u32) -> Result<serde_json::Value> {
+        let searcher = self.searcher()?;
+        let doc_address = split_doc_address(&searcher, doc_id)
+            .ok_or_else(|| SplitsError::InvalidOperation(format!("Document ID {} out of range", doc_id)))?;
+        let segment_reader = searcher.segment_reader(doc_address.segment_ord);
+        if segment_reader.is_deleted(doc_address.doc_id) {
+            return Err(SplitsError::InvalidOperation(format!("Document {} is deleted", doc_id)));
+        }
+        
+        let doc: TantivyDocument = segment_reader.get_store_reader(1)?.get(doc_address.doc_id)?;
+        self.document_to_json(&doc)
+    }
+    
+    /// Returns the stored fields of up to `n` live documents picked at random, as by `get_document`
+    ///
+    /// Documents are picked uniformly by reservoir sampling over the live
+    /// doc ids, so only the picked documents are read from the doc store, and
+    /// returned in doc id order. The same `seed` picks the same documents of
+    /// the same split. All live documents are returned when there are at most
+    /// `n` of them.
+    pub fn sample(&self, n: usize, seed: u64) -> Result<Vec<serde_json::Value>> {
+        if n == 0 || self.is_empty_split()? {
+            return Ok(Vec::new());
+        }
+        
+        let searcher = self.searcher()?;
+        let mut rng = SplitMix64(seed);
+        let mut reservoir = Vec::new();
+        let mut seen = 0u64;
+        for (segment_ord, segment_reader) in searcher.segment_readers().iter().enumerate() {
+            for doc_id in segment_reader.doc_ids_alive() {
+                let doc_address = DocAddress::new(segment_ord as u32, doc_id);
+                if reservoir.len() < n {
+                    reservoir.push(doc_address);
+                } else {
+                    let slot = rng.next_below(seen + 1);
+                    if slot < n as u64 {
+                        reservoir[slot as usize] = doc_address;
+                    }
+                }
+                seen += 1;
+            }
+        }
+        reservoir.sort_unstable();
+        
+        reservoir.into_iter()
+            .map(|doc_address| self.document_to_json(&searcher.doc::<TantivyDocument>(doc_address)?))
+            .collect()
+    }
+    
+    /// Converts the stored fields of a document to a JSON object, see `get_document`
+    fn document_to_json(&self, doc: &TantivyDocument) -> Result<serde_json::Value> {
+        let schema = self.index()?.schema();
+        let mut document = serde_json::Map::new();
+        for (field, field_entry) in schema.fields().filter(|(_, field_entry)| field_entry.is_stored()) {
+            let field_values: Vec<OwnedValue> = doc.get_all(field).map(OwnedValue::from).collect();
+            if !field_values.is_empty() {
+                document.insert(field_entry.name().to_string(), Self::values_to_json(field_values)?);
+            }
+        }
+        Ok(serde_json::Value::Object(document))
+    }
+    
+    /// Exports fields as an Arrow IPC stream of one record batch, one row per live document
+    ///
+    /// Each field becomes a column named after it, read as by `column`. See
+    /// `arrow_export` for the type mapping and how multi-valued fields map to
+    /// list columns. All fields are checked before any is read, failing with
+    /// a `FieldError` naming the first one that is unknown or has no Arrow type.
+    #[cfg(feature = "arrow")]
+    pub fn to_arrow(&self, fields: &[&str]) -> Result<Vec<u8>> {
+        let schema = self.index()?.schema();
+        let mut kinds = Vec::with_capacity(fields.len());
+        for field in fields {
+            let kind = FieldKind::of(schema.get_field_entry(self.tantivy_field(field)?).field_type());
+            crate::arrow_export::data_type(field, kind)?;
+            kinds.push(kind);
+        }
+        
+        let columns = fields.iter().zip(kinds)
+            .map(|(field, kind)| Ok((field.to_string(), kind, self.column(field)?)))
+            .collect::<Result<Vec<_>>>()?;
+        crate::arrow_export::to_ipc_stream(columns)
+    }
+    
+    /// Returns the byte ranges of the compressed blocks in the store files
+    ///
+    /// Ranges are in document order, each an offset into the store file of
+    /// the segment holding the block, named by `store_block_file`; a split of
+    /// a single segment has a single store file. They can be served as-is to
+    /// clients doing their own decompression. Each
+    /// block is compressed independently with the store's compressor (LZ4 by
+    /// default, recorded in the store footer). Once decompressed, a block holds
+    /// its serialized documents back to back, followed by the start offset of
+    /// each document and the document count, all as little-endian u32.
+    pub fn store_block_ranges(&self) -> Result<Vec<ByteRange>> {
+        Ok(self.store_checkpoints()?.into_iter().map(|block| block.byte_range).collect())
+    }
+    
+    /// Returns the name of the store file holding the store block at `idx`
+    ///
+    /// Indexes are positions in `store_block_ranges`.
+    pub fn store_block_file(&self, idx: usize) -> Result<String> {
+        Ok(self.store_block(idx)?.store_file)
+    }
+    
+    /// Returns the index of the store block holding document `doc_id`
+    ///
+    /// Indexes are positions in `store_block_ranges`. Blocks hold consecutive
+    /// documents, so sorting doc ids groups them by block, and reading each
+    /// block once serves all of its documents. Fails with `InvalidOperation`
+    /// for doc ids past the end of the split.
+    pub fn store_block_of(&self, doc_id: u32) -> Result<usize> {
+        self.store_checkpoints()?
+            .iter()
+            .position(|block| block.docs.contains(&doc_id))
+            .ok_or_else(|| SplitsError::InvalidOperation(format!("Document ID {} out of range", doc_id)))
+    }
+    
+    /// Reads the raw, still compressed, store block at `idx`
+    ///
+    /// See `store_block_ranges` for the block framing.
+    pub fn read_store_block(&self, idx: usize) -> Result<Vec<u8>> {
+        let block = self.store_block(idx)?;
+        self.read_byte_range(&block.store_file, &block.byte_range)
+    }
+    
+    /// Gets the store block at `idx`, see `store_block_ranges`
+    fn store_block(&self, idx: usize) -> Result<StoreBlock> {
+        let mut blocks = self.store_checkpoints()?;
+        if idx >= blocks.len() {
+            return Err(SplitsError::InvalidOperation(format!(
+                "Store block {} out of range, split has {} blocks", idx, blocks.len()
+            )));
+        }
+        Ok(blocks.swap_remove(idx))
+    }
+    
+    /// Returns the byte range of the store data in the split's store file
+    ///
+    /// The footer, any alignment padding followed by the hotcache and its
+    /// 8-byte size, is always appended after the store data, so the range
+    /// starts at 0 and ends where the footer begins. Splits generated from an
+    /// empty index have an empty range.
+    pub fn store_file_range(&self) -> Result<ByteRange> {
+        let store_file = self.find_store_file()?;
+        Ok(ByteRange { start: 0, end: self.store_data_len(&store_file)? })
+    }
+    
+    /// Reads the store data of the split, without the footer
+    ///
+    /// This is the store file as Tantivy wrote it, for external store
+    /// decoders. See `store_file_range`.
+    pub fn read_store_data(&self) -> Result<Vec<u8>> {
+        let store_file = self.find_store_file()?;
+        let range = ByteRange { start: 0, end: self.store_data_len(&store_file)? };
+        self.read_byte_range(&store_file, &range)
+    }
+    
+    /// Reads `length` bytes at `start` of one of the split's files, for clients doing their own decoding
+    ///
+    /// `file_name` must name a file of the split exactly, as listed by the
+    /// split's storage: paths, including any `..` component, are rejected.
+    /// Bytes are returned as stored, so the store file includes the hotcache
+    /// footer and files of encrypted splits are ciphertext. Fails with
+    /// `InvalidOperation` for unknown files and ranges past the end of the
+    /// file.
+    pub fn read_file_range(&self, file_name: &str, start: u64, length: u64) -> Result<Vec<u8>> {
+        if file_name.is_empty() || file_name.contains(['/', '\\']) || file_name == ".." {
+            return Err(SplitsError::InvalidOperation(format!("Invalid split file name: {}", file_name)));
+        }
+        if !self.storage.list_files()?.iter().any(|name| name == file_name) {
+            return Err(SplitsError::InvalidOperation(format!(
+                "File {} not found in split {}", file_name, self.split_path.display()
+            )));
+        }
+        
+        let file_len = self.storage.file_len(file_name)?.unwrap_or(0);
+        let range = start.checked_add(length)
+            .filter(|end| *end <= file_len)
+            .map(|end| ByteRange { start, end })
+            .ok_or_else(|| SplitsError::InvalidOperation(format!(
+                "Range of {} bytes at {} is out of bounds of {}, which has {} bytes", length, start, file_name, file_len
+            )))?;
+        self.read_byte_range(file_name, &range)
+    }
+    
+    /// Describes every field of the split's schema, in schema order
+    ///
+    /// Unlike the raw schema JSON, this gives planners a typed view that is
+    /// stable across Tantivy versions. The sub-paths of indexed JSON fields
+    /// are read from their term dictionaries, so describing those costs a
+    /// scan of their terms.
+    pub fn fields(&self) -> Result<Vec<FieldDescriptor>> {
+        let schema = self.index()?.schema();
+        
+        schema.fields()
+            .map(|(field, entry)| Ok(FieldDescriptor {
+                name: entry.name().to_string(),
+                field_type: FieldKind::of(entry.field_type()),
+                stored: entry.is_stored(),
+                indexed: entry.is_indexed(),
+                fast: entry.is_fast(),
+                json_paths: match entry.field_type() {
+                    FieldType::JsonObject(_) if entry.is_indexed() => self.json_paths(field)?,
+                    _ => Vec::new(),
+                },
+            }))
+            .collect()
+    }
+    
+    /// Lists the distinct dotted paths holding values in an indexed JSON field, sorted
+    fn json_paths(&self, field: Field) -> Result<Vec<String>> {
+        if self.is_empty_split()? {
+            return Ok(Vec::new());
+        }
+        
+        let searcher = self.searcher()?;
+        let mut paths = BTreeSet::new();
+        for segment_reader in searcher.segment_readers() {
+            let inverted_index = segment_reader.inverted_index(field)?;
+            let mut stream = inverted_index.terms().stream()?;
+            while stream.advance() {
+                if let Some((path, _)) = split_json_term(stream.key()) {
+                    paths.insert(path);
+                }
+            }
+        }
+        Ok(paths.into_iter().collect())
+    }
+    
+    /// Computes corpus statistics of a field, e.g. for BM25/IDF across splits
+    ///
+    /// `docs_with_field` is obtained by walking every posting list of the
+    /// field, so its cost grows with the size of the field's postings.
+    /// `unique_terms` is summed over segments, which is exact for the single
+    /// segment of a generated split. Fails with a `FieldError` if the field
+    /// isn't indexed with term frequencies, since total tokens can't be known.
+    pub fn corpus_stats(&self, field: &str) -> Result<CorpusStats> {
+        let field_handle = self.tantivy_field(field)?;
+        if !self.index_record_option(field, field_handle)?.has_freq() {
+            return Err(SplitsError::FieldError(format!(
+                "Field '{}' is not indexed with term frequencies, total tokens are unavailable", field
+            )));
+        }
+        
+        let searcher = self.searcher()?;
+        let mut stats = CorpusStats {
+            total_docs: searcher.num_docs(),
+            ..CorpusStats::default()
+        };
+        
+        for segment_reader in searcher.segment_readers() {
+            let inverted_index = segment_reader.inverted_index(field_handle)?;
+            stats.total_tokens += inverted_index.total_num_tokens();
+            stats.unique_terms += inverted_index.terms().num_terms() as u64;
+            
+            let mut has_field = vec![false; segment_reader.max_doc() as usize];
+            let mut terms = inverted_index.terms().stream()?;
+            while terms.advance() {
+                let mut postings = inverted_index.read_postings_from_terminfo(terms.value(), IndexRecordOption::Basic)?;
+                let mut doc = postings.doc();
+                while doc != TERMINATED {
+                    has_field[doc as usize] = true;
+                    doc = postings.advance();
+                }
+            }
+            
+            stats.docs_with_field += segment_reader.doc_ids_alive()
+                .filter(|doc| has_field[*doc as usize])
+                .count() as u64;
+        }
+        
+        Ok(stats)
+    }
+    
+    /// Gathers inverted index statistics of every indexed field, e.g. for offline query tuning
+    ///
+    /// Counts come from the term dictionaries alone, without reading
+    /// posting lists, so document frequencies include deleted documents.
+    /// Section sizes are the bytes each field takes in the `.term`, `.idx`
+    /// and `.pos` files. Splits of empty indexes have no schema and yield
+    /// empty statistics.
+    pub fn index_stats(&self) -> Result<IndexStats> {
+        if self.is_empty_split()? {
+            return Ok(IndexStats::default());
+        }
+        
+        let searcher = self.searcher()?;
+        let schema = searcher.schema();
+        let mut stats = IndexStats {
+            num_docs: searcher.num_docs(),
+            fields: BTreeMap::new(),
+        };
+        
+        for segment_reader in searcher.segment_readers() {
+            let space_usage = segment_reader.space_usage()?;
+            let section_sizes = |usage: &tantivy::space_usage::PerFieldSpaceUsage| -> HashMap<Field, u64> {
+                usage.fields()
+                    .map(|(field, field_usage)| (*field, field_usage.total().get_bytes()))
+                    .collect()
+            };
+            let term_dict_sizes = section_sizes(space_usage.termdict());
+            let postings_sizes = section_sizes(space_usage.postings());
+            let positions_sizes = section_sizes(space_usage.positions());
+            
+            for (field, entry) in schema.fields().filter(|(_, entry)| entry.is_indexed()) {
+                let field_stats = stats.fields.entry(entry.name().to_string()).or_default();
+                let inverted_index = segment_reader.inverted_index(field)?;
+                let mut terms = inverted_index.terms().stream()?;
+                while terms.advance() {
+                    field_stats.num_terms += 1;
+                    field_stats.total_postings += terms.value().doc_freq as u64;
+                }
+                field_stats.term_dict_bytes += term_dict_sizes.get(&field).copied().unwrap_or(0);
+                field_stats.postings_bytes += postings_sizes.get(&field).copied().unwrap_or(0);
+                field_stats.positions_bytes += positions_sizes.get(&field).copied().unwrap_or(0);
+            }
+        }
+        
+        for field_stats in stats.fields.values_mut() {
+            if field_stats.num_terms > 0 {
+                field_stats.avg_doc_freq = field_stats.total_postings as f64 / field_stats.num_terms as f64;
+            }
+        }
+        Ok(stats)
+    }
+    
+    /// Sets how byte ranges, such as raw store blocks, are read from the split files
+    ///
+    /// Large sequential reads benefit from a bigger buffer; direct IO keeps
+    /// them out of the page cache where the platform and filesystem allow it,
+    /// and is silently skipped elsewhere. Options survive `reload`.
+    pub fn set_read_options(&mut self, options: ReadOptions) -> Result<()> {
+        options.validate()?;
+        self.read_options = options;
+        Ok(())
+    }
+    
+    /// Returns how byte ranges are read from the split files
+    pub fn read_options(&self) -> ReadOptions {
+        self.read_options
+    }
+    
+    /// Registers a tokenizer used when parsing queries against this split
+    ///
+    /// Custom tokenizers recorded at generation are registered automatically;
+    /// this is needed for splits generated without them, or to override one.
+    /// Registrations survive `reload`.
+    pub fn register_tokenizer(&self, name: &str, tokenizer: TextAnalyzer) -> Result<()> {
+        self.registered_tokenizers.lock()
+            .map_err(|e| SplitsError::InvalidOperation(format!("Failed to access tokenizers: {}", e)))?
+            .insert(name.to_string(), tokenizer.clone());
+        
+        // An index opened from now on picks the tokenizer up from the map;
+        // the lock is released first since opening the index takes it
+        if let Some(index) = self.index.peek() {
+            index.tokenizers().register(name, tokenizer);
+        }
+        
+        Ok(())
+    }
+    
+    /// Gets fast field data for a document range
+    ///
+    /// Values are decoded from the field's fast column, typed as described by
+    /// `FastFieldEncoding`, and serialized in the given `format`. Fails with a
+    /// `FieldError` if the field isn't fast or its type has no fixed-width
+    /// encoding, or if `doc_range` extends past the split's documents. A split
+    /// generated from an empty index has no documents: only empty ranges
+    /// starting at 0 are valid, and yield no data for any field.
+    ///
+    /// Date values are the ones stored in the column, already truncated to
+    /// the field's precision: seconds, Tantivy's default, unless the schema
+    /// sets a finer `DateTimePrecision`.
+    pub fn get_fast_field_data(
+        &self,
+        field: &str,
+        doc_range: std::ops::Range<u32>,
+        format: FastFieldFormat,
+    ) -> Result<Vec<u8>> {
+        if self.is_empty_split()? {
+            Self::check_doc_range(&doc_range, 0)?;
+            return Ok(Vec::new());
+        }
+        
+        let encoding = self.fast_field_encoding(field)?;
+        let searcher = self.searcher()?;
+        Self::encode_fast_field(&searcher, field, encoding, doc_range, format)
+    }
+    
+    /// Gets fast field data for several fields over the same document range
+    ///
+    /// All fields are validated before any data is read, so an unknown field
+    /// fails the whole call with a single error naming it. Each field is
+    /// encoded as by `get_fast_field_data` in the `RawLe` format.
+    pub fn get_fast_fields(&self, fields: &[&str], doc_range: std::ops::Range<u32>) -> Result<HashMap<String, Vec<u8>>> {
+        if self.is_empty_split()? {
+            Self::check_doc_range(&doc_range, 0)?;
+            return Ok(fields.iter().map(|field| (field.to_string(), Vec::new())).collect());
+        }
+        
+        let encodings = fields.iter()
+            .map(|field| Ok((*field, self.fast_field_encoding(field)?)))
+            .collect::<Result<Vec<_>>>()?;
+        
+        let searcher = self.searcher()?;
+        let mut result = HashMap::with_capacity(encodings.len());
+        for (field, encoding) in encodings {
+            let data = Self::encode_fast_field(&searcher, field, encoding, doc_range.clone(), FastFieldFormat::RawLe)?;
+            result.insert(field.to_string(), data);
+        }
+        
+        Ok(result)
+    }
+    
+    /// Gets the address and length of a fast field's column in the mapped split files
+    ///
+    /// The bytes are the column as Tantivy serializes it, codec-encoded and
+    /// followed by its index, not an array of values: this is for consumers
+    /// decoding Tantivy columns themselves, e.g. through the Java Foreign
+    /// Function & Memory API, without copying them out. A split generated
+    /// from an empty index has no column, and yields a zero length.
+    ///
+    /// The column is kept mapped until the reader is dropped, `reload`
+    /// included, so the address is valid exactly as long as the reader: it
+    /// must not be dereferenced once the reader is gone, and the bytes must
+    /// never be written to. Only splits whose files are memory-mapped, i.e.
+    /// split directories on local storage, have stable addresses; other
+    /// splits fail with `InvalidOperation`, as do splits of several segments,
+    /// each holding its own column. Fails with a `FieldError` if the field
+    /// isn't fast, or is stored as several columns, as JSON fields are.
+    pub fn fast_field_column_ptr(&self, field: &str) -> Result<(usize, usize)> {
+        if !self.storage.is_memory_mapped() {
+            return Err(SplitsError::InvalidOperation(format!(
+                "Split {} isn't memory-mapped: zero-copy column access requires a split directory on local storage",
+                self.split_path.display()
+            )));
+        }
+        
+        let mut pinned_columns = self.pinned_columns.lock()
+            .map_err(|e| SplitsError::InvalidOperation(format!("Failed to access pinned columns: {}", e)))?;
+        if let Some(bytes) = pinned_columns.get(field) {
+            return Ok((bytes.as_ptr() as usize, bytes.len()));
+        }
+        
+        if self.is_empty_split()? {
+            return Ok((0, 0));
+        }
+        let schema = self.index()?.schema();
+        if !schema.get_field_entry(self.tantivy_field(field)?).is_fast() {
+            return Err(SplitsError::FieldError(format!("Field '{}' is not a fast field", field)));
+        }
+        
+        let searcher = self.searcher()?;
+        let segment_reader = match searcher.segment_readers() {
+            [segment_reader] => segment_reader,
+            segment_readers => return Err(SplitsError::InvalidOperation(format!(
+                "Split {} holds {} segments, each with its own column of field '{}'",
+                self.split_path.display(), segment_readers.len(), field
+            ))),
+        };
+        let mut column_handles = segment_reader.fast_fields().dynamic_column_handles(field)?;
+        let column_handle = match column_handles.len() {
+            0 => return Ok((0, 0)),
+            1 => column_handles.remove(0),
+            count => return Err(SplitsError::FieldError(format!(
+                "Field '{}' is stored as {} columns, not a single one", field, count
+            ))),
+        };
+        
+        let bytes = column_handle.file_slice().read_bytes()?;
+        let column = (bytes.as_ptr() as usize, bytes.len());
+        pinned_columns.insert(field.to_string(), bytes);
+        Ok(column)
+    }
+    
+    /// Loads the split metadata from the sidecar JSON file written next to a split
+    ///
+    /// Only present when the split was generated with `write_sidecar_metadata`.
+    pub fn load_sidecar_metadata(split_path: &Path) -> Result<SplitMetadata> {
+        let sidecar_path = sidecar_metadata_path(split_path);
+        let json = fs::read_to_string(&sidecar_path).map_err(|e| SplitsError::InvalidSplit(
+            format!("Failed to read sidecar metadata {}: {}", sidecar_path.display(), e)
+        ))?;
+        
+        Ok(serde_json::from_str(&json)?)
+    }
+    
+    /// Returns whether the split may hold documents timestamped within `start..=end`
+    ///
+    /// Only the time range recorded in the sidecar metadata is consulted, so
+    /// no document is read. Splits without a sidecar, or generated without a
+    /// timestamp field, have no recorded range and always overlap.
+    pub fn overlaps_time_range(&self, start: i64, end: i64) -> Result<bool> {
+        if start > end {
+            return Err(SplitsError::InvalidOperation(format!(
+                "Invalid time range: start {} is after end {}", start, end
+            )));
+        }
+        
+        let split_path = match self.storage.local_path() {
+            Some(split_path) if sidecar_metadata_path(split_path).is_file() => split_path,
+            _ => return Ok(true),
+        };
+        
+        let metadata = Self::load_sidecar_metadata(split_path)?;
+        Ok(match (metadata.time_start, metadata.time_end) {
+            (Some(time_start), Some(time_end)) => time_start <= end && start <= time_end,
+            _ => true,
+        })
+    }
+    
+    /// Returns the Tantivy segment meta of the split as JSON
+    ///
+    /// Uses the `meta.json` embedded in the split when present, otherwise
+    /// reconstructs a minimal meta from the segment files and hotcache.
+    pub fn segment_meta_json(&self) -> Result<String> {
+        if self.storage.exists(META_FILE_NAME)? {
+            return String::from_utf8(self.storage.read_file(META_FILE_NAME)?)
+                .map_err(|e| SplitsError::InvalidSplit(format!("Split meta.json is not UTF-8: {}", e)));
+        }
+        
+        self.reconstruct_segment_meta()
+    }
+    
+    /// Exports the split as a standalone Tantivy index directory
+    ///
+    /// Writes the split's segment files and `meta.json` into `output_dir`,
+    /// which must be missing or empty. The hotcache footer, and any alignment
+    /// padding before it, is stripped from the store file, so plain Tantivy
+    /// tooling can open the result with `Index::open_in_dir`. `meta.json` is
+    /// written last: an interrupted export can't be opened. Fails with
+    /// `InvalidSplit` for splits without an embedded `meta.json`, such as
+    /// legacy splits or splits generated from an empty index.
+    pub fn export_index(&self, output_dir: &Path) -> Result<()> {
+        if !self.storage.exists(META_FILE_NAME)? {
+            return Err(SplitsError::InvalidSplit(
+                "Split does not embed an index meta.json and can't be exported".to_string()
+            ));
+        }
+        if output_dir.exists() && fs::read_dir(output_dir)?.next().is_some() {
+            return Err(SplitsError::InvalidOperation(format!(
+                "Export directory is not empty: {}", output_dir.display()
+            )));
+        }
+        fs::create_dir_all(output_dir)?;
+        
+        for segment_meta in self.index()?.searchable_segment_metas()? {
+            let store_path = segment_meta.relative_path(SegmentComponent::Store);
+            for relative_path in segment_meta.list_files() {
+                let file_name = file_name(&relative_path);
+                let len = match self.storage.file_len(&file_name)? {
+                    Some(len) => len,
+                    None => continue,
+                };
+                
+                let len = if relative_path == store_path { self.store_data_len(&file_name)? } else { len };
+                self.export_file(&file_name, len, &output_dir.join(&relative_path))?;
+            }
+        }
+        
+        let meta_len = self.storage.file_len(META_FILE_NAME)?.unwrap_or(0);
+        self.export_file(META_FILE_NAME, meta_len, &output_dir.join(META_FILE_NAME))?;
+        Ok(())
+    }
+    
+    /// Streams the split as an uncompressed tar archive
+    ///
+    /// Every file of the split is archived as-is, in name order at the root
+    /// of the archive, the store file with its hotcache footer: extracting
+    /// the archive into a directory gives back an identical split. Files are
+    /// read a bounded chunk at a time, so the split is never held in memory.
+    pub fn to_tar<W: Write>(&self, mut writer: W) -> Result<()> {
+        let mut file_names = self.storage.list_files()?;
+        file_names.sort();
+        
+        for file_name in file_names {
+            let len = match self.storage.file_len(&file_name)? {
+                Some(len) => len,
+                None => continue,
+            };
+            let modified = self.storage.modified(&file_name)?
+                .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+                .map_or(0, |modified| modified.as_secs());
+            writer.write_all(&tar_header(&file_name, len, modified)?)?;
+            
+            let mut start = 0;
+            while start < len {
+                let end = len.min(start + COPY_BUFFER_SIZE as u64);
+                writer.write_all(&self.read_byte_range(&file_name, &ByteRange { start, end })?)?;
+                start = end;
+            }
+            let padding = (TAR_BLOCK_SIZE - (len % TAR_BLOCK_SIZE as u64) as usize) % TAR_BLOCK_SIZE;
+            writer.write_all(&[0u8; TAR_BLOCK_SIZE][..padding])?;
+        }
+        
+        // The archive ends with two zero blocks
+        writer.write_all(&[0u8; 2 * TAR_BLOCK_SIZE])?;
+        writer.flush()?;
+        Ok(())
+    }
+    
+    /// Copies the first `len` bytes of a split file to `dst`, a bounded chunk at a time
+    fn export_file(&self, file_name: &str, len: u64, dst: &Path) -> Result<()> {
+        let mut writer = fs::File::create(dst)?;
+        let mut start = 0;
+        while start < len {
+            let end = len.min(start + COPY_BUFFER_SIZE as u64);
+            writer.write_all(&self.read_byte_range(file_name, &ByteRange { start, end })?)?;
+            start = end;
+        }
+        Ok(())
+    }
+    
+    /// Gets the split path
+    pub fn get_split_path(&self) -> &Path {
+        &self.split_path
+    }
+    
+    /// Gets the Tantivy index over the split's embedded `meta.json`
+    ///
+    /// The opened index is charged the size of the split files against the
+    /// reader memory budget, and reopened if it was evicted.
+    fn index(&self) -> Result<Index> {
+        self.index.get_or_try_init(|| {
+            if !self.storage.exists(META_FILE_NAME)? {
+                return Err(SplitsError::InvalidSplit(
+                    "Split does not embed an index meta.json".to_string()
+                ));
+            }
+            let index = Index::open(self.index_directory()?)?;
+            
+            // Custom tokenizers recorded at generation, then the caller's own
+            if let Some(hotcache) = &self.hotcache {
+                for (name, config) in hotcache.tokenizers()? {
+                    index.tokenizers().register(&name, config.build()?);
+                }
+            }
+            let registered_tokenizers = self.registered_tokenizers.lock()
+                .map_err(|e| SplitsError::InvalidOperation(format!("Failed to access tokenizers: {}", e)))?;
+            for (name, tokenizer) in registered_tokenizers.iter() {
+                index.tokenizers().register(name, tokenizer.clone());
+            }
+            
+            Ok((index, self.split_files_size()?))
+        })
+    }
+    
+    /// Gets the directory to open the index from, decrypting the split files if needed
+    ///
+    /// The store file is served without its hotcache footer, which Tantivy
+    /// would otherwise take for its own file footer. Decrypted store files
+    /// hold the store data alone already.
+    fn index_directory(&self) -> Result<Box<dyn Directory>> {
+        let encrypted_files = match &self.hotcache {
+            Some(hotcache) => hotcache.encrypted_files()?,
+            None => BTreeMap::new(),
+        };
+        if encrypted_files.is_empty() {
+            let directory = self.storage.directory()?;
+            let store_file = match self.find_store_file() {
+                Ok(store_file) => store_file,
+                Err(_) => return Ok(directory),
+            };
+            let store_data_len = self.store_data_len(&store_file)?;
+            return Ok(Box::new(TruncatedFileDirectory::new(directory, Path::new(&store_file), store_data_len)));
+        }
+        
+        let key = self.encryption_key.as_ref().ok_or_else(|| SplitsError::InvalidOperation(format!(
+            "Split {} is encrypted; set its encryption key first", self.split_path.display()
+        )))?;
+        Ok(Box::new(decrypt_split(self.storage.as_ref(), &encrypted_files, key)?))
+    }
+    
+    /// Sums the sizes of the files in the split
+    fn split_files_size(&self) -> Result<u64> {
+        let mut total = 0;
+        for file_name in self.storage.list_files()? {
+            total += self.storage.file_len(&file_name)?.unwrap_or(0);
+        }
+        Ok(total)
+    }
+    
+    /// Parses a query against the split's schema, defaulting to all indexed text fields
+    fn parse_query(&self, query: &str) -> Result<Box<dyn Query>> {
+        let index = self.index()?;
+        let schema = index.schema();
+        
+        let default_fields: Vec<Field> = schema.fields()
+            .filter(|(_, field_entry)| {
+                field_entry.is_indexed() && matches!(field_entry.field_type(), FieldType::Str(_))
+            })
+            .map(|(field, _)| field)
+            .collect();
+        
+        QueryParser::for_index(&index, default_fields)
+            .parse_query(query)
+            .map_err(|e| SplitsError::Query(format!("Failed to parse query '{}': {}", query, e)))
+    }
+    
+    /// Recursively estimates the number of hits of a parsed query
+    fn estimate_query_hits(searcher: &Searcher, query: &dyn Query) -> Result<u64> {
+        let num_docs = searcher.num_docs();
+        
+        if let Some(term_query) = query.downcast_ref::<TermQuery>() {
+            return Ok(searcher.doc_freq(term_query.term())?);
+        }
+        
+        if let Some(phrase_query) = query.downcast_ref::<PhraseQuery>() {
+            // A phrase can't match more documents than its rarest term
+            let mut estimate = num_docs;
+            for term in phrase_query.phrase_terms() {
+                estimate = estimate.min(searcher.doc_freq(&term)?);
+            }
+            return Ok(estimate);
+        }
+        
+        if let Some(boolean_query) = query.downcast_ref::<BooleanQuery>() {
+            let mut must: Option<u64> = None;
+            let mut should: Option<u64> = None;
+            let mut kept_fraction = 1.0f64;
+            
+            for (occur, sub_query) in boolean_query.clauses() {
+                let hits = Self::estimate_query_hits(searcher, sub_query.as_ref())?;
+                match occur {
+                    Occur::Must => must = Some(must.map_or(hits, |must| must.min(hits))),
+                    Occur::Should => should = Some(should.unwrap_or(0).saturating_add(hits)),
+                    Occur::MustNot => {
+                        if num_docs > 0 {
+                            kept_fraction *= 1.0 - (hits.min(num_docs) as f64 / num_docs as f64);
+                        }
+                    }
+                }
+            }
+            
+            let matching = must
+                .or_else(|| should.map(|should| should.min(num_docs)))
+                .unwrap_or(num_docs);
+            return Ok((matching as f64 * kept_fraction).round() as u64);
+        }
+        
+        // Unknown query shape: assume it may match everything
+        Ok(num_docs)
+    }
+    
+    /// Whether `column` knows how to read a fast field of this type from its column
+    fn has_fast_column(field_type: &FieldType) -> bool {
+        matches!(
+            field_type,
+            FieldType::U64(_) | FieldType::I64(_) | FieldType::F64(_)
+                | FieldType::Bool(_) | FieldType::Date(_) | FieldType::Str(_)
+        )
+    }
+    
+    /// Appends the fast field values of every live document in a segment
+    fn read_fast_column(
+        segment_reader: &SegmentReader,
+        field: &str,
+        field_type: &FieldType,
+        values: &mut Vec<serde_json::Value>,
+    ) -> Result<()> {
+        let fast_fields = segment_reader.fast_fields();
+        
+        match field_type {
+            FieldType::U64(_) => Self::read_numeric_column(segment_reader, fast_fields.u64(field)?, OwnedValue::U64, values),
+            FieldType::I64(_) => Self::read_numeric_column(segment_reader, fast_fields.i64(field)?, OwnedValue::I64, values),
+            FieldType::F64(_) => Self::read_numeric_column(segment_reader, fast_fields.f64(field)?, OwnedValue::F64, values),
+            FieldType::Bool(_) => Self::read_numeric_column(segment_reader, fast_fields.bool(field)?, OwnedValue::Bool, values),
+            FieldType::Date(_) => Self::read_numeric_column(segment_reader, fast_fields.date(field)?, OwnedValue::Date, values),
+            FieldType::Str(_) => {
+                let str_column = match fast_fields.str(field)? {
+                    Some(str_column) => str_column,
+                    // No document in this segment has a value
+                    None => {
+                        values.extend(segment_reader.doc_ids_alive().map(|_| serde_json::Value::Null));
+                        return Ok(());
+                    }
+                };
+                
+                let mut buffer = String::new();
+                for doc in segment_reader.doc_ids_alive() {
+                    let mut field_values = Vec::new();
+                    for ord in str_column.term_ords(doc) {
+                        buffer.clear();
+                        str_column.ord_to_str(ord, &mut buffer)?;
+                        field_values.push(OwnedValue::Str(buffer.clone()));
+                    }
+                    values.push(Self::values_to_json(field_values)?);
+                }
+                Ok(())
+            }
+            _ => Err(SplitsError::FieldError(format!(
+                "Field '{}' has an unsupported fast field type", field
+            ))),
+        }
+    }
+    
+    /// Appends the values of a numeric fast field column for every live document
+    fn read_numeric_column<T>(
+        segment_reader: &SegmentReader,
+        column: Column<T>,
+        to_value: impl Fn(T) -> OwnedValue,
+        values: &mut Vec<serde_json::Value>,
+    ) -> Result<()>
+    where
+        T: PartialOrd + Copy + std::fmt::Debug + Send + Sync + 'static,
+    {
+        for doc in segment_reader.doc_ids_alive() {
+            let field_values = column.values_for_doc(doc).map(&to_value).collect();
+            values.push(Self::values_to_json(field_values)?);
+        }
+        Ok(())
+    }
+    
+    /// Converts a document's values for one field into a single JSON value
+    fn values_to_json(mut field_values: Vec<OwnedValue>) -> Result<serde_json::Value> {
+        let value = match field_values.len() {
+            0 => return Ok(serde_json::Value::Null),
+            1 => serde_json::to_value(field_values.remove(0)),
+            _ => serde_json::to_value(field_values),
+        };
+        value.map_err(|e| SplitsError::Serialization(format!("Failed to convert field value: {}", e)))
+    }
+    
+    /// Resolves the fixed-width encoding of a fast field from the schema
+    fn fast_field_encoding(&self, field: &str) -> Result<FastFieldEncoding> {
+        let field_handle = self.tantivy_field(field)?;
+        let schema = self.index()?.schema();
+        let field_entry = schema.get_field_entry(field_handle);
+        if !field_entry.is_fast() {
+            return Err(SplitsError::FieldError(format!("Field '{}' is not a fast field", field)));
+        }
+        FastFieldEncoding::for_field(field, field_entry.field_type())
+    }
+    
+    /// Encodes the fast field values of `doc_range`, split-wide doc ids read from each segment they span
+    fn encode_fast_field(
+        searcher: &Searcher,
+        field: &str,
+        encoding: FastFieldEncoding,
+        doc_range: std::ops::Range<u32>,
+        format: FastFieldFormat,
+    ) -> Result<Vec<u8>> {
+        Self::check_doc_range(&doc_range, split_max_doc(searcher))?;
+        
+        let mut values = Vec::with_capacity(doc_range.len());
+        for (segment_reader, doc_base) in segment_doc_bases(searcher) {
+            let start = doc_range.start.max(doc_base);
+            let end = doc_range.end.min(doc_base + segment_reader.max_doc());
+            if start >= end {
+                continue;
+            }
+            
+            let segment_range = start - doc_base..end - doc_base;
+            let fast_fields = segment_reader.fast_fields();
+            values.extend(match encoding {
+                FastFieldEncoding::U64 => Self::column_bits(fast_fields.u64(field)?, segment_range, |value| value),
+                FastFieldEncoding::I64 => Self::column_bits(fast_fields.i64(field)?, segment_range, |value| value as u64),
+                FastFieldEncoding::F64 => Self::column_bits(fast_fields.f64(field)?, segment_range, f64::to_bits),
+                FastFieldEncoding::Bool => Self::column_bits(fast_fields.bool(field)?, segment_range, u64::from),
+                FastFieldEncoding::DateMillis => Self::column_bits(
+                    fast_fields.date(field)?,
+                    segment_range,
+                    |value| value.into_timestamp_millis() as u64,
+                ),
+            });
+        }
+        
+        Ok(format.encode(encoding, &values))
+    }
+    
+    /// Fails with a `FieldError` unless `doc_range` lies within the first `num_docs` documents
+    ///
+    /// Documents are addressed by id, so deleted documents still count.
+    fn check_doc_range(doc_range: &std::ops::Range<u32>, num_docs: u32) -> Result<()> {
+        if doc_range.start > doc_range.end || doc_range.end > num_docs {
+            return Err(SplitsError::FieldError(format!(
+                "Document range {}..{} is out of bounds: the split holds {} documents, valid ranges lie within 0..{}",
+                doc_range.start, doc_range.end, num_docs, num_docs
+            )));
+        }
+        Ok(())
+    }
+    
+    /// Reads the first value of each document as its 64-bit pattern, or `None` if it has none
+    fn column_bits<T>(
+        column: Column<T>,
+        doc_range: std::ops::Range<u32>,
+        to_bits: impl Fn(T) -> u64,
+    ) -> Vec<Option<u64>>
+    where
+        T: PartialOrd + Copy + std::fmt::Debug + Send + Sync + 'static,
+    {
+        doc_range.map(|doc| column.first(doc).map(&to_bits)).collect()
+    }
+    
+    /// Gets a searcher over the split's Tantivy index
+    fn searcher(&self) -> Result<Searcher> {
+        Ok(self.index()?.reader()?.searcher())
+    }
+    
+    /// Resolves a field name against the split's schema
+    fn tantivy_field(&self, field: &str) -> Result<Field> {
+        self.index()?.schema().get_field(field)
+            .map_err(|_| SplitsError::FieldError(format!("Field '{}' not found", field)))
+    }
+    
+    /// Gets the indexing options of a field, failing if it isn't indexed
+    fn index_record_option(&self, field_name: &str, field: Field) -> Result<IndexRecordOption> {
+        self.index()?.schema().get_field_entry(field).field_type()
+            .get_index_record_option()
+            .ok_or_else(|| SplitsError::FieldError(format!("Field '{}' is not indexed", field_name)))
+    }
+    
+    /// Computes the fingerprint of the split's store file
+    fn compute_fingerprint(&self) -> Result<SplitFingerprint> {
+        // The decompressed copy never changes, the file it was decompressed from may
+        if self.compression.is_some() {
+            let metadata = fs::metadata(&self.split_path)?;
+            return Ok(SplitFingerprint { len: metadata.len(), modified: metadata.modified().ok() });
+        }
+        
+        let store_file = self.find_store_file()?;
+        
+        Ok(SplitFingerprint {
+            len: self.storage.file_len(&store_file)?.unwrap_or(0),
+            modified: self.storage.modified(&store_file)?,
+        })
+    }
+    
+    /// Finds the name of the store file in the split, see `find_file_with_extension`
+    fn find_store_file(&self) -> Result<String> {
+        self.find_file_with_extension("store").map_err(|_| SplitsError::InvalidSplit(
+            "No store file found in split directory".to_string()
+        ))
+    }
+    
+    /// Checks that every segment file referenced by the split's meta.json is present
+    ///
+    /// Each file must be at least large enough to hold Tantivy's file footer.
+    /// Legacy splits without a meta.json only get the footer check done when
+    /// loading the hotcache.
+    fn check_not_truncated(&self) -> Result<()> {
+        if !self.storage.exists(META_FILE_NAME)? {
+            return Ok(());
+        }
+        
+        // Opened over the raw files, as the hotcache telling whether they're
+        // encrypted isn't loaded yet; only meta.json is read
+        let index = Index::open(self.storage.directory()?)?;
+        for segment_meta in index.searchable_segment_metas()? {
+            for component in REQUIRED_COMPONENTS {
+                let relative_path = segment_meta.relative_path(component);
+                let path = self.split_path.join(&relative_path);
+                let len = match self.storage.file_len(&file_name(&relative_path))? {
+                    Some(len) => len,
+                    None => return Err(SplitsError::InvalidSplit(format!(
+                        "split appears truncated: segment file {} is missing", path.display()
+                    ))),
+                };
+                
+                if len < MIN_SEGMENT_FILE_SIZE {
+                    return Err(SplitsError::InvalidSplit(format!(
+                        "split appears truncated: segment file {} is {} bytes, expected at least {}",
+                        path.display(), len, MIN_SEGMENT_FILE_SIZE
+                    )));
+                }
+            }
+        }
+        
+        Ok(())
+    }
+    
+    /// Gets the length of the Tantivy store data, before padding and footer
+    fn store_data_len(&self, store_file: &str) -> Result<u64> {
+        if let Some(store_data_len) = self.hotcache.as_ref().and_then(|hotcache| hotcache.store_data_len()) {
+            return Ok(store_data_len);
+        }
+        
+        let file_size = self.store_file_len(store_file)?;
+        let hotcache_size = self.read_footer_size(store_file, file_size)?;
+        
+        // Store files of plain Tantivy indexes have no hotcache footer and end
+        // with Tantivy's footer magic, which no hotcache size reaches
+        if hotcache_size >> 32 == u64::from(TANTIVY_FOOTER_MAGIC) {
+            return Ok(file_size);
+        }
+        
+        if hotcache_size > file_size {
+            return Err(SplitsError::InvalidSplit(format!(
+                "Footer of store file {} is larger than the file", store_file
+            )));
+        }
+        Ok(locate_footer(file_size, |range| self.read_byte_range(store_file, &range))?.start)
+    }
+    
+    /// Gets the length of the store file, failing if it's missing
+    fn store_file_len(&self, store_file: &str) -> Result<u64> {
+        self.storage.file_len(store_file)?
+            .ok_or_else(|| SplitsError::InvalidSplit(format!("Store file {} missing from split", store_file)))
+    }
+    
+    /// Reads the hotcache size, trailer included, ending the store file footer
+    fn read_footer_size(&self, store_file: &str, file_size: u64) -> Result<u64> {
+        if file_size < 8 {
+            return Err(SplitsError::InvalidSplit(format!(
+                "split appears truncated: store file {} is {} bytes, too small to contain the footer",
+                store_file, file_size
+            )));
+        }
+        
+        let size_bytes = self.read_byte_range(store_file, &ByteRange { start: file_size - 8, end: file_size })?;
+        let size_bytes: [u8; 8] = size_bytes.as_slice().try_into()
+            .map_err(|_| SplitsError::InvalidSplit(format!("Failed to read footer of store file {}", store_file)))?;
+        Ok(u64::from_le_bytes(size_bytes))
+    }
+    
+    /// Reads hotcache data from the footer of a store file
+    fn read_hotcache_from_footer(&self, store_file: &str) -> Result<Vec<u8>> {
+        let file_size = self.store_file_len(store_file)?;
+        
+        // The footer ends with the hotcache size, trailer included, as a
+        // little-endian u64. A size of 8 means the split has no hotcache.
+        let hotcache_size = self.read_footer_size(store_file, file_size)?;
+        
+        if hotcache_size > file_size {
+            return Err(SplitsError::InvalidSplit(format!(
+                "split appears truncated: footer of store file {} declares {} bytes but the file is only {} bytes",
+                store_file, hotcache_size, file_size
+            )));
+        }
+        
+        if hotcache_size < 8 {
+            return Err(SplitsError::InvalidSplit(
+                "Invalid hotcache size in footer".to_string()
+            ));
+        }
+        
+        // A versioned footer, if any, precedes the legacy one and is read instead
+        let footer = locate_footer(file_size, |range| self.read_byte_range(store_file, &range))?;
+        
+        // Store data, unless empty, ends with the doc store footer and Tantivy's footer
+        let store_data_len = footer.start;
+        if store_data_len > 0 && store_data_len < MIN_STORE_DATA_SIZE {
+            return Err(SplitsError::InvalidSplit(format!(
+                "split appears truncated: footer of store file {} leaves {} bytes of store data, expected at least {}",
+                store_file, store_data_len, MIN_STORE_DATA_SIZE
+            )));
+        }
+        
+        self.read_byte_range(store_file, &footer.payload)
+    }
+    
+    /// Decodes the block checkpoints of the doc store of every segment, in doc id order
+    ///
+    /// Doc ranges are split-wide, as `posting_cursor` numbers them. Splits
+    /// of empty indexes have no blocks.
+    fn store_checkpoints(&self) -> Result<Vec<StoreBlock>> {
+        if self.is_empty_split()? {
+            return Ok(Vec::new());
+        }
+        
+        let searcher = self.searcher()?;
+        let mut blocks = Vec::new();
+        for (store_file, (_, doc_base)) in self.segment_files(SegmentComponent::Store)?.into_iter().zip(segment_doc_bases(&searcher)) {
+            for (docs, byte_range) in self.segment_store_checkpoints(&store_file)? {
+                blocks.push(StoreBlock {
+                    docs: doc_base + docs.start..doc_base + docs.end,
+                    store_file: store_file.clone(),
+                    byte_range,
+                });
+            }
+        }
+        Ok(blocks)
+    }
+    
+    /// Decodes the block checkpoints of one segment's doc store, as doc ranges and byte ranges
+    ///
+    /// Tantivy's `StoreReader` keeps its checkpoints private, so they are
+    /// read from the store file: the doc store footer, just before Tantivy's
+    /// file footer, holds the offset of the skip index following the blocks,
+    /// whose last layer has one checkpoint per block. Only the footers and
+    /// skip index are read.
+    fn segment_store_checkpoints(&self, store_file: &str) -> Result<Vec<(std::ops::Range<u32>, ByteRange)>> {
+        let invalid = |reason: &str| SplitsError::InvalidSplit(format!("Invalid doc store in {}: {}", store_file, reason));
+        
+        // Opened through the index directory, which serves the store data without the hotcache footer
+        let store_slice = self.index_directory()?.open_read(Path::new(&store_file))
+            .map_err(tantivy::TantivyError::from)?;
+        let (_, doc_store) = Footer::extract_footer(store_slice)
+            .map_err(|_| invalid("missing Tantivy file footer"))?;
+        let doc_store_len = doc_store.len() as u64;
+        let footer_start = doc_store_len.checked_sub(DOC_STORE_FOOTER_LEN)
+            .ok_or_else(|| invalid("store data is smaller than the doc store footer"))?;
+        
+        // Footer: u32 version, u64 skip index offset, compressor id, reserved bytes
+        let footer = doc_store.read_bytes_slice(footer_start as usize..doc_store_len as usize)?;
+        let skip_index_start = u64::from_le_bytes(footer[4..12].try_into().expect("footer holds 8 offset bytes"));
+        if skip_index_start > footer_start {
+            return Err(invalid("skip index offset past the footer"));
+        }
+        let skip_index = doc_store.read_bytes_slice(skip_index_start as usize..footer_start as usize)?;
+        
+        // Skip index: the end offset of each layer, then the layers, the last one checkpointing blocks
+        let mut data = skip_index.as_slice();
+        let num_layers = read_vint(&mut data).ok_or_else(|| invalid("truncated skip index"))?;
+        let mut layer_ends = Vec::new();
+        for _ in 0..num_layers {
+            layer_ends.push(read_vint(&mut data).ok_or_else(|| invalid("truncated skip index"))? as usize);
+        }
+        let layer_start = if layer_ends.len() > 1 { layer_ends[layer_ends.len() - 2] } else { 0 };
+        let layer_end = layer_ends.last().copied().unwrap_or(0);
+        let mut layer = data.get(layer_start..layer_end).ok_or_else(|| invalid("skip index layer out of bounds"))?;
+        
+        // Blocks of checkpoints: count, first doc and byte offset, then the doc count and byte length of each
+        let mut checkpoints = Vec::new();
+        while !layer.is_empty() {
+            let mut next = || read_vint(&mut layer).ok_or_else(|| invalid("truncated skip index layer"));
+            let len = next()?;
+            if len == 0 {
+                continue;
+            }
+            let mut doc = next()? as u32;
+            let mut offset = next()?;
+            for _ in 0..len {
+                let num_docs = next()? as u32;
+                let num_bytes = next()?;
+                checkpoints.push((doc..doc + num_docs, ByteRange { start: offset, end: offset + num_bytes }));
+                doc += num_docs;
+                offset += num_bytes;
+            }
+        }
+        Ok(checkpoints)
+    }
+    
+    /// Checks whether the split was generated from an empty index
+    ///
+    /// Such splits hold nothing but a store file carrying the footer: no
+    /// `meta.json` and no other segment file. Read methods answer them with
+    /// empty results rather than failing on the missing files.
+    fn is_empty_split(&self) -> Result<bool> {
+        if self.storage.exists(META_FILE_NAME)? {
+            return Ok(false);
+        }
+        if self.hotcache.as_ref().is_some_and(|hotcache| hotcache.num_docs > 0) {
+            return Ok(false);
+        }
+        Ok(self.list_segment_files()?.iter().all(|file_name| file_name.ends_with(".store")))
+    }
+    
+    /// Locates the file holding a segment component
+    ///
+    /// Splits embedding a `meta.json` name the file through Tantivy's own
+    /// segment layout, rather than assuming the version's file naming, and
+    /// look it up in the split's storage, which finds it inside a split file
+    /// as well as in a split directory. Legacy splits without one fall back
+    /// to matching file extensions.
+    fn component_file(&self, component: SegmentComponent) -> Result<String> {
+        if self.storage.exists(META_FILE_NAME)? {
+            let segment_metas = self.index()?.searchable_segment_metas()?;
+            let segment_meta = segment_metas.first()
+                .ok_or_else(|| SplitsError::InvalidSplit(
+                    "Split meta.json lists no segments".to_string()
+                ))?;
+            return self.segment_meta_file(segment_meta, component);
+        }
+        
+        let extension = match component {
+            SegmentComponent::Postings => "idx",
+            SegmentComponent::Positions => "pos",
+            SegmentComponent::FastFields => "fast",
+            SegmentComponent::FieldNorms => "fieldnorm",
+            SegmentComponent::Terms => "term",
+            SegmentComponent::Store => "store",
+            SegmentComponent::TempStore => "store.temp",
+            SegmentComponent::Delete => "del",
+        };
+        
+        self.find_file_with_extension(extension).map_err(|_| SplitsError::InvalidSplit(format!(
+            "Unrecognized split format: no meta.json and no .{} file in {}",
+            extension, self.split_path.display()
+        )))
+    }
+    
+    /// Locates the file holding a segment component for every segment, in segment order
+    ///
+    /// Legacy splits without a `meta.json` hold a single segment, see
+    /// `component_file`.
+    fn segment_files(&self, component: SegmentComponent) -> Result<Vec<String>> {
+        if !self.storage.exists(META_FILE_NAME)? {
+            return Ok(vec![self.component_file(component)?]);
+        }
+        
+        self.index()?.searchable_segment_metas()?
+            .iter()
+            .map(|segment_meta| self.segment_meta_file(segment_meta, component))
+            .collect()
+    }
+    
+    /// Names the file of a segment component listed in `meta.json`, failing if it's missing
+    fn segment_meta_file(&self, segment_meta: &SegmentMeta, component: SegmentComponent) -> Result<String> {
+        let file_name = file_name(&segment_meta.relative_path(component));
+        if !self.storage.exists(&file_name)? {
+            // SegmentComponent implements neither Display nor Debug; the file extension names it
+            return Err(SplitsError::InvalidSplit(format!(
+                "Segment component file missing from split: {}",
+                self.split_path.join(&file_name).display()
+            )));
+        }
+        Ok(file_name)
+    }
+    
+    /// Finds a file with the given extension in the split
+    ///
+    /// Extensions are compared exactly, so a `.IDX` file isn't an `.idx` one.
+    /// When several files match, e.g. names differing only in case, which
+    /// collide on case-insensitive filesystems, the one in Tantivy's
+    /// lowercase naming wins, then the first by name, so the choice never
+    /// depends on the order files are listed in.
+    fn find_file_with_extension(&self, extension: &str) -> Result<String> {
+        let suffix = format!(".{}", extension);
+        self.storage.list_files()?
+            .into_iter()
+            .filter(|file_name| file_name.ends_with(&suffix))
+            .min_by_key(|file_name| (*file_name != file_name.to_lowercase(), file_name.clone()))
+            .ok_or_else(|| SplitsError::InvalidSplit(
+                format!("No {} file found in split directory", extension)
+            ))
+    }
+    
+    /// Checks if a filename is a segment file
+    ///
+    /// Segment files are named `<segment_id>.<extension>`, except delete
+    /// files, named `<segment_id>.<opstamp>.del` after the opstamp of the
+    /// deletes they hold.
+    fn is_segment_file(&self, filename: &str) -> bool {
+        // UUID-based filenames with known extensions
+        let extensions = ["store", "term", "idx", "fast", "pos", "fieldnorm"];
+        
+        if let Some(prefix) = filename.strip_suffix(".del") {
+            return match prefix.rsplit_once('.') {
+                Some((segment_id, opstamp)) => {
+                    is_segment_id(segment_id) && !opstamp.is_empty() && opstamp.bytes().all(|b| b.is_ascii_digit())
+                }
+                None => false,
+            };
+        }
+        
+        extensions.iter().any(|ext| {
+            filename.strip_suffix(ext)
+                .and_then(|prefix| prefix.strip_suffix('.'))
+                .is_some_and(is_segment_id)
+        })
+    }
+    
+    /// Builds a minimal segment meta from the files present in the split
+    fn reconstruct_segment_meta(&self) -> Result<String> {
+        let files = self.list_segment_files()?;
+        
+        let segment_id = files.iter()
+            .filter_map(|file_name| file_name.split('.').next())
+            .next()
+            .ok_or_else(|| SplitsError::InvalidSplit(
+                "No segment files found in split directory".to_string()
+            ))?;
+        
+        // Delete files are named `<segment_id>.<opstamp>.del`
+        let delete_opstamp = files.iter()
+            .filter(|file_name| file_name.ends_with(".del"))
+            .filter_map(|file_name| file_name.split('.').nth(1))
+            .find_map(|opstamp| opstamp.parse::<u64>().ok());
+        
+        let max_doc = self.hotcache.as_ref()
+            .map(|hotcache| hotcache.num_docs)
+            .unwrap_or(0);
+        
+        let meta = serde_json::json!({
+            "segment_id": segment_id,
+            "max_doc": max_doc,
+            "deletes": delete_opstamp.map(|opstamp| serde_json::json!({ "opstamp": opstamp })),
+            "reconstructed": true,
+        });
+        
+        Ok(serde_json::to_string_pretty(&meta)?)
+    }
+    
+    /// Gets a field's section range recorded in the hotcache, if it still lies within `file_name`
+    ///
+    /// A range past the end of the file is stale, e.g. after a repair rewrote
+    /// the file. It is ignored with a warning, like a field that wasn't
+    /// hotcached, so callers locate the section through Tantivy instead of
+    /// failing on a short read.
+    fn hotcached_range(
+        &self,
+        field: &str,
+        file_name: &str,
+        range_of: impl Fn(&FieldMetadata) -> Option<ByteRange>,
+    ) -> Result<Option<ByteRange>> {
+        let range = match &self.hotcache {
+            Some(hotcache) => hotcache.field_metadata()?.get(field).and_then(range_of),
+            None => None,
+        };
+        let range = match range {
+            Some(range) => range,
+            None => return Ok(None),
+        };
+        
+        let file_len = self.storage.file_len(file_name)?.unwrap_or(0);
+        if range.start > range.end || range.end > file_len {
+            log::warn!(
+                "Hotcached range {}..{} of field '{}' lies beyond {} ({} bytes), ignoring it",
+                range.start, range.end, field, file_name, file_len
+            );
+            return Ok(None);
+        }
+        Ok(Some(range))
+    }
+    
+    /// Reads data from a specific byte range of a split file, as set by `set_read_options`
+    fn read_byte_range(&self, file_name: &str, range: &ByteRange) -> Result<Vec<u8>> {
+        self.storage.read_range(file_name, range, &self.read_options)
+    }
+}
+
+/// Corpus statistics of a field, as returned by `QuickwitSplitReader::corpus_stats`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CorpusStats {
+    /// Number of live documents in the split
+    pub total_docs: u64,
+    /// Number of live documents with at least one term in the field
+    pub docs_with_field: u64,
+    /// Sum of term frequencies over the field, deleted documents included
+    pub total_tokens: u64,
+    /// Number of distinct terms in the field
+    pub unique_terms: u64,
+}
+
+/// Inverted index statistics of a split, as returned by `QuickwitSplitReader::index_stats`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct IndexStats {
+    /// Number of live documents in the split
+    pub num_docs: u64,
+    /// Statistics of every indexed field, by field name
+    pub fields: BTreeMap<String, FieldIndexStats>,
+}
+
+/// Inverted index statistics of a field, see `IndexStats`
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct FieldIndexStats {
+    /// Number of distinct terms
+    pub num_terms: u64,
+    /// Sum of the document frequencies of all terms, deleted documents included
+    pub total_postings: u64,
+    /// Average document frequency of a term, 0 without terms
+    pub avg_doc_freq: f64,
+    /// Size of the field's term dictionary, in the `.term` file
+    pub term_dict_bytes: u64,
+    /// Size of the field's posting lists, in the `.idx` file
+    pub postings_bytes: u64,
+    /// Size of the field's positions, in the `.pos` file; 0 if positions aren't indexed
+    pub positions_bytes: u64,
+}
+
+/// Fixed-width encoding of fast field values, as returned by
+/// `QuickwitSplitReader::get_fast_field_data` in the `FastFieldFormat::RawLe`
+/// format
+///
+/// Each document of the requested range takes `bytes_per_doc` bytes, in doc
+/// id order:
+/// - `U64`, `I64`: 8 bytes, little-endian
+/// - `F64`: 8 bytes, little-endian IEEE 754
+/// - `Bool`: 1 byte, 0 or 1
+/// - `DateMillis`: 8 bytes, little-endian i64 milliseconds since the Unix epoch
+///
+/// A document without a value is encoded as zeros, and a multi-valued
+/// document as its first value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FastFieldEncoding {
+    U64,
+    I64,
+    F64,
+    Bool,
+    DateMillis,
+}
+
+impl FastFieldEncoding {
+    /// Picks the encoding of a field, failing with a `FieldError` naming the
+    /// type if it has no fixed-width encoding
+    pub fn for_field(field: &str, field_type: &FieldType) -> Result<Self> {
+        match field_type {
+            FieldType::U64(_) => Ok(FastFieldEncoding::U64),
+            FieldType::I64(_) => Ok(FastFieldEncoding::I64),
+            FieldType::F64(_) => Ok(FastFieldEncoding::F64),
+            FieldType::Bool(_) => Ok(FastFieldEncoding::Bool),
+            FieldType::Date(_) => Ok(FastFieldEncoding::DateMillis),
+            other => Err(SplitsError::FieldError(format!(
+                "Fast field '{}' of type {} has no fixed-width encoding",
+                field, FieldKind::of(other).name()
+            ))),
+        }
+    }
+    
+    /// Gets the number of bytes each document takes
+    pub fn bytes_per_doc(&self) -> usize {
+        match self {
+            FastFieldEncoding::Bool => 1,
+            FastFieldEncoding::U64 | FastFieldEncoding::I64
+                | FastFieldEncoding::F64 | FastFieldEncoding::DateMillis => 8,
+        }
+    }
+}
+
+/// Serialization of the values returned by `QuickwitSplitReader::get_fast_field_data`
+///
+/// Every format holds one value per document of the requested range, in doc
+/// id order, typed as described by `FastFieldEncoding`:
+/// - `RawLe`: `bytes_per_doc` bytes per document, little-endian. A document
+///   without a value is encoded as zeros.
+/// - `Varint`: one unsigned LEB128 varint per document. `I64` and
+///   `DateMillis` values are zigzag-encoded first, `F64` values are their
+///   IEEE 754 bits and `Bool` values are 0 or 1. A document without a value
+///   is encoded as 0.
+/// - `Arrow`: the buffers of an Arrow primitive array, each zero-padded to a
+///   multiple of 8 bytes: a validity bitmap with one bit per document, least
+///   significant bit first, set if the document has a value, followed by the
+///   values. Values take 8 bytes little-endian, except `Bool` values which
+///   are bit-packed like the bitmap. A document without a value has zeros.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FastFieldFormat {
+    #[default]
+    RawLe,
+    Varint,
+    Arrow,
+}
+
+impl FastFieldFormat {
+    /// Maps the code passed across JNI: 0 `RawLe`, 1 `Varint`, 2 `Arrow`
+    pub fn from_code(code: i32) -> Result<Self> {
+        match code {
+            0 => Ok(FastFieldFormat::RawLe),
+            1 => Ok(FastFieldFormat::Varint),
+            2 => Ok(FastFieldFormat::Arrow),
+            other => Err(SplitsError::InvalidOperation(format!("Unknown fast field format code {}", other))),
+        }
+    }
+    
+    /// Serializes per-document values, given as their 64-bit patterns
+    fn encode(&self, encoding: FastFieldEncoding, values: &[Option<u64>]) -> Vec<u8> {
+        match self {
+            FastFieldFormat::RawLe => {
+                let bytes_per_doc = encoding.bytes_per_doc();
+                let mut data = Vec::with_capacity(values.len() * bytes_per_doc);
+                for value in values {
+                    data.extend_from_slice(&value.unwrap_or(0).to_le_bytes()[..bytes_per_doc]);
+                }
+                data
+            }
+            FastFieldFormat::Varint => {
+                let signed = matches!(encoding, FastFieldEncoding::I64 | FastFieldEncoding::DateMillis);
+                let mut data = Vec::with_capacity(values.len());
+                for value in values {
+                    let mut value = value.unwrap_or(0);
+                    if signed {
+                        value = ((value << 1) as i64 ^ ((value as i64) >> 63)) as u64;
+                    }
+                    while value >= 0x80 {
+                        data.push(value as u8 | 0x80);
+                        value >>= 7;
+                    }
+                    data.push(value as u8);
+                }
+                data
+            }
+            FastFieldFormat::Arrow => {
+                let mut data = pack_bits(values.iter().map(Option::is_some));
+                if encoding == FastFieldEncoding::Bool {
+                    data.extend(pack_bits(values.iter().map(|value| *value == Some(1))));
+                } else {
+                    for value in values {
+                        data.extend_from_slice(&value.unwrap_or(0).to_le_bytes());
+                    }
+                }
+                data
+            }
+        }
+    }
+}
+
+/// Packs bits least significant first, zero-padded to a multiple of 8 bytes
+fn pack_bits(bits: impl Iterator<Item = bool>) -> Vec<u8> {
+    let mut packed = Vec::new();
+    for (i, bit) in bits.enumerate() {
+        if i % 8 == 0 {
+            packed.push(0);
+        }
+        if bit {
+            *packed.last_mut().unwrap() |= 1 << (i % 8);
+        }
+    }
+    packed.resize(packed.len().div_ceil(8) * 8, 0);
+    packed
+}
+
+/// Splits a term of a JSON field into its dotted path and its typed value
+///
+/// JSON terms are keyed by the path, its segments separated by `\x01`, then
+/// `\x00`, the value's type code and its bytes. Returns `None` for keys not
+/// laid out this way.
+fn split_json_term(key: &[u8]) -> Option<(String, ValueBytes<&[u8]>)> {
+    let end_of_path = key.iter().position(|byte| *byte == JSON_END_OF_PATH)?;
+    let value = &key[end_of_path + 1..];
+    Type::from_code(*value.first()?)?;
+    let path = std::str::from_utf8(&key[..end_of_path]).ok()?.replace(JSON_PATH_SEGMENT_SEP, ".");
+    Some((path, ValueBytes::wrap(value)))
+}
+
+/// Renders a term of a JSON field as `<path>:<value>`, see `QuickwitSplitReader::field_terms`
+fn json_term_to_string(key: &[u8]) -> Option<String> {
+    let (path, value) = split_json_term(key)?;
+    let value = match value.typ() {
+        Type::Str => return Some(format!("{}:{}", path, value.as_str()?)),
+        Type::U64 => OwnedValue::U64(value.as_u64()?),
+        Type::I64 => OwnedValue::I64(value.as_i64()?),
+        Type::F64 => OwnedValue::F64(value.as_f64()?),
+        Type::Bool => OwnedValue::Bool(value.as_bool()?),
+        Type::Date => OwnedValue::Date(value.as_date()?),
+        _ => return None,
+    };
+    match serde_json::to_value(value).ok()? {
+        serde_json::Value::String(text) => Some(format!("{}:{}", path, text)),
+        other => Some(format!("{}:{}", path, other)),
+    }
+}
+
+/// Collects the values at a dotted `path` under a JSON value, flattening arrays
+fn collect_json_path_values(value: &OwnedValue, path: &[&str], values: &mut Vec<OwnedValue>) {
+    match (value, path.split_first()) {
+        (OwnedValue::Array(items), _) => {
+            for item in items {
+                collect_json_path_values(item, path, values);
+            }
+        }
+        (_, None) => values.push(value.clone()),
+        (OwnedValue::Object(entries), Some((segment, rest))) => {
+            for (key, entry) in entries {
+                if key == segment {
+                    collect_json_path_values(entry, rest, values);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Type of a schema field, as reported by `QuickwitSplitReader::fields`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldKind {
+    Text,
+    U64,
+    I64,
+    F64,
+    Bool,
+    Date,
+    Bytes,
+    Facet,
+    Json,
+    IpAddr,
+}
+
+impl FieldKind {
+    /// Maps a Tantivy field type to its kind
+    pub fn of(field_type: &FieldType) -> Self {
+        match field_type {
+            FieldType::Str(_) => FieldKind::Text,
+            FieldType::U64(_) => FieldKind::U64,
+            FieldType::I64(_) => FieldKind::I64,
+            FieldType::F64(_) => FieldKind::F64,
+            FieldType::Bool(_) => FieldKind::Bool,
+            FieldType::Date(_) => FieldKind::Date,
+            FieldType::Bytes(_) => FieldKind::Bytes,
+            FieldType::Facet(_) => FieldKind::Facet,
+            FieldType::JsonObject(_) => FieldKind::Json,
+            FieldType::IpAddr(_) => FieldKind::IpAddr,
+        }
+    }
+    
+    /// Gets the lowercase name of the kind, e.g. `text` or `u64`
+    pub fn name(&self) -> &'static str {
+        match self {
+            FieldKind::Text => "text",
+            FieldKind::U64 => "u64",
+            FieldKind::I64 => "i64",
+            FieldKind::F64 => "f64",
+            FieldKind::Bool => "bool",
+            FieldKind::Date => "date",
+            FieldKind::Bytes => "bytes",
+            FieldKind::Facet => "facet",
+            FieldKind::Json => "json",
+            FieldKind::IpAddr => "ip_addr",
+        }
+    }
+}
+
+/// A schema field, as returned by `QuickwitSplitReader::fields`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldDescriptor {
+    /// Field name
+    pub name: String,
+    /// Field type
+    pub field_type: FieldKind,
+    /// Whether values are kept in the doc store
+    pub stored: bool,
+    /// Whether the field is searchable
+    pub indexed: bool,
+    /// Whether the field has a columnar fast field
+    pub fast: bool,
+    /// Dotted sub-paths holding values, for indexed JSON fields; empty otherwise
+    pub json_paths: Vec<String>,
+}
+
+/// A compressed block of a segment's doc store
+struct StoreBlock {
+    /// Split-wide doc ids of the documents in the block
+    docs: std::ops::Range<u32>,
+    /// Store file of the segment holding the block
+    store_file: String,
+    /// Byte range of the block in the store file
+    byte_range: ByteRange,
+}
+
+/// Lazily advancing cursor over a term's posting list
+pub struct PostingCursor {
+    /// Postings of the segments holding the term, in segment order, not yet exhausted
+    segments: VecDeque<SegmentPostingCursor>,
+}
+
+/// Postings of a term in one segment
+struct SegmentPostingCursor {
+    postings: SegmentPostings,
+    /// Deleted documents to skip, if the segment has any
+    alive_bitset: Option<AliveBitSet>,
+    /// Split doc id of the segment's first document
+    doc_base: u32,
+}
+
+impl PostingCursor {
+    /// Returns up to `max` further doc ids, or an empty vec once exhausted
+    pub fn next_batch(&mut self, max: usize) -> Result<Vec<u32>> {
+        let size_hint = self.segments.iter().map(|segment| segment.postings.size_hint() as usize).sum::<usize>();
+        let mut batch = Vec::with_capacity(max.min(size_hint));
+        while batch.len() < max {
+            let segment = match self.segments.front_mut() {
+                Some(segment) => segment,
+                None => break,
+            };
+            let doc = segment.postings.doc();
+            if doc == TERMINATED {
+                self.segments.pop_front();
+                continue;
+            }
+            
+            let is_alive = segment.alive_bitset.as_ref()
+                .is_none_or(|alive_bitset| alive_bitset.is_alive(doc));
+            if is_alive {
+                batch.push(segment.doc_base + doc);
+            }
+            segment.postings.advance();
+        }
+        
+        Ok(batch)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+    
+    /// Builds a reader over `split_path` without loading its hotcache
+    fn unloaded_reader(split_path: &Path) -> QuickwitSplitReader {
+        let storage: Arc<dyn SplitStorage> = if split_path.is_file() {
+            Arc::new(SplitFileStorage::open(split_path).unwrap())
+        } else {
+            Arc::new(LocalStorage::new(split_path))
+        };
+        unloaded_reader_over(split_path, storage)
+    }
+    
+    fn unloaded_reader_over(split_path: &Path, storage: Arc<dyn SplitStorage>) -> QuickwitSplitReader {
+        QuickwitSplitReader {
+            split_path: split_path.to_path_buf(),
+            storage,
+            hotcache: None,
+            #[cfg(feature = "quickwit-compat")]
+            quickwit_hotcache: None,
+            fingerprint: None,
+            index: CacheSlot::new(),
+            registered_tokenizers: Mutex::default(),
+            read_options: ReadOptions::default(),
+            pinned_columns: Mutex::default(),
+            encryption_key: None,
+            decompressed_file: None,
+            compression: None,
+        }
+    }
+    
+    #[test]
+    fn test_is_segment_file() {
+        let temp_dir = TempDir::new().unwrap();
+        let reader = unloaded_reader(temp_dir.path());
+        
+        assert!(reader.is_segment_file("12345678-1234-1234-1234-123456789abc.store"));
+        assert!(reader.is_segment_file("87654321-4321-4321-4321-cba987654321.term"));
+        assert!(!reader.is_segment_file("not-a-uuid.store"));
+        assert!(!reader.is_segment_file("12345678-1234-1234-1234-123456789abc.unknown"));
+        
+        // Tantivy's own names, delete files carrying their opstamp
+        assert!(reader.is_segment_file("0c6c3e7e5d6c4f1c9a4e2b1d3f5a7c9e.fieldnorm"));
+        assert!(reader.is_segment_file("0c6c3e7e5d6c4f1c9a4e2b1d3f5a7c9e.123.del"));
+        assert!(reader.is_segment_file("12345678-1234-1234-1234-123456789abc.0.del"));
+        assert!(!reader.is_segment_file("0c6c3e7e5d6c4f1c9a4e2b1d3f5a7c9e.del"));
+        assert!(!reader.is_segment_file("0c6c3e7e5d6c4f1c9a4e2b1d3f5a7c9e.x1.del"));
+        assert!(!reader.is_segment_file("not-a-segment-id.123.del"));
+    }
+    
+    /// Builds a reader over a split with bool and date fast fields
+    fn bool_and_date_reader(temp_dir: &TempDir) -> QuickwitSplitReader {
+        use tantivy::schema::{DateOptions, DateTimePrecision, SchemaBuilder, FAST, STORED, TEXT};
+        use tantivy::DateTime;
+        
+        let mut schema_builder = SchemaBuilder::default();
+        let flag = schema_builder.add_bool_field("flag", FAST);
+        let timestamp = schema_builder.add_date_field(
+            "timestamp",
+            DateOptions::from(FAST).set_precision(DateTimePrecision::Milliseconds),
+        );
+        let title = schema_builder.add_text_field("title", TEXT | STORED);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(
+            flag => true, timestamp => DateTime::from_timestamp_millis(1_700_000_000_123), title => "a"
+        )).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "b")).unwrap();
+        index_writer.add_document(tantivy::doc!(
+            flag => false, timestamp => DateTime::from_timestamp_millis(-5), title => "c"
+        )).unwrap();
+        index_writer.commit().unwrap();
+        
+        unloaded_reader(temp_dir.path())
+    }
+    
+    #[test]
+    fn test_bool_fast_field_encodes_one_byte_per_doc() {
+        let temp_dir = TempDir::new().unwrap();
+        let reader = bool_and_date_reader(&temp_dir);
+        
+        assert_eq!(reader.get_fast_field_data("flag", 0..3, FastFieldFormat::RawLe).unwrap(), vec![1, 0, 0]);
+        assert_eq!(reader.get_fast_field_data("flag", 2..3, FastFieldFormat::RawLe).unwrap(), vec![0]);
+    }
+    
+    #[test]
+    fn test_fast_field_range_beyond_doc_count_is_rejected() {
+        let temp_dir = TempDir::new().unwrap();
+        let reader = bool_and_date_reader(&temp_dir);
+        
+        match reader.get_fast_field_data("flag", 1..4, FastFieldFormat::RawLe) {
+            Err(SplitsError::FieldError(msg)) => {
+                assert!(msg.contains("1..4"), "{}", msg);
+                assert!(msg.contains("within 0..3"), "{}", msg);
+            }
+            other => panic!("Expected FieldError, got {:?}", other),
+        }
+        assert!(matches!(reader.get_fast_fields(&["flag", "timestamp"], 0..4), Err(SplitsError::FieldError(_))));
+        assert_eq!(reader.get_fast_field_data("flag", 3..3, FastFieldFormat::RawLe).unwrap(), Vec::<u8>::new());
+    }
+    
+    #[test]
+    fn test_fast_field_column_ptr() {
+        let temp_dir = TempDir::new().unwrap();
+        let reader = bool_and_date_reader(&temp_dir);
+        
+        let (address, len) = reader.fast_field_column_ptr("timestamp").unwrap();
+        assert!(address != 0 && len > 0);
+        // The column lies within the mapped fast field file, and stays pinned
+        let column = unsafe { std::slice::from_raw_parts(address as *const u8, len) }.to_vec();
+        let fast_file = reader.storage.list_files().unwrap().into_iter()
+            .find(|name| name.ends_with(".fast"))
+            .unwrap();
+        let fast_data = reader.storage.read_file(&fast_file).unwrap();
+        assert!(fast_data.windows(len).any(|window| window == column.as_slice()));
+        assert_eq!(reader.fast_field_column_ptr("timestamp").unwrap(), (address, len));
+        
+        assert!(matches!(reader.fast_field_column_ptr("title"), Err(SplitsError::FieldError(_))));
+        assert!(matches!(reader.fast_field_column_ptr("missing"), Err(SplitsError::FieldError(_))));
+        
+        // Storages that don't map the split files can't hand out stable addresses
+        let files = reader.storage.list_files().unwrap();
+        let directory = reader.storage.directory().unwrap();
+        let reader = unloaded_reader_over(temp_dir.path(), Arc::new(DirectoryStorage::new(directory, files)));
+        assert!(matches!(reader.fast_field_column_ptr("timestamp"), Err(SplitsError::InvalidOperation(_))));
+    }
+    
+    #[test]
+    fn test_date_fast_field_encodes_millis() {
+        let temp_dir = TempDir::new().unwrap();
+        let reader = bool_and_date_reader(&temp_dir);
+        
+        let data = reader.get_fast_field_data("timestamp", 0..3, FastFieldFormat::RawLe).unwrap();
+        let millis: Vec<i64> = data.chunks(8)
+            .map(|chunk| i64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+        assert_eq!(millis, vec![1_700_000_000_123, 0, -5]);
+        
+        let fields = reader.get_fast_fields(&["flag", "timestamp"], 1..2).unwrap();
+        assert_eq!(fields["flag"], vec![0]);
+        assert_eq!(fields["timestamp"], vec![0; 8]);
+    }
+    
+    #[test]
+    fn test_fast_field_formats_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let reader = bool_and_date_reader(&temp_dir);
+        
+        // RawLe is the compatible default
+        assert_eq!(FastFieldFormat::default(), FastFieldFormat::RawLe);
+        assert_eq!(FastFieldFormat::from_code(0).unwrap(), FastFieldFormat::RawLe);
+        assert!(matches!(FastFieldFormat::from_code(3), Err(SplitsError::InvalidOperation(_))));
+        
+        let raw = reader.get_fast_field_data("timestamp", 0..3, FastFieldFormat::RawLe).unwrap();
+        let millis: Vec<i64> = raw.chunks(8)
+            .map(|chunk| i64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+        assert_eq!(millis, vec![1_700_000_000_123, 0, -5]);
+        
+        let varint = reader.get_fast_field_data("timestamp", 0..3, FastFieldFormat::Varint).unwrap();
+        let mut decoded = Vec::new();
+        let (mut value, mut shift) = (0u64, 0);
+        for byte in varint {
+            value |= u64::from(byte & 0x7f) << shift;
+            shift += 7;
+            if byte & 0x80 == 0 {
+                decoded.push((value >> 1) as i64 ^ -((value & 1) as i64));
+                value = 0;
+                shift = 0;
+            }
+        }
+        assert_eq!(decoded, vec![1_700_000_000_123, 0, -5]);
+        
+        // Validity bitmap padded to 8 bytes, then one 8-byte value per document
+        let arrow = reader.get_fast_field_data("timestamp", 0..3, FastFieldFormat::Arrow).unwrap();
+        assert_eq!(arrow.len(), 8 + 3 * 8);
+        assert_eq!(arrow[0], 0b101);
+        assert!(arrow[1..8].iter().all(|byte| *byte == 0));
+        let millis: Vec<i64> = arrow[8..].chunks(8)
+            .map(|chunk| i64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+        assert_eq!(millis, vec![1_700_000_000_123, 0, -5]);
+        
+        // Bool values are bit-packed after the validity bitmap
+        let arrow = reader.get_fast_field_data("flag", 0..3, FastFieldFormat::Arrow).unwrap();
+        assert_eq!(arrow, [&[0b101u8, 0, 0, 0, 0, 0, 0, 0][..], &[0b001, 0, 0, 0, 0, 0, 0, 0]].concat());
+        assert_eq!(reader.get_fast_field_data("flag", 0..3, FastFieldFormat::Varint).unwrap(), vec![1, 0, 0]);
+    }
+    
+    #[cfg(feature = "arrow")]
+    #[test]
+    fn test_to_arrow() {
+        use arrow::array::{Array, AsArray};
+        use arrow::datatypes::TimestampMillisecondType;
+        use arrow::ipc::reader::StreamReader;
+        
+        let temp_dir = TempDir::new().unwrap();
+        let reader = bool_and_date_reader(&temp_dir);
+        
+        let bytes = reader.to_arrow(&["title", "flag", "timestamp"]).unwrap();
+        let batch = StreamReader::try_new(bytes.as_slice(), None).unwrap().next().unwrap().unwrap();
+        assert_eq!(batch.num_rows(), 3);
+        assert_eq!(batch.schema().field(1).name(), "flag");
+        assert_eq!(batch.column(0).as_string::<i32>().value(2), "c");
+        
+        let flags = batch.column(1).as_boolean();
+        assert!(flags.value(0));
+        assert!(flags.is_null(1));
+        
+        let timestamps = batch.column(2).as_primitive::<TimestampMillisecondType>();
+        assert_eq!(timestamps.value(0), 1_700_000_000_123);
+        assert!(timestamps.is_null(1));
+        
+        assert!(matches!(reader.to_arrow(&["title", "missing"]), Err(SplitsError::FieldError(_))));
+    }
+    
+    #[test]
+    fn test_fast_field_rejects_unsupported_encoding() {
+        let temp_dir = TempDir::new().unwrap();
+        let reader = bool_and_date_reader(&temp_dir);
+        
+        // Not a fast field
+        assert!(matches!(reader.get_fast_field_data("title", 0..1, FastFieldFormat::RawLe), Err(SplitsError::FieldError(_))));
+        
+        let bytes_type = tantivy::schema::FieldType::Bytes(Default::default());
+        match FastFieldEncoding::for_field("payload", &bytes_type) {
+            Err(SplitsError::FieldError(msg)) => assert!(msg.contains("type bytes")),
+            other => panic!("Expected FieldError, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_segment_meta_json_reconstructed_from_files() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("12345678-1234-1234-1234-123456789abc.store"), b"").unwrap();
+        fs::write(temp_dir.path().join("12345678-1234-1234-1234-123456789abc.term"), b"").unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        
+        let meta: serde_json::Value = serde_json::from_str(&reader.segment_meta_json().unwrap()).unwrap();
+        assert_eq!(meta["segment_id"], "12345678-1234-1234-1234-123456789abc");
+        assert_eq!(meta["reconstructed"], true);
+        assert!(meta["deletes"].is_null());
+    }
+    
+    #[test]
+    fn test_fingerprint_changes_when_store_file_changes() {
+        let temp_dir = TempDir::new().unwrap();
+        let store_path = temp_dir.path().join("12345678-1234-1234-1234-123456789abc.store");
+        fs::write(&store_path, b"original").unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        let before = reader.compute_fingerprint().unwrap();
+        
+        fs::write(&store_path, b"replaced with new content").unwrap();
+        let after = reader.compute_fingerprint().unwrap();
+        
+        assert_ne!(before, after);
+    }
+    
+    #[test]
+    fn test_posting_cursor_pages_through_postings() {
+        use tantivy::schema::{SchemaBuilder, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        for i in 0..10 {
+            let text = if i % 2 == 0 { "quickwit split" } else { "other" };
+            index_writer.add_document(tantivy::doc!(title => text)).unwrap();
+        }
+        index_writer.commit().unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        let mut cursor = reader.posting_cursor("title", "quickwit").unwrap();
+        
+        assert_eq!(cursor.next_batch(3).unwrap(), vec![0, 2, 4]);
+        assert_eq!(cursor.next_batch(3).unwrap(), vec![6, 8]);
+        assert!(cursor.next_batch(3).unwrap().is_empty());
+        
+        let mut missing = reader.posting_cursor("title", "absent").unwrap();
+        assert!(missing.next_batch(3).unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_posting_cursor_spans_segments() {
+        use tantivy::schema::{SchemaBuilder, Value, STORED, STRING};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let tag = schema_builder.add_text_field("tag", STRING | STORED);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.set_merge_policy(Box::new(tantivy::indexer::NoMergePolicy));
+        for tags in [["a", "b", "a"], ["b", "b", "b"], ["a", "deleted", "a"]] {
+            for value in tags {
+                index_writer.add_document(tantivy::doc!(tag => value)).unwrap();
+            }
+            index_writer.commit().unwrap();
+        }
+        index_writer.delete_term(Term::from_field_text(tag, "deleted"));
+        index_writer.commit().unwrap();
+        drop(index_writer);
+        
+        // Segment order isn't commit order, so expected doc ids follow the reader's segments
+        let reader = unloaded_reader(temp_dir.path());
+        let searcher = reader.searcher().unwrap();
+        assert_eq!(searcher.segment_readers().len(), 3);
+        let mut expected = Vec::new();
+        let mut doc_base = 0;
+        for segment_reader in searcher.segment_readers() {
+            let store_reader = segment_reader.get_store_reader(1).unwrap();
+            for doc_id in segment_reader.doc_ids_alive() {
+                let doc: TantivyDocument = store_reader.get(doc_id).unwrap();
+                if doc.get_first(tag).and_then(|value| value.as_str()) == Some("a") {
+                    expected.push(doc_base + doc_id);
+                }
+            }
+            doc_base += segment_reader.max_doc();
+        }
+        assert_eq!(expected.len(), 4);
+        
+        let mut cursor = reader.posting_cursor("tag", "a").unwrap();
+        let mut doc_ids = cursor.next_batch(3).unwrap();
+        assert_eq!(doc_ids.len(), 3);
+        doc_ids.extend(cursor.next_batch(3).unwrap());
+        assert_eq!(doc_ids, expected);
+        assert!(cursor.next_batch(3).unwrap().is_empty());
+        assert_eq!(reader.posting_cursor("tag", "a").unwrap().next_batch(10).unwrap(), expected);
+    }
+    
+    #[test]
+    fn test_accessors_use_split_doc_ids_across_segments() {
+        use crate::split_generator::{MergePolicy, QuickwitSplitGenerator, SplitConfig};
+        use tantivy::schema::{SchemaBuilder, Value, FAST, STORED, STRING, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let tag = schema_builder.add_text_field("tag", STRING | STORED);
+        let body = schema_builder.add_text_field("body", TEXT | STORED);
+        let count = schema_builder.add_u64_field("count", FAST | STORED);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.set_merge_policy(Box::new(tantivy::indexer::NoMergePolicy));
+        for (segment, tags) in [["a", "b"], ["c", "deleted"], ["d", "e"]].iter().enumerate() {
+            for (i, value) in tags.iter().enumerate() {
+                let n = (segment * 2 + i) as u64;
+                let words = vec!["word"; n as usize + 1].join(" ");
+                index_writer.add_document(tantivy::doc!(tag => *value, body => words, count => n)).unwrap();
+            }
+            index_writer.commit().unwrap();
+        }
+        index_writer.delete_term(Term::from_field_text(tag, "deleted"));
+        index_writer.commit().unwrap();
+        drop(index_writer);
+        
+        let split_path = temp_dir.path().join("split");
+        QuickwitSplitGenerator::new(index, 1000).unwrap()
+            .with_config(SplitConfig { merge_policy: MergePolicy::NoMerge, legacy_directory_layout: true, ..SplitConfig::default() })
+            .generate_split(&split_path)
+            .unwrap();
+        let reader = QuickwitSplitReader::open(&split_path).unwrap();
+        let searcher = reader.searcher().unwrap();
+        assert_eq!(searcher.segment_readers().len(), 3);
+        
+        // Stored count of each split doc id, None for the deleted one, in the reader's segment order
+        let mut counts = Vec::new();
+        for segment_reader in searcher.segment_readers() {
+            let store_reader = segment_reader.get_store_reader(1).unwrap();
+            for doc_id in 0..segment_reader.max_doc() {
+                let doc: TantivyDocument = store_reader.get(doc_id).unwrap();
+                let alive = !segment_reader.is_deleted(doc_id);
+                counts.push(alive.then(|| doc.get_first(count).and_then(|value| value.as_u64()).unwrap()));
+            }
+        }
+        assert_eq!(counts.len(), 6);
+        
+        let mut alive_doc_ids = Vec::new();
+        for (doc_id, expected) in counts.iter().enumerate() {
+            let doc_id = doc_id as u32;
+            match expected {
+                Some(n) => {
+                    alive_doc_ids.push(doc_id);
+                    assert_eq!(reader.get_document(doc_id).unwrap()["count"], serde_json::json!(n));
+                    assert_eq!(reader.term_freq("body", "word", doc_id).unwrap() as u64, n + 1);
+                    assert_eq!(reader.term_positions("body", "word", doc_id).unwrap().len() as u64, n + 1);
+                    assert_eq!(reader.fieldnorm("body", doc_id).unwrap() as u64, n + 1);
+                }
+                None => {
+                    assert!(matches!(reader.get_document(doc_id), Err(SplitsError::InvalidOperation(_))));
+                    assert_eq!(reader.term_freq("body", "word", doc_id).unwrap(), 0);
+                }
+            }
+            let block = reader.read_store_block(reader.store_block_of(doc_id).unwrap()).unwrap();
+            assert!(!block.is_empty());
+        }
+        let posting_doc_ids: Vec<u32> = reader.read_posting_list_with_freqs("body", "word").unwrap()
+            .into_iter()
+            .map(|(doc_id, _)| doc_id)
+            .collect();
+        assert_eq!(posting_doc_ids, alive_doc_ids);
+        assert_eq!(reader.alive_bitset().unwrap(), vec![alive_doc_ids.iter().map(|doc_id| 1u8 << doc_id).sum::<u8>()]);
+        assert!(matches!(reader.get_document(6), Err(SplitsError::InvalidOperation(_))));
+        
+        // Each segment's store file holds its own blocks
+        let store_files: BTreeSet<String> = (0..reader.store_block_ranges().unwrap().len())
+            .map(|idx| reader.store_block_file(idx).unwrap())
+            .collect();
+        assert_eq!(store_files.len(), 3);
+        
+        let data = reader.get_fast_field_data("count", 1..5, FastFieldFormat::RawLe).unwrap();
+        let values: Vec<u64> = data.chunks(8).map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap())).collect();
+        let expected: Vec<u64> = counts[1..5].iter().map(|n| n.unwrap_or(3)).collect();
+        assert_eq!(values, expected);
+        assert!(matches!(reader.fast_field_column_ptr("count"), Err(SplitsError::InvalidOperation(_))));
+    }
+
+    #[test]
+    fn test_term_freq() {
+        use tantivy::schema::{SchemaBuilder, STRING, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let body = schema_builder.add_text_field("body", TEXT);
+        let tag = schema_builder.add_text_field("tag", STRING);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "split split quickwit split", tag => "a")).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "quickwit", tag => "b")).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "tantivy tantivy", tag => "c")).unwrap();
+        index_writer.commit().unwrap();
+        index_writer.delete_term(Term::from_field_text(tag, "a"));
+        index_writer.commit().unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        assert_eq!(reader.term_freq("body", "quickwit", 1).unwrap(), 1);
+        assert_eq!(reader.term_freq("body", "tantivy", 2).unwrap(), 2);
+        assert_eq!(reader.term_freq("body", "split", 1).unwrap(), 0);
+        assert_eq!(reader.term_freq("body", "absent", 0).unwrap(), 0);
+        
+        // Documents before the term's first posting, and deleted ones, don't contain it
+        assert_eq!(reader.term_freq("body", "tantivy", 0).unwrap(), 0);
+        assert_eq!(reader.term_freq("body", "split", 0).unwrap(), 0);
+        
+        // STRING fields are indexed without frequencies
+        assert!(matches!(reader.term_freq("tag", "a", 0), Err(SplitsError::FieldError(_))));
+    }
+    
+    #[test]
+    fn test_sample() {
+        use tantivy::schema::{SchemaBuilder, STORED, STRING};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let id = schema_builder.add_text_field("id", STRING | STORED);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        for i in 0..100 {
+            index_writer.add_document(tantivy::doc!(id => i.to_string())).unwrap();
+        }
+        index_writer.commit().unwrap();
+        index_writer.delete_term(Term::from_field_text(id, "7"));
+        index_writer.commit().unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        let sample = reader.sample(10, 42).unwrap();
+        assert_eq!(sample.len(), 10);
+        assert_eq!(reader.sample(10, 42).unwrap(), sample);
+        assert_ne!(reader.sample(10, 43).unwrap(), sample);
+        
+        let ids: BTreeSet<&str> = sample.iter().map(|doc| doc["id"].as_str().unwrap()).collect();
+        assert_eq!(ids.len(), 10);
+        
+        // Asking for more than there are returns every live document
+        let all = reader.sample(1000, 42).unwrap();
+        assert_eq!(all.len(), 99);
+        assert!(all.iter().all(|doc| doc["id"] != "7"));
+        assert!(reader.sample(0, 42).unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_fieldnorm() {
+        use tantivy::schema::{SchemaBuilder, TextFieldIndexing, TextOptions, STORED, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let body = schema_builder.add_text_field("body", TEXT);
+        let without_norms = TextOptions::default()
+            .set_indexing_options(TextFieldIndexing::default().set_fieldnorms(false));
+        let raw = schema_builder.add_text_field("raw", without_norms);
+        let stored = schema_builder.add_text_field("stored", STORED);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "one two three", raw => "a b", stored => "x")).unwrap();
+        index_writer.add_document(tantivy::doc!(raw => "c")).unwrap();
+        index_writer.commit().unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        assert_eq!(reader.fieldnorm("body", 0).unwrap(), 3);
+        assert_eq!(reader.fieldnorm("body", 1).unwrap(), 0);
+        assert!(matches!(reader.fieldnorm("body", 2), Err(SplitsError::InvalidOperation(_))));
+        
+        assert!(matches!(reader.fieldnorm("raw", 0), Err(SplitsError::FieldError(_))));
+        assert!(matches!(reader.fieldnorm("stored", 0), Err(SplitsError::FieldError(_))));
+        assert!(matches!(reader.fieldnorm("missing", 0), Err(SplitsError::FieldError(_))));
+    }
+    
+    #[test]
+    fn test_read_posting_list_with_freqs() {
+        use tantivy::schema::{SchemaBuilder, STRING, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let body = schema_builder.add_text_field("body", TEXT);
+        let tag = schema_builder.add_text_field("tag", STRING);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "split split quickwit split", tag => "a")).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "quickwit", tag => "b")).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "split", tag => "c")).unwrap();
+        index_writer.commit().unwrap();
+        index_writer.delete_term(Term::from_field_text(tag, "c"));
+        index_writer.commit().unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        assert_eq!(reader.read_posting_list_with_freqs("body", "split").unwrap(), vec![(0, 3)]);
+        assert_eq!(reader.read_posting_list_with_freqs("body", "quickwit").unwrap(), vec![(0, 1), (1, 1)]);
+        assert!(reader.read_posting_list_with_freqs("body", "absent").unwrap().is_empty());
+        
+        // STRING fields are indexed without frequencies
+        assert!(matches!(reader.read_posting_list_with_freqs("tag", "a"), Err(SplitsError::FieldError(_))));
+    }
+    
+    #[test]
+    fn test_empty_split_reads_return_empty() {
+        use crate::split_generator::QuickwitSplitGenerator;
+        use tantivy::schema::{SchemaBuilder, FAST, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        schema_builder.add_text_field("title", TEXT);
+        schema_builder.add_u64_field("id", FAST);
+        let split_path = temp_dir.path().join("empty_split");
+        QuickwitSplitGenerator::new(Index::create_in_ram(schema_builder.build()), 1000)
+            .unwrap()
+            .generate_split(&split_path)
+            .unwrap();
+        
+        let reader = QuickwitSplitReader::open(&split_path).unwrap();
+        assert!(reader.is_empty_split().unwrap());
+        assert!(reader.read_posting_list("title", "quickwit").unwrap().is_empty());
+        assert!(reader.get_fast_field_data("id", 0..0, FastFieldFormat::Arrow).unwrap().is_empty());
+        assert!(reader.get_fast_fields(&["id"], 0..0).unwrap()["id"].is_empty());
+        assert!(reader.field_terms("title").unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_field_terms() {
+        use tantivy::schema::{SchemaBuilder, FAST, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        schema_builder.add_u64_field("id", FAST);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "split quickwit")).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "quickwit tantivy")).unwrap();
+        index_writer.commit().unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        assert!(!reader.is_empty_split().unwrap());
+        assert_eq!(reader.field_terms("title").unwrap(), vec!["quickwit", "split", "tantivy"]);
+        assert!(matches!(reader.field_terms("id"), Err(SplitsError::FieldError(_))));
+    }
+    
+    #[test]
+    fn test_terms_with_prefix() {
+        use tantivy::schema::{SchemaBuilder, FAST, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        schema_builder.add_u64_field("id", FAST);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "quick quickwit queue")).unwrap();
+        index_writer.commit().unwrap();
+        index_writer.add_document(tantivy::doc!(title => "quiet quickstart split")).unwrap();
+        index_writer.commit().unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        assert_eq!(reader.terms_with_prefix("title", "qui", 10).unwrap(), vec!["quick", "quickstart", "quickwit", "quiet"]);
+        assert_eq!(reader.terms_with_prefix("title", "qui", 2).unwrap(), vec!["quick", "quickstart"]);
+        assert_eq!(reader.terms_with_prefix("title", "quickw", 10).unwrap(), vec!["quickwit"]);
+        assert_eq!(reader.terms_with_prefix("title", "", 2).unwrap(), vec!["queue", "quick"]);
+        assert!(reader.terms_with_prefix("title", "z", 10).unwrap().is_empty());
+        assert!(reader.terms_with_prefix("title", "qui", 0).unwrap().is_empty());
+        assert!(matches!(reader.terms_with_prefix("id", "1", 10), Err(SplitsError::FieldError(_))));
+    }
+    
+    #[test]
+    fn test_term_dict_bytes() {
+        use crate::split_generator::QuickwitSplitGenerator;
+        use tantivy::directory::FileSlice;
+        use tantivy::schema::{SchemaBuilder, FAST, TEXT};
+        use tantivy::termdict::TermDictionary;
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let body = schema_builder.add_text_field("body", TEXT);
+        schema_builder.add_u64_field("id", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "split quickwit", body => "tantivy")).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "quickwit search", body => "index")).unwrap();
+        index_writer.commit().unwrap();
+        
+        let split_path = temp_dir.path().join("split");
+        QuickwitSplitGenerator::new(index, 1000).unwrap().generate_split(&split_path).unwrap();
+        
+        // The hotcache records the range, and locating it from the footer agrees
+        let reader = QuickwitSplitReader::open(&split_path).unwrap();
+        let field_metadata = reader.get_hotcache_info().unwrap().field_metadata().unwrap();
+        assert!(field_metadata["title"].term_dict_range.is_some());
+        let bytes = reader.term_dict_bytes("title").unwrap();
+        assert_eq!(unloaded_reader(&split_path).term_dict_bytes("title").unwrap(), bytes);
+        
+        assert_eq!(u32::from_le_bytes(bytes[bytes.len() - 4..].try_into().unwrap()), 1);
+        let term_dict = TermDictionary::open(FileSlice::from(bytes)).unwrap();
+        assert_eq!(term_dict.num_terms(), 3);
+        assert!(term_dict.term_ord(b"quickwit").unwrap().is_some());
+        assert!(term_dict.term_ord(b"tantivy").unwrap().is_none());
+        
+        let body_dict = TermDictionary::open(FileSlice::from(reader.term_dict_bytes("body").unwrap())).unwrap();
+        assert!(body_dict.term_ord(b"tantivy").unwrap().is_some());
+        
+        assert!(matches!(reader.term_dict_bytes("id"), Err(SplitsError::FieldError(_))));
+    }
+    
+    #[test]
+    fn test_opstamp() {
+        use crate::split_generator::QuickwitSplitGenerator;
+        use tantivy::schema::{SchemaBuilder, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "split quickwit")).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "tantivy")).unwrap();
+        let opstamp = index_writer.commit().unwrap();
+        
+        let split_path = temp_dir.path().join("split");
+        let metadata = QuickwitSplitGenerator::new(index, 1000).unwrap().generate_split(&split_path).unwrap();
+        assert_eq!(metadata.opstamp, opstamp);
+        
+        let reader = QuickwitSplitReader::open(&split_path).unwrap();
+        assert_eq!(reader.get_hotcache_info().unwrap().opstamp(), Some(opstamp));
+        assert_eq!(reader.opstamp().unwrap(), opstamp);
+        
+        // Without a hotcache, the embedded meta.json agrees
+        assert_eq!(unloaded_reader(&split_path).opstamp().unwrap(), opstamp);
+    }
+    
+    #[test]
+    fn test_facet_counts() {
+        use crate::split_generator::QuickwitSplitGenerator;
+        use tantivy::schema::{FacetOptions, SchemaBuilder, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let category = schema_builder.add_facet_field("category", FacetOptions::default());
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        for path in ["/books/fiction", "/books/fiction", "/books/science", "/music/jazz"] {
+            index_writer.add_document(tantivy::doc!(
+                category => Facet::from_text(path).unwrap(),
+                title => "item"
+            )).unwrap();
+        }
+        index_writer.commit().unwrap();
+        
+        let split_path = temp_dir.path().join("split");
+        QuickwitSplitGenerator::new(index, 1000).unwrap().generate_split(&split_path).unwrap();
+        let reader = QuickwitSplitReader::open(&split_path).unwrap();
+        
+        assert_eq!(reader.facet_counts("category", "", 10).unwrap(), vec![
+            ("/books".to_string(), 3),
+            ("/music".to_string(), 1),
+        ]);
+        assert_eq!(reader.facet_counts("category", "/books", 1).unwrap(), vec![("/books/fiction".to_string(), 2)]);
+        assert!(reader.facet_counts("category", "/movies", 10).unwrap().is_empty());
+        
+        assert!(matches!(reader.facet_counts("title", "/", 10), Err(SplitsError::FieldError(_))));
+        assert!(matches!(reader.facet_counts("category", "books", 10), Err(SplitsError::Query(_))));
+    }
+    
+    #[test]
+    fn test_to_tar_archives_every_split_file() {
+        use crate::split_generator::QuickwitSplitGenerator;
+        use tantivy::schema::{SchemaBuilder, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "split quickwit")).unwrap();
+        index_writer.commit().unwrap();
+        
+        let split_path = temp_dir.path().join("split");
+        QuickwitSplitGenerator::new(index, 1000).unwrap().generate_split(&split_path).unwrap();
+        let reader = QuickwitSplitReader::open(&split_path).unwrap();
+        let mut archive = Vec::new();
+        reader.to_tar(&mut archive).unwrap();
+        assert_eq!(archive.len() % TAR_BLOCK_SIZE, 0);
+        
+        // Walk the archive's headers, checking each entry against the split file
+        let mut archived = Vec::new();
+        let mut offset = 0;
+        while archive[offset..offset + TAR_BLOCK_SIZE].iter().any(|byte| *byte != 0) {
+            let header = &archive[offset..offset + TAR_BLOCK_SIZE];
+            assert_eq!(&header[257..263], b"ustar\0");
+            let checksum = u64::from_str_radix(std::str::from_utf8(&header[148..154]).unwrap(), 8).unwrap();
+            let unchecked: u64 = header.iter().enumerate()
+                .map(|(i, byte)| if (148..156).contains(&i) { b' ' as u64 } else { *byte as u64 })
+                .sum();
+            assert_eq!(checksum, unchecked);
+            
+            let name_len = header[..100].iter().position(|byte| *byte == 0).unwrap_or(100);
+            let name = std::str::from_utf8(&header[..name_len]).unwrap().to_string();
+            let len = usize::from_str_radix(std::str::from_utf8(&header[124..135]).unwrap(), 8).unwrap();
+            let content = &archive[offset + TAR_BLOCK_SIZE..offset + TAR_BLOCK_SIZE + len];
+            assert_eq!(content, reader.storage.read_file(&name).unwrap().as_slice());
+            
+            archived.push(name);
+            offset += TAR_BLOCK_SIZE + len.div_ceil(TAR_BLOCK_SIZE) * TAR_BLOCK_SIZE;
+        }
+        assert_eq!(archive.len(), offset + 2 * TAR_BLOCK_SIZE);
+        
+        let mut expected = reader.storage.list_files().unwrap();
+        expected.sort();
+        assert_eq!(archived, expected);
+        assert!(archived.iter().any(|name| name.ends_with(".store")));
+    }
+    
+    #[test]
+    fn test_index_stats() {
+        use crate::split_generator::QuickwitSplitGenerator;
+        use tantivy::schema::{SchemaBuilder, FAST, STRING, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let tag = schema_builder.add_text_field("tag", STRING);
+        let id = schema_builder.add_u64_field("id", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "split quickwit", tag => "a", id => 1u64)).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "quickwit search", tag => "a", id => 2u64)).unwrap();
+        index_writer.commit().unwrap();
+        
+        let split_path = temp_dir.path().join("split");
+        QuickwitSplitGenerator::new(index, 1000).unwrap().generate_split(&split_path).unwrap();
+        let stats = QuickwitSplitReader::open(&split_path).unwrap().index_stats().unwrap();
+        assert_eq!(stats.num_docs, 2);
+        
+        // `id` is only a fast field
+        assert_eq!(stats.fields.keys().collect::<Vec<_>>(), vec!["tag", "title"]);
+        let title_stats = &stats.fields["title"];
+        assert_eq!(title_stats.num_terms, 3);
+        assert_eq!(title_stats.total_postings, 4);
+        assert!((title_stats.avg_doc_freq - 4.0 / 3.0).abs() < 1e-9);
+        assert!(title_stats.term_dict_bytes > 0);
+        assert!(title_stats.postings_bytes > 0);
+        assert!(title_stats.positions_bytes > 0);
+        
+        let tag_stats = &stats.fields["tag"];
+        assert_eq!((tag_stats.num_terms, tag_stats.total_postings), (1, 2));
+        assert_eq!(tag_stats.positions_bytes, 0);
+        
+        let json: serde_json::Value = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["fields"]["title"]["num_terms"], 3);
+    }
+    
+    #[test]
+    fn test_open_split_file() {
+        use crate::split_generator::{QuickwitSplitGenerator, SplitConfig};
+        use tantivy::schema::{SchemaBuilder, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "split quickwit")).unwrap();
+        index_writer.commit().unwrap();
+        
+        let config = SplitConfig { split_id: Some("abc".to_string()), ..SplitConfig::default() };
+        QuickwitSplitGenerator::new(index, 1000).unwrap().with_config(config).generate_split(temp_dir.path()).unwrap();
+        
+        let split_path = temp_dir.path().join("abc.split");
+        let reader = QuickwitSplitReader::open(&split_path).unwrap();
+        assert_eq!(reader.get_split_path(), split_path);
+        assert_eq!(reader.read_posting_list("title", "quickwit").unwrap(), vec![0]);
+        assert!(reader.storage.list_files().unwrap().iter().any(|name| name.ends_with(".store")));
+        
+        let not_a_split = temp_dir.path().join("other.split");
+        fs::write(&not_a_split, b"not a split").unwrap();
+        assert!(matches!(QuickwitSplitReader::open(&not_a_split), Err(SplitsError::InvalidSplit(_))));
+    }
+    
+    #[test]
+    fn test_stored_documents_read_past_hotcache_footer() {
+        use crate::split_generator::{QuickwitSplitGenerator, SplitConfig};
+        use tantivy::schema::{SchemaBuilder, STORED, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        for legacy_directory_layout in [false, true] {
+            let mut schema_builder = SchemaBuilder::default();
+            let title = schema_builder.add_text_field("title", TEXT | STORED);
+            let index = Index::create_in_ram(schema_builder.build());
+            let mut index_writer = index.writer(15_000_000).unwrap();
+            index_writer.add_document(tantivy::doc!(title => "split quickwit")).unwrap();
+            index_writer.add_document(tantivy::doc!(title => "tantivy")).unwrap();
+            index_writer.commit().unwrap();
+            
+            let split_path = temp_dir.path().join(format!("split-{}", legacy_directory_layout));
+            let config = SplitConfig { legacy_directory_layout, ..SplitConfig::default() };
+            QuickwitSplitGenerator::new(index, 1000).unwrap().with_config(config).generate_split(&split_path).unwrap();
+            
+            // The store file Tantivy reads documents from ends with the hotcache footer
+            let reader = QuickwitSplitReader::open(&split_path).unwrap();
+            assert!(reader.get_hotcache_info().is_some());
+            assert_eq!(reader.get_document(0).unwrap()["title"], "split quickwit");
+            assert_eq!(reader.get_document(1).unwrap()["title"], "tantivy");
+        }
+    }
+    
+    #[test]
+    fn test_stale_hotcached_ranges_fall_back_to_index() {
+        use crate::split_generator::QuickwitSplitGenerator;
+        use tantivy::schema::{SchemaBuilder, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "split quickwit")).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "tantivy")).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "quickwit search")).unwrap();
+        index_writer.commit().unwrap();
+        
+        let split_path = temp_dir.path().join("split");
+        QuickwitSplitGenerator::new(index, 1000).unwrap().generate_split(&split_path).unwrap();
+        let mut reader = QuickwitSplitReader::open(&split_path).unwrap();
+        let term_dict = reader.term_dict_bytes("title").unwrap();
+        
+        // Point the recorded ranges past the end of their files
+        let hotcache = reader.hotcache.as_mut().unwrap();
+        let mut field_metadata = hotcache.field_metadata().unwrap();
+        let title_metadata = field_metadata.get_mut("title").unwrap();
+        title_metadata.posting_range = Some(ByteRange { start: 0, end: 1 << 40 });
+        title_metadata.term_dict_range = Some(ByteRange { start: 1 << 40, end: (1 << 40) + 16 });
+        hotcache.set_field_metadata(&field_metadata).unwrap();
+        
+        assert_eq!(reader.read_posting_list("title", "quickwit").unwrap(), vec![0, 2]);
+        assert_eq!(reader.term_dict_bytes("title").unwrap(), term_dict);
+    }
+    
+    #[test]
+    fn test_export_index_opens_with_tantivy() {
+        use crate::split_generator::encode_footer;
+        use tantivy::collector::Count;
+        use tantivy::schema::{SchemaBuilder, Value, STORED, TEXT};
+        
+        let split_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT | STORED);
+        let index = Index::create_in_dir(split_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "quickwit split")).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "tantivy index")).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "quickwit search")).unwrap();
+        index_writer.commit().unwrap();
+        
+        // Append an empty hotcache footer, as the generator does
+        let segment_meta = index.searchable_segment_metas().unwrap().remove(0);
+        let store_path = split_dir.path().join(segment_meta.relative_path(SegmentComponent::Store));
+        let mut store = fs::read(&store_path).unwrap();
+        store.extend_from_slice(&encode_footer(&[]));
+        fs::write(&store_path, store).unwrap();
+        
+        let reader = unloaded_reader(split_dir.path());
+        let export_dir = TempDir::new().unwrap();
+        let output_dir = export_dir.path().join("index");
+        reader.export_index(&output_dir).unwrap();
+        
+        let exported = Index::open_in_dir(&output_dir).unwrap();
+        let searcher = exported.reader().unwrap().searcher();
+        let query = TermQuery::new(Term::from_field_text(title, "quickwit"), IndexRecordOption::Basic);
+        assert_eq!(searcher.search(&query, &Count).unwrap(), 2);
+        let doc: TantivyDocument = searcher.doc(tantivy::DocAddress::new(0, 1)).unwrap();
+        assert_eq!(doc.get_first(title).and_then(|value| value.as_str()), Some("tantivy index"));
+        
+        // Exporting again into the now populated directory fails
+        assert!(matches!(reader.export_index(&output_dir), Err(SplitsError::InvalidOperation(_))));
+    }
+    
+    #[test]
+    fn test_store_file_range_excludes_footer() {
+        use crate::split_generator::encode_footer;
+        use tantivy::schema::{SchemaBuilder, STORED, TEXT};
+        
+        let split_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT | STORED);
+        let index = Index::create_in_dir(split_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "quickwit split")).unwrap();
+        index_writer.commit().unwrap();
+        
+        let segment_meta = index.searchable_segment_metas().unwrap().remove(0);
+        let store_path = split_dir.path().join(segment_meta.relative_path(SegmentComponent::Store));
+        let store_data = fs::read(&store_path).unwrap();
+        let mut store = store_data.clone();
+        store.extend_from_slice(&encode_footer(&[]));
+        fs::write(&store_path, store).unwrap();
+        
+        let reader = unloaded_reader(split_dir.path());
+        assert_eq!(reader.store_file_range().unwrap(), ByteRange { start: 0, end: store_data.len() as u64 });
+        assert_eq!(reader.read_store_data().unwrap(), store_data);
+    }
+    
+    #[test]
+    fn test_total_term_freq() {
+        use tantivy::schema::{SchemaBuilder, STRING, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let body = schema_builder.add_text_field("body", TEXT);
+        let tag = schema_builder.add_text_field("tag", STRING);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "split split quickwit split", tag => "a")).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "quickwit split", tag => "b")).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "split", tag => "deleted")).unwrap();
+        index_writer.commit().unwrap();
+        index_writer.delete_term(Term::from_field_text(tag, "deleted"));
+        index_writer.commit().unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        assert_eq!(reader.total_term_freq("body", "split").unwrap(), 4);
+        assert_eq!(reader.total_term_freq("body", "quickwit").unwrap(), 2);
+        assert_eq!(reader.total_term_freq("body", "absent").unwrap(), 0);
+        assert!(matches!(reader.total_term_freq("tag", "a"), Err(SplitsError::FieldError(_))));
+    }
+    
+    #[cfg(feature = "quickwit-compat")]
+    #[test]
+    fn test_load_quickwit_hotcache() {
+        use crate::hotcache::build_quickwit_hotcache;
+        use crate::split_generator::encode_footer;
+        use std::io::Write;
+        use tantivy::schema::{SchemaBuilder, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "quickwit")).unwrap();
+        index_writer.commit().unwrap();
+        
+        let hotcache_bytes = build_quickwit_hotcache(temp_dir.path()).unwrap();
+        let mut reader = unloaded_reader(temp_dir.path());
+        let store_file = temp_dir.path().join(reader.find_store_file().unwrap());
+        fs::OpenOptions::new().append(true).open(&store_file).unwrap()
+            .write_all(&encode_footer(&hotcache_bytes)).unwrap();
+        
+        reader.load_hotcache().unwrap();
+        assert_eq!(reader.hotcache_format(), Some(HotcacheFormat::Quickwit));
+        assert!(reader.get_hotcache_info().is_none());
+        assert!(reader.quickwit_hotcache().is_some());
+    }
+    
+    #[test]
+    fn test_content_equals() {
+        use tantivy::schema::{SchemaBuilder, STORED, STRING, TEXT};
+        
+        let build_index = |path: &Path, titles: &[&str], deleted: Option<&str>| {
+            let mut schema_builder = SchemaBuilder::default();
+            let title = schema_builder.add_text_field("title", TEXT | STORED);
+            let tag = schema_builder.add_text_field("tag", STRING);
+            let index = Index::create_in_dir(path, schema_builder.build()).unwrap();
+            let mut index_writer = index.writer(15_000_000).unwrap();
+            for t in titles {
+                index_writer.add_document(tantivy::doc!(title => *t, tag => *t)).unwrap();
+            }
+            index_writer.commit().unwrap();
+            if let Some(deleted) = deleted {
+                index_writer.delete_term(Term::from_field_text(tag, deleted));
+                index_writer.commit().unwrap();
+            }
+        };
+        
+        let first = TempDir::new().unwrap();
+        let second = TempDir::new().unwrap();
+        let third = TempDir::new().unwrap();
+        build_index(first.path(), &["a", "b", "c"], None);
+        // Same content, another segment id and doc order
+        build_index(second.path(), &["c", "d", "a", "b"], Some("d"));
+        build_index(third.path(), &["a", "b", "e"], None);
+        
+        let first = unloaded_reader(first.path());
+        let second = unloaded_reader(second.path());
+        let third = unloaded_reader(third.path());
+        assert!(first.content_equals(&second).unwrap());
+        assert!(second.content_equals(&first).unwrap());
+        assert!(!first.content_equals(&third).unwrap());
+    }
+    
+    #[test]
+    fn test_content_digest() {
+        use crate::split_generator::{QuickwitSplitGenerator, SplitConfig};
+        use tantivy::schema::{SchemaBuilder, STORED, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let generate = |name: &str, titles: &[&str]| {
+            let mut schema_builder = SchemaBuilder::default();
+            let title = schema_builder.add_text_field("title", TEXT | STORED);
+            let index = Index::create_in_ram(schema_builder.build());
+            let mut index_writer = index.writer(15_000_000).unwrap();
+            for t in titles {
+                index_writer.add_document(tantivy::doc!(title => *t)).unwrap();
+            }
+            index_writer.commit().unwrap();
+            
+            let split_path = temp_dir.path().join(format!("{}.split", name));
+            QuickwitSplitGenerator::new(index, 1000).unwrap()
+                .with_config(SplitConfig { split_id: Some(name.to_string()), ..SplitConfig::default() })
+                .generate_split(&split_path)
+                .unwrap();
+            QuickwitSplitReader::open(&split_path).unwrap().content_digest().unwrap()
+        };
+        
+        let first = generate("first", &["a", "b", "c"]);
+        assert_eq!(first.len(), 64);
+        assert_eq!(first, generate("second", &["c", "a", "b"]));
+        assert_ne!(first, generate("third", &["a", "b", "e"]));
+        assert_ne!(first, generate("fourth", &["a", "b"]));
+    }
+    
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_open_compressed_split() {
+        use crate::compression::set_max_decompressed_bytes;
+        use crate::split_generator::QuickwitSplitGenerator;
+        use std::io::Write;
+        use tantivy::schema::{SchemaBuilder, STORED, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT | STORED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "archived quickwit")).unwrap();
+        index_writer.commit().unwrap();
+        
+        let split_path = temp_dir.path().join("archived.split");
+        QuickwitSplitGenerator::new(index.clone(), 1000).unwrap().generate_split(&split_path).unwrap();
+        let split = fs::read(&split_path).unwrap();
+        
+        let gz_path = temp_dir.path().join("archived.split.gz");
+        let mut encoder = flate2::write::GzEncoder::new(fs::File::create(&gz_path).unwrap(), flate2::Compression::default());
+        encoder.write_all(&split).unwrap();
+        encoder.finish().unwrap();
+        let zst_path = temp_dir.path().join("archived.split.zst");
+        fs::write(&zst_path, zstd::encode_all(split.as_slice(), 0).unwrap()).unwrap();
+        
+        for path in [&gz_path, &zst_path] {
+            let reader = QuickwitSplitReader::open(path).unwrap();
+            assert_eq!(reader.get_document(0).unwrap()["title"], "archived quickwit");
+            assert_eq!(reader.read_posting_list_with_freqs("title", "quickwit").unwrap(), vec![(0, 1)]);
+        }
+        
+        // Replacing the compressed file is picked up on reload
+        let mut reader = QuickwitSplitReader::open(&zst_path).unwrap();
+        let index = Index::create_in_ram(index.schema());
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "replaced")).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "replaced again")).unwrap();
+        index_writer.commit().unwrap();
+        let replacement_path = temp_dir.path().join("replacement.split");
+        QuickwitSplitGenerator::new(index, 1000).unwrap().generate_split(&replacement_path).unwrap();
+        fs::write(&zst_path, zstd::encode_all(fs::read(&replacement_path).unwrap().as_slice(), 0).unwrap()).unwrap();
+        reader.reload().unwrap();
+        assert_eq!(reader.read_posting_list("title", "replaced").unwrap(), vec![0, 1]);
+        
+        // Refused beyond the decompression limit, and when not validly compressed
+        set_max_decompressed_bytes(split.len() as u64 - 1);
+        assert!(matches!(QuickwitSplitReader::open(&gz_path), Err(SplitsError::InvalidOperation(_))));
+        set_max_decompressed_bytes(0);
+        let corrupt_path = temp_dir.path().join("corrupt.split.gz");
+        fs::write(&corrupt_path, &split).unwrap();
+        assert!(matches!(QuickwitSplitReader::open(&corrupt_path), Err(SplitsError::InvalidSplit(_))));
+    }
+    
+    #[test]
+    fn test_check_tantivy_version() {
+        let split_path = Path::new("/splits/a.split");
+        assert!(check_tantivy_version(split_path, tantivy::version_string()).is_ok());
+        // Readable formats of other versions and unparseable versions only warn
+        let readable = format!("tantivy v0.1.0, index_format v{}", tantivy::INDEX_FORMAT_OLDEST_SUPPORTED_VERSION);
+        assert!(check_tantivy_version(split_path, &readable).is_ok());
+        assert!(check_tantivy_version(split_path, "unknown").is_ok());
+        
+        assert!(matches!(
+            check_tantivy_version(split_path, "tantivy v9.0.0, index_format v99"),
+            Err(SplitsError::InvalidSplit(_))
+        ));
+        assert!(matches!(
+            check_tantivy_version(split_path, "tantivy v0.1.0, index_format v1"),
+            Err(SplitsError::InvalidSplit(_))
+        ));
+    }
+    
+    #[test]
+    fn test_split_checksum_matches_generation() {
+        use crate::split_generator::{QuickwitSplitGenerator, SplitConfig};
+        use tantivy::schema::{SchemaBuilder, STORED, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT | STORED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "quickwit")).unwrap();
+        index_writer.commit().unwrap();
+        
+        for legacy_directory_layout in [false, true] {
+            let split_path = temp_dir.path().join(format!("split-{}", legacy_directory_layout));
+            let config = SplitConfig { legacy_directory_layout, write_sidecar_metadata: true, ..SplitConfig::default() };
+            let metadata = QuickwitSplitGenerator::new(index.clone(), 1000).unwrap()
+                .with_config(config)
+                .generate_split(&split_path)
+                .unwrap();
+            
+            let checksum = QuickwitSplitReader::open(&split_path).unwrap().split_checksum().unwrap();
+            assert_eq!(metadata.checksum.as_deref(), Some(checksum.as_str()));
+            assert_eq!(QuickwitSplitReader::load_sidecar_metadata(&split_path).unwrap().checksum, Some(checksum));
+        }
+        
+        let file_checksum = QuickwitSplitReader::open(&temp_dir.path().join("split-false")).unwrap().split_checksum().unwrap();
+        let bytes = fs::read(temp_dir.path().join("split-false")).unwrap();
+        assert_eq!(file_checksum, blake3::hash(&bytes).to_hex().to_string());
+    }
+    
+    #[test]
+    fn test_doc_freqs() {
+        use tantivy::schema::{SchemaBuilder, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "split split quickwit")).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "quickwit tantivy")).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "split")).unwrap();
+        index_writer.commit().unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        assert_eq!(
+            reader.doc_freqs("body", &["tantivy", "split", "absent", "quickwit", "split"]).unwrap(),
+            vec![1, 2, 0, 2, 2]
+        );
+        assert!(reader.doc_freqs("body", &[]).unwrap().is_empty());
+        assert!(matches!(reader.doc_freqs("missing", &["split"]), Err(SplitsError::FieldError(_))));
+    }
+    
+    #[test]
+    fn test_alive_bitset() {
+        use tantivy::schema::{SchemaBuilder, STRING};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let tag = schema_builder.add_text_field("tag", STRING);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        for i in 0..10 {
+            let value = if i == 1 || i == 8 { "deleted" } else { "kept" };
+            index_writer.add_document(tantivy::doc!(tag => value)).unwrap();
+        }
+        index_writer.commit().unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        assert_eq!(reader.alive_bitset().unwrap(), vec![0b1111_1111, 0b0000_0011]);
+        
+        index_writer.delete_term(Term::from_field_text(tag, "deleted"));
+        index_writer.commit().unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        assert_eq!(reader.alive_bitset().unwrap(), vec![0b1111_1101, 0b0000_0010]);
+    }
+    
+    #[test]
+    fn test_term_positions() {
+        use tantivy::schema::{SchemaBuilder, STRING, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let body = schema_builder.add_text_field("body", TEXT);
+        let tag = schema_builder.add_text_field("tag", STRING);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "split split quickwit split", tag => "a")).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "quickwit", tag => "b")).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "tantivy", tag => "c")).unwrap();
+        index_writer.commit().unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        assert_eq!(reader.term_positions("body", "split", 0).unwrap(), vec![0, 1, 3]);
+        assert_eq!(reader.term_positions("body", "quickwit", 1).unwrap(), vec![0]);
+        assert!(reader.term_positions("body", "split", 1).unwrap().is_empty());
+        assert!(reader.term_positions("body", "absent", 0).unwrap().is_empty());
+        
+        // Documents before the term's first posting don't contain it
+        assert!(reader.term_positions("body", "tantivy", 1).unwrap().is_empty());
+        
+        // STRING fields are indexed without positions
+        assert!(matches!(reader.term_positions("tag", "a", 0), Err(SplitsError::FieldError(_))));
+    }
+    
+    #[test]
+    fn test_fields_describe_schema() {
+        use tantivy::schema::{SchemaBuilder, FAST, INDEXED, STORED, STRING, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        schema_builder.add_text_field("title", TEXT | STORED);
+        schema_builder.add_text_field("tag", STRING | FAST);
+        schema_builder.add_date_field("timestamp", INDEXED | FAST);
+        schema_builder.add_bool_field("flag", STORED);
+        Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        let describe = |name: &str, field_type, stored, indexed, fast| FieldDescriptor {
+            name: name.to_string(), field_type, stored, indexed, fast, json_paths: Vec::new(),
+        };
+        assert_eq!(reader.fields().unwrap(), vec![
+            describe("title", FieldKind::Text, true, true, false),
+            describe("tag", FieldKind::Text, false, true, true),
+            describe("timestamp", FieldKind::Date, false, true, true),
+            describe("flag", FieldKind::Bool, true, false, false),
+        ]);
+    }
+    
+    #[test]
+    fn test_file_lookup_prefers_exact_lowercase_names() {
+        let temp_dir = TempDir::new().unwrap();
+        let segment_id = "0123456789abcdef0123456789abcdef";
+        // Names differing only in case, as custom naming could produce on a case-sensitive filesystem
+        for file_name in [
+            format!("{}.IDX", segment_id),
+            format!("{}.idx", segment_id.to_uppercase()),
+            format!("{}.idx", segment_id),
+            format!("{}.store", segment_id.to_uppercase()),
+        ] {
+            fs::write(temp_dir.path().join(file_name), b"data").unwrap();
+        }
+        
+        let reader = unloaded_reader(temp_dir.path());
+        for _ in 0..3 {
+            assert_eq!(reader.find_file_with_extension("idx").unwrap(), format!("{}.idx", segment_id));
+        }
+        // Without a lowercase candidate, the mismatched case is still found
+        assert_eq!(reader.find_store_file().unwrap(), format!("{}.store", segment_id.to_uppercase()));
+        assert!(matches!(reader.find_file_with_extension("term"), Err(SplitsError::InvalidSplit(_))));
+    }
+    
+    #[test]
+    fn test_read_file_range() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("0123456789abcdef0123456789abcdef.idx"), b"0123456789").unwrap();
+        let reader = unloaded_reader(temp_dir.path());
+        
+        let file_name = "0123456789abcdef0123456789abcdef.idx";
+        assert_eq!(reader.read_file_range(file_name, 2, 3).unwrap(), b"234");
+        assert_eq!(reader.read_file_range(file_name, 10, 0).unwrap(), b"");
+        for (start, length) in [(8, 3), (11, 0), (u64::MAX, 2)] {
+            assert!(matches!(reader.read_file_range(file_name, start, length), Err(SplitsError::InvalidOperation(_))));
+        }
+        for name in ["missing.idx", "../0123456789abcdef0123456789abcdef.idx", "..", ""] {
+            assert!(matches!(reader.read_file_range(name, 0, 1), Err(SplitsError::InvalidOperation(_))));
+        }
+    }
+    
+    #[test]
+    fn test_json_fields() {
+        use tantivy::schema::{JsonObjectOptions, SchemaBuilder, STORED, STRING, TEXT};
+        use tantivy::IndexWriter;
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        schema_builder.add_json_field("attributes", TEXT | STORED);
+        schema_builder.add_json_field("metrics", JsonObjectOptions::from(STRING).set_fast(None));
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        let schema = index.schema();
+        let mut index_writer: IndexWriter = index.writer(15_000_000).unwrap();
+        for json in [
+            r#"{"attributes": {"color": "Red", "size": 3, "tags": ["a", "b"], "dims": {"width": 1.5}}, "metrics": {"value": 7}}"#,
+            r#"{"attributes": {"size": "large", "in_stock": true}, "metrics": {"value": "n/a"}}"#,
+        ] {
+            index_writer.add_document(TantivyDocument::parse_json(&schema, json).unwrap()).unwrap();
+        }
+        index_writer.commit().unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        // Terms of a path are ordered by value type first, so compare them sorted
+        let sorted_terms = |field: &str| {
+            let mut terms = reader.field_terms(field).unwrap();
+            terms.sort();
+            terms
+        };
+        assert_eq!(sorted_terms("attributes"), vec![
+            "color:red", "dims.width:1.5", "in_stock:true", "size:3", "size:large", "tags:a", "tags:b",
+        ]);
+        assert_eq!(sorted_terms("metrics"), vec!["value:7", "value:n/a"]);
+        
+        let fields = reader.fields().unwrap();
+        assert_eq!(fields[0].field_type, FieldKind::Json);
+        assert_eq!(fields[0].json_paths, vec!["color", "dims.width", "in_stock", "size", "tags"]);
+        assert_eq!(fields[1].json_paths, vec!["value"]);
+        
+        // Stored JSON keeps its nesting and value types; unstored fields are left out
+        assert_eq!(reader.get_document(0).unwrap(), serde_json::json!({
+            "attributes": {"color": "Red", "size": 3, "tags": ["a", "b"], "dims": {"width": 1.5}}
+        }));
+        assert!(matches!(reader.get_document(2), Err(SplitsError::InvalidOperation(_))));
+        
+        // Paths are read from the doc store, or from one fast column per value type
+        assert_eq!(reader.column("attributes.size").unwrap(), vec![serde_json::json!(3), serde_json::json!("large")]);
+        assert_eq!(reader.column("attributes.tags").unwrap(), vec![serde_json::json!(["a", "b"]), serde_json::Value::Null]);
+        assert_eq!(reader.column("metrics.value").unwrap(), vec![serde_json::json!(7), serde_json::json!("n/a")]);
+        assert!(matches!(reader.column("missing.path"), Err(SplitsError::FieldError(_))));
+    }
+    
+    #[test]
+    fn test_opening_beyond_memory_budget_evicts_least_recently_used() {
+        use tantivy::schema::{SchemaBuilder, TEXT};
+        
+        let temp_dirs: Vec<TempDir> = (0..2).map(|_| TempDir::new().unwrap()).collect();
+        for temp_dir in &temp_dirs {
+            let mut schema_builder = SchemaBuilder::default();
+            schema_builder.add_text_field("title", TEXT);
+            Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        }
+        let first = unloaded_reader(temp_dirs[0].path());
+        let second = unloaded_reader(temp_dirs[1].path());
+        
+        // Any split exceeds a one byte budget, so each open evicts the others
+        crate::memory_budget::set_reader_memory_budget(1).unwrap();
+        first.fields().unwrap();
+        second.fields().unwrap();
+        assert!(first.index.peek().is_none());
+        
+        // Evicted state is rebuilt transparently
+        assert_eq!(first.fields().unwrap().len(), 1);
+        crate::memory_budget::set_reader_memory_budget(0).unwrap();
+    }
+    
+    #[test]
+    fn test_component_file_uses_tantivy_layout() {
+        use tantivy::schema::{SchemaBuilder, FAST, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let id = schema_builder.add_u64_field("id", FAST);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "quickwit", id => 7u64)).unwrap();
+        index_writer.commit().unwrap();
+        
+        let segment_meta = index.searchable_segment_metas().unwrap().remove(0);
+        let reader = unloaded_reader(temp_dir.path());
+        
+        for component in [SegmentComponent::Terms, SegmentComponent::FastFields, SegmentComponent::Postings] {
+            assert_eq!(
+                reader.component_file(component).unwrap(),
+                file_name(&segment_meta.relative_path(component))
+            );
+        }
+        
+        let mut cursor = reader.posting_cursor("title", "quickwit").unwrap();
+        assert_eq!(cursor.next_batch(10).unwrap(), vec![0]);
+    }
+    
+    /// Generates a split in the single-file layout, every segment file packed into `split.split`
+    ///
+    /// Documents have `title` "quickwit split", "other" and "quickwit", and `id` 7, 8 and 9.
+    fn packed_split_fixture(temp_dir: &TempDir) -> PathBuf {
+        use crate::split_generator::QuickwitSplitGenerator;
+        use tantivy::schema::{SchemaBuilder, FAST, TEXT};
+        
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let id = schema_builder.add_u64_field("id", FAST);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        for (text, value) in [("quickwit split", 7u64), ("other", 8), ("quickwit", 9)] {
+            index_writer.add_document(tantivy::doc!(title => text, id => value)).unwrap();
+        }
+        index_writer.commit().unwrap();
+        
+        let split_path = temp_dir.path().join("split.split");
+        QuickwitSplitGenerator::new(index, 1000).unwrap().generate_split(&split_path).unwrap();
+        split_path
+    }
+    
+    #[test]
+    fn test_packed_split_reads_postings_and_fast_fields() {
+        let temp_dir = TempDir::new().unwrap();
+        let split_path = packed_split_fixture(&temp_dir);
+        assert!(split_path.is_file());
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 1);
+        
+        // Components resolve to files packed in the split, found by Tantivy's layout
+        let reader = QuickwitSplitReader::open(&split_path).unwrap();
+        let packed_files: Vec<String> = crate::split_bytes::split_file_ranges(&split_path).unwrap()
+            .into_iter()
+            .map(|(name, _)| name)
+            .collect();
+        for component in [SegmentComponent::Terms, SegmentComponent::Postings, SegmentComponent::Store] {
+            assert!(packed_files.contains(&reader.component_file(component).unwrap()));
+        }
+        
+        assert_eq!(reader.read_posting_list("title", "quickwit").unwrap(), vec![0, 2]);
+        let mut cursor = reader.posting_cursor("title", "split").unwrap();
+        assert_eq!(cursor.next_batch(10).unwrap(), vec![0]);
+        assert!(!reader.term_dict_bytes("title").unwrap().is_empty());
+        
+        let data = reader.get_fast_field_data("id", 0..3, FastFieldFormat::RawLe).unwrap();
+        let values: Vec<u64> = data.chunks(8)
+            .map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+            .collect();
+        assert_eq!(values, vec![7, 8, 9]);
+    }
+    
+    #[test]
+    fn test_component_file_rejects_unrecognized_format() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("unrelated.bin"), b"").unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        match reader.component_file(SegmentComponent::Terms) {
+            Err(SplitsError::InvalidSplit(msg)) => assert!(msg.contains("Unrecognized split format")),
+            other => panic!("Expected InvalidSplit, got {:?}", other),
+        }
+    }
+    
+    #[test]
+    fn test_estimate_hits() {
+        use tantivy::schema::{SchemaBuilder, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        for i in 0..10 {
+            let text = if i < 4 { "quickwit split" } else { "tantivy split" };
+            index_writer.add_document(tantivy::doc!(body => text)).unwrap();
+        }
+        index_writer.commit().unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        assert_eq!(reader.estimate_hits("quickwit").unwrap(), 4);
+        assert_eq!(reader.estimate_hits("+quickwit +split").unwrap(), 4);
+        assert_eq!(reader.estimate_hits("quickwit tantivy").unwrap(), 10);
+        assert_eq!(reader.estimate_hits("absent").unwrap(), 0);
+        assert!(matches!(reader.estimate_hits("body:("), Err(SplitsError::Query(_))));
+    }
+    
+    #[test]
+    fn test_column_reads_stored_and_fast_fields() {
+        use tantivy::schema::{SchemaBuilder, FAST, STORED, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT | STORED);
+        let id = schema_builder.add_u64_field("id", FAST);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "first", id => 1u64, body => "a")).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "second", id => 2u64, body => "b")).unwrap();
+        index_writer.add_document(tantivy::doc!(id => 3u64)).unwrap();
+        index_writer.commit().unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        assert_eq!(
+            reader.column("title").unwrap(),
+            vec![serde_json::json!("first"), serde_json::json!("second"), serde_json::Value::Null]
+        );
+        assert_eq!(
+            reader.column("id").unwrap(),
+            vec![serde_json::json!(1), serde_json::json!(2), serde_json::json!(3)]
+        );
+        assert!(matches!(reader.column("body"), Err(SplitsError::FieldError(_))));
+        assert!(matches!(reader.column("missing"), Err(SplitsError::FieldError(_))));
+    }
+    
+    #[test]
+    fn test_field_cardinality() {
+        use tantivy::schema::{SchemaBuilder, FAST, STRING, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let id = schema_builder.add_u64_field("id", FAST);
+        let color = schema_builder.add_text_field("color", STRING | FAST);
+        let body = schema_builder.add_text_field("body", TEXT);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        for i in 0..5000u64 {
+            let color_value = ["red", "green", "blue"][i as usize % 3];
+            index_writer.add_document(tantivy::doc!(id => i % 2000, color => color_value, body => "text")).unwrap();
+        }
+        index_writer.commit().unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        assert_eq!(reader.field_cardinality("id", true).unwrap(), 2000);
+        assert_eq!(reader.field_cardinality("color", true).unwrap(), 3);
+        assert_eq!(reader.field_cardinality("color", false).unwrap(), 3);
+        let estimate = reader.field_cardinality("id", false).unwrap();
+        assert!((1900..=2100).contains(&estimate), "estimate {} too far from 2000", estimate);
+        assert!(matches!(reader.field_cardinality("body", true), Err(SplitsError::FieldError(_))));
+    }
+    
+    #[test]
+    fn test_numeric_bounds() {
+        use tantivy::schema::{SchemaBuilder, FAST, INDEXED, STRING};
+        use tantivy::DateTime;
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let size = schema_builder.add_u64_field("size", FAST);
+        let offset = schema_builder.add_i64_field("offset", FAST);
+        let score = schema_builder.add_f64_field("score", FAST);
+        let timestamp = schema_builder.add_date_field("timestamp", FAST);
+        schema_builder.add_i64_field("unset", FAST);
+        schema_builder.add_i64_field("indexed", INDEXED);
+        let color = schema_builder.add_text_field("color", STRING | FAST);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        // Bounds span both segments
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(
+            size => u64::MAX, offset => -7i64, score => 2.5f64,
+            timestamp => DateTime::from_timestamp_secs(1_700_000_000), color => "red"
+        )).unwrap();
+        index_writer.commit().unwrap();
+        index_writer.add_document(tantivy::doc!(
+            size => 3u64, offset => 40i64, score => -0.5f64,
+            timestamp => DateTime::from_timestamp_secs(-60), color => "blue"
+        )).unwrap();
+        index_writer.commit().unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        assert_eq!(reader.numeric_bounds("size").unwrap(), (3, u64::MAX as i64));
+        assert_eq!(reader.numeric_bounds("offset").unwrap(), (-7, 40));
+        let (min, max) = reader.numeric_bounds("score").unwrap();
+        assert_eq!((f64::from_bits(min as u64), f64::from_bits(max as u64)), (-0.5, 2.5));
+        assert_eq!(reader.numeric_bounds("timestamp").unwrap(), (-60_000_000_000, 1_700_000_000_000_000_000));
+        
+        assert!(matches!(reader.numeric_bounds("unset"), Err(SplitsError::InvalidOperation(_))));
+        assert!(matches!(reader.numeric_bounds("indexed"), Err(SplitsError::FieldError(_))));
+        assert!(matches!(reader.numeric_bounds("color"), Err(SplitsError::FieldError(_))));
+        assert!(matches!(reader.numeric_bounds("missing"), Err(SplitsError::FieldError(_))));
+    }
+    
+    #[test]
+    fn test_open_split_without_hotcache() {
+        let temp_dir = TempDir::new().unwrap();
+        // Store data as short as a doc store can be, followed by an empty footer payload
+        let mut store = vec![0u8; MIN_STORE_DATA_SIZE as usize];
+        store.extend_from_slice(&8u64.to_le_bytes());
+        fs::write(temp_dir.path().join("segment.store"), store).unwrap();
+        
+        let reader = QuickwitSplitReader::open(temp_dir.path()).unwrap();
+        assert!(reader.get_hotcache_info().is_none());
+    }
+    
+    #[test]
+    fn test_store_blocks_cover_store_data() {
+        use tantivy::schema::{SchemaBuilder, STORED, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT | STORED);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        for i in 0..1000 {
+            index_writer.add_document(tantivy::doc!(title => format!("document number {}", i))).unwrap();
+        }
+        index_writer.commit().unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        let block_ranges = reader.store_block_ranges().unwrap();
+        assert!(!block_ranges.is_empty());
+        assert_eq!(block_ranges[0].start, 0);
+        for window in block_ranges.windows(2) {
+            assert_eq!(window[0].end, window[1].start);
+        }
+        
+        let first_block = reader.read_store_block(0).unwrap();
+        assert_eq!(first_block.len() as u64, block_ranges[0].size());
+        assert_eq!(reader.store_block_of(0).unwrap(), 0);
+        assert_eq!(reader.store_block_of(999).unwrap(), block_ranges.len() - 1);
+        assert!(matches!(reader.store_block_of(1000), Err(SplitsError::InvalidOperation(_))));
+        assert!(matches!(
+            reader.read_store_block(block_ranges.len()),
+            Err(SplitsError::InvalidOperation(_))
+        ));
+        
+        // Generated splits append the hotcache footer after Tantivy's footer
+        drop(index_writer);
+        let split_path = temp_dir.path().join("split");
+        crate::split_generator::QuickwitSplitGenerator::new(index, 1000).unwrap().generate_split(&split_path).unwrap();
+        let split_reader = QuickwitSplitReader::open(&split_path).unwrap();
+        assert_eq!(split_reader.store_block_ranges().unwrap(), block_ranges);
+        assert_eq!(split_reader.read_store_block(0).unwrap(), first_block);
+    }
+    
+    #[test]
+    fn test_open_detects_truncated_store_file() {
+        use tantivy::schema::{SchemaBuilder, STORED, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT | STORED);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "quickwit split")).unwrap();
+        index_writer.commit().unwrap();
+        
+        let segment_meta = index.searchable_segment_metas().unwrap().remove(0);
+        let store_path = temp_dir.path().join(segment_meta.relative_path(SegmentComponent::Store));
+        
+        // Truncate the store file as an interrupted write would
+        let store_file = fs::OpenOptions::new().write(true).open(&store_path).unwrap();
+        store_file.set_len(4).unwrap();
+        
+        match QuickwitSplitReader::open(temp_dir.path()) {
+            Err(SplitsError::InvalidSplit(msg)) => {
+                assert!(msg.contains("split appears truncated"));
+                assert!(msg.contains(&store_path.display().to_string()));
+            }
+            other => panic!("Expected InvalidSplit, got {:?}", other.map(|_| ())),
+        }
+    }
+    
+    #[test]
+    fn test_open_rejects_footer_overlapping_store_data() {
+        use crate::split_generator::{QuickwitSplitGenerator, SplitConfig};
+        use tantivy::schema::{SchemaBuilder, STORED, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT | STORED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "quickwit split")).unwrap();
+        index_writer.commit().unwrap();
+        
+        let split_path = temp_dir.path().join("split");
+        let config = SplitConfig { legacy_directory_layout: true, ..SplitConfig::default() };
+        QuickwitSplitGenerator::new(index, 1000).unwrap().with_config(config).generate_split(&split_path).unwrap();
+        let store_path = fs::read_dir(&split_path).unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.extension().is_some_and(|ext| ext == "store"))
+            .unwrap();
+        
+        // A footer claiming all but a few bytes leaves no room for the doc store's own footers
+        let mut store = fs::read(&store_path).unwrap();
+        let store_len = store.len();
+        store[store_len - 8..].copy_from_slice(&(store_len as u64 - 10).to_le_bytes());
+        fs::write(&store_path, &store).unwrap();
+        match QuickwitSplitReader::open(&split_path) {
+            Err(SplitsError::InvalidSplit(msg)) => {
+                assert!(msg.contains("leaves 10 bytes of store data"), "{}", msg);
+            }
+            other => panic!("Expected InvalidSplit, got {:?}", other.map(|_| ())),
+        }
+        
+        // As does a store file shorter than the footer's size field
+        fs::write(&store_path, [0u8; 4]).unwrap();
+        match QuickwitSplitReader::open(&split_path) {
+            Err(SplitsError::InvalidSplit(msg)) => assert!(msg.contains("split appears truncated"), "{}", msg),
+            other => panic!("Expected InvalidSplit, got {:?}", other.map(|_| ())),
+        }
+    }
+    
+    #[test]
+    fn test_dual_footer_split_opens_with_either_footer() {
+        use crate::split_generator::{locate_footer, QuickwitSplitGenerator, SplitConfig};
+        use tantivy::directory::RamDirectory;
+        use tantivy::schema::{SchemaBuilder, Value, STORED, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT | STORED);
+        let index = Index::create_in_ram(schema_builder.build());
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "quickwit split")).unwrap();
+        index_writer.commit().unwrap();
+        
+        let split_path = temp_dir.path().join("split");
+        let config = SplitConfig {
+            legacy_directory_layout: true,
+            dual_footer: true,
+            custom_metadata: BTreeMap::from([("team".to_string(), "a".to_string())]),
+            ..SplitConfig::default()
+        };
+        QuickwitSplitGenerator::new(index, 1000).unwrap().with_config(config).generate_split(&split_path).unwrap();
+        
+        // Readers of the versioned footer
+        let reader = QuickwitSplitReader::open(&split_path).unwrap();
+        assert_eq!(reader.custom_metadata().unwrap()["team"], "a");
+        assert_eq!(reader.get_document(0).unwrap()["title"], serde_json::json!("quickwit split"));
+        
+        // Readers of the legacy footer only know its trailing size and the recorded store data length
+        let open_as_legacy_reader = |split_path: &Path| -> (BTreeMap<String, String>, u64) {
+            let directory = RamDirectory::create();
+            let mut custom_metadata = BTreeMap::new();
+            // Lock files left by the readers opening the split aren't part of it
+            let paths = fs::read_dir(split_path).unwrap()
+                .map(|entry| entry.unwrap().path())
+                .filter(|path| !path.file_name().unwrap().to_string_lossy().ends_with(".lock"));
+            for path in paths {
+                let mut data = fs::read(&path).unwrap();
+                if path.extension().is_some_and(|ext| ext == "store") {
+                    let footer_len = u64::from_le_bytes(data[data.len() - 8..].try_into().unwrap()) as usize;
+                    let hotcache = HotcacheInfo::from_bytes(&data[data.len() - footer_len..data.len() - 8]).unwrap();
+                    custom_metadata = hotcache.custom_metadata().unwrap();
+                    data.truncate(hotcache.store_data_len().unwrap() as usize);
+                }
+                directory.atomic_write(Path::new(path.file_name().unwrap()), &data).unwrap();
+            }
+            let searcher = Index::open(directory).unwrap().reader().unwrap().searcher();
+            let doc: TantivyDocument = searcher.doc(tantivy::DocAddress::new(0, 0)).unwrap();
+            assert_eq!(doc.get_first(title).and_then(|value| value.as_str()), Some("quickwit split"));
+            (custom_metadata, searcher.num_docs())
+        };
+        let (custom_metadata, num_docs) = open_as_legacy_reader(&split_path);
+        assert_eq!(custom_metadata["team"], "a");
+        assert_eq!(num_docs, 1);
+        
+        // Updating the metadata keeps both footers
+        let updates = HashMap::from([("team".to_string(), "b".to_string())]);
+        QuickwitSplitGenerator::update_metadata(&split_path, updates).unwrap();
+        let store_path = fs::read_dir(&split_path).unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.extension().is_some_and(|ext| ext == "store"))
+            .unwrap();
+        let store = fs::read(&store_path).unwrap();
+        let footer = locate_footer(store.len() as u64, |range| Ok(store[range.start as usize..range.end as usize].to_vec())).unwrap();
+        assert!(footer.dual);
+        assert_eq!(QuickwitSplitReader::open(&split_path).unwrap().custom_metadata().unwrap()["team"], "b");
+        assert_eq!(open_as_legacy_reader(&split_path).0["team"], "b");
+    }
+    
+    #[test]
+    fn test_corpus_stats() {
+        use tantivy::schema::{SchemaBuilder, STRING, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let body = schema_builder.add_text_field("body", TEXT);
+        let tag = schema_builder.add_text_field("tag", STRING);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "quickwit split split", tag => "a")).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "tantivy", tag => "b")).unwrap();
+        index_writer.add_document(tantivy::doc!(tag => "c")).unwrap();
+        index_writer.commit().unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        assert_eq!(reader.corpus_stats("body").unwrap(), CorpusStats {
+            total_docs: 3,
+            docs_with_field: 2,
+            total_tokens: 4,
+            unique_terms: 3,
+        });
+        
+        // STRING fields are indexed without frequencies
+        assert!(matches!(reader.corpus_stats("tag"), Err(SplitsError::FieldError(_))));
+    }
+    
+    #[test]
+    fn test_registered_tokenizer_is_used_for_queries() {
+        use crate::tokenizers::TokenizerConfig;
+        use tantivy::schema::{SchemaBuilder, TextFieldIndexing, TextOptions};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let ngram = TokenizerConfig::Ngram { min_gram: 3, max_gram: 3, prefix_only: false, lowercase: true };
+        let mut schema_builder = SchemaBuilder::default();
+        let body = schema_builder.add_text_field("body", TextOptions::default().set_indexing_options(
+            TextFieldIndexing::default().set_tokenizer("trigram")
+        ));
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        index.tokenizers().register("trigram", ngram.build().unwrap());
+        
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "quickwit")).unwrap();
+        index_writer.commit().unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        reader.register_tokenizer("trigram", ngram.build().unwrap()).unwrap();
+        assert_eq!(reader.estimate_hits("body:ick").unwrap(), 1);
+    }
+    
+    #[test]
+    fn test_segment_meta_json_prefers_embedded_meta() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join(META_FILE_NAME), r#"{"segments":[]}"#).unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        
+        assert_eq!(reader.segment_meta_json().unwrap(), r#"{"segments":[]}"#);
+    }
+    
+    #[test]
+    fn test_overlaps_time_range() {
+        let temp_dir = TempDir::new().unwrap();
+        let split_path = temp_dir.path().join("split");
+        fs::create_dir(&split_path).unwrap();
+        let reader = unloaded_reader(&split_path);
+        
+        // No sidecar, no recorded range
+        assert!(reader.overlaps_time_range(0, 10).unwrap());
+        
+        fs::write(
+            sidecar_metadata_path(&split_path),
+            r#"{"split_id":"s","num_docs":2,"size_bytes":0,"hotcache_start":0,"hotcache_end":0,"time_start":100,"time_end":200}"#,
+        ).unwrap();
+        assert!(reader.overlaps_time_range(150, 160).unwrap());
+        assert!(reader.overlaps_time_range(0, 100).unwrap());
+        assert!(reader.overlaps_time_range(200, 300).unwrap());
+        assert!(!reader.overlaps_time_range(0, 99).unwrap());
+        assert!(!reader.overlaps_time_range(201, 300).unwrap());
+        assert!(reader.overlaps_time_range(20, 10).is_err());
+        
+        fs::write(
+            sidecar_metadata_path(&split_path),
+            r#"{"split_id":"s","num_docs":0,"size_bytes":0,"hotcache_start":0,"hotcache_end":0}"#,
+        ).unwrap();
+        assert!(reader.overlaps_time_range(0, 10).unwrap());
+    }
+    
+    #[test]
+    fn test_segment_files_with_sizes() {
+        let temp_dir = TempDir::new().unwrap();
+        let segment = "12345678-1234-1234-1234-123456789abc";
+        fs::write(temp_dir.path().join(format!("{}.store", segment)), vec![0u8; 42]).unwrap();
+        fs::write(temp_dir.path().join(format!("{}.idx", segment)), vec![0u8; 7]).unwrap();
+        fs::write(temp_dir.path().join(META_FILE_NAME), "{}").unwrap();
+        
+        let reader = unloaded_reader(temp_dir.path());
+        
+        assert_eq!(reader.segment_files_with_sizes().unwrap(), vec![
+            (format!("{}.idx", segment), 7),
+            (format!("{}.store", segment), 42),
+        ]);
+        assert_eq!(reader.list_segment_files().unwrap(), vec![
+            format!("{}.idx", segment),
+            format!("{}.store", segment),
+        ]);
+    }
+    
+    #[test]
+    fn test_read_split_through_directory_storage() {
+        use tantivy::schema::{SchemaBuilder, TEXT};
+        use tantivy::IndexSettings;
+        
+        let directory = RamDirectory::create();
+        let mut schema_builder = SchemaBuilder::default();
+        let title = schema_builder.add_text_field("title", TEXT);
+        let index = Index::create(directory.clone(), schema_builder.build(), IndexSettings::default()).unwrap();
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "quickwit")).unwrap();
+        index_writer.commit().unwrap();
+        
+        let mut files: Vec<String> = index.searchable_segment_metas().unwrap()[0].list_files()
+            .iter()
+            .map(|path| file_name(path))
+            .collect();
+        files.push(META_FILE_NAME.to_string());
+        
+        let storage = DirectoryStorage::new(Box::new(directory), files);
+        let reader = unloaded_reader_over(Path::new("ram://split"), Arc::new(storage));
+        
+        let mut cursor = reader.posting_cursor("title", "quickwit").unwrap();
+        assert_eq!(cursor.next_batch(10).unwrap(), vec![0]);
+        assert!(reader.segment_meta_json().unwrap().contains("segments"));
+        // No sidecar can exist off the local filesystem
+        assert!(reader.overlaps_time_range(0, 1).unwrap());
+    }
+    
+    #[test]
+    fn test_snippets() {
+        use tantivy::schema::{SchemaBuilder, STORED, TEXT};
+        
+        let temp_dir = TempDir::new().unwrap();
+        let mut schema_builder = SchemaBuilder::default();
+        let body = schema_builder.add_text_field("body", TEXT | STORED);
+        let title = schema_builder.add_text_field("title", TEXT | STORED);
+        let tag = schema_builder.add_text_field("tag", TEXT);
+        let index = Index::create_in_dir(temp_dir.path(), schema_builder.build()).unwrap();
+        let mut index_writer = index.writer(15_000_000).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "splits are searched by quickwit", tag => "a")).unwrap();
+        index_writer.add_document(tantivy::doc!(title => "no body here")).unwrap();
+        index_writer.add_document(tantivy::doc!(body => "nothing to see")).unwrap();
+        index_writer.commit().unwrap();
         
-        let doc_range = 10..15; // 5 documents
-        let byte_range = reader.calculate_doc_range_bytes(&base_range, doc_range).unwrap();
+        let reader = unloaded_reader(temp_dir.path());
+        let snippets = reader.snippets("quickwit", "body", &[0, 1, 2], 100).unwrap();
+        assert_eq!(snippets, vec![
+            "splits are searched by <b>quickwit</b>".to_string(),
+            String::new(),
+            String::new(),
+        ]);
         
-        assert_eq!(byte_range.start, 1000 + (10 * 8)); // 1080
-        assert_eq!(byte_range.end, 1000 + (10 * 8) + (5 * 8)); // 1120
+        assert!(reader.snippets("quickwit", "body", &[], 100).unwrap().is_empty());
+        assert!(matches!(reader.snippets("quickwit", "tag", &[0], 100), Err(SplitsError::FieldError(_))));
+        assert!(matches!(reader.snippets("quickwit", "body", &[3], 100), Err(SplitsError::InvalidOperation(_))));
     }
 }
\ No newline at end of file
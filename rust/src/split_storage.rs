@@ -0,0 +1,536 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+//! Storage backends split readers read split files from
+//!
+//! A reader addresses the files of a split by name, relative to the split
+//! root, and only ever needs to list them, get their length and read byte
+//! ranges of them, plus a Tantivy `Directory` to open the split's index
+//! through. `LocalStorage` serves split directories on the local
+//! filesystem; `SplitFileStorage` serves single-file `.split` splits in
+//! place; `DirectoryStorage` serves splits through any Tantivy
+//! `Directory`, such as a caching layer. Remote object stores plug in by
+//! implementing `SplitStorage`.
+
+use crate::error::{Result, SplitsError};
+use crate::hotcache::ByteRange;
+use crate::range_read::{read_file_range, ReadOptions};
+use crate::split_bytes::split_file_ranges;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, ErrorKind};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::SystemTime;
+use tantivy::directory::error::{DeleteError, LockError, OpenReadError, OpenWriteError};
+use tantivy::directory::{
+    Directory, DirectoryLock, FileHandle, Lock, MmapDirectory, OwnedBytes, WatchCallback, WatchHandle, WritePtr,
+};
+use tantivy::{HasLen, TantivyError};
+
+/// Source of the files of a split
+pub trait SplitStorage: Send + Sync {
+    /// Lists the names of the files at the root of the split, in no particular order
+    fn list_files(&self) -> Result<Vec<String>>;
+    
+    /// Gets the length of a file in bytes, `None` if there is no such file
+    fn file_len(&self, name: &str) -> Result<Option<u64>>;
+    
+    /// Gets the last modification time of a file, if the backend tracks one
+    fn modified(&self, name: &str) -> Result<Option<SystemTime>>;
+    
+    /// Reads a byte range of a file
+    ///
+    /// Backends without tunable reads are free to ignore `options`.
+    fn read_range(&self, name: &str, range: &ByteRange, options: &ReadOptions) -> Result<Vec<u8>>;
+    
+    /// Gets a Tantivy directory over the split, to open its index through
+    fn directory(&self) -> Result<Box<dyn Directory>>;
+    
+    /// Gets the path of the split on the local filesystem, its directory or
+    /// its split file, for backends having one
+    fn local_path(&self) -> Option<&Path> {
+        None
+    }
+    
+    /// Whether the split files are memory-mapped, so bytes read through
+    /// `directory` stay at a stable address while held
+    fn is_memory_mapped(&self) -> bool {
+        false
+    }
+    
+    /// Checks whether a file exists
+    fn exists(&self, name: &str) -> Result<bool> {
+        Ok(self.file_len(name)?.is_some())
+    }
+    
+    /// Reads a whole file
+    fn read_file(&self, name: &str) -> Result<Vec<u8>> {
+        let len = self.file_len(name)?
+            .ok_or_else(|| SplitsError::InvalidSplit(format!("File {} missing from split", name)))?;
+        self.read_range(name, &ByteRange { start: 0, end: len }, &ReadOptions::default())
+    }
+}
+
+/// A split directory on the local filesystem
+#[derive(Debug, Clone)]
+pub struct LocalStorage {
+    root: PathBuf,
+}
+
+impl LocalStorage {
+    /// Creates a storage over the split directory at `root`
+    pub fn new(root: &Path) -> Self {
+        LocalStorage { root: root.to_path_buf() }
+    }
+    
+    fn metadata(&self, name: &str) -> Result<Option<fs::Metadata>> {
+        match fs::metadata(self.root.join(name)) {
+            Ok(metadata) if metadata.is_file() => Ok(Some(metadata)),
+            Ok(_) => Ok(None),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+impl SplitStorage for LocalStorage {
+    fn list_files(&self) -> Result<Vec<String>> {
+        let mut files = Vec::new();
+        for entry in fs::read_dir(&self.root)? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() || entry.path().is_file() {
+                files.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+        Ok(files)
+    }
+    
+    fn file_len(&self, name: &str) -> Result<Option<u64>> {
+        Ok(self.metadata(name)?.map(|metadata| metadata.len()))
+    }
+    
+    fn modified(&self, name: &str) -> Result<Option<SystemTime>> {
+        Ok(self.metadata(name)?.and_then(|metadata| metadata.modified().ok()))
+    }
+    
+    fn read_range(&self, name: &str, range: &ByteRange, options: &ReadOptions) -> Result<Vec<u8>> {
+        read_file_range(&self.root.join(name), range, options)
+    }
+    
+    fn directory(&self) -> Result<Box<dyn Directory>> {
+        let directory = MmapDirectory::open(&self.root)
+            .map_err(|e| SplitsError::InvalidSplit(format!(
+                "Failed to open split directory {}: {}", self.root.display(), e
+            )))?;
+        Ok(Box::new(directory))
+    }
+    
+    fn local_path(&self) -> Option<&Path> {
+        Some(&self.root)
+    }
+    
+    fn is_memory_mapped(&self) -> bool {
+        true
+    }
+}
+
+/// A single-file split on the local filesystem, as written to `<split_id>.split`
+///
+/// The files of the split are located once, when the storage is created,
+/// and read in place from the split file: a split file replaced afterwards
+/// needs a new storage.
+#[derive(Debug, Clone)]
+pub struct SplitFileStorage {
+    path: PathBuf,
+    files: Arc<BTreeMap<String, ByteRange>>,
+}
+
+impl SplitFileStorage {
+    /// Creates a storage over the split file at `path`, failing with
+    /// `InvalidSplit` if it isn't one
+    pub fn open(path: &Path) -> Result<Self> {
+        let files = split_file_ranges(path)?.into_iter().collect();
+        Ok(SplitFileStorage { path: path.to_path_buf(), files: Arc::new(files) })
+    }
+}
+
+impl SplitStorage for SplitFileStorage {
+    fn list_files(&self) -> Result<Vec<String>> {
+        Ok(self.files.keys().cloned().collect())
+    }
+    
+    fn file_len(&self, name: &str) -> Result<Option<u64>> {
+        Ok(self.files.get(name).map(ByteRange::size))
+    }
+    
+    fn modified(&self, name: &str) -> Result<Option<SystemTime>> {
+        if !self.files.contains_key(name) {
+            return Ok(None);
+        }
+        Ok(fs::metadata(&self.path)?.modified().ok())
+    }
+    
+    fn read_range(&self, name: &str, range: &ByteRange, options: &ReadOptions) -> Result<Vec<u8>> {
+        let file_range = self.files.get(name)
+            .ok_or_else(|| SplitsError::InvalidSplit(format!("File {} missing from split", name)))?;
+        if range.start > range.end || range.end > file_range.size() {
+            return Err(io::Error::new(ErrorKind::UnexpectedEof, format!(
+                "Range {}..{} is past the end of {}", range.start, range.end, name
+            )).into());
+        }
+        
+        let range = ByteRange { start: file_range.start + range.start, end: file_range.start + range.end };
+        read_file_range(&self.path, &range, options)
+    }
+    
+    fn directory(&self) -> Result<Box<dyn Directory>> {
+        Ok(Box::new(SplitFileDirectory { path: Arc::new(self.path.clone()), files: self.files.clone() }))
+    }
+    
+    fn local_path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+}
+
+/// Read-only Tantivy directory over the files of a split file
+#[derive(Debug, Clone)]
+struct SplitFileDirectory {
+    path: Arc<PathBuf>,
+    files: Arc<BTreeMap<String, ByteRange>>,
+}
+
+impl SplitFileDirectory {
+    fn read_only(path: &Path) -> io::Error {
+        io::Error::new(ErrorKind::PermissionDenied, format!("Split files are read-only: {}", path.display()))
+    }
+}
+
+impl Directory for SplitFileDirectory {
+    fn get_file_handle(&self, path: &Path) -> std::result::Result<Arc<dyn FileHandle>, OpenReadError> {
+        let range = self.files.get(path.to_string_lossy().as_ref())
+            .ok_or_else(|| OpenReadError::FileDoesNotExist(path.to_path_buf()))?;
+        let file = fs::File::open(self.path.as_path())
+            .map_err(|e| OpenReadError::wrap_io_error(e, path.to_path_buf()))?;
+        Ok(Arc::new(SplitFileHandle { file, range: *range }))
+    }
+    
+    fn delete(&self, path: &Path) -> std::result::Result<(), DeleteError> {
+        Err(DeleteError::IoError { io_error: Arc::new(Self::read_only(path)), filepath: path.to_path_buf() })
+    }
+    
+    fn exists(&self, path: &Path) -> std::result::Result<bool, OpenReadError> {
+        Ok(self.files.contains_key(path.to_string_lossy().as_ref()))
+    }
+    
+    fn open_write(&self, path: &Path) -> std::result::Result<WritePtr, OpenWriteError> {
+        Err(OpenWriteError::wrap_io_error(Self::read_only(path), path.to_path_buf()))
+    }
+    
+    fn atomic_read(&self, path: &Path) -> std::result::Result<Vec<u8>, OpenReadError> {
+        let handle = self.get_file_handle(path)?;
+        let bytes = handle.read_bytes(0..handle.len())
+            .map_err(|e| OpenReadError::wrap_io_error(e, path.to_path_buf()))?;
+        Ok(bytes.as_slice().to_vec())
+    }
+    
+    fn atomic_write(&self, path: &Path, _data: &[u8]) -> io::Result<()> {
+        Err(Self::read_only(path))
+    }
+    
+    fn sync_directory(&self) -> io::Result<()> {
+        Ok(())
+    }
+    
+    // Nothing writes to a split file, so readers need no lock file
+    fn acquire_lock(&self, _lock: &Lock) -> std::result::Result<DirectoryLock, LockError> {
+        Ok(DirectoryLock::from(Box::new(())))
+    }
+    
+    fn watch(&self, _watch_callback: WatchCallback) -> tantivy::Result<WatchHandle> {
+        Ok(WatchHandle::empty())
+    }
+}
+
+/// A file of a split file, read on demand
+///
+/// The split file is opened once per handle and read at offsets, so
+/// concurrent reads share the open file without seeking it.
+#[derive(Debug)]
+struct SplitFileHandle {
+    file: fs::File,
+    range: ByteRange,
+}
+
+impl HasLen for SplitFileHandle {
+    fn len(&self) -> usize {
+        self.range.size() as usize
+    }
+}
+
+impl FileHandle for SplitFileHandle {
+    fn read_bytes(&self, range: Range<usize>) -> io::Result<OwnedBytes> {
+        if range.start > range.end || range.end > self.len() {
+            return Err(io::Error::new(ErrorKind::UnexpectedEof, format!(
+                "Range {:?} is past the end of a {} byte file", range, self.len()
+            )));
+        }
+        
+        let mut data = vec![0u8; range.len()];
+        read_exact_at(&self.file, &mut data, self.range.start + range.start as u64)?;
+        Ok(OwnedBytes::new(data))
+    }
+}
+
+/// Reads exactly `buf.len()` bytes of `file` at `offset`, leaving its cursor alone
+#[cfg(unix)]
+fn read_exact_at(file: &fs::File, buf: &mut [u8], offset: u64) -> io::Result<()> {
+    std::os::unix::fs::FileExt::read_exact_at(file, buf, offset)
+}
+
+/// Reads exactly `buf.len()` bytes of `file` at `offset`
+#[cfg(windows)]
+fn read_exact_at(file: &fs::File, mut buf: &mut [u8], mut offset: u64) -> io::Result<()> {
+    use std::os::windows::fs::FileExt;
+    
+    while !buf.is_empty() {
+        match file.seek_read(buf, offset) {
+            Ok(0) => return Err(io::Error::new(ErrorKind::UnexpectedEof, "failed to fill whole buffer")),
+            Ok(n) => {
+                buf = &mut buf[n..];
+                offset += n as u64;
+            }
+            Err(e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(())
+}
+
+/// Tantivy directory serving one file of an inner directory cut to a length
+///
+/// Split store files end with the hotcache footer, after Tantivy's own
+/// file footer. Indexes are opened through this directory serving the store
+/// file cut to its store data, so Tantivy finds its footer at the end.
+#[derive(Debug, Clone)]
+pub(crate) struct TruncatedFileDirectory {
+    inner: Box<dyn Directory>,
+    path: PathBuf,
+    len: u64,
+}
+
+impl TruncatedFileDirectory {
+    /// Wraps `inner`, serving the file at `path` cut to its first `len` bytes
+    pub(crate) fn new(inner: Box<dyn Directory>, path: &Path, len: u64) -> Self {
+        TruncatedFileDirectory { inner, path: path.to_path_buf(), len }
+    }
+}
+
+impl Directory for TruncatedFileDirectory {
+    fn get_file_handle(&self, path: &Path) -> std::result::Result<Arc<dyn FileHandle>, OpenReadError> {
+        let handle = self.inner.get_file_handle(path)?;
+        if path != self.path {
+            return Ok(handle);
+        }
+        if (handle.len() as u64) < self.len {
+            return Err(OpenReadError::wrap_io_error(io::Error::new(ErrorKind::UnexpectedEof, format!(
+                "File is {} bytes, shorter than its {} bytes of data", handle.len(), self.len
+            )), path.to_path_buf()));
+        }
+        Ok(Arc::new(TruncatedFileHandle { inner: handle, len: self.len as usize }))
+    }
+    
+    fn delete(&self, path: &Path) -> std::result::Result<(), DeleteError> {
+        self.inner.delete(path)
+    }
+    
+    fn exists(&self, path: &Path) -> std::result::Result<bool, OpenReadError> {
+        self.inner.exists(path)
+    }
+    
+    fn open_write(&self, path: &Path) -> std::result::Result<WritePtr, OpenWriteError> {
+        self.inner.open_write(path)
+    }
+    
+    fn atomic_read(&self, path: &Path) -> std::result::Result<Vec<u8>, OpenReadError> {
+        let mut data = self.inner.atomic_read(path)?;
+        if path == self.path {
+            data.truncate(self.len as usize);
+        }
+        Ok(data)
+    }
+    
+    fn atomic_write(&self, path: &Path, data: &[u8]) -> io::Result<()> {
+        self.inner.atomic_write(path, data)
+    }
+    
+    fn sync_directory(&self) -> io::Result<()> {
+        self.inner.sync_directory()
+    }
+    
+    fn acquire_lock(&self, lock: &Lock) -> std::result::Result<DirectoryLock, LockError> {
+        self.inner.acquire_lock(lock)
+    }
+    
+    fn watch(&self, watch_callback: WatchCallback) -> tantivy::Result<WatchHandle> {
+        self.inner.watch(watch_callback)
+    }
+}
+
+/// The first bytes of a file of an inner directory
+#[derive(Debug)]
+struct TruncatedFileHandle {
+    inner: Arc<dyn FileHandle>,
+    len: usize,
+}
+
+impl HasLen for TruncatedFileHandle {
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl FileHandle for TruncatedFileHandle {
+    fn read_bytes(&self, range: Range<usize>) -> io::Result<OwnedBytes> {
+        if range.start > range.end || range.end > self.len {
+            return Err(io::Error::new(ErrorKind::UnexpectedEof, format!(
+                "Range {:?} is past the end of a {} byte file", range, self.len
+            )));
+        }
+        self.inner.read_bytes(range)
+    }
+}
+
+/// A split served through a Tantivy `Directory`
+///
+/// Directories can't list their files, so the names of the split's files
+/// are given upfront, e.g. from `SplitMetadata::files`.
+pub struct DirectoryStorage {
+    directory: Box<dyn Directory>,
+    files: Vec<String>,
+}
+
+impl DirectoryStorage {
+    /// Creates a storage over `directory`, holding the split files named in `files`
+    pub fn new(directory: Box<dyn Directory>, files: Vec<String>) -> Self {
+        DirectoryStorage { directory, files }
+    }
+}
+
+impl SplitStorage for DirectoryStorage {
+    fn list_files(&self) -> Result<Vec<String>> {
+        Ok(self.files.clone())
+    }
+    
+    fn file_len(&self, name: &str) -> Result<Option<u64>> {
+        if !self.files.iter().any(|file| file == name) {
+            return Ok(None);
+        }
+        
+        match self.directory.get_file_handle(Path::new(name)) {
+            Ok(handle) => Ok(Some(handle.len() as u64)),
+            Err(OpenReadError::FileDoesNotExist(_)) => Ok(None),
+            Err(e) => Err(TantivyError::from(e).into()),
+        }
+    }
+    
+    fn modified(&self, _name: &str) -> Result<Option<SystemTime>> {
+        Ok(None)
+    }
+    
+    fn read_range(&self, name: &str, range: &ByteRange, _options: &ReadOptions) -> Result<Vec<u8>> {
+        let handle = self.directory.get_file_handle(Path::new(name))
+            .map_err(TantivyError::from)?;
+        let bytes = handle.read_bytes(range.start as usize..range.end as usize)?;
+        Ok(bytes.as_slice().to_vec())
+    }
+    
+    fn directory(&self) -> Result<Box<dyn Directory>> {
+        Ok(self.directory.box_clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::split_bytes::write_split_file;
+    use tantivy::directory::RamDirectory;
+    use tempfile::TempDir;
+    
+    #[test]
+    fn test_local_and_directory_storage_agree() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("a.store"), b"hello split").unwrap();
+        fs::create_dir(temp_dir.path().join("nested")).unwrap();
+        
+        let ram_directory = RamDirectory::create();
+        ram_directory.atomic_write(Path::new("a.store"), b"hello split").unwrap();
+        
+        let split_file_dir = TempDir::new().unwrap();
+        let split_file_path = split_file_dir.path().join("a.split");
+        write_split_file(temp_dir.path(), &split_file_path).unwrap();
+        
+        let local = LocalStorage::new(temp_dir.path());
+        let directory = DirectoryStorage::new(Box::new(ram_directory), vec!["a.store".to_string()]);
+        let split_file = SplitFileStorage::open(&split_file_path).unwrap();
+        let range = ByteRange { start: 6, end: 11 };
+        
+        for storage in [&local as &dyn SplitStorage, &directory, &split_file] {
+            assert_eq!(storage.list_files().unwrap(), vec!["a.store".to_string()]);
+            assert_eq!(storage.file_len("a.store").unwrap(), Some(11));
+            assert_eq!(storage.file_len("missing.store").unwrap(), None);
+            assert_eq!(storage.read_range("a.store", &range, &ReadOptions::default()).unwrap(), b"split");
+            assert_eq!(storage.read_file("a.store").unwrap(), b"hello split");
+        }
+        assert_eq!(local.file_len("nested").unwrap(), None);
+        assert_eq!(local.local_path(), Some(temp_dir.path()));
+        assert!(directory.local_path().is_none());
+        assert_eq!(split_file.local_path(), Some(split_file_path.as_path()));
+    }
+    
+    #[test]
+    fn test_split_file_storage() {
+        let temp_dir = TempDir::new().unwrap();
+        let split_dir = temp_dir.path().join("split");
+        fs::create_dir(&split_dir).unwrap();
+        fs::write(split_dir.join("a.idx"), b"postings").unwrap();
+        fs::write(split_dir.join("a.store"), b"hello split").unwrap();
+        let split_file_path = temp_dir.path().join("a.split");
+        write_split_file(&split_dir, &split_file_path).unwrap();
+        
+        let storage = SplitFileStorage::open(&split_file_path).unwrap();
+        assert_eq!(storage.list_files().unwrap(), vec!["a.idx".to_string(), "a.store".to_string()]);
+        assert!(storage.read_range("a.idx", &ByteRange { start: 4, end: 9 }, &ReadOptions::default()).is_err());
+        
+        let directory = storage.directory().unwrap();
+        assert_eq!(directory.atomic_read(Path::new("a.idx")).unwrap(), b"postings");
+        let handle = directory.get_file_handle(Path::new("a.store")).unwrap();
+        assert_eq!(handle.read_bytes(6..11).unwrap().as_slice(), b"split");
+        assert_eq!(handle.read_bytes(0..5).unwrap().as_slice(), b"hello");
+        assert!(handle.read_bytes(6..12).is_err());
+        assert!(!directory.exists(Path::new("missing.store")).unwrap());
+        assert!(directory.atomic_write(Path::new("a.idx"), b"").is_err());
+        
+        // Cut in the middle of the last file
+        let bytes = fs::read(&split_file_path).unwrap();
+        fs::write(&split_file_path, &bytes[..bytes.len() - 3]).unwrap();
+        assert!(matches!(SplitFileStorage::open(&split_file_path), Err(SplitsError::InvalidSplit(_))));
+    }
+}
@@ -0,0 +1,118 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+//! Bounded-memory streaming copies that hash what they copy
+//!
+//! `fs::copy` is the fastest way to duplicate a file, but writers that
+//! concatenate files or need a digest of their content would otherwise have
+//! to buffer whole files. These helpers stream through a fixed-size buffer
+//! instead, so multi-gigabyte `.store` files are processed with constant
+//! memory while their BLAKE3 hash is computed on the fly.
+
+use crate::error::Result;
+use std::fs::File;
+use std::io::{BufWriter, ErrorKind, Read, Write};
+use std::path::Path;
+
+/// Size of the buffer data is streamed through
+pub const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// Outcome of a streaming copy
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CopyDigest {
+    /// Number of bytes copied
+    pub len: u64,
+    /// BLAKE3 hash of the copied bytes
+    pub hash: blake3::Hash,
+}
+
+/// Copies `reader` to the end of `writer`, hashing the bytes as they pass
+///
+/// Appending to a writer that already holds data is how files get
+/// concatenated: the digest only covers the bytes copied by this call.
+pub fn copy_with_hash<R: Read, W: Write>(reader: &mut R, writer: &mut W) -> Result<CopyDigest> {
+    let mut buffer = vec![0u8; COPY_BUFFER_SIZE];
+    let mut hasher = blake3::Hasher::new();
+    let mut len = 0u64;
+    
+    loop {
+        let read = match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(read) => read,
+            Err(e) if e.kind() == ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        hasher.update(&buffer[..read]);
+        writer.write_all(&buffer[..read])?;
+        len += read as u64;
+    }
+    writer.flush()?;
+    
+    Ok(CopyDigest { len, hash: hasher.finalize() })
+}
+
+/// Copies the file at `src` to `dst`, returning the hash of its content
+pub fn copy_file_with_hash(src: &Path, dst: &Path) -> Result<CopyDigest> {
+    let mut reader = File::open(src)?;
+    let mut writer = BufWriter::with_capacity(COPY_BUFFER_SIZE, File::create(dst)?);
+    
+    let digest = copy_with_hash(&mut reader, &mut writer)?;
+    writer.into_inner().map_err(|e| e.into_error())?.sync_all()?;
+    
+    Ok(digest)
+}
+
+/// Hashes the file at `path` without copying it
+pub fn hash_file(path: &Path) -> Result<CopyDigest> {
+    copy_with_hash(&mut File::open(path)?, &mut std::io::sink())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+    
+    #[test]
+    fn test_copy_file_with_hash_streams_large_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let src = temp_dir.path().join("large.store");
+        let dst = temp_dir.path().join("copy.store");
+        
+        // Several buffers' worth plus a partial one
+        let data: Vec<u8> = (0..COPY_BUFFER_SIZE * 3 + 12345).map(|i| (i * 31 % 251) as u8).collect();
+        std::fs::write(&src, &data).unwrap();
+        
+        let digest = copy_file_with_hash(&src, &dst).unwrap();
+        
+        assert_eq!(digest.len, data.len() as u64);
+        assert_eq!(digest.hash, blake3::hash(&data));
+        assert_eq!(std::fs::read(&dst).unwrap(), data);
+        assert_eq!(hash_file(&dst).unwrap(), digest);
+    }
+    
+    #[test]
+    fn test_copy_with_hash_appends_to_writer() {
+        let mut output = b"header".to_vec();
+        let digest = copy_with_hash(&mut &b"payload"[..], &mut output).unwrap();
+        
+        assert_eq!(output, b"headerpayload");
+        assert_eq!(digest.len, 7);
+        assert_eq!(digest.hash, blake3::hash(b"payload"));
+    }
+}
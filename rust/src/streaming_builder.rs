@@ -0,0 +1,317 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+//! Incremental split generation, as documents arrive
+//!
+//! A `StreamingSplitBuilder` buffers documents into an in-RAM index and
+//! emits a finished split as soon as `target_docs_per_split` of them are
+//! buffered, then starts over with an empty buffer. Documents come either one
+//! at a time, or a committed segment of another index at a time, as a
+//! pipeline notified of commits would feed them. `finish` flushes the last,
+//! partial split.
+//!
+//! Memory is bounded by the buffer: at most `target_docs_per_split`
+//! documents, indexed, plus the indexing arena of `WRITER_MEMORY_BYTES`.
+//! Documents of a segment are buffered one at a time, so adding a large
+//! segment emits several splits rather than holding it whole.
+
+use crate::error::{Result, SplitsError};
+use crate::split_generator::{split_file_name, QuickwitSplitGenerator, SplitConfig, SplitMetadata};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tantivy::schema::Schema;
+use tantivy::{Index, IndexWriter, TantivyDocument};
+use uuid::Uuid;
+
+/// Memory budget of the indexing arena of the buffer
+pub const WRITER_MEMORY_BYTES: usize = 50_000_000;
+
+/// Number of decompressed doc store blocks cached while reading a segment
+const STORE_CACHE_BLOCKS: usize = 10;
+
+/// Builds splits from a stream of documents, emitting one per `target_docs_per_split` documents
+pub struct StreamingSplitBuilder {
+    /// Schema of every buffered document
+    schema: Schema,
+    /// Directory splits are written to
+    output_dir: PathBuf,
+    /// Number of documents per emitted split
+    target_docs_per_split: usize,
+    /// Options of every emitted split
+    config: SplitConfig,
+    /// Writer of the in-RAM index buffering documents, see `new_buffer`
+    writer: IndexWriter,
+    /// Number of documents in the buffer
+    buffered_docs: usize,
+    /// Splits emitted so far, in order
+    splits: Vec<SplitMetadata>,
+    /// Whether `finish` was called
+    finished: bool,
+}
+
+impl StreamingSplitBuilder {
+    /// Starts building splits of documents with `schema` into `output_dir`
+    ///
+    /// Splits are named like partitioned ones: `<split_id>-<n>` when
+    /// `SplitConfig::split_id` is set, or else a fresh id each. Custom
+    /// tokenizers of the configuration are registered on the buffer, so
+    /// documents are tokenized as the split records.
+    pub fn new(schema: Schema, output_dir: &Path, target_docs_per_split: usize, config: SplitConfig) -> Result<Self> {
+        if target_docs_per_split == 0 {
+            return Err(SplitsError::InvalidOperation(
+                "Target docs per split must be greater than 0".to_string()
+            ));
+        }
+        fs::create_dir_all(output_dir)?;
+        
+        let writer = new_buffer(&schema, &config)?;
+        Ok(StreamingSplitBuilder {
+            schema,
+            output_dir: output_dir.to_path_buf(),
+            target_docs_per_split,
+            config,
+            writer,
+            buffered_docs: 0,
+            splits: Vec::new(),
+            finished: false,
+        })
+    }
+    
+    /// Buffers a document, emitting a split if the buffer reached its target size
+    pub fn add_document(&mut self, doc: TantivyDocument) -> Result<Option<SplitMetadata>> {
+        self.ensure_not_finished()?;
+        self.writer.add_document(doc)?;
+        self.buffered_docs += 1;
+        
+        if self.buffered_docs < self.target_docs_per_split {
+            return Ok(None);
+        }
+        self.flush()
+    }
+    
+    /// Buffers a document given as JSON, as parsed by Tantivy against the schema
+    ///
+    /// Fails with a `Serialization` error on JSON not matching the schema.
+    pub fn add_json_document(&mut self, json: &str) -> Result<Option<SplitMetadata>> {
+        let doc = TantivyDocument::parse_json(&self.schema, json)
+            .map_err(|e| SplitsError::Serialization(format!("Invalid document: {}", e)))?;
+        self.add_document(doc)
+    }
+    
+    /// Buffers the live documents of a committed segment of `index`, emitting splits as the buffer fills
+    ///
+    /// `segment_id` is the segment's UUID, as Tantivy names its files.
+    /// Documents are rebuilt from the doc store, so `index` must have the
+    /// builder's schema with every field stored. Fails with
+    /// `InvalidOperation` if the segment isn't among the committed ones.
+    pub fn add_segment(&mut self, index: &Index, segment_id: &str) -> Result<Vec<SplitMetadata>> {
+        self.ensure_not_finished()?;
+        if index.schema() != self.schema {
+            return Err(SplitsError::InvalidOperation(
+                "Segment index schema differs from the streaming builder's".to_string()
+            ));
+        }
+        let unstored: Vec<&str> = self.schema.fields()
+            .filter(|(_, entry)| !entry.is_stored())
+            .map(|(_, entry)| entry.name())
+            .collect();
+        if !unstored.is_empty() {
+            return Err(SplitsError::FieldError(format!(
+                "Adding a segment re-indexes stored documents, but these fields aren't stored: {}",
+                unstored.join(", ")
+            )));
+        }
+        
+        let searcher = index.reader()?.searcher();
+        let segment_reader = searcher.segment_readers()
+            .iter()
+            .find(|segment_reader| segment_reader.segment_id().uuid_string() == segment_id)
+            .ok_or_else(|| SplitsError::InvalidOperation(format!(
+                "Segment {} not found among the committed segments of the index", segment_id
+            )))?;
+        
+        let store_reader = segment_reader.get_store_reader(STORE_CACHE_BLOCKS)?;
+        let mut emitted = Vec::new();
+        for doc_id in segment_reader.doc_ids_alive() {
+            let doc: TantivyDocument = store_reader.get(doc_id)?;
+            emitted.extend(self.add_document(doc)?);
+        }
+        Ok(emitted)
+    }
+    
+    /// Flushes the buffered documents into a final split, if any
+    ///
+    /// Returns every split emitted since the builder was created, in order.
+    /// Documents can't be added afterwards.
+    pub fn finish(&mut self) -> Result<Vec<SplitMetadata>> {
+        self.ensure_not_finished()?;
+        self.flush()?;
+        self.finished = true;
+        Ok(self.splits.clone())
+    }
+    
+    /// Gets the number of documents buffered for the next split
+    pub fn buffered_docs(&self) -> usize {
+        self.buffered_docs
+    }
+    
+    /// Gets the splits emitted so far, in order
+    pub fn splits(&self) -> &[SplitMetadata] {
+        &self.splits
+    }
+    
+    /// Writes the buffered documents as a split and starts a new buffer
+    ///
+    /// On failure the buffer keeps its documents, so the next flush writes
+    /// them along with the ones added meanwhile.
+    fn flush(&mut self) -> Result<Option<SplitMetadata>> {
+        if self.buffered_docs == 0 {
+            return Ok(None);
+        }
+        
+        let next_writer = new_buffer(&self.schema, &self.config)?;
+        self.writer.commit()?;
+        let index = self.writer.index().clone();
+        
+        let mut config = self.config.clone();
+        let split_id = match &self.config.split_id {
+            Some(prefix) => format!("{}-{}", prefix, self.splits.len()),
+            None => Uuid::now_v7().to_string(),
+        };
+        config.split_id = Some(split_id.clone());
+        let split_path = if self.config.legacy_directory_layout {
+            self.output_dir.join(&split_id)
+        } else {
+            self.output_dir.join(split_file_name(&split_id))
+        };
+        
+        let metadata = QuickwitSplitGenerator::new(index, self.target_docs_per_split)?
+            .with_config(config)
+            .generate_split(&split_path)?;
+        self.writer = next_writer;
+        self.buffered_docs = 0;
+        log::debug!("Emitted streaming split {} of {} documents", metadata.split_id, metadata.num_docs);
+        self.splits.push(metadata.clone());
+        Ok(Some(metadata))
+    }
+    
+    fn ensure_not_finished(&self) -> Result<()> {
+        if self.finished {
+            return Err(SplitsError::InvalidOperation("Streaming builder already finished".to_string()));
+        }
+        Ok(())
+    }
+}
+
+/// Creates an empty in-RAM index to buffer documents into, and its writer
+fn new_buffer(schema: &Schema, config: &SplitConfig) -> Result<IndexWriter> {
+    let index = Index::create_in_ram(schema.clone());
+    for (name, tokenizer) in &config.tokenizers {
+        index.tokenizers().register(name, tokenizer.build()?);
+    }
+    Ok(index.writer(WRITER_MEMORY_BYTES)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::split_reader::QuickwitSplitReader;
+    use tantivy::schema::{STORED, TEXT};
+    use tempfile::TempDir;
+    
+    fn test_schema() -> Schema {
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", TEXT | STORED);
+        schema_builder.build()
+    }
+    
+    #[test]
+    fn test_emits_a_split_per_target_docs_and_flushes_on_finish() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = SplitConfig { split_id: Some("stream".to_string()), ..SplitConfig::default() };
+        let mut builder = StreamingSplitBuilder::new(test_schema(), temp_dir.path(), 2, config).unwrap();
+        
+        assert!(builder.add_json_document(r#"{"title": "first"}"#).unwrap().is_none());
+        let emitted = builder.add_json_document(r#"{"title": "second"}"#).unwrap().unwrap();
+        assert_eq!(emitted.split_id, "stream-0");
+        assert_eq!(emitted.num_docs, 2);
+        assert_eq!(builder.buffered_docs(), 0);
+        
+        builder.add_json_document(r#"{"title": "third"}"#).unwrap();
+        assert!(matches!(builder.add_json_document("not json"), Err(SplitsError::Serialization(_))));
+        let splits = builder.finish().unwrap();
+        assert_eq!(splits.iter().map(|split| split.num_docs).collect::<Vec<_>>(), vec![2, 1]);
+        assert_eq!(splits[1].split_id, "stream-1");
+        
+        let reader = QuickwitSplitReader::open(&temp_dir.path().join("stream-1.split")).unwrap();
+        assert_eq!(reader.field_terms("title").unwrap(), vec!["third"]);
+        assert!(matches!(builder.add_json_document(r#"{"title": "late"}"#), Err(SplitsError::InvalidOperation(_))));
+    }
+    
+    #[test]
+    fn test_add_segment() {
+        let temp_dir = TempDir::new().unwrap();
+        let source = Index::create_in_ram(test_schema());
+        let title = source.schema().get_field("title").unwrap();
+        let mut index_writer: IndexWriter = source.writer(15_000_000).unwrap();
+        for value in ["a", "b", "c"] {
+            index_writer.add_document(tantivy::doc!(title => value)).unwrap();
+        }
+        index_writer.commit().unwrap();
+        let segment_id = source.searchable_segment_ids().unwrap()[0].uuid_string();
+        
+        let mut builder = StreamingSplitBuilder::new(test_schema(), temp_dir.path(), 2, SplitConfig::default()).unwrap();
+        let emitted = builder.add_segment(&source, &segment_id).unwrap();
+        assert_eq!(emitted.len(), 1);
+        assert_eq!(builder.buffered_docs(), 1);
+        assert!(matches!(builder.add_segment(&source, "missing"), Err(SplitsError::InvalidOperation(_))));
+        assert_eq!(builder.finish().unwrap().len(), 2);
+        
+        let mut schema_builder = Schema::builder();
+        schema_builder.add_text_field("title", TEXT);
+        let unstored = Index::create_in_ram(schema_builder.build());
+        let mut builder = StreamingSplitBuilder::new(unstored.schema(), temp_dir.path(), 2, SplitConfig::default()).unwrap();
+        assert!(matches!(builder.add_segment(&unstored, &segment_id), Err(SplitsError::FieldError(_))));
+        assert!(builder.finish().unwrap().is_empty());
+    }
+    
+    #[test]
+    fn test_failed_flush_keeps_buffered_documents() {
+        let temp_dir = TempDir::new().unwrap();
+        let config = SplitConfig { split_id: Some("stream".to_string()), ..SplitConfig::default() };
+        let mut builder = StreamingSplitBuilder::new(test_schema(), temp_dir.path(), 2, config).unwrap();
+        
+        // An existing file at the split's path fails its generation
+        let blocker = temp_dir.path().join("stream-0.split");
+        fs::write(&blocker, b"taken").unwrap();
+        builder.add_json_document(r#"{"title": "first"}"#).unwrap();
+        assert!(builder.add_json_document(r#"{"title": "second"}"#).is_err());
+        assert_eq!(builder.buffered_docs(), 2);
+        assert!(builder.splits().is_empty());
+        
+        fs::remove_file(&blocker).unwrap();
+        builder.add_json_document(r#"{"title": "third"}"#).unwrap();
+        let splits = builder.finish().unwrap();
+        assert_eq!(splits.len(), 1);
+        assert_eq!(splits[0].num_docs, 3);
+        
+        let reader = QuickwitSplitReader::open(&temp_dir.path().join("stream-0.split")).unwrap();
+        assert_eq!(reader.field_terms("title").unwrap(), vec!["first", "second", "third"]);
+    }
+}
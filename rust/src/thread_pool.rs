@@ -0,0 +1,82 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+//! Crate-wide thread pool bounding the CPU used by split generation
+//!
+//! Merges and parallel file copies from every generator run on this single
+//! pool, so total concurrency stays bounded however many generators are
+//! active. Tantivy's `IndexWriter` still spawns its own indexing and merge
+//! threads while a merge runs on the pool; the pool caps how many such merges
+//! run at once, not the threads each of them uses internally.
+
+use crate::error::{Result, SplitsError};
+use once_cell::sync::OnceCell;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Configured pool size, 0 meaning the available parallelism
+static MAX_THREADS: AtomicUsize = AtomicUsize::new(0);
+
+static POOL: OnceCell<rayon::ThreadPool> = OnceCell::new();
+
+/// Sets the number of threads of the shared pool
+///
+/// Must be called before the first generation, as the pool is built on
+/// first use and cannot be resized afterwards.
+pub fn set_max_threads(num_threads: usize) -> Result<()> {
+    if num_threads == 0 {
+        return Err(SplitsError::InvalidOperation(
+            "Max threads must be greater than 0".to_string()
+        ));
+    }
+    if POOL.get().is_some() {
+        return Err(SplitsError::InvalidOperation(
+            "Thread pool is already initialized; set max threads at startup".to_string()
+        ));
+    }
+    
+    MAX_THREADS.store(num_threads, Ordering::SeqCst);
+    Ok(())
+}
+
+/// Gets the number of threads of the shared pool
+pub fn max_threads() -> usize {
+    match MAX_THREADS.load(Ordering::SeqCst) {
+        0 => std::thread::available_parallelism().map(|n| n.get()).unwrap_or(1),
+        num_threads => num_threads,
+    }
+}
+
+/// Gets the shared pool, building it on first use
+pub fn global_pool() -> Result<&'static rayon::ThreadPool> {
+    POOL.get_or_try_init(|| {
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(max_threads())
+            .thread_name(|i| format!("quickwit-splits-{}", i))
+            .build()
+            .map_err(|e| SplitsError::InvalidOperation(format!("Failed to build thread pool: {}", e)))
+    })
+}
+
+/// Runs `op` on the shared pool
+///
+/// Merges run here rather than on the caller's thread, so concurrent
+/// generators and merges can't oversubscribe cores.
+pub fn install<R: Send>(op: impl FnOnce() -> Result<R> + Send) -> Result<R> {
+    global_pool()?.install(op)
+}
@@ -0,0 +1,139 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+//! Serializable tokenizer configurations
+//!
+//! Splits indexed with custom tokenizers need the same tokenizers at query
+//! time. The generator records their configuration in the hotcache so the
+//! reader can rebuild them. Tantivy's built-in tokenizers (`default`, `raw`,
+//! `en_stem` and `whitespace`) are always available and need no configuration.
+
+use crate::error::{Result, SplitsError};
+use serde::{Deserialize, Serialize};
+use tantivy::tokenizer::{
+    Language, LowerCaser, NgramTokenizer, RawTokenizer, RemoveLongFilter, SimpleTokenizer,
+    Stemmer, TextAnalyzer, Tokenizer, WhitespaceTokenizer,
+};
+
+/// Tokens longer than this are dropped by the stemming tokenizer, like Tantivy's `en_stem`
+const STEM_MAX_TOKEN_LEN: usize = 40;
+
+/// Configuration of a tokenizer that can be rebuilt from its serialized form
+///
+/// Serialized as JSON with a `type` tag, e.g.
+/// `{"type": "ngram", "min_gram": 2, "max_gram": 3}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TokenizerConfig {
+    /// Splits on non-alphanumeric characters
+    Simple {
+        #[serde(default = "default_lowercase")]
+        lowercase: bool,
+    },
+    /// Splits on whitespace
+    Whitespace {
+        #[serde(default = "default_lowercase")]
+        lowercase: bool,
+    },
+    /// Emits the whole text as a single token
+    Raw,
+    /// Emits the n-grams of the text
+    Ngram {
+        min_gram: usize,
+        max_gram: usize,
+        #[serde(default)]
+        prefix_only: bool,
+        #[serde(default = "default_lowercase")]
+        lowercase: bool,
+    },
+    /// Simple tokenizer followed by lowercasing and stemming in `language`
+    Stem {
+        language: Language,
+    },
+}
+
+fn default_lowercase() -> bool {
+    true
+}
+
+impl TokenizerConfig {
+    /// Parses a tokenizer configuration from JSON
+    pub fn from_json(json: &str) -> Result<Self> {
+        serde_json::from_str(json)
+            .map_err(|e| SplitsError::Serialization(format!("Invalid tokenizer config: {}", e)))
+    }
+    
+    /// Builds the tokenizer described by this configuration
+    pub fn build(&self) -> Result<TextAnalyzer> {
+        Ok(match self {
+            TokenizerConfig::Simple { lowercase } => with_lowercase(SimpleTokenizer::default(), *lowercase),
+            TokenizerConfig::Whitespace { lowercase } => with_lowercase(WhitespaceTokenizer::default(), *lowercase),
+            TokenizerConfig::Raw => TextAnalyzer::from(RawTokenizer::default()),
+            TokenizerConfig::Ngram { min_gram, max_gram, prefix_only, lowercase } => {
+                let tokenizer = NgramTokenizer::new(*min_gram, *max_gram, *prefix_only)
+                    .map_err(|e| SplitsError::InvalidOperation(format!("Invalid ngram tokenizer: {}", e)))?;
+                with_lowercase(tokenizer, *lowercase)
+            }
+            TokenizerConfig::Stem { language } => TextAnalyzer::builder(SimpleTokenizer::default())
+                .filter(RemoveLongFilter::limit(STEM_MAX_TOKEN_LEN))
+                .filter(LowerCaser)
+                .filter(Stemmer::new(*language))
+                .build(),
+        })
+    }
+}
+
+/// Wraps `tokenizer` in an analyzer, lowercasing its tokens if requested
+fn with_lowercase<T: Tokenizer>(tokenizer: T, lowercase: bool) -> TextAnalyzer {
+    if lowercase {
+        TextAnalyzer::builder(tokenizer).filter(LowerCaser).build()
+    } else {
+        TextAnalyzer::from(tokenizer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    
+    fn tokens(config: &TokenizerConfig, text: &str) -> Vec<String> {
+        let mut analyzer = config.build().unwrap();
+        let mut stream = analyzer.token_stream(text);
+        let mut tokens = Vec::new();
+        stream.process(&mut |token| tokens.push(token.text.clone()));
+        tokens
+    }
+    
+    #[test]
+    fn test_tokenizer_config_from_json() {
+        let config = TokenizerConfig::from_json(r#"{"type": "ngram", "min_gram": 2, "max_gram": 3, "prefix_only": true}"#).unwrap();
+        assert_eq!(tokens(&config, "Split"), vec!["sp", "spl"]);
+        
+        let config = TokenizerConfig::from_json(r#"{"type": "stem", "language": "English"}"#).unwrap();
+        assert_eq!(tokens(&config, "Running splits"), vec!["run", "split"]);
+        
+        assert!(matches!(TokenizerConfig::from_json(r#"{"type": "unknown"}"#), Err(SplitsError::Serialization(_))));
+    }
+    
+    #[test]
+    fn test_invalid_ngram_config_fails_to_build() {
+        let config = TokenizerConfig::Ngram { min_gram: 3, max_gram: 2, prefix_only: false, lowercase: true };
+        assert!(matches!(config.build(), Err(SplitsError::InvalidOperation(_))));
+    }
+}
@@ -0,0 +1,149 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+//! End-to-end round trip: index, generate a split, open it and read it back
+//!
+//! Indexes documents into an on-disk index, over several commits so
+//! generation merges segments, runs the full `generate_split` pipeline and
+//! checks that documents, postings, terms and fast fields read back through
+//! `QuickwitSplitReader` match what was indexed, for both split layouts.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::Path;
+use tantivy::schema::{SchemaBuilder, FAST, STORED, STRING, TEXT};
+use tantivy::{doc, Index};
+use tantivy4java_splits::split_generator::{QuickwitSplitGenerator, SplitConfig};
+use tantivy4java_splits::split_reader::QuickwitSplitReader;
+use tempfile::TempDir;
+
+const NUM_DOCS: u64 = 300;
+const CATEGORIES: [&str; 3] = ["books", "games", "music"];
+
+/// An indexed document, as expected back from the split
+struct Expected {
+    title: String,
+    category: &'static str,
+}
+
+/// Builds an on-disk index of `NUM_DOCS` documents over three commits
+fn build_index(index_dir: &Path) -> BTreeMap<u64, Expected> {
+    let mut schema_builder = SchemaBuilder::default();
+    let id = schema_builder.add_u64_field("id", FAST | STORED);
+    let title = schema_builder.add_text_field("title", TEXT | STORED);
+    let category = schema_builder.add_text_field("category", STRING | FAST | STORED);
+    let index = Index::create_in_dir(index_dir, schema_builder.build()).unwrap();
+    
+    let mut expected = BTreeMap::new();
+    let mut index_writer: tantivy::IndexWriter = index.writer(15_000_000).unwrap();
+    for i in 0..NUM_DOCS {
+        let animal = if i % 5 == 0 { "fox" } else { "dog" };
+        let title_value = format!("the quick {} number {}", animal, i);
+        let category_value = CATEGORIES[i as usize % CATEGORIES.len()];
+        index_writer.add_document(doc!(id => i, title => title_value.clone(), category => category_value)).unwrap();
+        if i % 100 == 99 {
+            index_writer.commit().unwrap();
+        }
+        expected.insert(i, Expected { title: title_value, category: category_value });
+    }
+    index_writer.commit().unwrap();
+    expected
+}
+
+/// Reads every document back from the split and compares it with what was indexed
+fn assert_round_trip(split_path: &Path, expected: &BTreeMap<u64, Expected>) {
+    let reader = QuickwitSplitReader::open(split_path).unwrap();
+    assert!(reader.get_hotcache_info().is_some());
+    
+    // Stored fields
+    let mut seen = BTreeSet::new();
+    for doc_id in 0..NUM_DOCS as u32 {
+        let document = reader.get_document(doc_id).unwrap();
+        let id = document["id"].as_u64().unwrap();
+        let indexed = &expected[&id];
+        assert_eq!(document["title"], indexed.title.as_str());
+        assert_eq!(document["category"], indexed.category);
+        assert!(seen.insert(id), "id {} read twice", id);
+    }
+    assert_eq!(seen.len() as u64, NUM_DOCS);
+    assert!(reader.get_document(NUM_DOCS as u32).is_err());
+    
+    // Postings
+    let fox_docs = reader.read_posting_list("title", "fox").unwrap();
+    assert_eq!(fox_docs.len() as u64, NUM_DOCS / 5);
+    for doc_id in fox_docs {
+        let id = reader.get_document(doc_id).unwrap()["id"].as_u64().unwrap();
+        assert!(expected[&id].title.contains("fox"));
+    }
+    assert_eq!(reader.read_posting_list("title", "quick").unwrap().len() as u64, NUM_DOCS);
+    assert!(reader.read_posting_list("title", "cat").unwrap().is_empty());
+    
+    // Terms
+    assert_eq!(reader.field_terms("category").unwrap(), CATEGORIES.to_vec());
+    
+    // Fast fields
+    let mut ids: Vec<u64> = reader.column("id").unwrap().iter().map(|value| value.as_u64().unwrap()).collect();
+    ids.sort_unstable();
+    assert_eq!(ids, (0..NUM_DOCS).collect::<Vec<_>>());
+    assert_eq!(reader.numeric_bounds("id").unwrap(), (0, NUM_DOCS as i64 - 1));
+    assert_eq!(reader.field_cardinality("category", true).unwrap(), CATEGORIES.len() as u64);
+}
+
+#[test]
+fn test_split_file_round_trip() {
+    let temp_dir = TempDir::new().unwrap();
+    let index_dir = temp_dir.path().join("index");
+    std::fs::create_dir(&index_dir).unwrap();
+    let expected = build_index(&index_dir);
+    
+    let split_path = temp_dir.path().join("round-trip.split");
+    let metadata = QuickwitSplitGenerator::open_in_dir(&index_dir, 1000).unwrap()
+        .generate_split(&split_path)
+        .unwrap();
+    assert!(split_path.is_file());
+    assert_eq!(metadata.num_docs as u64, NUM_DOCS);
+    assert_eq!(metadata.source_segments, 3);
+    
+    assert_round_trip(&split_path, &expected);
+}
+
+#[test]
+fn test_split_directory_round_trip() {
+    let temp_dir = TempDir::new().unwrap();
+    let index_dir = temp_dir.path().join("index");
+    std::fs::create_dir(&index_dir).unwrap();
+    let expected = build_index(&index_dir);
+    
+    let split_path = temp_dir.path().join("split");
+    let index = Index::open_in_dir(&index_dir).unwrap();
+    let config = SplitConfig {
+        legacy_directory_layout: true,
+        write_sidecar_metadata: true,
+        ..SplitConfig::default()
+    };
+    let metadata = QuickwitSplitGenerator::new(index, 1000).unwrap()
+        .with_config(config)
+        .generate_split(&split_path)
+        .unwrap();
+    assert!(split_path.is_dir());
+    assert_eq!(metadata.num_docs as u64, NUM_DOCS);
+    
+    assert_round_trip(&split_path, &expected);
+    let sidecar = QuickwitSplitReader::load_sidecar_metadata(&split_path).unwrap();
+    assert_eq!(sidecar.num_docs, metadata.num_docs);
+}
@@ -0,0 +1,120 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+package com.tantivy4java.splits;
+
+import java.util.Objects;
+
+/**
+ * Outcome of generating the split of one input of a batch.
+ * 
+ * <p>Returned by {@link QuickwitSplitGenerator#generateBatch(java.util.List, java.util.List, SplitConfig)},
+ * one per input. Exactly one of the metadata and the error message is set,
+ * so failed inputs can be retried on their own.
+ */
+public class BatchResult {
+    
+    private final String indexPath;
+    private final String outputPath;
+    private final SplitMetadata metadata;
+    private final String error;
+    
+    /**
+     * Creates a new batch result.
+     * 
+     * @param indexPath Directory of the input index
+     * @param outputPath Output path of the split
+     * @param metadata Metadata of the generated split, null on failure
+     * @param error Message of the failure, null on success
+     */
+    public BatchResult(String indexPath, String outputPath, SplitMetadata metadata, String error) {
+        this.indexPath = indexPath;
+        this.outputPath = outputPath;
+        this.metadata = metadata;
+        this.error = error;
+    }
+    
+    /**
+     * Gets the directory of the input index.
+     * 
+     * @return Index directory
+     */
+    public String getIndexPath() {
+        return indexPath;
+    }
+    
+    /**
+     * Gets the output path of the split.
+     * 
+     * @return Split output path
+     */
+    public String getOutputPath() {
+        return outputPath;
+    }
+    
+    /**
+     * Checks whether the split was generated.
+     * 
+     * @return true if generation succeeded
+     */
+    public boolean isSuccess() {
+        return error == null;
+    }
+    
+    /**
+     * Gets the metadata of the generated split.
+     * 
+     * @return Split metadata, or null if generation failed
+     */
+    public SplitMetadata getMetadata() {
+        return metadata;
+    }
+    
+    /**
+     * Gets why generation failed.
+     * 
+     * @return Error message, or null if generation succeeded
+     */
+    public String getError() {
+        return error;
+    }
+    
+    @Override
+    public boolean equals(Object obj) {
+        if (this == obj) return true;
+        if (obj == null || getClass() != obj.getClass()) return false;
+        
+        BatchResult that = (BatchResult) obj;
+        return Objects.equals(indexPath, that.indexPath) &&
+               Objects.equals(outputPath, that.outputPath) &&
+               Objects.equals(metadata, that.metadata) &&
+               Objects.equals(error, that.error);
+    }
+    
+    @Override
+    public int hashCode() {
+        return Objects.hash(indexPath, outputPath, metadata, error);
+    }
+    
+    @Override
+    public String toString() {
+        return String.format("BatchResult{indexPath='%s', outputPath='%s', metadata=%s, error='%s'}",
+                           indexPath, outputPath, metadata, error);
+    }
+}
@@ -0,0 +1,112 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+package com.tantivy4java.splits;
+
+import java.util.Objects;
+
+/**
+ * Corpus statistics of a field in a split.
+ * 
+ * <p>Used to compute BM25/IDF scores consistently across splits: summing
+ * these statistics over all splits of an index gives index-wide values.
+ */
+public class CorpusStats {
+    
+    private final long totalDocs;
+    private final long docsWithField;
+    private final long totalTokens;
+    private final long uniqueTerms;
+    
+    /**
+     * Creates new corpus statistics.
+     * 
+     * @param totalDocs Number of live documents in the split
+     * @param docsWithField Number of live documents with at least one term in the field
+     * @param totalTokens Sum of term frequencies over the field
+     * @param uniqueTerms Number of distinct terms in the field
+     */
+    public CorpusStats(long totalDocs, long docsWithField, long totalTokens, long uniqueTerms) {
+        this.totalDocs = totalDocs;
+        this.docsWithField = docsWithField;
+        this.totalTokens = totalTokens;
+        this.uniqueTerms = uniqueTerms;
+    }
+    
+    /**
+     * Gets the number of live documents in the split.
+     * 
+     * @return Document count
+     */
+    public long getTotalDocs() {
+        return totalDocs;
+    }
+    
+    /**
+     * Gets the number of live documents with at least one term in the field.
+     * 
+     * @return Document count
+     */
+    public long getDocsWithField() {
+        return docsWithField;
+    }
+    
+    /**
+     * Gets the sum of term frequencies over the field.
+     * 
+     * <p>Includes deleted documents, as Tantivy does for BM25.
+     * 
+     * @return Total token count
+     */
+    public long getTotalTokens() {
+        return totalTokens;
+    }
+    
+    /**
+     * Gets the number of distinct terms in the field.
+     * 
+     * @return Unique term count
+     */
+    public long getUniqueTerms() {
+        return uniqueTerms;
+    }
+    
+    @Override
+    public boolean equals(Object obj) {
+        if (this == obj) return true;
+        if (obj == null || getClass() != obj.getClass()) return false;
+        
+        CorpusStats that = (CorpusStats) obj;
+        return totalDocs == that.totalDocs &&
+               docsWithField == that.docsWithField &&
+               totalTokens == that.totalTokens &&
+               uniqueTerms == that.uniqueTerms;
+    }
+    
+    @Override
+    public int hashCode() {
+        return Objects.hash(totalDocs, docsWithField, totalTokens, uniqueTerms);
+    }
+    
+    @Override
+    public String toString() {
+        return String.format("CorpusStats{totalDocs=%d, docsWithField=%d, totalTokens=%d, uniqueTerms=%d}",
+                           totalDocs, docsWithField, totalTokens, uniqueTerms);
+    }
+}
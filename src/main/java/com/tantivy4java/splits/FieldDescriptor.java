@@ -0,0 +1,204 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+package com.tantivy4java.splits;
+
+import java.util.ArrayList;
+import java.util.Collections;
+import java.util.List;
+import java.util.Objects;
+
+/**
+ * Describes a field of a split's schema.
+ */
+public class FieldDescriptor {
+    
+    /**
+     * Type of a schema field.
+     */
+    public enum Type {
+        TEXT("text"),
+        U64("u64"),
+        I64("i64"),
+        F64("f64"),
+        BOOL("bool"),
+        DATE("date"),
+        BYTES("bytes"),
+        FACET("facet"),
+        JSON("json"),
+        IP_ADDR("ip_addr");
+        
+        private final String name;
+        
+        Type(String name) {
+            this.name = name;
+        }
+        
+        /**
+         * Gets the type from its native name, e.g. {@code "u64"}.
+         * 
+         * @param name Native type name
+         * @return Matching type
+         * @throws IllegalArgumentException if no type has this name
+         */
+        public static Type fromName(String name) {
+            for (Type type : values()) {
+                if (type.name.equals(name)) {
+                    return type;
+                }
+            }
+            throw new IllegalArgumentException("Unknown field type: " + name);
+        }
+        
+        /**
+         * Gets the native name of this type.
+         * 
+         * @return Native type name
+         */
+        public String getName() {
+            return name;
+        }
+    }
+    
+    private final String name;
+    private final Type type;
+    private final boolean stored;
+    private final boolean indexed;
+    private final boolean fast;
+    private final List<String> jsonPaths;
+    
+    /**
+     * Creates a new field descriptor.
+     * 
+     * @param name Field name
+     * @param type Field type
+     * @param stored Whether values are kept in the doc store
+     * @param indexed Whether the field is searchable
+     * @param fast Whether the field has a columnar fast field
+     * @throws NullPointerException if name or type is null
+     */
+    public FieldDescriptor(String name, Type type, boolean stored, boolean indexed, boolean fast) {
+        this(name, type, stored, indexed, fast, Collections.emptyList());
+    }
+    
+    /**
+     * Creates a new field descriptor, with the sub-paths of a JSON field.
+     * 
+     * @param name Field name
+     * @param type Field type
+     * @param stored Whether values are kept in the doc store
+     * @param indexed Whether the field is searchable
+     * @param fast Whether the field has a columnar fast field
+     * @param jsonPaths Dotted sub-paths holding values, for JSON fields
+     * @throws NullPointerException if name, type or jsonPaths is null
+     */
+    public FieldDescriptor(String name, Type type, boolean stored, boolean indexed, boolean fast,
+                           List<String> jsonPaths) {
+        this.name = Objects.requireNonNull(name, "Name cannot be null");
+        this.type = Objects.requireNonNull(type, "Type cannot be null");
+        this.stored = stored;
+        this.indexed = indexed;
+        this.fast = fast;
+        this.jsonPaths = Collections.unmodifiableList(
+            new ArrayList<>(Objects.requireNonNull(jsonPaths, "JSON paths cannot be null")));
+    }
+    
+    /**
+     * Gets the field name.
+     * 
+     * @return Field name
+     */
+    public String getName() {
+        return name;
+    }
+    
+    /**
+     * Gets the field type.
+     * 
+     * @return Field type
+     */
+    public Type getType() {
+        return type;
+    }
+    
+    /**
+     * Checks whether field values are kept in the doc store.
+     * 
+     * @return true if the field is stored
+     */
+    public boolean isStored() {
+        return stored;
+    }
+    
+    /**
+     * Checks whether the field is searchable.
+     * 
+     * @return true if the field is indexed
+     */
+    public boolean isIndexed() {
+        return indexed;
+    }
+    
+    /**
+     * Checks whether the field has a columnar fast field.
+     * 
+     * @return true if the field is fast
+     */
+    public boolean isFast() {
+        return fast;
+    }
+    
+    /**
+     * Gets the dotted sub-paths holding values in an indexed JSON field,
+     * e.g. {@code user.address.city}, sorted.
+     * 
+     * <p>Paths are those of the split's documents, not declared by the
+     * schema, so they differ between splits. Other fields have none.
+     * 
+     * @return JSON sub-paths, empty for non-JSON fields
+     */
+    public List<String> getJsonPaths() {
+        return jsonPaths;
+    }
+    
+    @Override
+    public boolean equals(Object obj) {
+        if (this == obj) return true;
+        if (obj == null || getClass() != obj.getClass()) return false;
+        
+        FieldDescriptor that = (FieldDescriptor) obj;
+        return stored == that.stored &&
+               indexed == that.indexed &&
+               fast == that.fast &&
+               name.equals(that.name) &&
+               type == that.type &&
+               jsonPaths.equals(that.jsonPaths);
+    }
+    
+    @Override
+    public int hashCode() {
+        return Objects.hash(name, type, stored, indexed, fast, jsonPaths);
+    }
+    
+    @Override
+    public String toString() {
+        return String.format("FieldDescriptor{name='%s', type=%s, stored=%b, indexed=%b, fast=%b, jsonPaths=%s}",
+                           name, type.getName(), stored, indexed, fast, jsonPaths);
+    }
+}
@@ -0,0 +1,54 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+package com.tantivy4java.splits;
+
+/**
+ * Receives log records emitted by the native library.
+ * 
+ * <p>Set with {@link QuickwitSplits#setLogListener(LogListener)}. Records
+ * are delivered synchronously on the native thread that logged them, which
+ * may be a library worker thread, so implementations must be thread-safe and
+ * should return quickly. Exceptions thrown by the listener are discarded.
+ * Records logged by the listener itself through the native library are
+ * dropped.
+ */
+@FunctionalInterface
+public interface LogListener {
+    
+    /**
+     * Severity of a log record, from most to least severe.
+     */
+    enum Level {
+        ERROR,
+        WARN,
+        INFO,
+        DEBUG,
+        TRACE
+    }
+    
+    /**
+     * Called for each native log record.
+     * 
+     * @param level Severity of the record
+     * @param target Native module the record comes from, e.g. {@code tantivy4java_splits::split_generator}
+     * @param message Formatted message
+     */
+    void onLog(Level level, String target, String message);
+}
@@ -0,0 +1,109 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+package com.tantivy4java.splits;
+
+/**
+ * Cursor over the posting list of a term in a Quickwit split.
+ * 
+ * <p>Obtained from {@link QuickwitSplitReader#openPostingCursor(String, String)}.
+ * Document IDs are returned in ascending order, in batches, so that the
+ * postings of common terms can be paged through without materializing the
+ * whole list. Deleted documents are skipped.
+ * 
+ * <p>Example usage:
+ * <pre>{@code
+ * try (PostingCursor cursor = reader.openPostingCursor("title", "quickwit")) {
+ *     int[] batch;
+ *     while ((batch = cursor.nextBatch(1000)).length > 0) {
+ *         process(batch);
+ *     }
+ * }
+ * }</pre>
+ */
+public class PostingCursor implements AutoCloseable {
+    
+    private long nativeHandle;
+    private boolean closed = false;
+    
+    PostingCursor(long nativeHandle) {
+        this.nativeHandle = nativeHandle;
+    }
+    
+    /**
+     * Returns the next batch of document IDs.
+     * 
+     * @param maxDocs Maximum number of document IDs to return (must be > 0)
+     * @return Next document IDs, or an empty array once the cursor is exhausted
+     * @throws IllegalArgumentException if maxDocs <= 0
+     * @throws IllegalStateException if cursor is closed
+     */
+    public int[] nextBatch(int maxDocs) {
+        if (maxDocs <= 0) {
+            throw new IllegalArgumentException("Max docs must be positive, got: " + maxDocs);
+        }
+        ensureNotClosed();
+        return nextBatchNative(nativeHandle, maxDocs);
+    }
+    
+    /**
+     * Checks if this cursor has been closed.
+     * 
+     * @return true if closed, false otherwise
+     */
+    public boolean isClosed() {
+        return closed;
+    }
+    
+    /**
+     * Closes this cursor and releases native resources.
+     * 
+     * <p>This method is idempotent and safe to call multiple times.
+     */
+    @Override
+    public void close() {
+        if (!closed && nativeHandle != 0) {
+            closeNative(nativeHandle);
+            nativeHandle = 0;
+            closed = true;
+        }
+    }
+    
+    private void ensureNotClosed() {
+        if (closed) {
+            throw new IllegalStateException("Posting cursor has been closed");
+        }
+    }
+    
+    // Native method declarations
+    private native int[] nextBatchNative(long handle, int maxDocs);
+    private native void closeNative(long handle);
+    
+    /**
+     * Finalizer to ensure native resources are cleaned up.
+     */
+    @Override
+    protected void finalize() throws Throwable {
+        try {
+            close();
+        } finally {
+            super.finalize();
+        }
+    }
+}
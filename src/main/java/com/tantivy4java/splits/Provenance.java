@@ -0,0 +1,98 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+package com.tantivy4java.splits;
+
+import java.util.Objects;
+
+/**
+ * When and by what a split was produced.
+ * 
+ * <p>Recorded in the split at generation time to correlate split issues
+ * with specific producer builds. Values are null for splits that don't
+ * record them.
+ */
+public class Provenance {
+    
+    private final Long createdAtMillis;
+    private final String crateVersion;
+    private final String tantivyVersion;
+    
+    /**
+     * Creates new provenance information.
+     * 
+     * @param createdAtMillis Creation time in Unix milliseconds (nullable)
+     * @param crateVersion Version of the native library that produced the split (nullable)
+     * @param tantivyVersion Tantivy version the split was written with (nullable)
+     */
+    public Provenance(Long createdAtMillis, String crateVersion, String tantivyVersion) {
+        this.createdAtMillis = createdAtMillis;
+        this.crateVersion = crateVersion;
+        this.tantivyVersion = tantivyVersion;
+    }
+    
+    /**
+     * Gets the creation time of the split.
+     * 
+     * @return Creation time in Unix milliseconds, or null if unknown
+     */
+    public Long getCreatedAtMillis() {
+        return createdAtMillis;
+    }
+    
+    /**
+     * Gets the version of the native library that produced the split.
+     * 
+     * @return Library version, or null if unknown
+     */
+    public String getCrateVersion() {
+        return crateVersion;
+    }
+    
+    /**
+     * Gets the Tantivy version and index format the split was written with.
+     * 
+     * @return Tantivy version string, or null if unknown
+     */
+    public String getTantivyVersion() {
+        return tantivyVersion;
+    }
+    
+    @Override
+    public boolean equals(Object obj) {
+        if (this == obj) return true;
+        if (obj == null || getClass() != obj.getClass()) return false;
+        
+        Provenance that = (Provenance) obj;
+        return Objects.equals(createdAtMillis, that.createdAtMillis) &&
+               Objects.equals(crateVersion, that.crateVersion) &&
+               Objects.equals(tantivyVersion, that.tantivyVersion);
+    }
+    
+    @Override
+    public int hashCode() {
+        return Objects.hash(createdAtMillis, crateVersion, tantivyVersion);
+    }
+    
+    @Override
+    public String toString() {
+        return String.format("Provenance{createdAtMillis=%s, crateVersion='%s', tantivyVersion='%s'}",
+                           createdAtMillis, crateVersion, tantivyVersion);
+    }
+}
@@ -22,6 +22,9 @@ package com.tantivy4java.splits;
 import com.tantivy4java.Index;
 import java.io.IOException;
 import java.nio.file.Path;
+import java.time.Instant;
+import java.util.List;
+import java.util.Map;
 import java.util.Objects;
 
 /**
@@ -58,6 +61,10 @@ public class QuickwitSplitGenerator implements AutoCloseable {
     /**
      * Creates a new split generator for the given index.
      * 
+     * <p>The target number of documents per split isn't enforced: each
+     * generation makes one split of the whole index, and logs a warning when
+     * it holds more documents than the target.
+     * 
      * @param index The Tantivy index to generate splits from
      * @param targetDocsPerSplit Target number of documents per split (must be > 0)
      * @throws NullPointerException if index is null
@@ -79,6 +86,36 @@ public class QuickwitSplitGenerator implements AutoCloseable {
         }
     }
     
+    private QuickwitSplitGenerator(long nativeHandle, int targetDocsPerSplit) {
+        this.index = null;
+        this.targetDocsPerSplit = targetDocsPerSplit;
+        this.nativeHandle = nativeHandle;
+    }
+    
+    /**
+     * Creates a split generator for a Tantivy index stored on disk.
+     * 
+     * <p>The index is opened natively from its directory, so no live
+     * {@link Index} is needed, which suits batch generation from indexes
+     * written by another process. {@link #getIndex()} returns null for such
+     * a generator.
+     * 
+     * @param indexPath Directory holding the Tantivy index
+     * @param targetDocsPerSplit Target number of documents per split (must be > 0)
+     * @return Generator over the index
+     * @throws NullPointerException if indexPath is null
+     * @throws IllegalArgumentException if targetDocsPerSplit <= 0
+     * @throws IOException if the directory doesn't hold a valid Tantivy index
+     */
+    public static QuickwitSplitGenerator fromPath(Path indexPath, int targetDocsPerSplit) throws IOException {
+        Objects.requireNonNull(indexPath, "Index path cannot be null");
+        if (targetDocsPerSplit <= 0) {
+            throw new IllegalArgumentException("Target docs per split must be positive, got: " + targetDocsPerSplit);
+        }
+        
+        return new QuickwitSplitGenerator(createFromPathNative(indexPath.toString(), targetDocsPerSplit), targetDocsPerSplit);
+    }
+    
     /**
      * Generates a Quickwit split from the current state of the index.
      * 
@@ -86,15 +123,23 @@ public class QuickwitSplitGenerator implements AutoCloseable {
      * <ol>
      *   <li>Merges all segments in the index into a single segment</li>
      *   <li>Generates hotcache metadata for efficient field access</li>
-     *   <li>Copies segment files to the output directory</li>
+     *   <li>Copies segment files to a staging directory</li>
      *   <li>Embeds hotcache as a footer in the appropriate files</li>
+     *   <li>Bundles the files into a single split file at {@code outputPath}</li>
      * </ol>
      * 
-     * @param outputPath Directory where the split files will be written
+     * <p>If {@code outputPath} is an existing directory, the split is written
+     * in it as {@code <split_id>.split}. With
+     * {@link SplitConfig.Builder#legacyDirectoryLayout(boolean)}, the split
+     * files are written to the {@code outputPath} directory instead.
+     * 
+     * @param outputPath Split file, directory receiving the split file, or
+     *        split directory with the legacy layout
      * @return Metadata describing the generated split
      * @throws NullPointerException if outputPath is null
      * @throws IOException if split generation fails or output path is invalid
-     * @throws IllegalStateException if generator is closed
+     * @throws IllegalStateException if generator is closed, or a split
+     *         already exists at the output path and overwriting is disabled
      */
     public SplitMetadata generateSplit(Path outputPath) throws IOException {
         Objects.requireNonNull(outputPath, "Output path cannot be null");
@@ -109,6 +154,167 @@ public class QuickwitSplitGenerator implements AutoCloseable {
         return generateSplitNative(nativeHandle, outputPath.toString());
     }
     
+    /**
+     * Generates a Quickwit split and streams it to a sink, e.g. an object storage multipart upload.
+     * 
+     * <p>Nothing is published on local disk: the split files are assembled in
+     * a temporary directory, removed once streamed, and the serialized split
+     * is passed to {@code sink} chunk by chunk as it is read. See
+     * {@link UploadSink} for chunk sizes and ordering. On failure, the caller
+     * is expected to abort its multipart upload.
+     * 
+     * @param sink Sink receiving the serialized split
+     * @return Metadata describing the generated split
+     * @throws NullPointerException if sink is null
+     * @throws IOException if split generation or the upload fails
+     * @throws IllegalStateException if generator is closed
+     */
+    public SplitMetadata generateSplitStreaming(UploadSink sink) throws IOException {
+        Objects.requireNonNull(sink, "Upload sink cannot be null");
+        ensureNotClosed();
+        
+        return generateSplitStreamingNative(nativeHandle, sink);
+    }
+    
+    /**
+     * Generates one split per distinct value of a partition field.
+     * 
+     * <p>Each split is written to {@code outputDir/<split id>.split}, or the
+     * {@code outputDir/<split id>} directory with the legacy layout, and tagged
+     * with {@code partitionField:value}. The partition field must be an indexed text
+     * field with at most {@link SplitConfig#getMaxPartitions()} distinct values.
+     * 
+     * <p>Every partition is re-indexed from the stored documents, so this costs
+     * roughly as much as indexing the whole corpus again, and requires every
+     * field of the schema to be stored.
+     * 
+     * @param partitionField Field whose values partition the documents
+     * @param outputDir Directory under which the splits are written
+     * @return Metadata of the generated splits, in partition value order
+     * @throws NullPointerException if partitionField or outputDir is null
+     * @throws IllegalArgumentException if the partition field is not an indexed
+     *         text field, or some schema field is not stored
+     * @throws IllegalStateException if generator is closed, or the field has too
+     *         many distinct values
+     * @throws IOException if split generation fails
+     */
+    public List<SplitMetadata> generatePartitionedSplits(String partitionField, Path outputDir) throws IOException {
+        Objects.requireNonNull(partitionField, "Partition field cannot be null");
+        Objects.requireNonNull(outputDir, "Output directory cannot be null");
+        ensureNotClosed();
+        
+        return generatePartitionedSplitsNative(nativeHandle, partitionField, outputDir.toString());
+    }
+    
+    /**
+     * Signs a generated split, writing a detached signature file into it.
+     * 
+     * <p>The signature is a keyed BLAKE3 MAC over the hashes of every file of
+     * the split, stored as {@code split.sig}. The same secret key is needed to
+     * verify it. Sign the split after generation and before uploading it; any
+     * previous signature is replaced.
+     * 
+     * @param splitPath Directory of a generated split
+     * @param key 32-byte secret key
+     * @throws NullPointerException if splitPath or key is null
+     * @throws IllegalArgumentException if key is not 32 bytes long
+     * @throws IOException if the split files can't be read or the signature written
+     */
+    public static void signSplit(Path splitPath, byte[] key) throws IOException {
+        Objects.requireNonNull(splitPath, "Split path cannot be null");
+        Objects.requireNonNull(key, "Key cannot be null");
+        if (key.length != 32) {
+            throw new IllegalArgumentException("Signing key must be 32 bytes, got " + key.length);
+        }
+        
+        signSplitNative(splitPath.toString(), key);
+    }
+    
+    /**
+     * Generates one split per input index, in parallel.
+     * 
+     * <p>The index in {@code indexPaths.get(i)} is written as a split at
+     * {@code outputPaths.get(i)}, as by {@link #fromPath(Path, int)} and
+     * {@link #generateSplit(Path)}, with the same options for all. Inputs run
+     * concurrently on the native thread pool, see
+     * {@link QuickwitSplits#setMaxThreads(int)}. A failing input doesn't stop
+     * the batch: its result carries the error, so only failures need retrying.
+     * 
+     * @param indexPaths Directories of the input indices
+     * @param outputPaths Output paths of the splits, one per index
+     * @param config Generation options of every split
+     * @return One result per input, in input order
+     * @throws NullPointerException if any argument or path is null
+     * @throws IllegalArgumentException if the path lists differ in size
+     */
+    public static List<BatchResult> generateBatch(List<Path> indexPaths, List<Path> outputPaths, SplitConfig config) {
+        Objects.requireNonNull(indexPaths, "Index paths cannot be null");
+        Objects.requireNonNull(outputPaths, "Output paths cannot be null");
+        Objects.requireNonNull(config, "Config cannot be null");
+        if (indexPaths.size() != outputPaths.size()) {
+            throw new IllegalArgumentException("Got " + indexPaths.size() + " index paths but "
+                + outputPaths.size() + " output paths");
+        }
+        
+        String[] indexPathStrings = new String[indexPaths.size()];
+        String[] outputPathStrings = new String[outputPaths.size()];
+        for (int i = 0; i < indexPathStrings.length; i++) {
+            indexPathStrings[i] = Objects.requireNonNull(indexPaths.get(i), "Index path cannot be null").toString();
+            outputPathStrings[i] = Objects.requireNonNull(outputPaths.get(i), "Output path cannot be null").toString();
+        }
+        
+        return generateBatchNative(indexPathStrings, outputPathStrings, config.toJson());
+    }
+    
+    /**
+     * Merges entries into the custom metadata of an existing split.
+     * 
+     * <p>Only the footer of the split's store file is rewritten; the store data
+     * and segment files are copied unchanged into a temporary file that then
+     * replaces the split, so readers never see a partial update. Keys of
+     * {@code updates} replace existing ones, other keys are kept. A sidecar
+     * metadata file next to the split is updated too, and provides the
+     * returned tags and time range, which are empty without it.
+     * 
+     * @param splitPath Split file, or split directory
+     * @param updates Custom metadata entries to set
+     * @return Metadata of the updated split
+     * @throws NullPointerException if splitPath, updates or any of its keys or values is null
+     * @throws IllegalStateException if the split has no embedded hotcache in the Simple format
+     * @throws IOException if the split can't be read or rewritten
+     */
+    public static SplitMetadata updateMetadata(Path splitPath, Map<String, String> updates) throws IOException {
+        Objects.requireNonNull(splitPath, "Split path cannot be null");
+        Objects.requireNonNull(updates, "Updates cannot be null");
+        
+        String[] keys = new String[updates.size()];
+        String[] values = new String[updates.size()];
+        int i = 0;
+        for (Map.Entry<String, String> entry : updates.entrySet()) {
+            keys[i] = Objects.requireNonNull(entry.getKey(), "Metadata key cannot be null");
+            values[i] = Objects.requireNonNull(entry.getValue(), "Metadata value cannot be null");
+            i++;
+        }
+        
+        return updateMetadataNative(splitPath.toString(), keys, values);
+    }
+    
+    /**
+     * Sets the options used for subsequent split generation.
+     * 
+     * <p>Among others, this controls whether an existing split at the output
+     * path is overwritten or makes generation fail.
+     * 
+     * @param config Generation options
+     * @throws NullPointerException if config is null
+     * @throws IllegalStateException if generator is closed
+     */
+    public void setConfig(SplitConfig config) {
+        Objects.requireNonNull(config, "Config cannot be null");
+        ensureNotClosed();
+        configureNative(nativeHandle, config.toJson());
+    }
+    
     /**
      * Gets the target number of documents per split.
      * 
@@ -118,15 +324,94 @@ public class QuickwitSplitGenerator implements AutoCloseable {
         return targetDocsPerSplit;
     }
     
+    /**
+     * Picks up the documents committed to the index since this generator was
+     * created or last refreshed.
+     * 
+     * <p>Generation works on a snapshot of the index: a split reflects the
+     * index state as of the most recent refresh, or of the generator's
+     * creation, even if more documents were committed since.
+     * 
+     * @throws IllegalStateException if generator is closed
+     */
+    public void refresh() {
+        ensureNotClosed();
+        refreshNative(nativeHandle);
+    }
+    
+    /**
+     * Gets how far the running or last split generation got, in percent.
+     * 
+     * <p>Meant to be polled from another thread while {@code generateSplit},
+     * {@code generateSplitStreaming} or {@code generatePartitionedSplits}
+     * runs: it never waits on the generation. Progress is reset to 0 when a
+     * generation starts, advances at the boundaries of its phases, and
+     * reaches 100 once the split is published. It stays where it was if
+     * generation fails.
+     * 
+     * @return Percent complete, between 0 and 100
+     * @throws IllegalStateException if generator is closed
+     */
+    public int getProgress() {
+        ensureNotClosed();
+        return getProgressNative(nativeHandle);
+    }
+    
+    /**
+     * Gets the number of segments in the source index, which generation merges into one.
+     * 
+     * <p>Many small segments suggest commits are more frequent than merging
+     * can keep up with.
+     * 
+     * @return Number of source segments
+     * @throws IllegalStateException if generator is closed
+     */
+    public int getSourceSegmentCount() {
+        ensureNotClosed();
+        return sourceSegmentCountNative(nativeHandle);
+    }
+    
+    /**
+     * Estimates the size of the split the index would currently generate, without generating it.
+     * 
+     * <p>The estimate sums the real lengths of the source segment files, so
+     * it is cheap. For an index without deletes, the real split usually lands
+     * within a few percent of the upper bound; with deletes, between the two
+     * bounds. Neither includes the split's metadata and hotcache, which add a
+     * few KiB.
+     * 
+     * @return Split size estimate
+     * @throws IllegalStateException if generator is closed
+     */
+    public SplitPlan planSplit() {
+        ensureNotClosed();
+        return planSplitNative(nativeHandle);
+    }
+    
     /**
      * Gets the index associated with this generator.
      * 
-     * @return The index this generator operates on
+     * @return The index this generator operates on, or null if it was
+     *         created with {@link #fromPath(Path, int)}
      */
     public Index getIndex() {
         return index;
     }
     
+    /**
+     * Gets when the native generator behind this instance was created.
+     * 
+     * <p>Helps tracking down instances that are never closed, see
+     * {@link QuickwitSplits#reapStaleHandles(long)}.
+     * 
+     * @return Creation time of the native generator
+     * @throws IllegalStateException if generator is closed, or was reaped
+     */
+    public Instant getCreationTime() {
+        ensureNotClosed();
+        return Instant.ofEpochMilli(QuickwitSplits.handleCreatedAt(nativeHandle));
+    }
+    
     /**
      * Checks if this generator has been closed.
      * 
@@ -164,7 +449,18 @@ public class QuickwitSplitGenerator implements AutoCloseable {
     
     // Native method declarations
     private native long createNative(long indexHandle, int targetDocsPerSplit);
+    private static native long createFromPathNative(String indexPath, int targetDocsPerSplit) throws IOException;
+    private native void configureNative(long handle, String configJson);
+    private static native void signSplitNative(String splitPath, byte[] key) throws IOException;
+    private static native List<BatchResult> generateBatchNative(String[] indexPaths, String[] outputPaths, String configJson);
+    private static native SplitMetadata updateMetadataNative(String splitPath, String[] keys, String[] values) throws IOException;
     private native SplitMetadata generateSplitNative(long handle, String outputPath) throws IOException;
+    private native List<SplitMetadata> generatePartitionedSplitsNative(long handle, String partitionField, String outputDir) throws IOException;
+    private native SplitMetadata generateSplitStreamingNative(long handle, UploadSink sink) throws IOException;
+    private native void refreshNative(long handle);
+    private native int getProgressNative(long handle);
+    private native int sourceSegmentCountNative(long handle);
+    private native SplitPlan planSplitNative(long handle);
     private native void destroyNative(long handle);
     
     /**
@@ -22,7 +22,10 @@ package com.tantivy4java.splits;
 import java.io.IOException;
 import java.nio.file.Path;
 import java.nio.file.Files;
+import java.time.Instant;
+import java.util.ArrayList;
 import java.util.List;
+import java.util.Map;
 import java.util.Objects;
 
 /**
@@ -52,6 +55,46 @@ import java.util.Objects;
  */
 public class QuickwitSplitReader implements AutoCloseable {
     
+    /**
+     * Serialization of the values returned by
+     * {@link #getFastFieldData(String, int, int, FastFieldFormat)}.
+     * 
+     * <p>Every format holds one value per document of the range, in document
+     * order.
+     */
+    public enum FastFieldFormat {
+        /**
+         * Fixed width per document, little-endian, as described on
+         * {@link #getFastFieldData(String, int, int)}.
+         */
+        RAW_LE(0),
+        /**
+         * One unsigned LEB128 varint per document. {@code i64} and {@code date}
+         * values are zigzag-encoded first, {@code f64} values are their
+         * IEEE 754 bits and {@code bool} values are 0 or 1. A document
+         * without a value is encoded as 0.
+         */
+        VARINT(1),
+        /**
+         * The buffers of an Arrow primitive array, each zero-padded to a
+         * multiple of 8 bytes: a validity bitmap with one bit per document,
+         * least significant bit first, followed by the values. Values take
+         * 8 bytes little-endian, except {@code bool} values which are
+         * bit-packed like the bitmap.
+         */
+        ARROW(2);
+        
+        private final int code;
+        
+        FastFieldFormat(int code) {
+            this.code = code;
+        }
+        
+        int getCode() {
+            return code;
+        }
+    }
+    
     private long nativeHandle;
     private final Path splitPath;
     private boolean closed = false;
@@ -61,12 +104,15 @@ public class QuickwitSplitReader implements AutoCloseable {
      * 
      * <p>This constructor:
      * <ol>
-     *   <li>Validates the split directory structure</li>
+     *   <li>Validates the split structure</li>
      *   <li>Loads hotcache metadata from the split footer</li>
      *   <li>Initializes native resources for efficient data access</li>
      * </ol>
      * 
-     * @param splitPath Path to the split directory
+     * <p>The split is either a single {@code .split} file, read in place, or
+     * a split directory of the legacy layout.
+     * 
+     * @param splitPath Path to the split file or directory
      * @throws NullPointerException if splitPath is null
      * @throws IOException if split cannot be opened or is invalid
      */
@@ -76,8 +122,8 @@ public class QuickwitSplitReader implements AutoCloseable {
         if (!Files.exists(splitPath)) {
             throw new IOException("Split path does not exist: " + splitPath);
         }
-        if (!Files.isDirectory(splitPath)) {
-            throw new IOException("Split path is not a directory: " + splitPath);
+        if (!Files.isRegularFile(splitPath) && !Files.isDirectory(splitPath)) {
+            throw new IOException("Split path is neither a file nor a directory: " + splitPath);
         }
         
         this.splitPath = splitPath;
@@ -103,6 +149,95 @@ public class QuickwitSplitReader implements AutoCloseable {
         return getHotcacheInfoNative(nativeHandle);
     }
     
+    /**
+     * Gets when and by what this split was produced.
+     * 
+     * <p>Useful to correlate split issues with specific producer builds.
+     * Values are null when the split doesn't record them, e.g. splits
+     * generated without a hotcache.
+     * 
+     * @return Split provenance
+     * @throws IllegalStateException if reader is closed
+     */
+    public Provenance getProvenance() {
+        ensureNotClosed();
+        return provenanceNative(nativeHandle);
+    }
+    
+    /**
+     * Gets the Tantivy version and index format this split was written with.
+     * 
+     * <p>For example {@code tantivy v0.24.2, index_format v7}. Splits whose
+     * index format this build can't read are refused when opened; other
+     * versions are logged as a warning through the log listener.
+     * 
+     * @return Tantivy version of the split, or null if the split doesn't record it
+     * @throws IllegalStateException if reader is closed
+     */
+    public String getTantivyVersion() {
+        ensureNotClosed();
+        return tantivyVersionNative(nativeHandle);
+    }
+    
+    /**
+     * Gets the opstamp of the source index's last commit this split reflects.
+     * 
+     * <p>Every indexing operation with a lower opstamp is included in the
+     * split, which lets callers reconcile splits against a write-ahead log.
+     * 
+     * @return Commit opstamp
+     * @throws IOException if the split records no opstamp
+     * @throws IllegalStateException if reader is closed
+     */
+    public long getOpstamp() throws IOException {
+        ensureNotClosed();
+        return opstampNative(nativeHandle);
+    }
+    
+    /**
+     * Gets the key/value metadata this split was generated with.
+     * 
+     * @return Metadata set through {@link SplitConfig.Builder#customMetadata(String, String)},
+     *         empty if the split has none or no hotcache
+     * @throws IllegalStateException if reader is closed
+     */
+    public Map<String, String> getCustomMetadata() {
+        ensureNotClosed();
+        return customMetadataNative(nativeHandle);
+    }
+    
+    /**
+     * Sets the key to decrypt this split with.
+     * 
+     * <p>Needed to read an encrypted split, see
+     * {@link SplitConfig.Builder#encryptionKey(byte[])}. The key is checked on
+     * the next read: a wrong key, or altered split files, then fail with
+     * {@link IOException}. Methods returning raw split bytes, such as
+     * {@link #readStoreBlock(int)}, return them encrypted regardless.
+     * 
+     * @param key 32-byte key the split was generated with
+     * @throws IllegalArgumentException if the key isn't 32 bytes long
+     * @throws IllegalStateException if reader is closed
+     */
+    public void setEncryptionKey(byte[] key) {
+        ensureNotClosed();
+        if (key == null || key.length != 32) {
+            throw new IllegalArgumentException("Encryption key must be 32 bytes");
+        }
+        setEncryptionKeyNative(nativeHandle, key);
+    }
+    
+    /**
+     * Checks whether this split is encrypted, requiring a key to be read.
+     * 
+     * @return true if the split files are encrypted
+     * @throws IllegalStateException if reader is closed
+     */
+    public boolean isEncrypted() {
+        ensureNotClosed();
+        return isEncryptedNative(nativeHandle);
+    }
+    
     /**
      * Lists all segment files in this split.
      * 
@@ -117,6 +252,20 @@ public class QuickwitSplitReader implements AutoCloseable {
         return listSegmentFilesNative(nativeHandle);
     }
     
+    /**
+     * Lists all segment files in this split with their sizes.
+     * 
+     * <p>Useful for building manifests of the split. The store file's size
+     * includes the hotcache footer.
+     * 
+     * @return Map from segment file name to its length in bytes, sorted by name
+     * @throws IllegalStateException if reader is closed
+     */
+    public Map<String, Long> segmentFilesWithSizes() {
+        ensureNotClosed();
+        return segmentFilesWithSizesNative(nativeHandle);
+    }
+    
     /**
      * Reads the posting list for a given field and term.
      * 
@@ -147,44 +296,1096 @@ public class QuickwitSplitReader implements AutoCloseable {
     }
     
     /**
-     * Gets fast field data for a document range.
+     * Reads the posting list for a given field and term, with term frequencies.
      * 
-     * <p>Fast fields provide efficient access to stored field values by
-     * document ID. The returned data format depends on the field type
-     * and encoding used in the original index.
+     * <p>Each entry is a {@code {docId, termFreq}} pair, giving how many times
+     * the term occurs in the document's field. Entries are sorted in ascending
+     * order by document ID. Use {@link #readPostingList(String, String)} when
+     * frequencies aren't needed.
      * 
-     * @param field Field name to read
-     * @param startDoc Starting document ID (inclusive)
-     * @param endDoc Ending document ID (exclusive)
-     * @return Raw field data as byte array
+     * @param field Field name to search in
+     * @param term Term to find
+     * @return Document ID and term frequency pairs, empty if none found
+     * @throws NullPointerException if field or term is null
+     * @throws IllegalArgumentException if field or term is empty, or the field
+     *         doesn't exist or isn't indexed with term frequencies
+     * @throws IllegalStateException if reader is closed
+     */
+    public int[][] readPostingListWithFreqs(String field, String term) {
+        Objects.requireNonNull(field, "Field cannot be null");
+        Objects.requireNonNull(term, "Term cannot be null");
+        
+        if (field.isEmpty()) {
+            throw new IllegalArgumentException("Field cannot be empty");
+        }
+        if (term.isEmpty()) {
+            throw new IllegalArgumentException("Term cannot be empty");
+        }
+        
+        ensureNotClosed();
+        int[] docFreqs = readPostingListWithFreqsNative(nativeHandle, field, term);
+        int[][] postings = new int[docFreqs.length / 2][];
+        for (int i = 0; i < postings.length; i++) {
+            postings[i] = new int[] {docFreqs[2 * i], docFreqs[2 * i + 1]};
+        }
+        return postings;
+    }
+    
+    /**
+     * Opens a cursor over the posting list for a given field and term.
+     * 
+     * <p>Unlike {@link #readPostingList(String, String)}, document IDs are
+     * decoded lazily in batches, keeping memory bounded when paging through
+     * the postings of common terms. The cursor must be closed when done.
+     * 
+     * @param field Field name to search in
+     * @param term Term to find
+     * @return Cursor yielding document IDs in ascending order
+     * @throws NullPointerException if field or term is null
+     * @throws IllegalArgumentException if field or term is empty, or field doesn't exist
+     * @throws IllegalStateException if reader is closed
+     */
+    public PostingCursor openPostingCursor(String field, String term) {
+        Objects.requireNonNull(field, "Field cannot be null");
+        Objects.requireNonNull(term, "Term cannot be null");
+        
+        if (field.isEmpty()) {
+            throw new IllegalArgumentException("Field cannot be empty");
+        }
+        if (term.isEmpty()) {
+            throw new IllegalArgumentException("Term cannot be empty");
+        }
+        
+        ensureNotClosed();
+        return new PostingCursor(openPostingCursorNative(nativeHandle, field, term));
+    }
+    
+    /**
+     * Gets the frequency of a term in a specific document.
+     * 
+     * @param field Field name to search in
+     * @param term Term to count
+     * @param docId Document ID
+     * @return Number of occurrences of the term in the document's field, 0 if absent
+     * @throws NullPointerException if field or term is null
+     * @throws IllegalArgumentException if field or term is empty, docId is negative,
+     *         or the field doesn't exist or isn't indexed with frequencies
+     * @throws IllegalStateException if reader is closed
+     */
+    public int termFreq(String field, String term, int docId) {
+        Objects.requireNonNull(field, "Field cannot be null");
+        Objects.requireNonNull(term, "Term cannot be null");
+        
+        if (field.isEmpty()) {
+            throw new IllegalArgumentException("Field cannot be empty");
+        }
+        if (term.isEmpty()) {
+            throw new IllegalArgumentException("Term cannot be empty");
+        }
+        if (docId < 0) {
+            throw new IllegalArgumentException("Document ID cannot be negative: " + docId);
+        }
+        
+        ensureNotClosed();
+        return termFreqNative(nativeHandle, field, term, docId);
+    }
+    
+    /**
+     * Gets the field norm of a field in a specific document.
+     * 
+     * <p>The field norm is the field's length in tokens, as BM25 scoring uses
+     * it, for custom relevance scoring consistent with Tantivy's. Norms are
+     * stored in one byte: lengths are exact up to 40 and rounded down beyond.
+     * 
+     * @param field Field name
+     * @param docId Document ID
+     * @return Length of the document's field in tokens, 0 if the field is empty
+     * @throws NullPointerException if field is null
+     * @throws IllegalArgumentException if field is empty, docId is negative,
+     *         or the field doesn't exist or isn't indexed with field norms
+     * @throws IllegalStateException if reader is closed, or docId is past the end of the split
+     */
+    public int fieldnorm(String field, int docId) {
+        Objects.requireNonNull(field, "Field cannot be null");
+        
+        if (field.isEmpty()) {
+            throw new IllegalArgumentException("Field cannot be empty");
+        }
+        if (docId < 0) {
+            throw new IllegalArgumentException("Document ID cannot be negative: " + docId);
+        }
+        
+        ensureNotClosed();
+        return fieldnormNative(nativeHandle, field, docId);
+    }
+    
+    /**
+     * Lists the distinct terms of an indexed text or JSON field, in term order.
+     * 
+     * <p>Terms of a JSON field are listed as {@code <path>:<value>}, the path
+     * dotted from the field's root, e.g. {@code user.age:42}.
+     * 
+     * <p>A split generated from an empty index yields an empty list for any field.
+     * 
+     * @param field Field name
+     * @return Terms of the field
      * @throws NullPointerException if field is null
-     * @throws IllegalArgumentException if field is empty, doesn't exist, or document range is invalid
+     * @throws IllegalArgumentException if field is empty, or the field doesn't
+     *         exist or isn't an indexed text or JSON field
      * @throws IllegalStateException if reader is closed
      */
-    public byte[] getFastFieldData(String field, int startDoc, int endDoc) {
+    public List<String> fieldTerms(String field) {
+        Objects.requireNonNull(field, "Field cannot be null");
+        if (field.isEmpty()) {
+            throw new IllegalArgumentException("Field cannot be empty");
+        }
+        ensureNotClosed();
+        return fieldTermsNative(nativeHandle, field);
+    }
+    
+    /**
+     * Lists the distinct terms of an indexed text field starting with a prefix.
+     * 
+     * <p>Intended for typeahead directly against a split. Terms are sorted in
+     * term order, i.e. by their UTF-8 bytes, and only the first {@code limit}
+     * in that order are returned. An empty prefix matches every term.
+     * 
+     * @param field Field name
+     * @param prefix Prefix terms must start with
+     * @param limit Maximum number of terms to return
+     * @return Matching terms, in term order
+     * @throws NullPointerException if field or prefix is null
+     * @throws IllegalArgumentException if field is empty, limit is negative, or
+     *         the field doesn't exist or isn't an indexed text field
+     * @throws IllegalStateException if reader is closed
+     */
+    public List<String> termsWithPrefix(String field, String prefix, int limit) {
         Objects.requireNonNull(field, "Field cannot be null");
+        Objects.requireNonNull(prefix, "Prefix cannot be null");
         
         if (field.isEmpty()) {
             throw new IllegalArgumentException("Field cannot be empty");
         }
-        if (startDoc < 0) {
-            throw new IllegalArgumentException("Start document cannot be negative: " + startDoc);
+        if (limit < 0) {
+            throw new IllegalArgumentException("Limit cannot be negative: " + limit);
         }
-        if (endDoc < startDoc) {
-            throw new IllegalArgumentException("End document cannot be less than start: " + endDoc + " < " + startDoc);
+        
+        ensureNotClosed();
+        return termsWithPrefixNative(nativeHandle, field, prefix, limit);
+    }
+    
+    /**
+     * Gets the serialized term dictionary of an indexed field.
+     * 
+     * <p>The bytes are in Tantivy's FST term dictionary format, version 1:
+     * a {@code tantivy-fst} map from each term to its ordinal, then the term
+     * info store, then the store's length as a little-endian 64-bit integer
+     * and the format version as a little-endian 32-bit integer. This lets
+     * clients do their own term lookups. A split generated from an empty
+     * index, or a field without any term, yields an empty array.
+     * 
+     * @param field Field name
+     * @return Serialized term dictionary
+     * @throws NullPointerException if field is null
+     * @throws IllegalArgumentException if field is empty, or the field doesn't
+     *         exist or isn't indexed
+     * @throws IllegalStateException if reader is closed
+     */
+    public byte[] termDictBytes(String field) {
+        Objects.requireNonNull(field, "Field cannot be null");
+        if (field.isEmpty()) {
+            throw new IllegalArgumentException("Field cannot be empty");
+        }
+        ensureNotClosed();
+        return termDictBytesNative(nativeHandle, field);
+    }
+    
+    /**
+     * Gets the total number of occurrences of a term across all live documents.
+     * 
+     * <p>Decodes the term's posting list once, so the cost grows with the
+     * number of documents containing the term.
+     * 
+     * @param field Field name to search in
+     * @param term Term to count
+     * @return Sum of the term's frequencies over all documents, 0 if absent
+     * @throws NullPointerException if field or term is null
+     * @throws IllegalArgumentException if field or term is empty, or the field
+     *         doesn't exist or isn't indexed with frequencies
+     * @throws IllegalStateException if reader is closed
+     */
+    public long totalTermFreq(String field, String term) {
+        Objects.requireNonNull(field, "Field cannot be null");
+        Objects.requireNonNull(term, "Term cannot be null");
+        
+        if (field.isEmpty()) {
+            throw new IllegalArgumentException("Field cannot be empty");
+        }
+        if (term.isEmpty()) {
+            throw new IllegalArgumentException("Term cannot be empty");
         }
         
         ensureNotClosed();
-        return getFastFieldDataNative(nativeHandle, field, startDoc, endDoc);
+        return totalTermFreqNative(nativeHandle, field, term);
     }
     
     /**
-     * Gets the split directory path.
+     * Counts the distinct values a fast field takes over the live documents.
+     * 
+     * <p>An exact count holds every distinct value in memory while the
+     * column is scanned, so memory grows with the cardinality. An estimate
+     * uses HyperLogLog in a fixed 16 KiB and lands within about 1% of the
+     * exact count, which suits fields with millions of distinct values. Both
+     * read each value of the column once.
      * 
-     * @return Path to the split directory
+     * @param field Fast field name
+     * @param exact true for an exact count, false for an estimate
+     * @return Number of distinct values
+     * @throws NullPointerException if field is null
+     * @throws IllegalArgumentException if field is empty, or the field doesn't
+     *         exist or isn't a fast field
+     * @throws IllegalStateException if reader is closed
      */
-    public Path getSplitPath() {
-        return splitPath;
+    public long fieldCardinality(String field, boolean exact) {
+        Objects.requireNonNull(field, "Field cannot be null");
+        
+        if (field.isEmpty()) {
+            throw new IllegalArgumentException("Field cannot be empty");
+        }
+        
+        ensureNotClosed();
+        return fieldCardinalityNative(nativeHandle, field, exact);
+    }
+    
+    /**
+     * Gets the smallest and largest values of a numeric fast field.
+     * 
+     * <p>The bounds come from the min and max recorded with each column, so
+     * no document is read, which makes them cheap enough for pruning splits
+     * by range. They include deleted documents, so may be looser than the
+     * live values. Both are returned as long bits: u64 values reinterpreted,
+     * use {@link Long#compareUnsigned(long, long)}; f64 values as
+     * {@link Double#doubleToRawLongBits(double)}, decoded with
+     * {@link Double#longBitsToDouble(long)}; dates as nanoseconds since the
+     * epoch, at the field's precision.
+     * 
+     * @param field u64, i64, f64 or date fast field name
+     * @return {@code [min, max]}
+     * @throws NullPointerException if field is null
+     * @throws IllegalArgumentException if field is empty, or the field doesn't
+     *         exist or isn't a numeric fast field
+     * @throws IllegalStateException if reader is closed or no document has a
+     *         value for the field
+     */
+    public long[] numericBounds(String field) {
+        Objects.requireNonNull(field, "Field cannot be null");
+        
+        if (field.isEmpty()) {
+            throw new IllegalArgumentException("Field cannot be empty");
+        }
+        
+        ensureNotClosed();
+        return numericBoundsNative(nativeHandle, field);
+    }
+    
+    /**
+     * Gets the document frequency of each of a batch of terms.
+     * 
+     * <p>Resolves all terms in a single native call, e.g. to assemble the IDF
+     * vector of a multi-term query. Like Tantivy's own statistics, counts
+     * include deleted documents until the split is merged.
+     * 
+     * @param field Field name to search in
+     * @param terms Terms to look up
+     * @return Document frequency of each term, in input order, 0 if absent
+     * @throws NullPointerException if field, terms or any term is null
+     * @throws IllegalArgumentException if field is empty or doesn't exist
+     * @throws IllegalStateException if reader is closed
+     */
+    public long[] docFreqs(String field, String[] terms) {
+        Objects.requireNonNull(field, "Field cannot be null");
+        Objects.requireNonNull(terms, "Terms cannot be null");
+        
+        if (field.isEmpty()) {
+            throw new IllegalArgumentException("Field cannot be empty");
+        }
+        for (String term : terms) {
+            Objects.requireNonNull(term, "Term cannot be null");
+        }
+        
+        ensureNotClosed();
+        return docFreqsNative(nativeHandle, field, terms);
+    }
+    
+    /**
+     * Checks whether another split holds the same schema and live documents.
+     * 
+     * <p>Documents are compared by stored content, regardless of doc IDs,
+     * split IDs, creation time or file-level bytes, which lets pipelines
+     * assert that split generation is deterministic. Fields that aren't
+     * stored are only compared through the schema.
+     * 
+     * @param other Reader over the split to compare with
+     * @return true if both splits have the same content
+     * @throws NullPointerException if other is null
+     * @throws IllegalStateException if either reader is closed
+     */
+    public boolean contentEquals(QuickwitSplitReader other) {
+        Objects.requireNonNull(other, "Other reader cannot be null");
+        
+        ensureNotClosed();
+        other.ensureNotClosed();
+        return contentEqualsNative(nativeHandle, other.nativeHandle);
+    }
+    
+    /**
+     * Computes a digest of this split's logical content, for deduplication.
+     * 
+     * <p>The digest covers the schema and the stored content of the live
+     * documents, exactly what {@link #contentEquals(QuickwitSplitReader)}
+     * compares: splits generated from the same data share a digest whatever
+     * their split ID, creation time, doc order or file bytes. Every live
+     * document is read.
+     * 
+     * @return Hex encoded BLAKE3 digest, 64 characters long
+     * @throws IllegalStateException if reader is closed
+     */
+    public String getContentDigest() {
+        ensureNotClosed();
+        return contentDigestNative(nativeHandle);
+    }
+    
+    /**
+     * Computes a checksum of this split's raw bytes, for transfer verification.
+     * 
+     * <p>The checksum covers every byte of the split file, hotcache footer
+     * included, and matches the checksum recorded in the sidecar metadata at
+     * generation: compare it against a re-downloaded split to confirm the bytes
+     * survived the transfer. Unlike {@link #getContentDigest()}, it changes with
+     * any rewrite of the split, even one keeping the same documents. The whole
+     * split is read.
+     * 
+     * @return Hex encoded BLAKE3 hash, 64 characters long
+     * @throws IllegalStateException if reader is closed, or the split isn't on the local filesystem
+     * @throws IOException if the split can't be read
+     */
+    public String getSplitChecksum() throws IOException {
+        ensureNotClosed();
+        return splitChecksumNative(nativeHandle);
+    }
+    
+    /**
+     * Checks whether the split may hold documents timestamped within a range.
+     * 
+     * <p>Only the time range recorded in the split's sidecar metadata is
+     * consulted, so no document is read. Splits without sidecar metadata, or
+     * generated without a timestamp field, always overlap.
+     * 
+     * @param start Start of the range, inclusive
+     * @param end End of the range, inclusive
+     * @return false only if the split's recorded time range lies outside the range
+     * @throws IllegalArgumentException if start is after end
+     * @throws IllegalStateException if reader is closed
+     */
+    public boolean overlapsTimeRange(long start, long end) {
+        if (start > end) {
+            throw new IllegalArgumentException("Start cannot be after end: " + start + " > " + end);
+        }
+        
+        ensureNotClosed();
+        return overlapsTimeRangeNative(nativeHandle, start, end);
+    }
+    
+    /**
+     * Gets the positions of a term in a specific document.
+     * 
+     * <p>Positions are token offsets within the field, in increasing order.
+     * They allow phrase matching and proximity scoring directly against the split.
+     * 
+     * @param field Field name to search in
+     * @param term Term to locate
+     * @param docId Document ID
+     * @return Positions of the term in the document's field, empty if absent
+     * @throws NullPointerException if field or term is null
+     * @throws IllegalArgumentException if field or term is empty, docId is negative,
+     *         or the field doesn't exist or isn't indexed with positions
+     * @throws IllegalStateException if reader is closed
+     */
+    public int[] termPositions(String field, String term, int docId) {
+        Objects.requireNonNull(field, "Field cannot be null");
+        Objects.requireNonNull(term, "Term cannot be null");
+        
+        if (field.isEmpty()) {
+            throw new IllegalArgumentException("Field cannot be empty");
+        }
+        if (term.isEmpty()) {
+            throw new IllegalArgumentException("Term cannot be empty");
+        }
+        if (docId < 0) {
+            throw new IllegalArgumentException("Document ID cannot be negative: " + docId);
+        }
+        
+        ensureNotClosed();
+        return termPositionsNative(nativeHandle, field, term, docId);
+    }
+    
+    /**
+     * Gets which documents of the split are alive, as a packed bitset.
+     * 
+     * <p>Bit {@code docId % 8} of byte {@code docId / 8} is set when the
+     * document is alive, least significant bit first:
+     * {@code (bitset[docId >> 3] & (1 << (docId & 7))) != 0}. Lets callers
+     * filter doc IDs obtained from postings without a native call per
+     * document. Every document is alive if the split has no deletes.
+     * 
+     * @return Alive-docs bitset covering every document ID of the split
+     * @throws IllegalStateException if reader is closed
+     */
+    public byte[] aliveBitset() {
+        ensureNotClosed();
+        return aliveBitsetNative(nativeHandle);
+    }
+    
+    /**
+     * Estimates the number of documents matching a query without running it.
+     * 
+     * <p>The query uses Tantivy's query syntax, with all indexed text fields as
+     * default fields. The estimate is derived from term document frequencies and
+     * the query's boolean structure, assuming independent clauses. It is meant
+     * for cheap planning decisions such as split pruning, and may over- or
+     * under-count for complex queries.
+     * 
+     * @param query Query string
+     * @return Estimated number of matching documents
+     * @throws NullPointerException if query is null
+     * @throws IllegalArgumentException if the query cannot be parsed
+     * @throws IllegalStateException if reader is closed
+     */
+    public long estimateHits(String query) {
+        Objects.requireNonNull(query, "Query cannot be null");
+        ensureNotClosed();
+        return estimateHitsNative(nativeHandle, query);
+    }
+    
+    /**
+     * Gets the most frequent facets directly under a facet path.
+     * 
+     * <p>Facets are paths like {@code /category/books}; an empty prefix or
+     * {@code /} counts the top-level facets. Counts are numbers of live
+     * documents holding the facet or one of its descendants.
+     * 
+     * @param field Facet field name
+     * @param prefix Facet path whose children are counted
+     * @param topK Maximum number of facets to return
+     * @return Map from facet path to document count, highest count first
+     * @throws NullPointerException if field or prefix is null
+     * @throws IllegalArgumentException if field is empty, topK is negative, the field
+     *         doesn't exist or isn't a facet field, or the prefix isn't a facet path
+     * @throws IllegalStateException if reader is closed
+     */
+    public Map<String, Long> facetCounts(String field, String prefix, int topK) {
+        Objects.requireNonNull(field, "Field cannot be null");
+        Objects.requireNonNull(prefix, "Prefix cannot be null");
+        
+        if (field.isEmpty()) {
+            throw new IllegalArgumentException("Field cannot be empty");
+        }
+        if (topK < 0) {
+            throw new IllegalArgumentException("Top k cannot be negative: " + topK);
+        }
+        
+        ensureNotClosed();
+        return facetCountsNative(nativeHandle, field, prefix, topK);
+    }
+    
+    /**
+     * Gets highlighted excerpts of a field for documents matching a query.
+     * 
+     * <p>The query uses Tantivy's query syntax, with all indexed text fields as
+     * default fields. Each excerpt is the fragment of the stored field value
+     * with the most matching terms, HTML escaped, with matches wrapped in
+     * {@code <b>} tags. Documents without a value for the field, or whose
+     * value matches none of the query terms, get an empty string.
+     * 
+     * @param query Query string
+     * @param field Stored text field to take excerpts from
+     * @param docIds Document IDs to get excerpts for
+     * @param maxChars Maximum number of characters of each excerpt
+     * @return One excerpt per document ID, in the same order
+     * @throws NullPointerException if query, field or docIds is null
+     * @throws IllegalArgumentException if field is empty, maxChars is not positive,
+     *         a document ID is negative, the query cannot be parsed, or the field
+     *         isn't a stored text field
+     * @throws IllegalStateException if reader is closed or a document ID is out of range
+     */
+    public List<String> snippets(String query, String field, int[] docIds, int maxChars) {
+        Objects.requireNonNull(query, "Query cannot be null");
+        Objects.requireNonNull(field, "Field cannot be null");
+        Objects.requireNonNull(docIds, "Document IDs cannot be null");
+        
+        if (field.isEmpty()) {
+            throw new IllegalArgumentException("Field cannot be empty");
+        }
+        if (maxChars <= 0) {
+            throw new IllegalArgumentException("Max chars must be positive: " + maxChars);
+        }
+        for (int docId : docIds) {
+            if (docId < 0) {
+                throw new IllegalArgumentException("Document ID cannot be negative: " + docId);
+            }
+        }
+        
+        ensureNotClosed();
+        return snippetsNative(nativeHandle, query, field, docIds, maxChars);
+    }
+    
+    /**
+     * Registers a tokenizer used when parsing queries against this split.
+     * 
+     * <p>Splits indexed with custom tokenizers must be queried with the same
+     * tokenizers. Those recorded at generation are registered automatically;
+     * this method covers splits generated without them, or overrides one.
+     * Tantivy's built-in tokenizers ({@code default}, {@code raw},
+     * {@code en_stem} and {@code whitespace}) are always available.
+     * 
+     * <p>The configuration is a JSON object with a {@code type} of:
+     * <ul>
+     *   <li>{@code simple}: splits on non-alphanumeric characters</li>
+     *   <li>{@code whitespace}: splits on whitespace</li>
+     *   <li>{@code raw}: the whole text as a single token</li>
+     *   <li>{@code ngram}: n-grams, with {@code min_gram}, {@code max_gram}
+     *       and optional {@code prefix_only}</li>
+     *   <li>{@code stem}: simple tokens, lowercased and stemmed in
+     *       {@code language} (e.g. {@code "English"})</li>
+     * </ul>
+     * Except for {@code raw} and {@code stem}, tokens are lowercased unless
+     * {@code "lowercase": false} is given. For example:
+     * {@code {"type": "ngram", "min_gram": 2, "max_gram": 3}}.
+     * 
+     * @param name Tokenizer name, as referenced by the schema
+     * @param configJson Tokenizer configuration
+     * @throws NullPointerException if name or configJson is null
+     * @throws IllegalStateException if reader is closed or the tokenizer parameters are invalid
+     * @throws RuntimeException if the configuration cannot be parsed
+     */
+    public void registerTokenizer(String name, String configJson) {
+        Objects.requireNonNull(name, "Tokenizer name cannot be null");
+        Objects.requireNonNull(configJson, "Tokenizer config cannot be null");
+        ensureNotClosed();
+        registerTokenizerNative(nativeHandle, name, configJson);
+    }
+    
+    /**
+     * Exports the split as a standalone Tantivy index directory.
+     * 
+     * <p>The exported directory holds the split's segment files and
+     * {@code meta.json}, without the hotcache footer, so it can be opened by
+     * plain Tantivy tooling.
+     * 
+     * @param outputDir Directory to export to, which must be missing or empty
+     * @throws NullPointerException if outputDir is null
+     * @throws IOException if the split embeds no index metadata, or files can't be written
+     * @throws IllegalStateException if reader is closed, or outputDir is not empty
+     */
+    public void exportIndex(Path outputDir) throws IOException {
+        Objects.requireNonNull(outputDir, "Output directory cannot be null");
+        ensureNotClosed();
+        exportIndexNative(nativeHandle, outputDir.toString());
+    }
+    
+    /**
+     * Writes the split as a single uncompressed tar archive.
+     * 
+     * <p>Every file of the split is archived as-is, hotcache footer included,
+     * so extracting the archive gives back an identical split directory. The
+     * split is streamed to the file rather than held in memory. An existing
+     * file at outputPath is replaced.
+     * 
+     * @param outputPath File to write the archive to
+     * @throws NullPointerException if outputPath is null
+     * @throws IOException if the split can't be read or the archive can't be written
+     * @throws IllegalStateException if reader is closed
+     */
+    public void toTar(Path outputPath) throws IOException {
+        Objects.requireNonNull(outputPath, "Output path cannot be null");
+        ensureNotClosed();
+        toTarNative(nativeHandle, outputPath.toString());
+    }
+    
+    /**
+     * Describes every field of the split's schema, in schema order.
+     * 
+     * @return Field descriptors
+     * @throws IllegalStateException if reader is closed
+     */
+    public List<FieldDescriptor> fields() {
+        ensureNotClosed();
+        return fieldsNative(nativeHandle);
+    }
+    
+    /**
+     * Gets corpus statistics of a field, for BM25/IDF computation across splits.
+     * 
+     * <p>Total token counts require the field to be indexed with term
+     * frequencies; other fields are rejected.
+     * 
+     * @param field Field name
+     * @return Corpus statistics of the field
+     * @throws NullPointerException if field is null
+     * @throws IllegalArgumentException if the field doesn't exist or isn't indexed with frequencies
+     * @throws IllegalStateException if reader is closed
+     */
+    public CorpusStats corpusStats(String field) {
+        Objects.requireNonNull(field, "Field cannot be null");
+        ensureNotClosed();
+        return corpusStatsNative(nativeHandle, field);
+    }
+    
+    /**
+     * Gets the stored fields of a live document, as a JSON object.
+     * 
+     * <p>Each stored field the document has a value for maps to that value,
+     * or to an array for multi-valued fields. JSON fields keep their nested
+     * objects and arrays.
+     * 
+     * @param docId Document ID within the split
+     * @return JSON object of the document's stored fields
+     * @throws IllegalArgumentException if docId is negative
+     * @throws IllegalStateException if reader is closed, or the document is
+     *         out of range or deleted
+     */
+    public String getDocument(int docId) {
+        if (docId < 0) {
+            throw new IllegalArgumentException("Document ID cannot be negative: " + docId);
+        }
+        ensureNotClosed();
+        return getDocumentNative(nativeHandle, docId);
+    }
+    
+    /**
+     * Gets the stored fields of up to {@code n} live documents picked at random.
+     * 
+     * <p>Documents are picked uniformly without reading the others from the
+     * doc store, and returned in document ID order, as JSON objects like
+     * {@link #getDocument(int)} returns. The same seed picks the same
+     * documents of the same split, for reproducible profiling. Every live
+     * document is returned when there are at most {@code n}.
+     * 
+     * @param n Maximum number of documents to return
+     * @param seed Seed of the random selection
+     * @return JSON objects of the sampled documents' stored fields
+     * @throws IllegalArgumentException if n is negative
+     * @throws IllegalStateException if reader is closed
+     */
+    public List<String> sample(int n, long seed) {
+        if (n < 0) {
+            throw new IllegalArgumentException("Sample size cannot be negative: " + n);
+        }
+        ensureNotClosed();
+        return sampleNative(nativeHandle, n, seed);
+    }
+    
+    /**
+     * Gets the values of one field for every live document, as a JSON array.
+     * 
+     * <p>Fast fields are read from their column; other fields are read from
+     * the doc store. This is cheaper than fetching whole documents when only
+     * one field is needed. Each array entry is {@code null} when the document
+     * has no value, the value itself, or a nested array for multi-valued fields.
+     * A dotted path into a JSON field, e.g. {@code attributes.color}, reads
+     * the values at that path.
+     * 
+     * @param field Field name, or path into a JSON field
+     * @return JSON array with one entry per live document
+     * @throws NullPointerException if field is null
+     * @throws IllegalArgumentException if the field doesn't exist or is neither stored nor fast
+     * @throws IllegalStateException if reader is closed
+     */
+    public String column(String field) {
+        Objects.requireNonNull(field, "Field cannot be null");
+        ensureNotClosed();
+        return columnNative(nativeHandle, field);
+    }
+    
+    /**
+     * Exports fields as an Arrow IPC stream, for loading into Arrow-based
+     * engines such as DuckDB.
+     * 
+     * <p>The stream holds a single record batch with one row per live document
+     * and one column per field, read as by {@link #column(String)}. Text maps
+     * to {@code Utf8}, {@code u64}, {@code i64}, {@code f64} and {@code bool}
+     * to their Arrow counterparts, and dates to UTC {@code Timestamp} in
+     * milliseconds. A field with several values in any document becomes a
+     * {@code List} column of its type. The bytes can be read back with Arrow's
+     * {@code ArrowStreamReader}.
+     * 
+     * @param fields Field names to export
+     * @return Arrow IPC stream bytes
+     * @throws NullPointerException if fields or any field name is null
+     * @throws IllegalArgumentException if a field doesn't exist, is neither
+     *         stored nor fast, or has no Arrow type
+     * @throws IllegalStateException if reader is closed, or the native library
+     *         was built without the {@code arrow} feature
+     */
+    public byte[] toArrow(List<String> fields) {
+        Objects.requireNonNull(fields, "Fields cannot be null");
+        for (String field : fields) {
+            Objects.requireNonNull(field, "Field cannot be null");
+        }
+        
+        ensureNotClosed();
+        return toArrowNative(nativeHandle, fields.toArray(new String[0]));
+    }
+    
+    /**
+     * Gets the byte ranges of the compressed doc store blocks.
+     * 
+     * <p>Ranges are offsets into the split's store file, in document order.
+     * Each block is compressed independently with the store's compressor
+     * (LZ4 by default). Once decompressed, a block holds its serialized
+     * documents back to back, followed by the start offset of each document
+     * and the document count, all as little-endian 32-bit integers. This lets
+     * a byte-range service serve blocks without decompressing them.
+     * 
+     * @return Store block ranges in document order
+     * @throws IllegalStateException if reader is closed
+     */
+    public List<ByteRange> getStoreBlockRanges() {
+        ensureNotClosed();
+        long[] bounds = storeBlockRangesNative(nativeHandle);
+        List<ByteRange> ranges = new ArrayList<>(bounds.length / 2);
+        for (int i = 0; i < bounds.length; i += 2) {
+            ranges.add(new ByteRange(bounds[i], bounds[i + 1]));
+        }
+        return ranges;
+    }
+    
+    /**
+     * Gets the index of the doc store block holding a document.
+     * 
+     * <p>Indexes are positions in {@link #getStoreBlockRanges()}. Blocks hold
+     * consecutive documents, so sorting doc ids groups them by block, and
+     * reading each block once serves all of its documents.
+     * 
+     * @param docId Document ID
+     * @return Index of the block holding the document
+     * @throws IllegalArgumentException if docId is negative
+     * @throws IllegalStateException if reader is closed, or docId is past the end of the split
+     */
+    public int storeBlockOf(int docId) {
+        if (docId < 0) {
+            throw new IllegalArgumentException("Document ID cannot be negative: " + docId);
+        }
+        ensureNotClosed();
+        return storeBlockOfNative(nativeHandle, docId);
+    }
+    
+    /**
+     * Reads a raw, still compressed, doc store block.
+     * 
+     * @param index Block index, as in {@link #getStoreBlockRanges()}
+     * @return Compressed block bytes
+     * @throws IllegalArgumentException if index is negative
+     * @throws IllegalStateException if reader is closed or index is out of range
+     */
+    public byte[] readStoreBlock(int index) {
+        if (index < 0) {
+            throw new IllegalArgumentException("Block index cannot be negative");
+        }
+        ensureNotClosed();
+        return readStoreBlockNative(nativeHandle, index);
+    }
+    
+    /**
+     * Gets the byte range of the store data in the split's store file.
+     *
+     * <p>The hotcache footer is always appended after the store data, so the
+     * range starts at 0 and ends where the footer begins. Reading this range
+     * gives the store exactly as Tantivy wrote it, for external store decoders.
+     *
+     * @return Range of the store data, footer excluded
+     * @throws IOException if the store file is missing or its footer is invalid
+     * @throws IllegalStateException if reader is closed
+     */
+    public ByteRange getStoreFileRange() throws IOException {
+        ensureNotClosed();
+        long[] bounds = storeFileRangeNative(nativeHandle);
+        return new ByteRange(bounds[0], bounds[1]);
+    }
+    
+    /**
+     * Reads the store data of the split, without the hotcache footer.
+     *
+     * @return Store data, as in {@link #getStoreFileRange()}
+     * @throws IOException if the store file is missing or its footer is invalid
+     * @throws IllegalStateException if reader is closed
+     */
+    public byte[] readStoreData() throws IOException {
+        ensureNotClosed();
+        return readStoreDataNative(nativeHandle);
+    }
+    
+    /**
+     * Reads raw bytes from one of the split's files, for clients doing their
+     * own decoding.
+     * 
+     * <p>The file is named exactly as in {@link #listSegmentFiles()}, or
+     * {@code meta.json}; paths are rejected. Bytes are returned as stored:
+     * the store file includes the hotcache footer, and files of encrypted
+     * splits are ciphertext.
+     * 
+     * @param fileName Name of a file of the split
+     * @param start Offset of the first byte to read
+     * @param length Number of bytes to read
+     * @return The bytes read
+     * @throws NullPointerException if fileName is null
+     * @throws IllegalArgumentException if start or length is negative, length
+     *         exceeds the largest array size, or fileName is a path
+     * @throws IllegalStateException if reader is closed, the file isn't part
+     *         of the split, or the range is past the end of the file
+     */
+    public byte[] readByteRange(String fileName, long start, long length) {
+        Objects.requireNonNull(fileName, "File name cannot be null");
+        if (fileName.contains("/") || fileName.contains("\\") || fileName.equals("..")) {
+            throw new IllegalArgumentException("File name cannot be a path: " + fileName);
+        }
+        if (start < 0 || length < 0) {
+            throw new IllegalArgumentException("Range start and length cannot be negative");
+        }
+        if (length > Integer.MAX_VALUE - 8) {
+            throw new IllegalArgumentException("Range length exceeds the largest array size: " + length);
+        }
+        ensureNotClosed();
+        return readByteRangeNative(nativeHandle, fileName, start, length);
+    }
+    
+    /**
+     * Gets fast field data for a document range.
+     * 
+     * <p>Fast fields provide efficient access to field values by document ID.
+     * Each document of the range takes a fixed number of bytes, in document
+     * order, depending on the field type:
+     * <ul>
+     *   <li>{@code u64}, {@code i64}: 8 bytes, little-endian</li>
+     *   <li>{@code f64}: 8 bytes, little-endian IEEE 754</li>
+     *   <li>{@code bool}: 1 byte, 0 or 1</li>
+     *   <li>{@code date}: 8 bytes, little-endian milliseconds since the Unix epoch</li>
+     * </ul>
+     * A document without a value is encoded as zeros, and a multi-valued
+     * document as its first value.
+     * 
+     * @param field Field name to read
+     * @param startDoc Starting document ID (inclusive)
+     * @param endDoc Ending document ID (exclusive)
+     * @return Encoded field values
+     * @throws NullPointerException if field is null
+     * @throws IllegalArgumentException if field is empty, doesn't exist, isn't a fast
+     *         field, or its type has no fixed-width encoding, or the document
+     *         range extends past the split's documents
+     * @throws IllegalStateException if reader is closed
+     */
+    public byte[] getFastFieldData(String field, int startDoc, int endDoc) {
+        return getFastFieldData(field, startDoc, endDoc, FastFieldFormat.RAW_LE);
+    }
+    
+    /**
+     * Gets fast field data for a document range, serialized in the given format.
+     * 
+     * @param field Field name to read
+     * @param startDoc Starting document ID (inclusive)
+     * @param endDoc Ending document ID (exclusive)
+     * @param format Serialization of the returned values
+     * @return Encoded field values
+     * @throws NullPointerException if field or format is null
+     * @throws IllegalArgumentException if field is empty, doesn't exist, isn't a fast
+     *         field, or its type has no fixed-width encoding, or the document
+     *         range extends past the split's documents
+     * @throws IllegalStateException if reader is closed
+     */
+    public byte[] getFastFieldData(String field, int startDoc, int endDoc, FastFieldFormat format) {
+        Objects.requireNonNull(field, "Field cannot be null");
+        Objects.requireNonNull(format, "Format cannot be null");
+        
+        if (field.isEmpty()) {
+            throw new IllegalArgumentException("Field cannot be empty");
+        }
+        if (startDoc < 0) {
+            throw new IllegalArgumentException("Start document cannot be negative: " + startDoc);
+        }
+        if (endDoc < startDoc) {
+            throw new IllegalArgumentException("End document cannot be less than start: " + endDoc + " < " + startDoc);
+        }
+        
+        ensureNotClosed();
+        return getFastFieldDataNative(nativeHandle, field, startDoc, endDoc, format.getCode());
+    }
+    
+    /**
+     * Gets fast field data for several fields over the same document range.
+     * 
+     * <p>Equivalent to calling {@link #getFastFieldData(String, int, int)} once per
+     * field, but crosses the native boundary only once, which suits row-oriented
+     * retrieval of several columns.
+     * 
+     * @param fields Field names to read
+     * @param startDoc Starting document ID (inclusive)
+     * @param endDoc Ending document ID (exclusive)
+     * @return Map from field name to encoded field values
+     * @throws NullPointerException if fields or any field name is null
+     * @throws IllegalArgumentException if a field is empty or doesn't exist, or document range is invalid
+     * @throws IllegalStateException if reader is closed
+     */
+    public Map<String, byte[]> getFastFields(List<String> fields, int startDoc, int endDoc) {
+        Objects.requireNonNull(fields, "Fields cannot be null");
+        
+        for (String field : fields) {
+            Objects.requireNonNull(field, "Field cannot be null");
+            if (field.isEmpty()) {
+                throw new IllegalArgumentException("Field cannot be empty");
+            }
+        }
+        if (startDoc < 0) {
+            throw new IllegalArgumentException("Start document cannot be negative: " + startDoc);
+        }
+        if (endDoc < startDoc) {
+            throw new IllegalArgumentException("End document cannot be less than start: " + endDoc + " < " + startDoc);
+        }
+        
+        ensureNotClosed();
+        return getFastFieldsNative(nativeHandle, fields.toArray(new String[0]), startDoc, endDoc);
+    }
+    
+    /**
+     * Gets the native address and length of a fast field's column, for zero-copy access.
+     * 
+     * <p>The column is mapped from the split files and returned as
+     * {@code {address, length}}, e.g. for wrapping in a
+     * {@code java.lang.foreign.MemorySegment} on JDKs providing the Foreign
+     * Function &amp; Memory API. The bytes are the column as Tantivy serializes
+     * it, codec-encoded and followed by its index, not an array of values;
+     * see {@link #getFastFieldData(String, int, int)} for decoded values. A
+     * split generated from an empty index has no column and yields a zero
+     * length.
+     * 
+     * <p>The address stays valid until this reader is closed, and must not be
+     * accessed afterwards, nor written to: doing so can crash the JVM. Only
+     * split directories on local storage are memory-mapped and support this.
+     * 
+     * @param field Fast field name
+     * @return Address and length of the column, in bytes
+     * @throws NullPointerException if field is null
+     * @throws IllegalArgumentException if field is empty, doesn't exist, isn't a
+     *         fast field, or is stored as several columns
+     * @throws IllegalStateException if reader is closed, or the split isn't
+     *         memory-mapped, e.g. a single-file split
+     */
+    public long[] getFastFieldColumn(String field) {
+        Objects.requireNonNull(field, "Field cannot be null");
+        if (field.isEmpty()) {
+            throw new IllegalArgumentException("Field cannot be empty");
+        }
+        
+        ensureNotClosed();
+        return fastFieldColumnNative(nativeHandle, field);
+    }
+    
+    /**
+     * Gets inverted index statistics of every indexed field as JSON.
+     * 
+     * <p>The JSON object holds {@code num_docs}, the number of live
+     * documents, and {@code fields}, mapping each indexed field name to its
+     * {@code num_terms}, {@code total_postings}, {@code avg_doc_freq}, and
+     * the sizes in bytes of its term dictionary, posting lists and positions
+     * ({@code term_dict_bytes}, {@code postings_bytes},
+     * {@code positions_bytes}). Document frequencies include deleted
+     * documents. One call replaces per-field statistics round trips.
+     * 
+     * @return Index statistics JSON string
+     * @throws IllegalStateException if reader is closed
+     */
+    public String getIndexStatsJson() {
+        ensureNotClosed();
+        return indexStatsNative(nativeHandle);
+    }
+    
+    /**
+     * Gets the Tantivy segment meta of this split as JSON.
+     * 
+     * <p>Returns the {@code meta.json} embedded in the split when present,
+     * otherwise a minimal meta reconstructed from the segment files. Useful
+     * for verifying deletes, doc counts, and opstamps without a separate
+     * Tantivy dependency.
+     * 
+     * @return Segment meta JSON string
+     * @throws IllegalStateException if reader is closed
+     */
+    public String getSegmentMetaJson() {
+        ensureNotClosed();
+        return segmentMetaJsonNative(nativeHandle);
+    }
+    
+    /**
+     * Sets how byte ranges, such as raw store blocks, are read from the split files.
+     * 
+     * <p>Large sequential reads benefit from a bigger buffer. Direct IO
+     * bypasses the page cache, for workloads that would otherwise evict
+     * hotter data; it is only used on Linux filesystems supporting it, and
+     * reads silently go through the page cache elsewhere. Options survive
+     * {@link #reload()}.
+     * 
+     * @param bufferSize Size of the chunks reads are issued in, in bytes
+     * @param directIo Whether to bypass the page cache where supported
+     * @throws IllegalArgumentException if bufferSize is not positive
+     * @throws IllegalStateException if reader is closed
+     */
+    public void setReadOptions(int bufferSize, boolean directIo) {
+        if (bufferSize <= 0) {
+            throw new IllegalArgumentException("Buffer size must be positive: " + bufferSize);
+        }
+        
+        ensureNotClosed();
+        setReadOptionsNative(nativeHandle, bufferSize, directIo);
+    }
+    
+    /**
+     * Reloads the split if its files changed since it was opened.
+     * 
+     * <p>Intended for long-lived readers pointing at a mutable path, such as a
+     * symlink flipped to a new split. Changes are detected from the store file
+     * size and modification time. The reloaded state is swapped in atomically:
+     * reads running concurrently with a reload see a consistent snapshot of
+     * either the old or the new split. If the reload fails, the reader keeps
+     * serving the previous split.
+     * 
+     * @throws IOException if the changed split cannot be loaded
+     * @throws IllegalStateException if reader is closed
+     */
+    public void reload() throws IOException {
+        ensureNotClosed();
+        reloadNative(nativeHandle);
+    }
+    
+    /**
+     * Gets the split path.
+     * 
+     * @return Path to the split file or directory
+     */
+    public Path getSplitPath() {
+        return splitPath;
+    }
+    
+    /**
+     * Gets when the native reader behind this instance was created.
+     * 
+     * <p>Helps tracking down instances that are never closed, see
+     * {@link QuickwitSplits#reapStaleHandles(long)}.
+     * 
+     * @return Creation time of the native reader
+     * @throws IllegalStateException if reader is closed, or was reaped
+     */
+    public Instant getCreationTime() {
+        ensureNotClosed();
+        return Instant.ofEpochMilli(QuickwitSplits.handleCreatedAt(nativeHandle));
     }
     
     /**
@@ -226,8 +1427,56 @@ public class QuickwitSplitReader implements AutoCloseable {
     private native long openNative(String splitPath) throws IOException;
     private native HotcacheInfo getHotcacheInfoNative(long handle);
     private native List<String> listSegmentFilesNative(long handle);
+    private native Map<String, Long> segmentFilesWithSizesNative(long handle);
+    private native Provenance provenanceNative(long handle);
+    private native String tantivyVersionNative(long handle);
+    private native long opstampNative(long handle);
+    private native Map<String, String> customMetadataNative(long handle);
     private native int[] readPostingListNative(long handle, String field, String term);
-    private native byte[] getFastFieldDataNative(long handle, String field, int startDoc, int endDoc);
+    private native int[] readPostingListWithFreqsNative(long handle, String field, String term);
+    private native long openPostingCursorNative(long handle, String field, String term);
+    private native int termFreqNative(long handle, String field, String term, int docId);
+    private native int fieldnormNative(long handle, String field, int docId);
+    private native List<String> fieldTermsNative(long handle, String field);
+    private native List<String> termsWithPrefixNative(long handle, String field, String prefix, int limit);
+    private native byte[] termDictBytesNative(long handle, String field);
+    private native long totalTermFreqNative(long handle, String field, String term);
+    private native long fieldCardinalityNative(long handle, String field, boolean exact);
+    private native long[] numericBoundsNative(long handle, String field);
+    private native long[] docFreqsNative(long handle, String field, String[] terms);
+    private native boolean contentEqualsNative(long handle, long otherHandle);
+    private native String contentDigestNative(long handle);
+    private native String splitChecksumNative(long handle) throws IOException;
+    private native boolean overlapsTimeRangeNative(long handle, long start, long end);
+    private native int[] termPositionsNative(long handle, String field, String term, int docId);
+    private native byte[] aliveBitsetNative(long handle);
+    private native long estimateHitsNative(long handle, String query);
+    private native Map<String, Long> facetCountsNative(long handle, String field, String prefix, int topK);
+    private native List<String> snippetsNative(long handle, String query, String field, int[] docIds, int maxChars);
+    private native String getDocumentNative(long handle, int docId);
+    private native List<String> sampleNative(long handle, int n, long seed);
+    private native String columnNative(long handle, String field);
+    private native byte[] toArrowNative(long handle, String[] fields);
+    private native void exportIndexNative(long handle, String outputDir) throws IOException;
+    private native void toTarNative(long handle, String outputPath) throws IOException;
+    private native List<FieldDescriptor> fieldsNative(long handle);
+    private native CorpusStats corpusStatsNative(long handle, String field);
+    private native void registerTokenizerNative(long handle, String name, String configJson);
+    private native long[] storeBlockRangesNative(long handle);
+    private native int storeBlockOfNative(long handle, int docId);
+    private native byte[] readStoreBlockNative(long handle, int index);
+    private native long[] storeFileRangeNative(long handle);
+    private native byte[] readStoreDataNative(long handle);
+    private native byte[] readByteRangeNative(long handle, String fileName, long start, long length);
+    private native byte[] getFastFieldDataNative(long handle, String field, int startDoc, int endDoc, int encoding);
+    private native Map<String, byte[]> getFastFieldsNative(long handle, String[] fields, int startDoc, int endDoc);
+    private native long[] fastFieldColumnNative(long handle, String field);
+    private native String segmentMetaJsonNative(long handle);
+    private native String indexStatsNative(long handle);
+    private native void reloadNative(long handle) throws IOException;
+    private native void setReadOptionsNative(long handle, int bufferSize, boolean directIo);
+    private native void setEncryptionKeyNative(long handle, byte[] key);
+    private native boolean isEncryptedNative(long handle);
     private native void closeNative(long handle);
     
     /**
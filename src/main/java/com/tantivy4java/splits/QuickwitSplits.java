@@ -19,16 +19,49 @@
 
 package com.tantivy4java.splits;
 
+import java.io.IOException;
 import java.io.InputStream;
 import java.nio.file.Files;
 import java.nio.file.Path;
 import java.nio.file.StandardCopyOption;
+import java.util.Map;
+import java.util.Objects;
 
 /**
  * Main entry point for Quickwit Splits4Java library.
  * Handles native library loading and version information.
  */
 public class QuickwitSplits {
+    
+    /**
+     * How the schemas of splits merged by
+     * {@link #mergeSplitBytes(SchemaCompatibility, byte[]...)} must relate.
+     */
+    public enum SchemaCompatibility {
+        /**
+         * All splits must have the same schema, with fields declared in the
+         * same order.
+         */
+        STRICT(0),
+        /**
+         * Splits may declare their fields in any order, and each may lack
+         * fields of the others, as long as fields of the same name have the
+         * same type and options. The merged schema holds every field;
+         * documents of a split lacking a field have no value for it.
+         */
+        UNION(1);
+        
+        private final int code;
+        
+        SchemaCompatibility(int code) {
+            this.code = code;
+        }
+        
+        int getCode() {
+            return code;
+        }
+    }
+    
     private static boolean loaded = false;
 
     static {
@@ -87,6 +120,214 @@ public class QuickwitSplits {
      */
     public static native String getVersion();
 
+    /**
+     * Set the size of the native thread pool shared by all split generators.
+     * 
+     * <p>Segment merges and file copies of every generator run on this single
+     * pool, bounding total CPU use regardless of how many generators are active.
+     * Defaults to the number of available processors. Tantivy's index writer
+     * still uses its own internal threads while a merge runs; the pool bounds
+     * how many merges run concurrently.
+     * 
+     * <p>Must be called once at startup, before any split is generated.
+     * 
+     * @param maxThreads Number of threads (must be > 0)
+     * @throws IllegalArgumentException if maxThreads <= 0
+     * @throws IllegalStateException if the pool is already in use
+     */
+    public static void setMaxThreads(int maxThreads) {
+        if (maxThreads <= 0) {
+            throw new IllegalArgumentException("Max threads must be positive, got: " + maxThreads);
+        }
+        setMaxThreadsNative(maxThreads);
+    }
+    
+    private static native void setMaxThreadsNative(int maxThreads);
+
+    /**
+     * Set the memory budget shared by the caches of all open split readers.
+     * 
+     * <p>When the caches of all readers together exceed the budget, the least
+     * recently used entries are evicted, whichever reader they belong to, and
+     * rebuilt on next use. An opened split is charged the size of its files:
+     * memory-mapped pages are managed by the OS, so this only bounds them
+     * heuristically. A budget of 0, the default, disables the limit.
+     * 
+     * @param bytes Budget in bytes (must be >= 0)
+     * @throws IllegalArgumentException if bytes < 0
+     */
+    public static void setReaderMemoryBudget(long bytes) {
+        if (bytes < 0) {
+            throw new IllegalArgumentException("Reader memory budget cannot be negative, got: " + bytes);
+        }
+        setReaderMemoryBudgetNative(bytes);
+    }
+    
+    private static native void setReaderMemoryBudgetNative(long bytes);
+
+    /**
+     * Set the largest decompressed size of compressed splits readers accept.
+     * 
+     * <p>Splits compressed as a whole, named {@code .split.gz} or
+     * {@code .split.zst}, are decompressed when opened, into memory or a
+     * temporary file for the lifetime of the reader. Opening one decompressing
+     * beyond this limit fails instead, checked while decompressing. A limit of
+     * 0, the default, accepts any size.
+     * 
+     * @param bytes Limit in bytes (must be >= 0)
+     * @throws IllegalArgumentException if bytes < 0
+     */
+    public static void setMaxDecompressedSplitBytes(long bytes) {
+        if (bytes < 0) {
+            throw new IllegalArgumentException("Max decompressed split bytes cannot be negative, got: " + bytes);
+        }
+        setMaxDecompressedSplitBytesNative(bytes);
+    }
+    
+    private static native void setMaxDecompressedSplitBytesNative(long bytes);
+
+    /**
+     * Set the directory large compressed splits are decompressed into.
+     * 
+     * <p>Compressed splits too large to be held in memory are decompressed to
+     * a temporary file in this directory, deleted when the reader is closed.
+     * It needs free space for the decompressed size of every such split open
+     * at once. Passing null restores the default, the system temporary
+     * directory.
+     * 
+     * @param dir Directory for decompressed splits, or null for the default
+     */
+    public static void setDecompressionTempDir(Path dir) {
+        setDecompressionTempDirNative(dir == null ? null : dir.toString());
+    }
+    
+    private static native void setDecompressionTempDirNative(String dir);
+
+    /**
+     * Set the listener receiving the native library's log records.
+     * 
+     * <p>Records at debug level and above are forwarded; native logging is
+     * disabled while no listener is set, the default. Passing null removes
+     * the current listener.
+     * 
+     * @param listener Listener to receive records, or null to disable logging
+     * @throws IllegalStateException if the native library runs in a process
+     *         where another native logger is already installed
+     */
+    public static void setLogListener(LogListener listener) {
+        setLogCallbackNative(listener);
+    }
+    
+    private static native void setLogCallbackNative(LogListener listener);
+
+    /**
+     * Close native objects whose handle went unused for longer than a threshold.
+     * 
+     * <p>Generators, readers and posting cursors that are never closed stay
+     * in the native registries forever. This is a safety net for such leaks,
+     * not a substitute for closing objects: an instance whose native object
+     * was reaped fails on next use. A handle counts as used by every call
+     * made through it, so the threshold must exceed the longest time an open
+     * object may legitimately sit idle.
+     * 
+     * @param maxAgeMillis Idle time, in milliseconds, after which a handle is reaped (must be >= 0)
+     * @return Number of native objects closed
+     * @throws IllegalArgumentException if maxAgeMillis < 0
+     */
+    public static int reapStaleHandles(long maxAgeMillis) {
+        if (maxAgeMillis < 0) {
+            throw new IllegalArgumentException("Max age cannot be negative, got: " + maxAgeMillis);
+        }
+        return reapStaleHandlesNative(maxAgeMillis);
+    }
+    
+    private static native int reapStaleHandlesNative(long maxAgeMillis);
+
+    /**
+     * Count the native objects currently registered, for leak monitoring.
+     * 
+     * @return Number of open objects by kind, under the keys
+     *         {@code generators}, {@code readers}, {@code postingCursors} and
+     *         {@code streamingBuilders}
+     */
+    public static Map<String, Integer> registrySizes() {
+        return registrySizesNative();
+    }
+    
+    private static native Map<String, Integer> registrySizesNative();
+
+    /**
+     * Get the creation time of a native object, in milliseconds since the epoch.
+     * 
+     * @param handle Handle of a registered native object
+     * @return Creation time of the object
+     * @throws IllegalStateException if the handle isn't registered
+     */
+    static long handleCreatedAt(long handle) {
+        return handleCreatedAtNative(handle);
+    }
+    
+    private static native long handleCreatedAtNative(long handle);
+
+    /**
+     * Merge splits held in memory into a single split, without touching local disk.
+     * 
+     * <p>Each split is in the serialized split format: a flat list of the
+     * split's files, as produced by the native {@code split_to_bytes}. All
+     * splits must share the same schema; see
+     * {@link #mergeSplitBytes(SchemaCompatibility, byte[]...)} to merge splits
+     * with compatible schemas. The merged split holds a single segment and is
+     * returned in the same format.
+     * 
+     * @param splits Serialized splits to merge
+     * @return Serialized merged split
+     * @throws NullPointerException if splits or any of its elements is null
+     * @throws IllegalArgumentException if no split is given
+     * @throws IllegalStateException if the schemas differ or the merged split
+     *         exceeds the maximum byte array size
+     * @throws IOException if a split is malformed, or if two splits contain
+     *         the same segment (e.g. one split is a copy of another)
+     */
+    public static byte[] mergeSplitBytes(byte[]... splits) throws IOException {
+        return mergeSplitBytes(SchemaCompatibility.STRICT, splits);
+    }
+    
+    /**
+     * Merge splits held in memory whose schemas relate as {@code compatibility} allows.
+     * 
+     * <p>Splits with identical schemas are merged as by
+     * {@link #mergeSplitBytes(byte[]...)}. Otherwise their segments can't be
+     * merged as they are, so every document is re-indexed from its stored
+     * fields into the merged schema: this costs about as much as indexing the
+     * documents again, and requires every field of these splits to be stored.
+     * 
+     * @param compatibility How the schemas of the splits must relate
+     * @param splits Serialized splits to merge
+     * @return Serialized merged split
+     * @throws NullPointerException if compatibility, splits or any of its
+     *         elements is null
+     * @throws IllegalArgumentException if no split is given, or a split must
+     *         be re-indexed but has fields that aren't stored
+     * @throws IllegalStateException if the schemas aren't compatible, e.g. a
+     *         field has different types in two splits, or the merged split
+     *         exceeds the maximum byte array size
+     * @throws IOException if a split is malformed, or if two splits contain
+     *         the same segment (e.g. one split is a copy of another)
+     */
+    public static byte[] mergeSplitBytes(SchemaCompatibility compatibility, byte[]... splits) throws IOException {
+        Objects.requireNonNull(compatibility, "Schema compatibility cannot be null");
+        Objects.requireNonNull(splits, "Splits cannot be null");
+        if (splits.length == 0) {
+            throw new IllegalArgumentException("At least one split is required");
+        }
+        for (byte[] split : splits) {
+            Objects.requireNonNull(split, "Split cannot be null");
+        }
+        return mergeSplitBytesNative(splits, compatibility.getCode());
+    }
+    
+    private static native byte[] mergeSplitBytesNative(byte[][] splits, int compatibility) throws IOException;
+
     /**
      * Ensure the native library is loaded.
      * This method can be called to trigger library loading if needed.
@@ -0,0 +1,1033 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+package com.tantivy4java.splits;
+
+import com.fasterxml.jackson.core.JsonProcessingException;
+import com.fasterxml.jackson.databind.JsonNode;
+import com.fasterxml.jackson.databind.ObjectMapper;
+import com.fasterxml.jackson.databind.node.ObjectNode;
+import java.nio.file.Path;
+import java.util.ArrayList;
+import java.util.Arrays;
+import java.util.Collections;
+import java.util.LinkedHashMap;
+import java.util.List;
+import java.util.Map;
+import java.util.Objects;
+
+/**
+ * Options controlling how a split is generated.
+ * 
+ * <p>Instances are immutable and created through {@link #builder()}. Options
+ * left unset keep their default value.
+ * 
+ * <p>Example usage:
+ * <pre>{@code
+ * SplitConfig config = SplitConfig.builder()
+ *     .timestampField("timestamp")
+ *     .overwrite(true)
+ *     .build();
+ * generator.setConfig(config);
+ * }</pre>
+ */
+public class SplitConfig {
+    
+    private static final ObjectMapper MAPPER = new ObjectMapper();
+    
+    /**
+     * Layout of the hotcache embedded in a split.
+     */
+    public enum HotcacheFormat {
+        /** Split metadata for this library's readers. */
+        SIMPLE("simple"),
+        /**
+         * Quickwit's native hotcache, letting Quickwit open the split on its
+         * fast path. Requires a native library built with the
+         * {@code quickwit-compat} feature.
+         */
+        QUICKWIT("quickwit");
+        
+        private final String name;
+        
+        HotcacheFormat(String name) {
+            this.name = name;
+        }
+        
+        /**
+         * Gets the native name of the format, e.g. {@code "quickwit"}.
+         * 
+         * @return Native format name
+         */
+        public String getName() {
+            return name;
+        }
+    }
+    
+    /**
+     * How many segments a split keeps out of the index's segments.
+     * 
+     * <p>Several segments let searches run on them in parallel, but only
+     * {@link #forceSingle()}, the default, is fully Quickwit-compatible:
+     * Quickwit expects a split to hold a single segment, and so do the reader
+     * methods working on the split's segment, such as store block and fast
+     * field reads. Field ranges are only hotcached for single-segment splits.
+     */
+    public static final class MergePolicy {
+        
+        private static final MergePolicy FORCE_SINGLE = new MergePolicy("force_single", 1);
+        private static final MergePolicy NO_MERGE = new MergePolicy("no_merge", 0);
+        
+        private final String name;
+        private final int maxSegments;
+        
+        private MergePolicy(String name, int maxSegments) {
+            this.name = name;
+            this.maxSegments = maxSegments;
+        }
+        
+        /**
+         * Merges every segment into one.
+         * 
+         * @return The policy
+         */
+        public static MergePolicy forceSingle() {
+            return FORCE_SINGLE;
+        }
+        
+        /**
+         * Merges the segments with the fewest documents together until at
+         * most {@code maxSegments} remain.
+         * 
+         * @param maxSegments Largest number of segments in the split
+         * @return The policy
+         * @throws IllegalArgumentException if maxSegments is not positive
+         */
+        public static MergePolicy maxSegments(int maxSegments) {
+            if (maxSegments <= 0) {
+                throw new IllegalArgumentException("Max segments must be positive");
+            }
+            return new MergePolicy("max_segments", maxSegments);
+        }
+        
+        /**
+         * Keeps the index's segments as they are.
+         * 
+         * @return The policy
+         */
+        public static MergePolicy noMerge() {
+            return NO_MERGE;
+        }
+        
+        /**
+         * Gets the largest number of segments the split keeps.
+         * 
+         * @return Largest segment count, or 0 if segments aren't merged
+         */
+        public int getMaxSegments() {
+            return maxSegments;
+        }
+        
+        private JsonNode toJsonNode() {
+            if (!name.equals("max_segments")) {
+                return MAPPER.getNodeFactory().textNode(name);
+            }
+            ObjectNode node = MAPPER.createObjectNode();
+            node.put(name, maxSegments);
+            return node;
+        }
+        
+        @Override
+        public boolean equals(Object obj) {
+            if (this == obj) return true;
+            if (obj == null || getClass() != obj.getClass()) return false;
+            
+            MergePolicy that = (MergePolicy) obj;
+            return maxSegments == that.maxSegments && name.equals(that.name);
+        }
+        
+        @Override
+        public int hashCode() {
+            return Objects.hash(name, maxSegments);
+        }
+        
+        @Override
+        public String toString() {
+            return toJsonNode().toString();
+        }
+    }
+    
+    private final String timestampField;
+    private final List<String> tagFields;
+    private final List<String> bloomFields;
+    private final boolean writeSidecarMetadata;
+    private final boolean overwrite;
+    private final boolean legacyDirectoryLayout;
+    private final String splitId;
+    private final Long createdAtMillis;
+    private final Map<String, String> tokenizers;
+    private final boolean embedHotcache;
+    private final HotcacheFormat hotcacheFormat;
+    private final List<String> hotcacheFields;
+    private final List<String> hotcacheExclude;
+    private final int maxPartitions;
+    private final Long alignSections;
+    private final boolean dualFooter;
+    private final boolean exclusive;
+    private final long lockTimeoutMillis;
+    private final Path outputBaseDir;
+    private final Long maxSplitSizeBytes;
+    private final Map<String, String> customMetadata;
+    private final boolean verifyMerge;
+    private final MergePolicy mergePolicy;
+    private final Integer mergeThreads;
+    private final byte[] encryptionKey;
+    
+    private SplitConfig(Builder builder) {
+        this.timestampField = builder.timestampField;
+        this.tagFields = Collections.unmodifiableList(new ArrayList<>(builder.tagFields));
+        this.bloomFields = Collections.unmodifiableList(new ArrayList<>(builder.bloomFields));
+        this.writeSidecarMetadata = builder.writeSidecarMetadata;
+        this.overwrite = builder.overwrite;
+        this.legacyDirectoryLayout = builder.legacyDirectoryLayout;
+        this.splitId = builder.splitId;
+        this.createdAtMillis = builder.createdAtMillis;
+        this.tokenizers = Collections.unmodifiableMap(new LinkedHashMap<>(builder.tokenizers));
+        this.embedHotcache = builder.embedHotcache;
+        this.hotcacheFormat = builder.hotcacheFormat;
+        this.hotcacheFields = builder.hotcacheFields == null
+            ? null
+            : Collections.unmodifiableList(new ArrayList<>(builder.hotcacheFields));
+        this.hotcacheExclude = Collections.unmodifiableList(new ArrayList<>(builder.hotcacheExclude));
+        this.maxPartitions = builder.maxPartitions;
+        this.alignSections = builder.alignSections;
+        this.dualFooter = builder.dualFooter;
+        this.exclusive = builder.exclusive;
+        this.lockTimeoutMillis = builder.lockTimeoutMillis;
+        this.outputBaseDir = builder.outputBaseDir;
+        this.maxSplitSizeBytes = builder.maxSplitSizeBytes;
+        this.customMetadata = Collections.unmodifiableMap(new LinkedHashMap<>(builder.customMetadata));
+        this.verifyMerge = builder.verifyMerge;
+        this.mergePolicy = builder.mergePolicy;
+        this.mergeThreads = builder.mergeThreads;
+        this.encryptionKey = builder.encryptionKey == null ? null : builder.encryptionKey.clone();
+    }
+    
+    /**
+     * Creates a builder with every option at its default value.
+     * 
+     * @return New builder
+     */
+    public static Builder builder() {
+        return new Builder();
+    }
+    
+    /**
+     * Gets the field holding document timestamps.
+     * 
+     * @return Timestamp field name, or null if not set
+     */
+    public String getTimestampField() {
+        return timestampField;
+    }
+    
+    /**
+     * Gets the fields whose values are recorded as split tags.
+     * 
+     * @return Tag field names
+     */
+    public List<String> getTagFields() {
+        return tagFields;
+    }
+    
+    /**
+     * Gets the fields for which bloom filters are recorded.
+     * 
+     * @return Bloom filter field names
+     */
+    public List<String> getBloomFields() {
+        return bloomFields;
+    }
+    
+    /**
+     * Checks whether split metadata is written as JSON next to the split.
+     * 
+     * @return true if the sidecar metadata file is written
+     */
+    public boolean isWriteSidecarMetadata() {
+        return writeSidecarMetadata;
+    }
+    
+    /**
+     * Checks whether an existing split at the output path is replaced.
+     * 
+     * @return true if existing splits are overwritten
+     */
+    public boolean isOverwrite() {
+        return overwrite;
+    }
+    
+    /**
+     * Checks whether splits are written as a directory of files rather than a single {@code .split} file.
+     * 
+     * @return true for the legacy directory layout
+     */
+    public boolean isLegacyDirectoryLayout() {
+        return legacyDirectoryLayout;
+    }
+    
+    /**
+     * Gets the split id to record in the split metadata.
+     * 
+     * @return Split id, or null to generate a time-ordered UUID
+     */
+    public String getSplitId() {
+        return splitId;
+    }
+    
+    /**
+     * Gets the creation time to record in the split provenance.
+     * 
+     * @return Creation time in Unix milliseconds, or null to use the time of generation
+     */
+    public Long getCreatedAtMillis() {
+        return createdAtMillis;
+    }
+    
+    /**
+     * Gets the custom tokenizers the index was built with.
+     * 
+     * @return Map from tokenizer name to JSON configuration
+     */
+    public Map<String, String> getTokenizers() {
+        return tokenizers;
+    }
+    
+    /**
+     * Checks whether the hotcache is embedded in the split.
+     * 
+     * @return true if the hotcache is embedded
+     */
+    public boolean isEmbedHotcache() {
+        return embedHotcache;
+    }
+    
+    /**
+     * Gets the layout of the embedded hotcache.
+     * 
+     * @return Hotcache format
+     */
+    public HotcacheFormat getHotcacheFormat() {
+        return hotcacheFormat;
+    }
+    
+    /**
+     * Gets the fields whose section ranges are recorded in the hotcache.
+     * 
+     * @return Hotcached field names, or null if every field is hotcached
+     */
+    public List<String> getHotcacheFields() {
+        return hotcacheFields;
+    }
+    
+    /**
+     * Gets the fields left out of the hotcache.
+     * 
+     * @return Excluded field names
+     */
+    public List<String> getHotcacheExclude() {
+        return hotcacheExclude;
+    }
+    
+    /**
+     * Gets the largest number of splits partitioned generation may produce.
+     * 
+     * @return Maximum number of partitions
+     */
+    public int getMaxPartitions() {
+        return maxPartitions;
+    }
+    
+    /**
+     * Gets the boundary the hotcache is aligned to within the store file.
+     * 
+     * @return Alignment in bytes, or null if sections are not aligned
+     */
+    public Long getAlignSections() {
+        return alignSections;
+    }
+    
+    /**
+     * Checks whether generated splits carry a versioned footer ahead of the legacy one.
+     * 
+     * @return true if dual footers are written
+     */
+    public boolean isDualFooter() {
+        return dualFooter;
+    }
+    
+    /**
+     * Checks whether generation holds an exclusive lock on the output's parent directory.
+     * 
+     * @return true if generations into the same directory are serialized
+     */
+    public boolean isExclusive() {
+        return exclusive;
+    }
+    
+    /**
+     * Gets how long generation waits for the exclusive lock.
+     * 
+     * @return Lock timeout in milliseconds
+     */
+    public long getLockTimeoutMillis() {
+        return lockTimeoutMillis;
+    }
+    
+    /**
+     * Gets the directory output paths must resolve inside.
+     * 
+     * @return Output base directory, or null if output paths are unrestricted
+     */
+    public Path getOutputBaseDir() {
+        return outputBaseDir;
+    }
+    
+    /**
+     * Gets the largest size of a generated split.
+     * 
+     * @return Maximum split size in bytes, or null if unlimited
+     */
+    public Long getMaxSplitSizeBytes() {
+        return maxSplitSizeBytes;
+    }
+    
+    /**
+     * Gets the key/value metadata recorded in generated splits.
+     * 
+     * @return Map from metadata key to value
+     */
+    public Map<String, String> getCustomMetadata() {
+        return customMetadata;
+    }
+    
+    /**
+     * Checks whether generation verifies that merging left a single segment.
+     * 
+     * @return true if the merge is verified
+     */
+    public boolean isVerifyMerge() {
+        return verifyMerge;
+    }
+    
+    /**
+     * Gets how many segments generated splits keep.
+     * 
+     * @return Merge policy
+     */
+    public MergePolicy getMergePolicy() {
+        return mergePolicy;
+    }
+    
+    /**
+     * Gets the number of threads of the index writer merging segments.
+     * 
+     * @return Merge thread count, or null for Tantivy's default
+     */
+    public Integer getMergeThreads() {
+        return mergeThreads;
+    }
+    
+    /**
+     * Gets the key generated splits are encrypted with.
+     * 
+     * @return Copy of the 32-byte key, or null if splits aren't encrypted
+     */
+    public byte[] getEncryptionKey() {
+        return encryptionKey == null ? null : encryptionKey.clone();
+    }
+    
+    /**
+     * Serializes this configuration to the JSON understood by the native library.
+     * 
+     * @return JSON representation
+     * @throws IllegalArgumentException if a tokenizer configuration is not valid JSON
+     */
+    public String toJson() {
+        return toJsonNode(false).toString();
+    }
+    
+    private ObjectNode toJsonNode(boolean redactKey) {
+        ObjectNode node = MAPPER.createObjectNode();
+        if (timestampField != null) {
+            node.put("timestamp_field", timestampField);
+        }
+        tagFields.forEach(node.putArray("tag_fields")::add);
+        bloomFields.forEach(node.putArray("bloom_fields")::add);
+        node.put("write_sidecar_metadata", writeSidecarMetadata);
+        node.put("overwrite", overwrite);
+        node.put("legacy_directory_layout", legacyDirectoryLayout);
+        if (splitId != null) {
+            node.put("split_id", splitId);
+        }
+        if (createdAtMillis != null) {
+            node.put("created_at_millis", createdAtMillis);
+        }
+        ObjectNode tokenizersNode = node.putObject("tokenizers");
+        for (Map.Entry<String, String> tokenizer : tokenizers.entrySet()) {
+            try {
+                tokenizersNode.set(tokenizer.getKey(), MAPPER.readTree(tokenizer.getValue()));
+            } catch (JsonProcessingException e) {
+                throw new IllegalArgumentException("Invalid config for tokenizer '" + tokenizer.getKey() + "'", e);
+            }
+        }
+        node.put("embed_hotcache", embedHotcache);
+        node.put("hotcache_format", hotcacheFormat.getName());
+        if (hotcacheFields != null) {
+            hotcacheFields.forEach(node.putArray("hotcache_fields")::add);
+        }
+        hotcacheExclude.forEach(node.putArray("hotcache_exclude")::add);
+        node.put("max_partitions", maxPartitions);
+        if (alignSections != null) {
+            node.put("align_sections", alignSections);
+        }
+        node.put("dual_footer", dualFooter);
+        node.put("exclusive", exclusive);
+        node.put("lock_timeout_millis", lockTimeoutMillis);
+        if (outputBaseDir != null) {
+            node.put("output_base_dir", outputBaseDir.toString());
+        }
+        if (maxSplitSizeBytes != null) {
+            node.put("max_split_size_bytes", maxSplitSizeBytes);
+        }
+        ObjectNode customMetadataNode = node.putObject("custom_metadata");
+        customMetadata.forEach(customMetadataNode::put);
+        node.put("verify_merge", verifyMerge);
+        node.set("merge_policy", mergePolicy.toJsonNode());
+        if (mergeThreads != null) {
+            node.put("merge_threads", mergeThreads);
+        }
+        if (encryptionKey != null) {
+            node.put("encryption_key", redactKey ? "<redacted>" : toHex(encryptionKey));
+        }
+        return node;
+    }
+    
+    private static String toHex(byte[] bytes) {
+        StringBuilder hex = new StringBuilder(bytes.length * 2);
+        for (byte b : bytes) {
+            hex.append(String.format("%02x", b));
+        }
+        return hex.toString();
+    }
+    
+    @Override
+    public boolean equals(Object obj) {
+        if (this == obj) return true;
+        if (obj == null || getClass() != obj.getClass()) return false;
+        
+        SplitConfig that = (SplitConfig) obj;
+        return writeSidecarMetadata == that.writeSidecarMetadata &&
+               overwrite == that.overwrite &&
+               legacyDirectoryLayout == that.legacyDirectoryLayout &&
+               verifyMerge == that.verifyMerge &&
+               mergePolicy.equals(that.mergePolicy) &&
+               Objects.equals(mergeThreads, that.mergeThreads) &&
+               Arrays.equals(encryptionKey, that.encryptionKey) &&
+               embedHotcache == that.embedHotcache &&
+               hotcacheFormat == that.hotcacheFormat &&
+               maxPartitions == that.maxPartitions &&
+               exclusive == that.exclusive &&
+               lockTimeoutMillis == that.lockTimeoutMillis &&
+               Objects.equals(alignSections, that.alignSections) &&
+               dualFooter == that.dualFooter &&
+               Objects.equals(outputBaseDir, that.outputBaseDir) &&
+               Objects.equals(maxSplitSizeBytes, that.maxSplitSizeBytes) &&
+               Objects.equals(customMetadata, that.customMetadata) &&
+               Objects.equals(timestampField, that.timestampField) &&
+               Objects.equals(tagFields, that.tagFields) &&
+               Objects.equals(bloomFields, that.bloomFields) &&
+               Objects.equals(hotcacheFields, that.hotcacheFields) &&
+               Objects.equals(hotcacheExclude, that.hotcacheExclude) &&
+               Objects.equals(splitId, that.splitId) &&
+               Objects.equals(createdAtMillis, that.createdAtMillis) &&
+               Objects.equals(tokenizers, that.tokenizers);
+    }
+    
+    @Override
+    public int hashCode() {
+        return 31 * Arrays.hashCode(encryptionKey) + Objects.hash(timestampField, tagFields, bloomFields, writeSidecarMetadata,
+                            overwrite, splitId, tokenizers, embedHotcache, hotcacheFormat, maxPartitions, alignSections,
+                            exclusive, lockTimeoutMillis, hotcacheFields, hotcacheExclude, outputBaseDir,
+                            createdAtMillis, maxSplitSizeBytes, legacyDirectoryLayout, customMetadata,
+                            verifyMerge, mergePolicy, mergeThreads, dualFooter);
+    }
+    
+    @Override
+    public String toString() {
+        // Never prints the encryption key
+        return "SplitConfig" + toJsonNode(true);
+    }
+    
+    /**
+     * Builder for {@link SplitConfig}.
+     */
+    public static class Builder {
+        
+        private String timestampField;
+        private final List<String> tagFields = new ArrayList<>();
+        private final List<String> bloomFields = new ArrayList<>();
+        private boolean writeSidecarMetadata = false;
+        private boolean overwrite = false;
+        private boolean legacyDirectoryLayout = false;
+        private String splitId;
+        private Long createdAtMillis;
+        private final Map<String, String> tokenizers = new LinkedHashMap<>();
+        private boolean embedHotcache = true;
+        private HotcacheFormat hotcacheFormat = HotcacheFormat.SIMPLE;
+        private List<String> hotcacheFields;
+        private final List<String> hotcacheExclude = new ArrayList<>();
+        private int maxPartitions = 100;
+        private Long alignSections;
+        private boolean dualFooter = false;
+        private boolean exclusive = false;
+        private long lockTimeoutMillis = 30_000;
+        private Path outputBaseDir;
+        private Long maxSplitSizeBytes;
+        private final Map<String, String> customMetadata = new LinkedHashMap<>();
+        private boolean verifyMerge = false;
+        private MergePolicy mergePolicy = MergePolicy.forceSingle();
+        private Integer mergeThreads;
+        private byte[] encryptionKey;
+        
+        private Builder() {
+        }
+        
+        /**
+         * Sets the field holding document timestamps, used to record the split time range.
+         * 
+         * @param timestampField Date or i64 field name
+         * @return This builder
+         * @throws NullPointerException if timestampField is null
+         */
+        public Builder timestampField(String timestampField) {
+            this.timestampField = Objects.requireNonNull(timestampField, "Timestamp field cannot be null");
+            return this;
+        }
+        
+        /**
+         * Adds a field whose values are recorded as split tags.
+         * 
+         * <p>A field with more than 1000 distinct values records no tags, and
+         * the split metadata carries a warning instead.
+         * 
+         * @param field Field name
+         * @return This builder
+         * @throws NullPointerException if field is null
+         */
+        public Builder tagField(String field) {
+            tagFields.add(Objects.requireNonNull(field, "Tag field cannot be null"));
+            return this;
+        }
+        
+        /**
+         * Adds a field for which a bloom filter is recorded.
+         * 
+         * @param field Field name
+         * @return This builder
+         * @throws NullPointerException if field is null
+         */
+        public Builder bloomField(String field) {
+            bloomFields.add(Objects.requireNonNull(field, "Bloom field cannot be null"));
+            return this;
+        }
+        
+        /**
+         * Sets whether to write the split metadata as {@code <split>.json} next to the split.
+         * 
+         * @param writeSidecarMetadata true to write the sidecar metadata file
+         * @return This builder
+         */
+        public Builder writeSidecarMetadata(boolean writeSidecarMetadata) {
+            this.writeSidecarMetadata = writeSidecarMetadata;
+            return this;
+        }
+        
+        /**
+         * Sets whether to replace a split already present at the output path.
+         * 
+         * <p>When false (the default), generation fails if the output file
+         * exists, or the output directory exists and isn't empty. When true,
+         * the existing split is replaced once the new split is fully written.
+         * 
+         * @param overwrite true to overwrite existing splits
+         * @return This builder
+         */
+        public Builder overwrite(boolean overwrite) {
+            this.overwrite = overwrite;
+            return this;
+        }
+        
+        /**
+         * Sets whether to write splits as a directory of files, as earlier versions did.
+         * 
+         * <p>By default, a split is written as a single file following
+         * Quickwit's {@code <split_id>.split} naming, which
+         * {@link QuickwitSplitReader} reads in place.
+         * 
+         * @param legacyDirectoryLayout true to write split directories
+         * @return This builder
+         */
+        public Builder legacyDirectoryLayout(boolean legacyDirectoryLayout) {
+            this.legacyDirectoryLayout = legacyDirectoryLayout;
+            return this;
+        }
+        
+        /**
+         * Sets the split id to record in the split metadata.
+         * 
+         * <p>Lets callers use meaningful, reproducible ids, e.g. derived from a
+         * partition key. Must be usable as a file name.
+         * 
+         * @param splitId Split id
+         * @return This builder
+         * @throws NullPointerException if splitId is null
+         */
+        public Builder splitId(String splitId) {
+            this.splitId = Objects.requireNonNull(splitId, "Split ID cannot be null");
+            return this;
+        }
+        
+        /**
+         * Sets the creation time to record in the split provenance.
+         * 
+         * <p>Defaults to the time of generation. Pinned along with the split
+         * id, it makes generation reproducible: the same index then yields
+         * byte-identical splits, up to the ids of merged segments.
+         * 
+         * @param createdAtMillis Creation time in Unix milliseconds
+         * @return This builder
+         */
+        public Builder createdAtMillis(long createdAtMillis) {
+            this.createdAtMillis = createdAtMillis;
+            return this;
+        }
+        
+        /**
+         * Declares a custom tokenizer the index was built with.
+         * 
+         * <p>The configuration is recorded in the split so readers parse
+         * queries with the same tokenizer. See
+         * {@link QuickwitSplitReader#registerTokenizer(String, String)} for
+         * the configuration format.
+         * 
+         * @param name Tokenizer name, as referenced by the schema
+         * @param configJson Tokenizer configuration
+         * @return This builder
+         * @throws NullPointerException if name or configJson is null
+         */
+        public Builder tokenizer(String name, String configJson) {
+            tokenizers.put(
+                Objects.requireNonNull(name, "Tokenizer name cannot be null"),
+                Objects.requireNonNull(configJson, "Tokenizer config cannot be null"));
+            return this;
+        }
+        
+        /**
+         * Sets whether to embed the hotcache in the split.
+         * 
+         * @param embedHotcache false to generate a split without hotcache
+         * @return This builder
+         */
+        public Builder embedHotcache(boolean embedHotcache) {
+            this.embedHotcache = embedHotcache;
+            return this;
+        }
+        
+        /**
+         * Sets the layout of the embedded hotcache.
+         * 
+         * <p>{@link HotcacheFormat#QUICKWIT} can't be combined with
+         * {@link #alignSections(long)}; generation fails if both are set.
+         * 
+         * @param hotcacheFormat Hotcache format
+         * @return This builder
+         * @throws NullPointerException if hotcacheFormat is null
+         */
+        public Builder hotcacheFormat(HotcacheFormat hotcacheFormat) {
+            this.hotcacheFormat = Objects.requireNonNull(hotcacheFormat, "Hotcache format cannot be null");
+            return this;
+        }
+        
+        /**
+         * Adds a field whose posting and fast field ranges are recorded in the hotcache.
+         * 
+         * <p>Every field is hotcached until the first call; from then on, only
+         * the added fields are. Each hotcached field grows the split footer and
+         * the reader warm-up. Fields left out stay fully queryable, but their
+         * first access reads the segment files without knowing where the
+         * field's sections lie, which is slower on remote storage.
+         * 
+         * @param field Field name
+         * @return This builder
+         * @throws NullPointerException if field is null
+         */
+        public Builder hotcacheField(String field) {
+            if (hotcacheFields == null) {
+                hotcacheFields = new ArrayList<>();
+            }
+            hotcacheFields.add(Objects.requireNonNull(field, "Hotcache field cannot be null"));
+            return this;
+        }
+        
+        /**
+         * Leaves a field out of the hotcache, even if added with {@link #hotcacheField(String)}.
+         * 
+         * @param field Field name
+         * @return This builder
+         * @throws NullPointerException if field is null
+         */
+        public Builder hotcacheExclude(String field) {
+            hotcacheExclude.add(Objects.requireNonNull(field, "Hotcache exclude field cannot be null"));
+            return this;
+        }
+        
+        /**
+         * Sets the largest number of splits partitioned generation may produce.
+         * 
+         * @param maxPartitions Maximum number of partitions (must be > 0)
+         * @return This builder
+         * @throws IllegalArgumentException if maxPartitions <= 0
+         */
+        public Builder maxPartitions(int maxPartitions) {
+            if (maxPartitions <= 0) {
+                throw new IllegalArgumentException("Max partitions must be greater than 0");
+            }
+            this.maxPartitions = maxPartitions;
+            return this;
+        }
+        
+        /**
+         * Aligns the hotcache to a boundary within the store file.
+         * 
+         * <p>The store data is padded so the hotcache starts on a multiple of
+         * {@code alignment}, e.g. 4096 to match the page size, which improves
+         * page-cache behavior when warming up. Off by default to avoid the
+         * padding overhead.
+         * 
+         * @param alignment Alignment in bytes (must be a power of two)
+         * @return This builder
+         * @throws IllegalArgumentException if alignment is not a power of two
+         */
+        public Builder alignSections(long alignment) {
+            if (alignment <= 0 || Long.bitCount(alignment) != 1) {
+                throw new IllegalArgumentException("Section alignment must be a power of two, got " + alignment);
+            }
+            this.alignSections = alignment;
+            return this;
+        }
+        
+        /**
+         * Sets whether to write a versioned footer ahead of the legacy one.
+         * 
+         * <p>Readers of this version read the versioned footer, which carries
+         * a magic and version, while readers of the legacy size-only footer
+         * still open the split. The hotcache is written twice, growing the
+         * store file by its size plus 16 bytes, so this is meant only for
+         * migration windows where both reader versions open the same splits.
+         * Only applies to an embedded {@link HotcacheFormat#SIMPLE} hotcache;
+         * generation fails with the {@link HotcacheFormat#QUICKWIT} format.
+         * Disabled by default.
+         * 
+         * @param dualFooter true to write both footers
+         * @return This builder
+         */
+        public Builder dualFooter(boolean dualFooter) {
+            this.dualFooter = dualFooter;
+            return this;
+        }
+        
+        /**
+         * Sets whether generation holds an exclusive lock on the output's parent directory.
+         * 
+         * <p>Producers generating into the same base directory, from any
+         * process, then run one at a time. The lock is advisory and released
+         * when generation ends.
+         * 
+         * @param exclusive true to serialize generations into the same directory
+         * @return This builder
+         */
+        public Builder exclusive(boolean exclusive) {
+            this.exclusive = exclusive;
+            return this;
+        }
+        
+        /**
+         * Sets how long generation waits for the exclusive lock before failing.
+         * 
+         * @param lockTimeoutMillis Lock timeout in milliseconds (must be >= 0)
+         * @return This builder
+         * @throws IllegalArgumentException if lockTimeoutMillis < 0
+         */
+        public Builder lockTimeoutMillis(long lockTimeoutMillis) {
+            if (lockTimeoutMillis < 0) {
+                throw new IllegalArgumentException("Lock timeout cannot be negative");
+            }
+            this.lockTimeoutMillis = lockTimeoutMillis;
+            return this;
+        }
+        
+        /**
+         * Restricts split output to paths resolving inside a directory.
+         * 
+         * <p>Output paths are resolved with symlinks followed, so a symlink
+         * pointing outside the directory is rejected as well.
+         * 
+         * @param outputBaseDir Existing directory splits must be written inside
+         * @return This builder
+         * @throws NullPointerException if outputBaseDir is null
+         */
+        public Builder outputBaseDir(Path outputBaseDir) {
+            this.outputBaseDir = Objects.requireNonNull(outputBaseDir, "Output base directory cannot be null");
+            return this;
+        }
+        
+        /**
+         * Sets the largest size of a generated split.
+         * 
+         * <p>The size is checked once the split is fully written; larger
+         * splits fail with {@link IllegalStateException} and nothing is
+         * published. Unlimited by default.
+         * 
+         * @param maxSplitSizeBytes Maximum split size in bytes (must be > 0)
+         * @return This builder
+         * @throws IllegalArgumentException if maxSplitSizeBytes <= 0
+         */
+        public Builder maxSplitSizeBytes(long maxSplitSizeBytes) {
+            if (maxSplitSizeBytes <= 0) {
+                throw new IllegalArgumentException("Max split size must be greater than 0");
+            }
+            this.maxSplitSizeBytes = maxSplitSizeBytes;
+            return this;
+        }
+        
+        /**
+         * Adds a key/value pair to record in generated splits.
+         * 
+         * <p>Lets metastores tag splits with e.g. their source, pipeline id or
+         * environment; read back with {@link QuickwitSplitReader#getCustomMetadata()}.
+         * Only recorded with the {@link HotcacheFormat#SIMPLE} hotcache format,
+         * and not when the hotcache isn't embedded.
+         * 
+         * @param key Metadata key
+         * @param value Metadata value
+         * @return This builder
+         * @throws NullPointerException if key or value is null
+         */
+        public Builder customMetadata(String key, String value) {
+            customMetadata.put(
+                Objects.requireNonNull(key, "Metadata key cannot be null"),
+                Objects.requireNonNull(value, "Metadata value cannot be null"));
+            return this;
+        }
+        
+        /**
+         * Sets whether to verify that merging the index segments left a single segment.
+         * 
+         * <p>When the merge policy merges several segments, generation re-reads
+         * the index after merging them and fails with {@link IllegalStateException},
+         * naming the number of segments left, if they didn't collapse into
+         * one. This catches a merge that silently failed before a split
+         * missing documents is published. Disabled by default.
+         * 
+         * @param verifyMerge true to verify the merge
+         * @return This builder
+         */
+        public Builder verifyMerge(boolean verifyMerge) {
+            this.verifyMerge = verifyMerge;
+            return this;
+        }
+        
+        /**
+         * Sets how many segments generated splits keep out of the index's segments.
+         * 
+         * <p>Only the default, {@link MergePolicy#forceSingle()}, is fully
+         * Quickwit-compatible, see {@link MergePolicy}.
+         * 
+         * @param mergePolicy Merge policy
+         * @return This builder
+         * @throws NullPointerException if mergePolicy is null
+         */
+        public Builder mergePolicy(MergePolicy mergePolicy) {
+            this.mergePolicy = Objects.requireNonNull(mergePolicy, "Merge policy cannot be null");
+            return this;
+        }
+        
+        /**
+         * Sets the number of threads of the index writer merging segments.
+         * 
+         * <p>Tantivy sizes its writer to the machine's cores by default, which
+         * oversubscribes containers limited to fewer. A count above the
+         * available parallelism is allowed, with a warning in
+         * {@link SplitMetadata#getWarnings()}.
+         * 
+         * @param mergeThreads Merge thread count (must be > 0)
+         * @return This builder
+         * @throws IllegalArgumentException if mergeThreads <= 0
+         */
+        public Builder mergeThreads(int mergeThreads) {
+            if (mergeThreads <= 0) {
+                throw new IllegalArgumentException("Merge threads must be greater than 0");
+            }
+            this.mergeThreads = mergeThreads;
+            return this;
+        }
+        
+        /**
+         * Sets the key to encrypt generated splits with, using AES-256-GCM.
+         * 
+         * <p>Segment files are encrypted at rest; only the hotcache summary,
+         * e.g. split id, document count and custom metadata, stays readable,
+         * and no field ranges are hotcached. Readers need the same key, see
+         * {@link QuickwitSplitReader#setEncryptionKey(byte[])}. Requires a
+         * native library built with the {@code encryption} feature and an
+         * embedded hotcache in the {@link HotcacheFormat#SIMPLE} format.
+         * 
+         * @param encryptionKey 32-byte key, or null to disable encryption
+         * @return This builder
+         * @throws IllegalArgumentException if the key isn't 32 bytes long
+         */
+        public Builder encryptionKey(byte[] encryptionKey) {
+            if (encryptionKey != null && encryptionKey.length != 32) {
+                throw new IllegalArgumentException("Encryption key must be 32 bytes, got " + encryptionKey.length);
+            }
+            this.encryptionKey = encryptionKey == null ? null : encryptionKey.clone();
+            return this;
+        }
+        
+        /**
+         * Builds the configuration.
+         * 
+         * @return New configuration
+         */
+        public SplitConfig build() {
+            return new SplitConfig(this);
+        }
+    }
+}
@@ -19,6 +19,9 @@
 
 package com.tantivy4java.splits;
 
+import java.util.ArrayList;
+import java.util.Collections;
+import java.util.List;
 import java.util.Objects;
 
 /**
@@ -30,6 +33,7 @@ import java.util.Objects;
  *   <li>Document count</li>
  *   <li>Size information</li>
  *   <li>Hotcache location for efficient access</li>
+ *   <li>Non-fatal warnings raised during generation</li>
  * </ul>
  */
 public class SplitMetadata {
@@ -38,6 +42,7 @@ public class SplitMetadata {
     private final int numDocs;
     private final long sizeBytes;
     private final ByteRange hotcacheRange;
+    private final List<String> warnings;
     
     /**
      * Creates new split metadata.
@@ -50,8 +55,26 @@ public class SplitMetadata {
      * @throws IllegalArgumentException if numDocs or sizeBytes is negative
      */
     public SplitMetadata(String splitId, int numDocs, long sizeBytes, ByteRange hotcacheRange) {
+        this(splitId, numDocs, sizeBytes, hotcacheRange, Collections.emptyList());
+    }
+    
+    /**
+     * Creates new split metadata carrying generation warnings.
+     * 
+     * @param splitId Unique identifier for the split
+     * @param numDocs Number of documents in the split
+     * @param sizeBytes Total size of split files in bytes
+     * @param hotcacheRange Byte range where hotcache metadata is stored
+     * @param warnings Non-fatal issues met while generating the split
+     * @throws NullPointerException if splitId, hotcacheRange or warnings is null
+     * @throws IllegalArgumentException if numDocs or sizeBytes is negative
+     */
+    public SplitMetadata(String splitId, int numDocs, long sizeBytes, ByteRange hotcacheRange,
+                         List<String> warnings) {
         this.splitId = Objects.requireNonNull(splitId, "Split ID cannot be null");
         this.hotcacheRange = Objects.requireNonNull(hotcacheRange, "Hotcache range cannot be null");
+        this.warnings = Collections.unmodifiableList(
+            new ArrayList<>(Objects.requireNonNull(warnings, "Warnings cannot be null")));
         
         if (numDocs < 0) {
             throw new IllegalArgumentException("Number of docs cannot be negative: " + numDocs);
@@ -100,6 +123,19 @@ public class SplitMetadata {
         return hotcacheRange;
     }
     
+    /**
+     * Gets the non-fatal issues met while generating the split.
+     * 
+     * <p>Generation succeeded despite them, e.g. a tag field with too many
+     * distinct values recorded no tags, or configured hotcache fields
+     * couldn't be applied.
+     * 
+     * @return Unmodifiable list of warnings, empty if there were none
+     */
+    public List<String> getWarnings() {
+        return warnings;
+    }
+    
     @Override
     public boolean equals(Object obj) {
         if (this == obj) return true;
@@ -109,17 +145,18 @@ public class SplitMetadata {
         return numDocs == that.numDocs &&
                sizeBytes == that.sizeBytes &&
                Objects.equals(splitId, that.splitId) &&
-               Objects.equals(hotcacheRange, that.hotcacheRange);
+               Objects.equals(hotcacheRange, that.hotcacheRange) &&
+               Objects.equals(warnings, that.warnings);
     }
     
     @Override
     public int hashCode() {
-        return Objects.hash(splitId, numDocs, sizeBytes, hotcacheRange);
+        return Objects.hash(splitId, numDocs, sizeBytes, hotcacheRange, warnings);
     }
     
     @Override
     public String toString() {
-        return String.format("SplitMetadata{splitId='%s', numDocs=%d, sizeBytes=%d, hotcacheRange=%s}",
-                           splitId, numDocs, sizeBytes, hotcacheRange);
+        return String.format("SplitMetadata{splitId='%s', numDocs=%d, sizeBytes=%d, hotcacheRange=%s, warnings=%s}",
+                           splitId, numDocs, sizeBytes, hotcacheRange, warnings);
     }
 }
\ No newline at end of file
@@ -0,0 +1,112 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+package com.tantivy4java.splits;
+
+import java.util.Objects;
+
+/**
+ * Size estimate of the split an index would generate.
+ * 
+ * <p>Returned by {@link QuickwitSplitGenerator#planSplit()}. The bounds come
+ * from the real lengths of the source segment files: the upper bound assumes
+ * they are merged as they are, the lower bound that deleted documents' data
+ * is fully compacted away.
+ */
+public class SplitPlan {
+    
+    private final long numDocs;
+    private final int sourceSegments;
+    private final long minSizeBytes;
+    private final long maxSizeBytes;
+    
+    /**
+     * Creates a new split plan.
+     * 
+     * @param numDocs Number of live documents the split would hold
+     * @param sourceSegments Number of source segments merged into the split
+     * @param minSizeBytes Lower bound of the split size in bytes
+     * @param maxSizeBytes Upper bound of the split size in bytes
+     */
+    public SplitPlan(long numDocs, int sourceSegments, long minSizeBytes, long maxSizeBytes) {
+        this.numDocs = numDocs;
+        this.sourceSegments = sourceSegments;
+        this.minSizeBytes = minSizeBytes;
+        this.maxSizeBytes = maxSizeBytes;
+    }
+    
+    /**
+     * Gets the number of live documents the split would hold.
+     * 
+     * @return Document count
+     */
+    public long getNumDocs() {
+        return numDocs;
+    }
+    
+    /**
+     * Gets the number of source segments merged into the split.
+     * 
+     * @return Segment count
+     */
+    public int getSourceSegments() {
+        return sourceSegments;
+    }
+    
+    /**
+     * Gets the lower bound of the split size.
+     * 
+     * @return Size in bytes
+     */
+    public long getMinSizeBytes() {
+        return minSizeBytes;
+    }
+    
+    /**
+     * Gets the upper bound of the split size.
+     * 
+     * @return Size in bytes
+     */
+    public long getMaxSizeBytes() {
+        return maxSizeBytes;
+    }
+    
+    @Override
+    public boolean equals(Object obj) {
+        if (this == obj) return true;
+        if (obj == null || getClass() != obj.getClass()) return false;
+        
+        SplitPlan that = (SplitPlan) obj;
+        return numDocs == that.numDocs &&
+               sourceSegments == that.sourceSegments &&
+               minSizeBytes == that.minSizeBytes &&
+               maxSizeBytes == that.maxSizeBytes;
+    }
+    
+    @Override
+    public int hashCode() {
+        return Objects.hash(numDocs, sourceSegments, minSizeBytes, maxSizeBytes);
+    }
+    
+    @Override
+    public String toString() {
+        return String.format("SplitPlan{numDocs=%d, sourceSegments=%d, minSizeBytes=%d, maxSizeBytes=%d}",
+                           numDocs, sourceSegments, minSizeBytes, maxSizeBytes);
+    }
+}
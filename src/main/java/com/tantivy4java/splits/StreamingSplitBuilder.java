@@ -0,0 +1,199 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+package com.tantivy4java.splits;
+
+import java.io.IOException;
+import java.nio.file.Path;
+import java.util.List;
+import java.util.Objects;
+
+/**
+ * Builds splits incrementally, as documents arrive.
+ * 
+ * <p>Documents are buffered in an in-memory index; as soon as
+ * {@code targetDocsPerSplit} of them are buffered, a split is written to the
+ * output directory and a new buffer started. Documents are added one at a
+ * time as JSON, or a committed segment of another index at a time, as a
+ * pipeline notified of commits would feed them. {@link #finish()} writes the
+ * last, partial split.
+ * 
+ * <p>Memory is bounded by the buffer: at most {@code targetDocsPerSplit}
+ * indexed documents, plus an indexing arena of about 50 MB. Closing the
+ * builder without finishing it drops the buffered documents.
+ * 
+ * <p>Example usage:
+ * <pre>{@code
+ * try (StreamingSplitBuilder builder = StreamingSplitBuilder.begin(
+ *         schemaJson, Paths.get("output"), 100_000, SplitConfig.builder().build())) {
+ *     for (String document : documents) {
+ *         SplitMetadata emitted = builder.addDocument(document);
+ *         if (emitted != null) {
+ *             publish(emitted);
+ *         }
+ *     }
+ *     List<SplitMetadata> splits = builder.finish();
+ * }
+ * }</pre>
+ */
+public class StreamingSplitBuilder implements AutoCloseable {
+    
+    private long nativeHandle;
+    private boolean closed = false;
+    
+    private StreamingSplitBuilder(long nativeHandle) {
+        this.nativeHandle = nativeHandle;
+    }
+    
+    /**
+     * Starts building splits of documents with the given schema.
+     * 
+     * <p>Splits are written to {@code outputDir} as {@code <split_id>.split},
+     * named {@code <split_id>-<n>} when the configuration sets a split id.
+     * 
+     * @param schemaJson Tantivy schema of the documents, as JSON
+     * @param outputDir Directory receiving the splits, created if missing
+     * @param targetDocsPerSplit Number of documents per split (must be > 0)
+     * @param config Options of every split
+     * @return New builder
+     * @throws NullPointerException if schemaJson, outputDir or config is null
+     * @throws IllegalArgumentException if targetDocsPerSplit <= 0
+     * @throws IOException if the output directory can't be created
+     * @throws RuntimeException if the schema or configuration is invalid
+     */
+    public static StreamingSplitBuilder begin(String schemaJson, Path outputDir, int targetDocsPerSplit,
+                                              SplitConfig config) throws IOException {
+        Objects.requireNonNull(schemaJson, "Schema cannot be null");
+        Objects.requireNonNull(outputDir, "Output directory cannot be null");
+        Objects.requireNonNull(config, "Config cannot be null");
+        if (targetDocsPerSplit <= 0) {
+            throw new IllegalArgumentException("Target docs per split must be positive, got: " + targetDocsPerSplit);
+        }
+        
+        return new StreamingSplitBuilder(
+            beginStreamingNative(schemaJson, outputDir.toString(), targetDocsPerSplit, config.toJson()));
+    }
+    
+    /**
+     * Buffers a document, writing a split if the buffer reached its target size.
+     * 
+     * @param documentJson Document as JSON, keyed by field name
+     * @return Metadata of the split written, or null if the document was only buffered
+     * @throws NullPointerException if documentJson is null
+     * @throws IOException if writing the split fails
+     * @throws RuntimeException if the document doesn't match the schema
+     * @throws IllegalStateException if the builder is closed or finished
+     */
+    public SplitMetadata addDocument(String documentJson) throws IOException {
+        Objects.requireNonNull(documentJson, "Document cannot be null");
+        ensureNotClosed();
+        return addDocumentNative(nativeHandle, documentJson);
+    }
+    
+    /**
+     * Buffers the live documents of a committed segment, writing splits as the buffer fills.
+     * 
+     * <p>Meant to be called on commit notifications of the index a pipeline
+     * writes to. That index must have the builder's schema, with every field
+     * stored, as documents are rebuilt from the doc store.
+     * 
+     * @param indexPath Directory of the index holding the segment
+     * @param segmentId UUID of the segment, as named in its files
+     * @return Metadata of the splits written, possibly none
+     * @throws NullPointerException if indexPath or segmentId is null
+     * @throws IOException if the index can't be read or writing a split fails
+     * @throws IllegalArgumentException if a field of the schema isn't stored
+     * @throws IllegalStateException if the builder is closed or finished, the
+     *         schemas differ, or the segment isn't committed in the index
+     */
+    public List<SplitMetadata> addSegment(Path indexPath, String segmentId) throws IOException {
+        Objects.requireNonNull(indexPath, "Index path cannot be null");
+        Objects.requireNonNull(segmentId, "Segment id cannot be null");
+        ensureNotClosed();
+        return addSegmentNative(nativeHandle, indexPath.toString(), segmentId);
+    }
+    
+    /**
+     * Writes the buffered documents as a final split, if any.
+     * 
+     * <p>No documents can be added afterwards; the builder still has to be closed.
+     * 
+     * @return Metadata of every split written by this builder, in order
+     * @throws IOException if writing the split fails
+     * @throws IllegalStateException if the builder is closed or already finished
+     */
+    public List<SplitMetadata> finish() throws IOException {
+        ensureNotClosed();
+        return finishNative(nativeHandle);
+    }
+    
+    /**
+     * Checks if this builder has been closed.
+     * 
+     * @return true if closed, false otherwise
+     */
+    public boolean isClosed() {
+        return closed;
+    }
+    
+    /**
+     * Closes this builder and releases native resources, dropping unfinished documents.
+     * 
+     * <p>This method is idempotent and safe to call multiple times.
+     */
+    @Override
+    public void close() {
+        if (!closed && nativeHandle != 0) {
+            closeNative(nativeHandle);
+            nativeHandle = 0;
+            closed = true;
+        }
+    }
+    
+    /**
+     * Ensures this builder has not been closed.
+     * 
+     * @throws IllegalStateException if the builder is closed
+     */
+    private void ensureNotClosed() {
+        if (closed) {
+            throw new IllegalStateException("Streaming split builder has been closed");
+        }
+    }
+    
+    // Native method declarations
+    private static native long beginStreamingNative(String schemaJson, String outputDir, int targetDocsPerSplit,
+                                                    String configJson) throws IOException;
+    private native SplitMetadata addDocumentNative(long handle, String documentJson) throws IOException;
+    private native List<SplitMetadata> addSegmentNative(long handle, String indexPath, String segmentId) throws IOException;
+    private native List<SplitMetadata> finishNative(long handle) throws IOException;
+    private native void closeNative(long handle);
+    
+    /**
+     * Finalizer to ensure native resources are cleaned up.
+     */
+    @Override
+    protected void finalize() throws Throwable {
+        try {
+            close();
+        } finally {
+            super.finalize();
+        }
+    }
+}
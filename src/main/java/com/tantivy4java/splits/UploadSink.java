@@ -0,0 +1,52 @@
+/*
+ * Licensed to the Apache Software Foundation (ASF) under one
+ * or more contributor license agreements.  See the NOTICE file
+ * distributed with this work for additional information
+ * regarding copyright ownership.  The ASF licenses this file
+ * to you under the Apache License, Version 2.0 (the
+ * "License"); you may not use this file except in compliance
+ * with the License.  You may obtain a copy of the License at
+ *
+ *   http://www.apache.org/licenses/LICENSE-2.0
+ *
+ * Unless required by applicable law or agreed to in writing,
+ * software distributed under the License is distributed on an
+ * "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+ * KIND, either express or implied.  See the License for the
+ * specific language governing permissions and limitations
+ * under the License.
+ */
+
+package com.tantivy4java.splits;
+
+import java.io.IOException;
+
+/**
+ * Receives a split streamed by {@link QuickwitSplitGenerator#generateSplitStreaming(UploadSink)}.
+ * 
+ * <p>The split arrives serialized, in consecutive chunks: each chunk starts
+ * at the offset where the previous one ended, and every chunk but the last
+ * is 8 MiB, so chunk {@code offset / (8 * 1024 * 1024)} can be uploaded as
+ * one part of an object storage multipart upload. The hotcache footer is in
+ * the last chunk.
+ * 
+ * <p>Chunks are uploaded one at a time and in order, but not necessarily on
+ * the thread that called {@code generateSplitStreaming}: like every callback
+ * from the native library, the sink may run on an arbitrary thread, so it
+ * must not rely on thread-local state.
+ */
+@FunctionalInterface
+public interface UploadSink {
+    
+    /**
+     * Uploads the next chunk of the split.
+     * 
+     * <p>The array is not reused by the caller. Throwing stops generation,
+     * and the exception is rethrown from {@code generateSplitStreaming}.
+     * 
+     * @param chunk Chunk of the serialized split
+     * @param offset Offset of the chunk in the serialized split
+     * @throws IOException if the upload fails
+     */
+    void upload(byte[] chunk, long offset) throws IOException;
+}
@@ -101,6 +101,17 @@ public class QuickwitSplitGeneratorTest {
         });
     }
     
+    @Test
+    void testNonUtf8ArgumentThrowsCleanException() {
+        // A lone surrogate cannot be converted to UTF-8 on the native side,
+        // which must reject it before the argument reaches the index
+        RuntimeException e = assertThrows(RuntimeException.class, () -> {
+            generator.generatePartitionedSplits("title\uD800", tempDir.resolve("partitions"));
+        });
+        assertTrue(e.getMessage().contains("partitionField"));
+        assertFalse(Files.exists(tempDir.resolve("partitions")));
+    }
+    
     @Disabled("Native implementation not complete")
     @Test
     void testGenerateSplitBasicFunctionality() throws IOException {
@@ -119,12 +130,28 @@ public class QuickwitSplitGeneratorTest {
         assertEquals(2, metadata.getNumDocs());
         assertTrue(metadata.getSizeBytes() > 0);
         
-        // Verify output files exist
-        assertTrue(Files.exists(outputPath));
-        assertTrue(Files.isDirectory(outputPath));
+        // An existing directory receives a single <split_id>.split file
+        Path splitFile = outputPath.resolve(metadata.getSplitId() + ".split");
+        assertTrue(Files.isRegularFile(splitFile));
+        assertEquals(metadata.getSizeBytes(), Files.size(splitFile));
+        assertEquals(1, Files.list(outputPath).count());
+    }
+    
+    @Disabled("Native implementation not complete")
+    @Test
+    void testGenerateSplitWithLegacyDirectoryLayout() throws IOException {
+        Path outputPath = tempDir.resolve("legacy_split");
         
-        // Should have at least one segment file
+        generator.setConfig(SplitConfig.builder().legacyDirectoryLayout(true).build());
+        SplitMetadata metadata = generator.generateSplit(outputPath);
+        
+        // The split files are written into the output directory
+        assertTrue(Files.isDirectory(outputPath));
         assertTrue(Files.list(outputPath).count() > 0);
+        
+        try (QuickwitSplitReader reader = new QuickwitSplitReader(outputPath)) {
+            assertEquals(metadata.getNumDocs(), reader.getHotcacheInfo().getNumDocs());
+        }
     }
     
     @Disabled("Native implementation not complete")
@@ -219,6 +246,42 @@ public class QuickwitSplitGeneratorTest {
         assertEquals(1, metadata.getNumDocs());
     }
     
+    @Disabled("Native implementation not complete")
+    @Test
+    void testGenerateSplitFailsIfOutputExists() throws IOException {
+        Path outputPath = tempDir.resolve("existing_split");
+        Files.createDirectories(outputPath);
+        Files.write(outputPath.resolve("meta.json"), new byte[0]);
+        
+        generator.setConfig(SplitConfig.builder().legacyDirectoryLayout(true).build());
+        assertThrows(IllegalStateException.class, () -> {
+            generator.generateSplit(outputPath);
+        });
+        
+        generator.setConfig(SplitConfig.builder().legacyDirectoryLayout(true).overwrite(true).build());
+        SplitMetadata metadata = generator.generateSplit(outputPath);
+        assertNotNull(metadata);
+    }
+    
+    @Disabled("Native implementation not complete")
+    @Test
+    void testGetProgress() throws IOException {
+        assertEquals(0, generator.getProgress());
+        
+        generator.generateSplit(tempDir.resolve("progress_split"));
+        assertEquals(100, generator.getProgress());
+        
+        generator.close();
+        assertThrows(IllegalStateException.class, () -> generator.getProgress());
+    }
+    
+    @Test
+    void testSetConfigValidatesInput() {
+        assertThrows(NullPointerException.class, () -> {
+            generator.setConfig(null);
+        });
+    }
+    
     @Test
     void testGeneratorConfigurationMethods() {
         // Test getting configured target docs per split
@@ -218,6 +218,36 @@ public class QuickwitSplitReaderTest {
         });
     }
     
+    @Test
+    void testGetFastFieldsValidatesParameters() throws IOException {
+        reader = new QuickwitSplitReader(splitPath);
+        
+        // Test null field list
+        assertThrows(NullPointerException.class, () -> {
+            reader.getFastFields(null, 0, 10);
+        });
+        
+        // Test empty field name in list
+        assertThrows(IllegalArgumentException.class, () -> {
+            reader.getFastFields(List.of("timestamp", ""), 0, 10);
+        });
+        
+        // Test invalid document range
+        assertThrows(IllegalArgumentException.class, () -> {
+            reader.getFastFields(List.of("timestamp"), 10, 5);
+        });
+    }
+    
+    @Disabled("Native implementation not complete")
+    @Test
+    void testReadStoreBlockValidatesParameters() throws IOException {
+        reader = new QuickwitSplitReader(splitPath);
+        
+        assertThrows(IllegalArgumentException.class, () -> {
+            reader.readStoreBlock(-1);
+        });
+    }
+    
     @Disabled("Native implementation not complete")
     @Test
     void testHotcacheInfoContainsExpectedFields() throws IOException {
@@ -309,6 +339,10 @@ public class QuickwitSplitReaderTest {
         assertThrows(IllegalStateException.class, () -> {
             reader.getFastFieldData("timestamp", 0, 10);
         });
+        
+        assertThrows(IllegalStateException.class, () -> {
+            reader.getSegmentMetaJson();
+        });
     }
     
     @Disabled("Native implementation not complete")
@@ -163,12 +163,12 @@ public class QuickwitSplitsIntegrationTest {
                 assertTrue(metadata.getSizeBytes() > 0);
                 assertNotNull(metadata.getHotcacheRange());
                 
-                // Verify split files exist
-                assertTrue(Files.exists(splitDir));
-                assertTrue(Files.isDirectory(splitDir));
+                // Verify the split file exists
+                Path splitFile = splitDir.resolve(metadata.getSplitId() + ".split");
+                assertTrue(Files.isRegularFile(splitFile));
                 
                 // Step 3: Read the split
-                try (QuickwitSplitReader reader = new QuickwitSplitReader(splitDir)) {
+                try (QuickwitSplitReader reader = new QuickwitSplitReader(splitFile)) {
                     
                     // Verify hotcache info
                     HotcacheInfo hotcache = reader.getHotcacheInfo();